@@ -114,7 +114,7 @@ fn bench_bundle_operations_baseline(c: &mut Criterion) {
                 |b, (expr, data)| {
                     let rt = tokio::runtime::Runtime::new().unwrap();
                     b.iter(|| {
-                        let mut engine = FhirPathEngine::new();
+                        let engine = FhirPathEngine::new();
                         black_box(rt.block_on(engine.evaluate(black_box(expr), (**data).clone())))
                     })
                 },
@@ -258,11 +258,34 @@ fn bench_arc_bundle_operations(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_json_string_interning(c: &mut Criterion) {
+    let (_, _, large) = load_test_data();
+
+    let mut group = c.benchmark_group("json_string_interning");
+    group.sample_size(10);
+
+    // Systems and codes repeat across nearly every entry of a large Bundle
+    let expression = "Bundle.entry.resource.code.coding.system";
+
+    for (name, enabled) in [("plain", false), ("interned", true)] {
+        group.bench_with_input(BenchmarkId::new(name, "large"), &large, |b, data| {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            b.iter(|| {
+                let engine = FhirPathEngine::new().with_json_string_interning(enabled);
+                black_box(rt.block_on(engine.evaluate(black_box(expression), data.clone())))
+            });
+        });
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     bundle_baseline_benchmarks,
     bench_bundle_operations_baseline,
     bench_memory_cloning_baseline,
-    bench_arc_bundle_operations
+    bench_arc_bundle_operations,
+    bench_json_string_interning
 );
 
 criterion_main!(bundle_baseline_benchmarks);
@@ -0,0 +1,94 @@
+//! Streaming vs eager Bundle evaluation
+//!
+//! Compares evaluating `Bundle.entry.resource.id` on the fully loaded large
+//! fixture with streaming the entries through `evaluate_bundle_entries`, both in
+//! time and in peak heap usage. Peak usage is tracked by a counting allocator
+//! and printed before the timing runs.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use octofhir_fhirpath::engine::FhirPathEngine;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs::File;
+use std::hint::black_box;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const FIXTURE: &str = "benches/fixtures/large.json";
+
+/// Allocator that records current and peak heap usage
+struct PeakAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: PeakAllocator = PeakAllocator;
+
+/// Peak heap growth in bytes while running `f`
+fn peak_bytes(f: impl FnOnce()) -> usize {
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    f();
+    PEAK.load(Ordering::Relaxed) - baseline
+}
+
+fn eager(engine: &FhirPathEngine, runtime: &tokio::runtime::Runtime) {
+    let text = std::fs::read_to_string(FIXTURE).unwrap();
+    let bundle: serde_json::Value = serde_json::from_str(&text).unwrap();
+    drop(text);
+    let ids = runtime
+        .block_on(engine.evaluate("Bundle.entry.resource.id", bundle))
+        .unwrap();
+    black_box(ids);
+}
+
+fn streaming(engine: &FhirPathEngine, runtime: &tokio::runtime::Runtime) {
+    let file = File::open(FIXTURE).unwrap();
+    let ids = runtime
+        .block_on(engine.evaluate_bundle_entries("id", file))
+        .unwrap();
+    black_box(ids);
+}
+
+fn bench_bundle_streaming(c: &mut Criterion) {
+    if !Path::new(FIXTURE).exists() {
+        println!("Skipping bundle streaming benchmark - {FIXTURE} not found");
+        return;
+    }
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let engine = FhirPathEngine::new();
+
+    let eager_peak = peak_bytes(|| eager(&engine, &runtime));
+    let streaming_peak = peak_bytes(|| streaming(&engine, &runtime));
+    println!(
+        "Peak heap for Bundle.entry.resource.id on {FIXTURE}: eager {:.1} MiB, streaming {:.1} MiB",
+        eager_peak as f64 / (1024.0 * 1024.0),
+        streaming_peak as f64 / (1024.0 * 1024.0)
+    );
+
+    let mut group = c.benchmark_group("bundle_streaming");
+    group.sample_size(10);
+    group.bench_function("eager", |b| b.iter(|| eager(&engine, &runtime)));
+    group.bench_function("streaming", |b| b.iter(|| streaming(&engine, &runtime)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_bundle_streaming);
+criterion_main!(benches);
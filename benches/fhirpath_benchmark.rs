@@ -87,7 +87,7 @@ fn bench_evaluator(c: &mut Criterion) {
             |b, expr| {
                 let rt = tokio::runtime::Runtime::new().unwrap();
                 b.iter(|| {
-                    let mut engine = FhirPathEngine::new();
+                    let engine = FhirPathEngine::new();
                     black_box(rt.block_on(engine.evaluate(black_box(expr), input.clone())))
                 })
             },
@@ -119,7 +119,7 @@ fn bench_throughput(c: &mut Criterion) {
     group.bench_function("evaluator_throughput", |b| {
         let rt = tokio::runtime::Runtime::new().unwrap();
         b.iter(|| {
-            let mut engine = FhirPathEngine::new();
+            let engine = FhirPathEngine::new();
             black_box(rt.block_on(engine.evaluate(black_box(expression), input.clone())))
         })
     });
@@ -127,6 +127,81 @@ fn bench_throughput(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_compiled_vs_string(c: &mut Criterion) {
+    let expression = "Bundle.entry.resource.where(resourceType = 'Patient' and active).name.family";
+    let bundles: Vec<Value> = (0..100)
+        .map(|i| {
+            serde_json::json!({
+                "resourceType": "Bundle",
+                "type": "collection",
+                "entry": [
+                    {"resource": {"resourceType": "Patient", "active": i % 2 == 0, "name": [{"family": format!("F{i}")}]}},
+                    {"resource": {"resourceType": "Observation", "status": "final"}}
+                ]
+            })
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("compiled");
+    group.measurement_time(std::time::Duration::from_secs(5)); // Fast benchmarking
+    group.throughput(Throughput::Elements(bundles.len() as u64));
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let engine = FhirPathEngine::new();
+
+    group.bench_function("string", |b| {
+        b.iter(|| {
+            for bundle in &bundles {
+                black_box(rt.block_on(engine.evaluate(black_box(expression), bundle.clone())))
+                    .unwrap();
+            }
+        })
+    });
+
+    let compiled = engine.compile(expression).unwrap();
+    group.bench_function("compiled", |b| {
+        b.iter(|| {
+            for bundle in &bundles {
+                black_box(rt.block_on(engine.evaluate_compiled(&compiled, bundle.clone())))
+                    .unwrap();
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_regex_over_collection(c: &mut Criterion) {
+    let mut group = c.benchmark_group("regex");
+    group.measurement_time(std::time::Duration::from_secs(5)); // Fast benchmarking
+
+    // One patient with many given names, so each pattern is applied per item
+    let given: Vec<String> = (0..1000).map(|i| format!("Name{i}")).collect();
+    let input = serde_json::json!({
+        "resourceType": "Patient",
+        "name": [{"given": given}]
+    });
+
+    for (name, expression) in [
+        (
+            "matches",
+            "Patient.name.given.where(matches('^Name[0-9]*7$')).count()",
+        ),
+        (
+            "replace_matches",
+            "Patient.name.given.select(replaceMatches('[0-9]+', '#')).distinct()",
+        ),
+    ] {
+        group.bench_function(name, |b| {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let engine = FhirPathEngine::new();
+            b.iter(|| black_box(rt.block_on(engine.evaluate(black_box(expression), input.clone()))))
+        });
+    }
+
+    group.finish();
+}
+
 fn bench_string_interning_performance(c: &mut Criterion) {
     let mut group = c.benchmark_group("string_interning");
     group.sample_size(20); // Reduced from 100
@@ -366,6 +441,8 @@ criterion_group!(
     bench_parser,
     bench_evaluator,
     bench_throughput,
+    bench_compiled_vs_string,
+    bench_regex_over_collection,
     bench_string_interning_performance,
     bench_tokenizer_interning,
     bench_tokenizer_streaming,
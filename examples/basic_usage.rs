@@ -14,7 +14,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("================================\n");
 
     // Create a FHIRPath engine
-    let mut engine = FhirPathEngine::new();
+    let engine = FhirPathEngine::new();
 
     // Example FHIR Patient resource
     let patient_json = json!({
@@ -12,7 +12,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🔧 FHIRPath Custom Functions Examples");
     println!("======================================\n");
 
-    let mut engine = FhirPathEngine::new();
+    let engine = FhirPathEngine::new();
 
     // Example FHIR data
     let patient = json!({
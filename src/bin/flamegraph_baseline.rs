@@ -20,7 +20,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(feature = "profiling")]
     let guard = ProfilerGuard::new(100)?;
 
-    let mut engine = FhirPathEngine::new();
+    let engine = FhirPathEngine::new();
 
     // Run enough iterations to get meaningful profiling data
     for i in 0..50 {
@@ -115,7 +115,7 @@ async fn handle_evaluate(expression: &str, input: Option<&str>, pretty: bool, qu
     };
 
     // Create FHIRPath engine and evaluate
-    let mut engine = octofhir_fhirpath::engine::FhirPathEngine::new();
+    let engine = octofhir_fhirpath::engine::FhirPathEngine::new();
 
     match engine.evaluate(expression, resource).await {
         Ok(result) => {
@@ -33,7 +33,7 @@ async fn profile_expression(expression: &str) {
 
     // Create sample FHIR Patient resource for testing
     let patient_data = create_sample_patient();
-    let mut engine = FhirPathEngine::new();
+    let engine = FhirPathEngine::new();
 
     println!("📊 Warming up...");
     // Warmup runs
@@ -217,7 +217,7 @@ async fn test_fhirpath_evaluation() {
     println!("---------------------------");
 
     let patient_data = create_sample_patient();
-    let mut engine = FhirPathEngine::new();
+    let engine = FhirPathEngine::new();
 
     // Test various expressions including .where() function
     let expressions = [
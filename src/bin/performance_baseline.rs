@@ -1,9 +1,11 @@
 //! Performance baseline measurement for Phase 0 optimizations
 
-use octofhir_fhirpath::engine::FhirPathEngine;
+use octofhir_fhirpath::evaluator::FhirPathEngine;
+use octofhir_fhirpath::model::FhirPathValue;
+use octofhir_fhirpath::parse;
 use serde_json::Value;
 use std::fs;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 const EXPRESSIONS: &[(&str, &str)] = &[
     ("simple_bundle_traversal", "Bundle.entry"),
@@ -40,29 +42,45 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("{:-<50}", "");
 
         for (expr_name, expression) in EXPRESSIONS {
-            let mut engine = FhirPathEngine::new();
+            let engine = FhirPathEngine::new();
 
             // Warm up
             for _ in 0..3 {
-                let _ = engine.evaluate(expression, dataset.clone()).await;
+                let ast = parse(expression)?;
+                let _ = engine
+                    .evaluate(&ast, FhirPathValue::from(dataset.clone()))
+                    .await;
             }
 
-            // Measure 10 iterations
-            let start = Instant::now();
+            // Measure 10 iterations, timing parsing and evaluation separately.
+            // Converting the JSON input into a value counts towards evaluation.
             let iterations = 10;
+            let mut parse_time = Duration::ZERO;
+            let mut eval_time = Duration::ZERO;
 
             for _ in 0..iterations {
-                let result = engine.evaluate(expression, dataset.clone()).await;
-                match result {
-                    Ok(_) => {}
-                    Err(e) => println!("  ❌ Error: {e}"),
+                let start = Instant::now();
+                let ast = parse(expression)?;
+                parse_time += start.elapsed();
+
+                let start = Instant::now();
+                let result = engine
+                    .evaluate(&ast, FhirPathValue::from(dataset.clone()))
+                    .await;
+                eval_time += start.elapsed();
+
+                if let Err(e) = result {
+                    println!("  ❌ Error: {e}");
                 }
             }
 
-            let elapsed = start.elapsed();
-            let avg_ms = elapsed.as_millis() as f64 / iterations as f64;
+            let avg_parse_us = parse_time.as_micros() as f64 / iterations as f64;
+            let avg_eval_ms = eval_time.as_micros() as f64 / 1000.0 / iterations as f64;
+            let avg_total_ms = avg_eval_ms + avg_parse_us / 1000.0;
 
-            println!("  {expr_name} - {avg_ms:.2}ms/eval");
+            println!(
+                "  {expr_name} - {avg_total_ms:.2}ms/eval (parse {avg_parse_us:.2}μs, eval {avg_eval_ms:.2}ms)"
+            );
         }
     }
 
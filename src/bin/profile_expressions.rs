@@ -151,7 +151,7 @@ fn profile_expression(
 ) -> Result<EvalStats> {
     // Warmup outside the profiler to stabilize JIT-like effects and caches
     {
-        let mut engine = FhirPathEngine::new();
+        let engine = FhirPathEngine::new();
         for _ in 0..warmup {
             let _ = rt.block_on(engine.evaluate(expr, input.clone()));
         }
@@ -173,7 +173,7 @@ fn profile_expression(
         .build()
         .context("starting pprof profiler")?;
 
-    let mut engine = FhirPathEngine::new();
+    let engine = FhirPathEngine::new();
 
     let start = Instant::now();
     for _ in 0..iterations {
@@ -126,7 +126,7 @@ async fn main() {
     println!("🔢 Total tests: {}", test_suite.tests.len());
     println!();
 
-    let mut engine = FhirPathEngine::new();
+    let engine = FhirPathEngine::new();
     let mut passed = 0;
     let mut failed = 0;
     let mut errors = 0;
@@ -12,9 +12,11 @@ use crate::compiler::bytecode::{
     Bytecode, BytecodeBuilder, BytecodeMetadata, Instruction, OptimizationLevel,
 };
 use crate::compiler::optimizer::{ExpressionOptimizer, OptimizationConfig};
-use crate::model::{FhirPathValue, quantity::Quantity};
+use crate::model::{
+    FhirPathValue, PrecisionDate, PrecisionDateTime, PrecisionTime, quantity::Quantity,
+};
 use crate::registry::FunctionRegistry;
-use chrono::DateTime;
+use crate::registry::function::FunctionError;
 use rust_decimal::Decimal;
 use std::sync::Arc;
 
@@ -29,6 +31,8 @@ pub enum CompilationError {
         expected: usize,
         got: usize,
     },
+    /// Lambda expression passed to a function that does not take one
+    UnexpectedLambda(String),
     /// Unsupported expression type
     UnsupportedExpression(String),
     /// Internal compiler error
@@ -53,6 +57,9 @@ impl std::fmt::Display for CompilationError {
                     "Function {function} expects {expected} arguments, got {got}"
                 )
             }
+            Self::UnexpectedLambda(function) => {
+                write!(f, "Function {function} does not accept lambda expressions")
+            }
             Self::UnsupportedExpression(desc) => write!(f, "Unsupported expression: {desc}"),
             Self::InternalError(msg) => write!(f, "Internal compiler error: {msg}"),
             Self::JumpTargetOutOfRange(offset) => write!(f, "Jump target out of range: {offset}"),
@@ -98,7 +105,6 @@ pub struct ExpressionCompiler {
     /// Compiler configuration
     config: CompilerConfig,
     /// Function registry for looking up function signatures
-    #[allow(dead_code)]
     functions: Arc<FunctionRegistry>,
     /// Expression optimizer for constant folding and other optimizations
     optimizer: ExpressionOptimizer,
@@ -416,6 +422,8 @@ impl ExpressionCompiler {
         data: &FunctionCallData,
         builder: &mut BytecodeBuilder,
     ) -> CompilationResult<()> {
+        self.validate_call_shape(&data.name, &data.args)?;
+
         // Check if this is a built-in function that can be inlined
         if self.config.function_inlining {
             if let Some(inlined) = self.try_inline_function(&data.name, &data.args) {
@@ -446,6 +454,8 @@ impl ExpressionCompiler {
         data: &MethodCallData,
         builder: &mut BytecodeBuilder,
     ) -> CompilationResult<()> {
+        self.validate_call_shape(&data.method, &data.args)?;
+
         // Compile base expression
         self.compile_expression(&data.base, builder)?;
 
@@ -621,24 +631,24 @@ impl ExpressionCompiler {
             LiteralValue::Decimal(d) => FhirPathValue::Decimal(d.parse().unwrap_or_default()),
             LiteralValue::String(s) => FhirPathValue::interned_string(s),
             LiteralValue::Date(d) => {
-                // Parse date string to NaiveDate
-                match d.parse() {
-                    Ok(date) => FhirPathValue::Date(date),
-                    Err(_) => FhirPathValue::Empty, // Invalid date becomes empty
+                // Parse date string, keeping its precision
+                match PrecisionDate::parse(d.strip_prefix('@').unwrap_or(d)) {
+                    Some(date) => FhirPathValue::Date(date),
+                    None => FhirPathValue::Empty, // Invalid date becomes empty
                 }
             }
             LiteralValue::DateTime(dt) => {
-                // Parse datetime string to DateTime<FixedOffset>
-                match DateTime::parse_from_rfc3339(dt) {
-                    Ok(datetime) => FhirPathValue::DateTime(datetime),
-                    Err(_) => FhirPathValue::Empty, // Invalid datetime becomes empty
+                // Parse datetime string, keeping its precision
+                match PrecisionDateTime::parse(dt.strip_prefix('@').unwrap_or(dt)) {
+                    Some(datetime) => FhirPathValue::DateTime(datetime),
+                    None => FhirPathValue::Empty, // Invalid datetime becomes empty
                 }
             }
             LiteralValue::Time(t) => {
-                // Parse time string to NaiveTime
-                match t.parse() {
-                    Ok(time) => FhirPathValue::Time(time),
-                    Err(_) => FhirPathValue::Empty, // Invalid time becomes empty
+                // Parse time string, keeping its precision
+                match PrecisionTime::parse(t.strip_prefix("@T").unwrap_or(t)) {
+                    Some(time) => FhirPathValue::Time(time),
+                    None => FhirPathValue::Empty, // Invalid time becomes empty
                 }
             }
             LiteralValue::Quantity { value, unit } => {
@@ -720,6 +730,24 @@ impl ExpressionCompiler {
         }
     }
 
+    /// Reject calls whose shape the function registry knows to be invalid
+    fn validate_call_shape(&self, name: &str, args: &[ExpressionNode]) -> CompilationResult<()> {
+        self.functions
+            .validate_call_shape(name, args)
+            .map_err(|err| match err {
+                FunctionError::InvalidArity { min, max, .. } => CompilationError::InvalidArity {
+                    function: name.to_string(),
+                    expected: if args.len() < min {
+                        min
+                    } else {
+                        max.unwrap_or(min)
+                    },
+                    got: args.len(),
+                },
+                _ => CompilationError::UnexpectedLambda(name.to_string()),
+            })
+    }
+
     /// Try to inline a simple function call
     fn try_inline_function(&self, name: &str, args: &[ExpressionNode]) -> Option<ExpressionNode> {
         // Only inline very simple functions for now
@@ -1025,4 +1053,53 @@ mod tests {
         assert!(disassembly.contains("CONST") || disassembly.contains("PUSH"));
         assert!(disassembly.contains("EQ"));
     }
+
+    fn compile_with_standard_registry(expression: &str) -> CompilationResult<Bytecode> {
+        let (functions, _) = crate::registry::create_standard_registries();
+        let mut compiler = ExpressionCompiler::new(Arc::new(functions));
+        compiler.compile(&crate::parse(expression).unwrap())
+    }
+
+    #[test]
+    fn test_lambda_arity_errors() {
+        match compile_with_standard_registry("Patient.name.where()") {
+            Err(CompilationError::InvalidArity {
+                function,
+                expected,
+                got,
+            }) => {
+                assert_eq!(function, "where");
+                assert_eq!(expected, 1);
+                assert_eq!(got, 0);
+            }
+            other => panic!("expected arity error for where(), got {other:?}"),
+        }
+
+        match compile_with_standard_registry("Patient.name.select(1, 2)") {
+            Err(CompilationError::InvalidArity {
+                function,
+                expected,
+                got,
+            }) => {
+                assert_eq!(function, "select");
+                assert_eq!(expected, 1);
+                assert_eq!(got, 2);
+            }
+            other => panic!("expected arity error for select(1, 2), got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_lambda_passed_to_non_lambda_function() {
+        assert!(matches!(
+            compile_with_standard_registry("Patient.name.given.substring(x => 1)"),
+            Err(CompilationError::UnexpectedLambda(name)) if name == "substring"
+        ));
+    }
+
+    #[test]
+    fn test_single_lambda_call_compiles() {
+        assert!(compile_with_standard_registry("Patient.name.where(use = 'official')").is_ok());
+        assert!(compile_with_standard_registry("Patient.name.exists()").is_ok());
+    }
 }
@@ -4,7 +4,7 @@
 //! or AST expressions to improve performance.
 
 use crate::ast::{BinaryOperator, ExpressionNode, LiteralValue, UnaryOperator};
-use crate::model::FhirPathValue;
+use crate::model::{FhirPathValue, PrecisionDate, PrecisionDateTime, PrecisionTime};
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 
@@ -227,28 +227,24 @@ impl ExpressionOptimizer {
             }
             LiteralValue::String(s) => FhirPathValue::interned_string(s),
             LiteralValue::Date(d) => {
-                // Parse date string to NaiveDate
-                match chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d") {
-                    Ok(date) => FhirPathValue::Date(date),
-                    Err(_) => FhirPathValue::String(d.clone().into()), // Fallback to string
+                // Parse date string, keeping its precision
+                match PrecisionDate::parse(d.strip_prefix('@').unwrap_or(d)) {
+                    Some(date) => FhirPathValue::Date(date),
+                    None => FhirPathValue::String(d.clone().into()), // Fallback to string
                 }
             }
             LiteralValue::DateTime(dt) => {
-                // Parse datetime string to DateTime<FixedOffset>
-                match chrono::DateTime::parse_from_rfc3339(dt) {
-                    Ok(datetime) => FhirPathValue::DateTime(
-                        datetime
-                            .with_timezone(&chrono::Utc)
-                            .with_timezone(&chrono::FixedOffset::east_opt(0).unwrap()),
-                    ),
-                    Err(_) => FhirPathValue::String(dt.clone().into()), // Fallback to string
+                // Parse datetime string, keeping its precision
+                match PrecisionDateTime::parse(dt.strip_prefix('@').unwrap_or(dt)) {
+                    Some(datetime) => FhirPathValue::DateTime(datetime),
+                    None => FhirPathValue::String(dt.clone().into()), // Fallback to string
                 }
             }
             LiteralValue::Time(t) => {
-                // Parse time string to NaiveTime
-                match chrono::NaiveTime::parse_from_str(t, "%H:%M:%S") {
-                    Ok(time) => FhirPathValue::Time(time),
-                    Err(_) => FhirPathValue::String(t.clone().into()), // Fallback to string
+                // Parse time string, keeping its precision
+                match PrecisionTime::parse(t.strip_prefix("@T").unwrap_or(t)) {
+                    Some(time) => FhirPathValue::Time(time),
+                    None => FhirPathValue::String(t.clone().into()), // Fallback to string
                 }
             }
             LiteralValue::Quantity { value, unit } => {
@@ -269,9 +265,9 @@ impl ExpressionOptimizer {
             FhirPathValue::Integer(i) => LiteralValue::Integer(i),
             FhirPathValue::Decimal(d) => LiteralValue::Decimal(d.to_string()),
             FhirPathValue::String(s) => LiteralValue::String(s.as_ref().to_string()),
-            FhirPathValue::Date(d) => LiteralValue::Date(d.format("%Y-%m-%d").to_string()),
-            FhirPathValue::DateTime(dt) => LiteralValue::DateTime(dt.to_rfc3339()),
-            FhirPathValue::Time(t) => LiteralValue::Time(t.format("%H:%M:%S").to_string()),
+            date @ FhirPathValue::Date(_) => LiteralValue::Date(date.to_string()),
+            datetime @ FhirPathValue::DateTime(_) => LiteralValue::DateTime(datetime.to_string()),
+            FhirPathValue::Time(t) => LiteralValue::Time(t.to_string()),
             FhirPathValue::Quantity(ref q) => LiteralValue::Quantity {
                 value: q.value.to_string(),
                 unit: q.unit.as_ref().unwrap_or(&"".to_string()).clone(),
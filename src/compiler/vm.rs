@@ -516,7 +516,9 @@ impl<'a> VmExecutor<'a> {
             "$this" => Ok(self.input.clone()),
             "$index" => Ok(FhirPathValue::Integer(0)), // Simplified
             "$total" => Ok(FhirPathValue::Integer(1)), // Simplified
-            _ => Ok(FhirPathValue::Empty),
+            // Environment variables live in the evaluation context, which the
+            // VM does not see; failing hands the expression to the interpreter
+            _ => Err(VmError::RuntimeError(format!("Unknown variable: {name}"))),
         }
     }
 
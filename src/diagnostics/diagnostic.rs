@@ -75,6 +75,8 @@ pub enum DiagnosticCode {
     ArithmeticOverflow,
     /// Invalid regular expression
     InvalidRegex,
+    /// Reference that could not be resolved to a resource
+    UnresolvedReference,
 
     // Custom error code
     /// Custom error with a string code
@@ -184,6 +186,7 @@ impl Diagnostic {
             DiagnosticCode::IndexOutOfBounds => "E301".to_string(),
             DiagnosticCode::ArithmeticOverflow => "E302".to_string(),
             DiagnosticCode::InvalidRegex => "E303".to_string(),
+            DiagnosticCode::UnresolvedReference => "E304".to_string(),
             DiagnosticCode::Custom(code) => code.clone(),
         }
     }
@@ -225,6 +228,7 @@ impl fmt::Display for DiagnosticCode {
             DiagnosticCode::IndexOutOfBounds => write!(f, "index out of bounds"),
             DiagnosticCode::ArithmeticOverflow => write!(f, "arithmetic overflow"),
             DiagnosticCode::InvalidRegex => write!(f, "invalid regular expression"),
+            DiagnosticCode::UnresolvedReference => write!(f, "unresolved reference"),
             DiagnosticCode::Custom(msg) => write!(f, "{msg}"),
         }
     }
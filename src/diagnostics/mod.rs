@@ -12,6 +12,7 @@ pub mod diagnostic_reporter;
 pub mod enhanced_diagnostic;
 pub mod formatter;
 pub mod location;
+pub mod warning_sink;
 
 pub use builder::DiagnosticBuilder;
 pub use diagnostic::{Diagnostic, DiagnosticCode, RelatedInformation, Severity, Suggestion};
@@ -25,6 +26,7 @@ pub use enhanced_diagnostic::{
 };
 pub use formatter::{DiagnosticFormatter, Format};
 pub use location::{Position, SourceLocation, Span};
+pub use warning_sink::WarningSink;
 
 // Re-export LSP types when feature is enabled
 #[cfg(feature = "lsp")]
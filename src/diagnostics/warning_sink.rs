@@ -0,0 +1,31 @@
+//! Collection point for the non-fatal warnings of an evaluation
+
+use super::Diagnostic;
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// Collects the warnings raised while an expression is evaluated
+///
+/// Clones share the same warnings, so functions can report into the sink the
+/// caller reads from once evaluation is done.
+#[derive(Debug, Clone, Default)]
+pub struct WarningSink {
+    warnings: Arc<Mutex<Vec<Diagnostic>>>,
+}
+
+impl WarningSink {
+    /// Create an empty sink
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a warning
+    pub fn push(&self, warning: Diagnostic) {
+        self.warnings.lock().push(warning);
+    }
+
+    /// Remove and return the warnings recorded so far, in the order raised
+    pub fn take(&self) -> Vec<Diagnostic> {
+        std::mem::take(&mut *self.warnings.lock())
+    }
+}
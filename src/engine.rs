@@ -2,13 +2,30 @@
 
 use super::error::Result;
 use crate::ast::ExpressionNode;
+use crate::diagnostics::{Diagnostic, WarningSink};
+use crate::evaluator::ErrorTrace;
 use crate::evaluator::FhirPathEngine as EvaluatorEngine;
-use crate::model::{FhirPathValue, ValuePoolConfig, configure_global_pools, global_pool_stats};
-use crate::parser::{cache_ast, get_cached_ast, parse_expression};
+use crate::evaluator::bundle_stream::entry_resources;
+use crate::model::string_intern::JsonStringInterner;
+use crate::model::{
+    CacheStats, CountingCache, FhirPathValue, JSON_STRING_INTERNER_CAPACITY, ValuePoolConfig,
+    configure_global_pools, global_pool_stats,
+};
+use crate::parser::{SpannedParseError, parse_expression_spanned};
 use crate::pipeline::global_pools;
-use crate::registry::create_standard_registries;
+use crate::registry::function::AsyncFhirPathFunction;
+use crate::registry::functions::{
+    AsyncReferenceResolver, DanglingReferences, ReferenceResolver, ReferenceSource,
+    ResolveFunction, ResolveMode, TraceFunction, TraceSink,
+};
+use crate::registry::{FunctionRegistry, create_standard_registries};
+use chrono::FixedOffset;
+use futures::{Stream, StreamExt};
+use octofhir_fhir_model::{ModelProvider, TypeReflectionInfo};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::io::Read;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 /// Main FHIRPath engine for parsing and evaluating expressions
@@ -16,10 +33,20 @@ use std::sync::Arc;
 pub struct FhirPathEngine {
     /// The underlying evaluator engine
     evaluator: EvaluatorEngine,
-    /// Cached compiled expressions for performance
-    expression_cache: HashMap<String, ExpressionNode>,
-    /// Maximum cache size to prevent memory issues
-    max_cache_size: usize,
+    /// Parsed expressions, shared by clones and concurrent evaluations
+    expression_cache: Option<Arc<ExpressionCache>>,
+    /// Attach the navigation stack leading to an evaluation error
+    capture_error_context: bool,
+    /// Reject navigation to elements the model does not define
+    strict_navigation: bool,
+    /// Model used to check element names during strict navigation
+    model_provider: Option<Arc<dyn ModelProvider>>,
+    /// What [`evaluate_bool`](Self::evaluate_bool) returns for an empty result
+    empty_invariant_result: bool,
+    /// The `resolve()` settings, updated one at a time by the builders
+    resolve: ResolveFunction,
+    /// Share one allocation between equal strings lifted from the input
+    intern_json_strings: bool,
 }
 
 impl Default for FhirPathEngine {
@@ -44,8 +71,13 @@ impl FhirPathEngine {
 
         Self {
             evaluator,
-            expression_cache: HashMap::new(),
-            max_cache_size: 1000,
+            expression_cache: ExpressionCache::with_capacity(DEFAULT_EXPRESSION_CACHE_CAPACITY),
+            capture_error_context: false,
+            strict_navigation: false,
+            model_provider: None,
+            empty_invariant_result: false,
+            resolve: ResolveFunction::new(),
+            intern_json_strings: false,
         }
     }
 
@@ -58,8 +90,13 @@ impl FhirPathEngine {
 
         Self {
             evaluator,
-            expression_cache: HashMap::new(),
-            max_cache_size: 1000,
+            expression_cache: ExpressionCache::with_capacity(DEFAULT_EXPRESSION_CACHE_CAPACITY),
+            capture_error_context: false,
+            strict_navigation: false,
+            model_provider: None,
+            empty_invariant_result: false,
+            resolve: ResolveFunction::new(),
+            intern_json_strings: false,
         }
     }
 
@@ -73,72 +110,484 @@ impl FhirPathEngine {
         Self::with_pool_config(pool_config)
     }
 
-    /// Evaluate an FHIRPath expression against input data
-    pub async fn evaluate(&mut self, expression: &str, input_data: Value) -> Result<FhirPathValue> {
-        // Handle parse errors by returning empty collection per FHIRPath spec
-        let ast = match self.get_or_compile_expression(expression) {
-            Ok(ast) => ast,
-            Err(e) => {
-                // Per FHIRPath spec, syntax errors should return empty collection
-                if e.to_string().contains("parse error")
-                    || e.to_string().contains("Parse error")
-                    || e.to_string().contains("Unclosed")
-                    || e.to_string().contains("Unexpected")
-                    || e.to_string().contains("Expected")
-                {
-                    return Ok(FhirPathValue::collection(vec![]));
-                } else {
-                    return Err(e);
-                }
-            }
-        };
+    /// Create an engine that follows the specification exactly
+    ///
+    /// `resolve()` fails on references it cannot find, and navigating to an
+    /// element the model does not define is an error. Element names can only be
+    /// checked against a model, so attach one with
+    /// [`with_model_provider`](Self::with_model_provider).
+    pub fn strict() -> Self {
+        Self::new()
+            .with_strict_navigation(true)
+            .with_dangling_references(DanglingReferences::Error)
+    }
 
-        let input_value = FhirPathValue::from(input_data);
+    /// Create an engine suited to servers handling real-world data
+    ///
+    /// Unlike [`new`](Self::new), `resolve()` also matches contained resources
+    /// by bare id. As with `new`, references it cannot find are dropped and
+    /// navigating to an unknown element yields empty.
+    pub fn lenient() -> Self {
+        Self::new().with_resolve_function(ResolveFunction::tolerant())
+    }
+
+    /// Enable or disable interning of the string values lifted from JSON input
+    ///
+    /// Large Bundles repeat the same systems, codes and urls many times. With
+    /// interning enabled those values share one allocation within an
+    /// evaluation instead of allocating a fresh string each time they are
+    /// navigated. Each evaluation has its own interner, holding at most
+    /// [`JSON_STRING_INTERNER_CAPACITY`] strings. Off by default.
+    pub fn with_json_string_interning(mut self, enabled: bool) -> Self {
+        self.intern_json_strings = enabled;
+        self
+    }
+
+    /// Enable or disable rejecting navigation to elements the model does not define
+    ///
+    /// Only takes effect once a model is attached with
+    /// [`with_model_provider`](Self::with_model_provider).
+    pub fn with_strict_navigation(mut self, enabled: bool) -> Self {
+        self.strict_navigation = enabled;
+        self
+    }
+
+    /// Attach the model used to check element names during strict navigation
+    pub fn with_model_provider(mut self, provider: Arc<dyn ModelProvider>) -> Self {
+        self.model_provider = Some(provider);
+        self
+    }
+
+    /// Set how `resolve()` treats references it cannot find
+    ///
+    /// The other `resolve()` settings, such as the tolerant matching of
+    /// [`lenient`](Self::lenient), are kept.
+    pub fn with_dangling_references(self, policy: DanglingReferences) -> Self {
+        let resolve = self.resolve.clone().with_dangling_references(policy);
+        self.with_resolve_function(resolve)
+    }
+
+    /// Set whether `resolve()` returns placeholder resources for references
+    /// it cannot find
+    ///
+    /// Defaults to [`ResolveMode::Strict`], where such references give empty.
+    /// [`ResolveMode::Placeholder`] is meant for tests; its resources carry
+    /// `_placeholder: true`. The other `resolve()` settings are kept.
+    pub fn with_resolve_mode(self, mode: ResolveMode) -> Self {
+        let resolve = self.resolve.clone().with_resolve_mode(mode);
+        self.with_resolve_function(resolve)
+    }
+
+    /// Send the name and value of every `trace()` call to `sink`
+    ///
+    /// For `Bundle.entry.trace('entries').resource` the sink receives
+    /// `"entries"` and the entries; `trace('ids', resource.id)` hands it the
+    /// id of each entry instead. Without a sink, `trace()` only passes its
+    /// input through.
+    pub fn with_trace_sink(self, sink: TraceSink) -> Self {
+        self.with_functions(|functions| {
+            functions.register_lambda(TraceFunction::new().with_sink(sink))
+        })
+    }
+
+    /// Send every `trace()` call to `sink`, summarizing collections of more
+    /// than `max_items` items as their size and first few items
+    ///
+    /// The traced collection still passes through `trace()` in full.
+    pub fn with_summarized_trace_sink(self, sink: TraceSink, max_items: usize) -> Self {
+        self.with_functions(|functions| {
+            functions.register_lambda(
+                TraceFunction::new()
+                    .with_sink(sink)
+                    .with_summary_threshold(max_items),
+            )
+        })
+    }
+
+    /// Use `resolve` for `resolve()`, remembering it for later builders
+    fn with_resolve_function(mut self, resolve: ResolveFunction) -> Self {
+        self.resolve = resolve.clone();
+        self.with_function(resolve)
+    }
+
+    /// Replace a function implementation used by the evaluator, keeping the rest
+    fn with_function(self, function: impl AsyncFhirPathFunction + 'static) -> Self {
+        self.with_functions(|functions| functions.register_async(function))
+    }
 
-        match self.evaluator.evaluate(&ast, input_value).await {
-            Ok(result) => Ok(result),
-            Err(eval_error) => Err(crate::error::FhirPathError::evaluation_error(
-                eval_error.to_string(),
-            )),
+    /// Update the functions used by the evaluator, keeping the rest
+    fn with_functions(mut self, register: impl FnOnce(&mut FunctionRegistry)) -> Self {
+        let (functions, operators) = self.evaluator.registries();
+        let mut functions = FunctionRegistry::clone(&functions);
+        register(&mut functions);
+        self.evaluator = EvaluatorEngine::with_registries(Arc::new(functions), operators)
+            .with_timezone(self.evaluator.timezone())
+            .with_reference_resolver(self.evaluator.reference_resolver())
+            .with_resolution_cache_size(self.evaluator.resolution_cache_size())
+            .with_variables(self.evaluator.variables());
+        self
+    }
+
+    /// Set the timezone `now()`, `today()` and `timeOfDay()` report in
+    ///
+    /// Defaults to the local timezone. Each evaluation reads the clock once,
+    /// so every call to these functions within it agrees.
+    pub fn with_timezone(mut self, timezone: FixedOffset) -> Self {
+        self.evaluator = self.evaluator.with_timezone(Some(timezone));
+        self
+    }
+
+    /// Let `resolve()` fetch references it cannot find in the data from `resolver`
+    ///
+    /// Contained and Bundle resources are searched first; `resolver` is only
+    /// asked for the references left over, e.g. `Patient/123` held in a database.
+    pub fn with_reference_resolver(mut self, resolver: Arc<dyn ReferenceResolver>) -> Self {
+        self.evaluator = self
+            .evaluator
+            .with_reference_resolver(Some(ReferenceSource::blocking(resolver)));
+        self
+    }
+
+    /// Let `resolve()` fetch references it cannot find in the data from an
+    /// asynchronous `resolver`, such as a FHIR server
+    ///
+    /// The references one `resolve()` call cannot find are fetched together,
+    /// with up to `max_concurrent` requests in flight.
+    pub fn with_async_reference_resolver(
+        mut self,
+        resolver: Arc<dyn AsyncReferenceResolver>,
+        max_concurrent: usize,
+    ) -> Self {
+        self.evaluator = self
+            .evaluator
+            .with_reference_resolver(Some(ReferenceSource::new(resolver, max_concurrent)));
+        self
+    }
+
+    /// Make `value` available to expressions as `%name`, e.g. a ValueSet URL
+    /// for `%allowedCodes` or a collection of codes
+    ///
+    /// `name` is given without the `%`. Setting a name again replaces its
+    /// value. Built-in variables cannot be replaced: `%context` and
+    /// `%rootResource` refer to the input, and `%resource` to the resource
+    /// holding the current item, such as a Bundle entry's resource. An
+    /// expression using a `%` variable that is neither built in nor set here
+    /// fails to evaluate.
+    pub fn set_variable(&mut self, name: impl Into<String>, value: FhirPathValue) {
+        self.evaluator.set_variable(name, value);
+    }
+
+    /// Set how many distinct references `resolve()` remembers the Bundle
+    /// entry of during one evaluation, 0 to search the Bundle every time
+    ///
+    /// Defaults to [`crate::registry::functions::DEFAULT_RESOLUTION_CACHE_SIZE`].
+    /// Each evaluation starts with an empty cache, so changes to the data
+    /// between evaluations are seen.
+    pub fn with_resolution_cache_size(mut self, size: usize) -> Self {
+        self.evaluator = self.evaluator.with_resolution_cache_size(size);
+        self
+    }
+
+    /// Keep up to `capacity` parsed expressions, so evaluating the same
+    /// expression string again skips parsing
+    ///
+    /// Expressions are keyed by their exact text and the least recently used
+    /// are evicted once `capacity` is reached. A capacity of zero disables the
+    /// cache. The engine starts with a fresh, empty cache, which clones of it
+    /// then share.
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.expression_cache = ExpressionCache::with_capacity(capacity);
+        self
+    }
+
+    /// Remove all parsed expressions from the expression cache
+    ///
+    /// The hit and miss counters are kept.
+    pub fn clear_expression_cache(&self) {
+        if let Some(cache) = &self.expression_cache {
+            cache.entries.clear();
         }
     }
 
-    /// Get or compile an expression, using global AST cache when possible
-    fn get_or_compile_expression(&mut self, expression: &str) -> Result<Arc<ExpressionNode>> {
-        // First try the global AST cache
-        if let Some(cached_ast) = get_cached_ast(expression) {
-            return Ok(cached_ast);
+    /// Statistics about the expression cache, all zero when it is disabled
+    pub fn expression_cache_stats(&self) -> ExpressionCacheStats {
+        self.expression_cache
+            .as_ref()
+            .map(|cache| cache.stats())
+            .unwrap_or_default()
+    }
+
+    /// Enable or disable capturing the navigation stack of evaluation errors
+    ///
+    /// When enabled, an error raised by [`evaluate`](Self::evaluate) carries the
+    /// path that led to it, available through
+    /// [`FhirPathError::navigation_stack`]. A function argument failing for one
+    /// input item points at that item, e.g.
+    /// `Bundle.entry.resource[3].select(name.given.substring(0, 1))`. The nodes
+    /// an error passes through are recorded as it propagates, so nothing is
+    /// evaluated twice.
+    ///
+    /// [`FhirPathError::navigation_stack`]: crate::error::FhirPathError::navigation_stack
+    pub fn with_error_context(mut self, enabled: bool) -> Self {
+        self.capture_error_context = enabled;
+        self
+    }
+
+    /// Set what [`evaluate_bool`](Self::evaluate_bool) returns when an
+    /// expression evaluates to empty
+    ///
+    /// Defaults to `false`, the convention for invariants. Constraints that
+    /// only apply when their element is present may want `true` instead.
+    pub fn with_empty_invariant_result(mut self, result: bool) -> Self {
+        self.empty_invariant_result = result;
+        self
+    }
+
+    /// Evaluate an invariant expression to a single boolean
+    ///
+    /// A single Boolean result is returned as is, and an empty result gives
+    /// `false` (see [`with_empty_invariant_result`](Self::with_empty_invariant_result)).
+    /// A result with more than one item or a non-Boolean item is a type error.
+    pub async fn evaluate_bool(&self, expression: &str, input_data: Value) -> Result<bool> {
+        let ast = self.get_or_compile_expression(expression)?;
+        let item = match self.evaluate_ast(&ast, input_data, None).await? {
+            FhirPathValue::Collection(items) if items.len() > 1 => {
+                return Err(crate::error::FhirPathError::type_error(format!(
+                    "Expected '{expression}' to give a single Boolean, got {} items",
+                    items.len()
+                )));
+            }
+            FhirPathValue::Collection(items) => items.first().cloned(),
+            FhirPathValue::Empty => None,
+            item => Some(item),
+        };
+
+        match item {
+            Some(FhirPathValue::Boolean(b)) => Ok(b),
+            Some(other) => Err(crate::error::FhirPathError::type_error(format!(
+                "Expected '{expression}' to give a Boolean, got a {}",
+                other.type_name()
+            ))),
+            None => Ok(self.empty_invariant_result),
         }
+    }
 
-        // Fall back to local cache for transition compatibility
-        if let Some(local_ast) = self.expression_cache.get(expression) {
-            let shared_ast = Arc::new(local_ast.clone());
-            // Cache in global cache for next time
-            cache_ast(expression, local_ast.clone());
-            return Ok(shared_ast);
+    /// Evaluate an FHIRPath expression against input data
+    ///
+    /// A syntax error is reported as [`FhirPathError::ParseError`] at the
+    /// position where parsing stopped.
+    ///
+    /// [`FhirPathError::ParseError`]: crate::error::FhirPathError::ParseError
+    pub async fn evaluate(&self, expression: &str, input_data: Value) -> Result<FhirPathValue> {
+        self.evaluate_expression(expression, input_data, None).await
+    }
+
+    /// Parse and evaluate `expression`, sending warnings to `warnings` if given
+    async fn evaluate_expression(
+        &self,
+        expression: &str,
+        input_data: Value,
+        warnings: Option<&WarningSink>,
+    ) -> Result<FhirPathValue> {
+        let ast = self.get_or_compile_expression(expression)?;
+        self.evaluate_ast(&ast, input_data, warnings).await
+    }
+
+    /// Parse `expression` once, to evaluate it any number of times with
+    /// [`evaluate_compiled`](Self::evaluate_compiled)
+    ///
+    /// A syntax error is reported along with the span of the expression where
+    /// parsing stopped, as [`evaluate`](Self::evaluate) reports it.
+    pub fn compile(
+        &self,
+        expression: &str,
+    ) -> std::result::Result<CompiledExpression, SpannedParseError> {
+        Ok(CompiledExpression {
+            source: expression.into(),
+            ast: Arc::new(parse_expression_spanned(expression)?),
+        })
+    }
+
+    /// Evaluate an expression parsed by [`compile`](Self::compile) against input data
+    ///
+    /// This skips parsing and the expression caches, and otherwise behaves
+    /// like [`evaluate`](Self::evaluate).
+    pub async fn evaluate_compiled(
+        &self,
+        expression: &CompiledExpression,
+        input_data: Value,
+    ) -> Result<FhirPathValue> {
+        self.evaluate_ast(&expression.ast, input_data, None).await
+    }
+
+    /// Evaluate an expression parsed by [`compile`](Self::compile) against
+    /// each resource of `resources`, such as the lines of a bulk export
+    /// `.ndjson` file
+    ///
+    /// Results come out in the order of the resources, one per resource. Each
+    /// resource is read from `resources` only once the previous result has
+    /// been taken, so only one is held at a time. Every resource is evaluated
+    /// on its own, as the root of its evaluation, and an error for one does
+    /// not stop the others. An iterator of parsed NDJSON lines can be passed
+    /// through `futures::stream::iter`.
+    pub fn evaluate_stream<'a, S>(
+        &'a self,
+        expression: &'a CompiledExpression,
+        resources: S,
+    ) -> impl Stream<Item = Result<FhirPathValue>> + 'a
+    where
+        S: Stream<Item = Value> + 'a,
+    {
+        resources.then(move |resource| self.evaluate_ast(&expression.ast, resource, None))
+    }
+
+    /// Evaluate a parsed expression against input data, sending warnings to
+    /// `warnings` if given
+    async fn evaluate_ast(
+        &self,
+        ast: &ExpressionNode,
+        input_data: Value,
+        warnings: Option<&WarningSink>,
+    ) -> Result<FhirPathValue> {
+        if self.strict_navigation
+            && let Some(provider) = &self.model_provider
+        {
+            let root_type = input_data.get("resourceType").and_then(|t| t.as_str());
+            check_navigation(provider.as_ref(), ast, root_type)?;
         }
 
-        // Parse and cache both globally and locally
-        let ast = parse_expression(expression)
-            .map_err(|e| crate::error::FhirPathError::parse_error(0, e.to_string()))?;
+        let input_value = FhirPathValue::from(input_data);
+        let error_trace = self.capture_error_context.then(ErrorTrace::new);
+
+        let evaluation = async {
+            if warnings.is_none() && error_trace.is_none() {
+                return self.evaluator.evaluate(ast, input_value).await;
+            }
+            self.evaluator
+                .evaluate_reporting(ast, input_value, warnings.cloned(), error_trace.clone())
+                .await
+        };
+        let evaluated = if self.intern_json_strings {
+            let capacity = NonZeroUsize::new(JSON_STRING_INTERNER_CAPACITY).unwrap();
+            Arc::new(JsonStringInterner::new(capacity))
+                .scope(evaluation)
+                .await
+        } else {
+            evaluation.await
+        };
+        evaluated.map_err(|eval_error| {
+            let error = crate::error::FhirPathError::evaluation_error(eval_error.to_string());
+            match error_trace.and_then(|trace| trace.navigation_stack()) {
+                Some(stack) => error.with_navigation_stack(stack),
+                None => error,
+            }
+        })
+    }
+
+    /// Evaluate an FHIRPath expression, returning the value together with any
+    /// non-fatal warnings raised while producing it
+    ///
+    /// Each reference `resolve()` could not find, whether dropped or replaced
+    /// by a placeholder resource under [`ResolveMode::Placeholder`], is
+    /// reported as an [`UnresolvedReference`] warning, even when the result
+    /// does not hold it, as in `subject.resolve().exists()`.
+    ///
+    /// [`UnresolvedReference`]: crate::diagnostics::DiagnosticCode::UnresolvedReference
+    pub async fn evaluate_with_warnings(
+        &self,
+        expression: &str,
+        input_data: Value,
+    ) -> Result<EvaluationOutcome> {
+        let warnings = WarningSink::new();
+        let value = self
+            .evaluate_expression(expression, input_data, Some(&warnings))
+            .await?;
+
+        Ok(EvaluationOutcome {
+            value,
+            warnings: warnings.take(),
+        })
+    }
 
-        // Cache globally (primary cache)
-        cache_ast(expression, ast.clone());
+    /// Evaluate an `all()` invariant and return the path of every item it fails for
+    ///
+    /// For `Patient.contact.all(telecom.exists())` this returns e.g.
+    /// `["Patient.contact[0]", "Patient.contact[2]"]`, or an empty list if the
+    /// invariant holds. The criteria is evaluated against each item separately,
+    /// and an item fails unless the criteria is `true` for it. Expressions that
+    /// are not of the form `<items>.all(<criteria>)` are rejected.
+    pub async fn evaluate_all_matches(
+        &self,
+        expression: &str,
+        input_data: Value,
+    ) -> Result<Vec<String>> {
+        let ast = self.get_or_compile_expression(expression)?;
+        self.evaluator
+            .failing_items(&ast, FhirPathValue::from(input_data))
+            .await
+            .map_err(|e| crate::error::FhirPathError::evaluation_error(e.to_string()))?
+            .ok_or_else(|| {
+                crate::error::FhirPathError::invalid_expression(format!(
+                    "Expected an expression of the form '<items>.all(<criteria>)', got '{expression}'"
+                ))
+            })
+    }
 
-        // Cache locally (fallback/transition cache)
-        if self.expression_cache.len() >= self.max_cache_size {
-            self.expression_cache.clear();
+    /// Evaluate an expression against every entry resource of a Bundle read from `reader`
+    ///
+    /// The Bundle is parsed as a stream, so only one entry is held in memory at a
+    /// time. Each entry resource is evaluated like [`evaluate`](Self::evaluate)
+    /// evaluates its input, so strict navigation and error context apply, and the
+    /// results are concatenated in entry order, e.g. `id` yields the id of every
+    /// entry resource.
+    ///
+    /// The expression only sees the entry resource, not the Bundle around it, so
+    /// this matches `Bundle.entry.resource.select(expression)` on the loaded
+    /// Bundle only for expressions that stay within the resource. `%rootResource`
+    /// is the entry resource rather than the Bundle, and `resolve()` does not find
+    /// sibling entries.
+    ///
+    /// The Bundle's `resourceType` must come before its `entry` array; a missing
+    /// or late `resourceType` is an error, raised before any entry is evaluated.
+    ///
+    /// Each entry is evaluated before the next one is read. Reads from `reader`
+    /// are synchronous, so a slow reader holds up the calling task between
+    /// entries.
+    pub async fn evaluate_bundle_entries<R: Read>(
+        &self,
+        expression: &str,
+        reader: R,
+    ) -> Result<FhirPathValue> {
+        let ast = self.get_or_compile_expression(expression)?;
+
+        let mut results = Vec::new();
+        for resource in entry_resources(reader) {
+            match self.evaluate_ast(&ast, resource?, None).await? {
+                FhirPathValue::Collection(items) => results.extend(items.iter().cloned()),
+                FhirPathValue::Empty => {}
+                item => results.push(item),
+            }
         }
-        self.expression_cache
-            .insert(expression.to_string(), ast.clone());
 
-        Ok(Arc::new(ast))
+        Ok(FhirPathValue::collection(results))
+    }
+
+    /// Get a parsed expression from the expression cache, parsing it on a miss
+    fn get_or_compile_expression(
+        &self,
+        expression: &str,
+    ) -> std::result::Result<Arc<ExpressionNode>, SpannedParseError> {
+        let parse = || parse_expression_spanned(expression).map(Arc::new);
+        match &self.expression_cache {
+            Some(cache) => cache.get_or_parse(expression, parse),
+            None => parse(),
+        }
     }
 
     /// Pool-optimized evaluation using global memory pools
     /// This method demonstrates integration with the async-first memory pool system
     pub async fn evaluate_with_pools(
-        &mut self,
+        &self,
         expression: &str,
         input_data: Value,
     ) -> Result<FhirPathValue> {
@@ -186,6 +635,183 @@ impl FhirPathEngine {
     }
 }
 
+/// How many parsed expressions an engine keeps unless configured otherwise
+pub const DEFAULT_EXPRESSION_CACHE_CAPACITY: usize = 1000;
+
+/// Parsed expressions keyed by their text, see [`FhirPathEngine::with_cache_capacity`]
+struct ExpressionCache {
+    entries: CountingCache<String, Arc<ExpressionNode>>,
+}
+
+/// Statistics about an engine's expression cache
+pub type ExpressionCacheStats = CacheStats;
+
+impl ExpressionCache {
+    /// Create a cache holding up to `capacity` expressions, or none for zero
+    fn with_capacity(capacity: usize) -> Option<Arc<Self>> {
+        NonZeroUsize::new(capacity).map(|capacity| {
+            Arc::new(Self {
+                entries: CountingCache::new(capacity),
+            })
+        })
+    }
+
+    fn stats(&self) -> ExpressionCacheStats {
+        self.entries.stats()
+    }
+
+    /// Look `expression` up, calling `parse` on a miss
+    ///
+    /// The lock is not held while parsing, so two evaluations missing on the
+    /// same expression at once both parse it. Expressions that fail to parse
+    /// are not cached.
+    fn get_or_parse(
+        &self,
+        expression: &str,
+        parse: impl FnOnce() -> std::result::Result<Arc<ExpressionNode>, SpannedParseError>,
+    ) -> std::result::Result<Arc<ExpressionNode>, SpannedParseError> {
+        if let Some(ast) = self.entries.get(expression) {
+            return Ok(ast);
+        }
+
+        let ast = parse()?;
+        self.entries.put(expression.to_string(), ast.clone());
+        Ok(ast)
+    }
+}
+
+/// An expression parsed by [`FhirPathEngine::compile`]
+///
+/// Only the syntax is fixed: functions are looked up when the expression is
+/// evaluated, so it can be evaluated by any engine, whatever functions that
+/// engine registers. Cloning is cheap.
+#[derive(Debug, Clone)]
+pub struct CompiledExpression {
+    source: Arc<str>,
+    ast: Arc<ExpressionNode>,
+}
+
+impl CompiledExpression {
+    /// The expression as written
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The parsed expression
+    pub fn ast(&self) -> &ExpressionNode {
+        &self.ast
+    }
+}
+
+/// Result of [`FhirPathEngine::evaluate_with_warnings`]
+#[derive(Debug, Clone)]
+pub struct EvaluationOutcome {
+    /// The evaluated value
+    pub value: FhirPathValue,
+    /// Non-fatal warnings raised during evaluation
+    pub warnings: Vec<Diagnostic>,
+}
+
+impl EvaluationOutcome {
+    /// Check whether evaluation produced any warnings
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+}
+
+/// Check that every element navigated from `context_type` is defined by the model
+///
+/// Returns the type reached by `node` when the schema can tell, so chains such
+/// as `Patient.contact.name` are checked step by step. Navigation through
+/// functions, operators and types the model does not describe is not checked.
+fn check_navigation(
+    provider: &dyn ModelProvider,
+    node: &ExpressionNode,
+    context_type: Option<&str>,
+) -> Result<Option<String>> {
+    match node {
+        // A leading type name such as `Patient` filters the input by type
+        ExpressionNode::Identifier(name) if name.starts_with(char::is_uppercase) => {
+            Ok((context_type == Some(name.as_str())).then(|| name.clone()))
+        }
+        ExpressionNode::Identifier(name) => check_element(provider, context_type, name),
+        ExpressionNode::Path { base, path } => {
+            let base_type = check_navigation(provider, base, context_type)?;
+            check_element(provider, base_type.as_deref(), path)
+        }
+        ExpressionNode::Index { base, index } => {
+            check_navigation(provider, index, context_type)?;
+            check_navigation(provider, base, context_type)
+        }
+        ExpressionNode::Filter { base, .. } => check_navigation(provider, base, context_type),
+        ExpressionNode::MethodCall(data) => {
+            let base_type = check_navigation(provider, &data.base, context_type)?;
+            // Filtering and subsetting keep the type of their input
+            let keeps_type = matches!(
+                data.method.as_str(),
+                "where" | "first" | "last" | "tail" | "skip" | "take" | "single" | "distinct"
+            );
+            Ok(base_type.filter(|_| keeps_type))
+        }
+        ExpressionNode::BinaryOp(data) => {
+            check_navigation(provider, &data.left, context_type)?;
+            check_navigation(provider, &data.right, context_type)?;
+            Ok(None)
+        }
+        ExpressionNode::Union { left, right } => {
+            check_navigation(provider, left, context_type)?;
+            check_navigation(provider, right, context_type)?;
+            Ok(None)
+        }
+        ExpressionNode::UnaryOp { operand, .. }
+        | ExpressionNode::TypeCheck {
+            expression: operand,
+            ..
+        }
+        | ExpressionNode::TypeCast {
+            expression: operand,
+            ..
+        } => {
+            check_navigation(provider, operand, context_type)?;
+            Ok(None)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Look up a single element, returning its type when the model describes it
+fn check_element(
+    provider: &dyn ModelProvider,
+    type_name: Option<&str>,
+    element: &str,
+) -> Result<Option<String>> {
+    let Some(type_name) = type_name else {
+        return Ok(None);
+    };
+    if element == "resourceType" || provider.get_type_reflection(type_name).is_none() {
+        return Ok(None);
+    }
+
+    let element_type = provider
+        .get_property_type(type_name, element)
+        .ok_or_else(|| {
+            crate::error::FhirPathError::invalid_expression(format!(
+                "Unknown element '{element}' on type '{type_name}'"
+            ))
+        })?;
+
+    Ok(reflected_type_name(&element_type))
+}
+
+fn reflected_type_name(info: &TypeReflectionInfo) -> Option<String> {
+    match info {
+        TypeReflectionInfo::SimpleType { name, .. }
+        | TypeReflectionInfo::ClassInfo { name, .. } => Some(name.clone()),
+        TypeReflectionInfo::ListType { element_type } => reflected_type_name(element_type),
+        _ => None,
+    }
+}
+
 /// Comprehensive memory statistics for the FHIRPath engine
 #[derive(Debug, Clone)]
 pub struct MemoryStats {
@@ -101,6 +101,13 @@ pub enum FhirPathError {
         max_arity: Option<usize>,
         actual: usize,
     },
+
+    /// Error annotated with the navigation path that led to it
+    #[error("{error} (at {})", stack.join("."))]
+    WithNavigationStack {
+        stack: Vec<String>,
+        error: Box<FhirPathError>,
+    },
 }
 
 impl FhirPathError {
@@ -247,6 +254,82 @@ impl FhirPathError {
             actual,
         }
     }
+
+    /// Annotate this error with the navigation path that led to it
+    pub fn with_navigation_stack(self, stack: Vec<String>) -> Self {
+        Self::WithNavigationStack {
+            stack,
+            error: Box::new(self),
+        }
+    }
+
+    /// Navigation path that led to this error, if it was captured
+    pub fn navigation_stack(&self) -> Option<&[String]> {
+        match self {
+            Self::WithNavigationStack { stack, .. } => Some(stack),
+            _ => None,
+        }
+    }
+
+    /// FHIR `IssueType` code that best describes this error
+    pub fn issue_code(&self) -> &'static str {
+        match self {
+            Self::ParseError { .. }
+            | Self::InvalidExpression { .. }
+            | Self::InvalidTypeSpecifier
+            | Self::InvalidArgumentCount { .. }
+            | Self::InvalidArity { .. } => "invalid",
+            Self::TypeError { .. }
+            | Self::InvalidOperandTypes { .. }
+            | Self::ConversionError { .. }
+            | Self::IncompatibleUnits { .. } => "value",
+            Self::UnknownFunction { .. } | Self::UnknownOperator { .. } => "not-supported",
+            Self::EvaluationError { .. }
+            | Self::FunctionError { .. }
+            | Self::ArithmeticError { .. }
+            | Self::IndexOutOfBounds { .. }
+            | Self::DivisionByZero
+            | Self::ArithmeticOverflow { .. } => "processing",
+            Self::Generic { .. } => "exception",
+            Self::WithNavigationStack { error, .. } => error.issue_code(),
+        }
+    }
+
+    /// Convert this error into a minimal FHIR `OperationOutcome` resource
+    ///
+    /// The outcome carries a single error issue whose `code` comes from
+    /// [`issue_code`](Self::issue_code) and whose `diagnostics` is the error
+    /// message. Parse errors also report their position in `location`.
+    pub fn to_operation_outcome(&self) -> serde_json::Value {
+        let mut issue = serde_json::json!({
+            "severity": "error",
+            "code": self.issue_code(),
+            "diagnostics": self.to_string(),
+        });
+
+        if let Self::ParseError { position, .. } = self {
+            issue["location"] = serde_json::json!([format!("position {position}")]);
+        }
+        if let Some(stack) = self.navigation_stack() {
+            issue["expression"] = serde_json::json!([stack.join(".")]);
+        }
+
+        serde_json::json!({
+            "resourceType": "OperationOutcome",
+            "issue": [issue],
+        })
+    }
+}
+
+/// A parse error keeps its position, and its message shows where in the
+/// expression parsing stopped
+impl From<crate::parser::SpannedParseError> for FhirPathError {
+    fn from(err: crate::parser::SpannedParseError) -> Self {
+        Self::ParseError {
+            position: err.span.start,
+            message: err.to_string(),
+        }
+    }
 }
 
 /// Convert from `Box<dyn std::error::Error>` for compatibility with tests
@@ -260,3 +343,54 @@ impl From<Box<dyn std::error::Error>> for FhirPathError {
 
 // Note: From<FhirPathError> for Box<dyn std::error::Error> is automatically provided by Rust
 // since FhirPathError implements std::error::Error via thiserror
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_error_operation_outcome() {
+        let error = FhirPathError::parse_error(7, "Unexpected token ')'");
+
+        assert_eq!(
+            error.to_operation_outcome(),
+            json!({
+                "resourceType": "OperationOutcome",
+                "issue": [{
+                    "severity": "error",
+                    "code": "invalid",
+                    "diagnostics": "Parse error at position 7: Unexpected token ')'",
+                    "location": ["position 7"]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_type_error_operation_outcome() {
+        let error = FhirPathError::type_error("Cannot add String and Integer");
+
+        assert_eq!(
+            error.to_operation_outcome(),
+            json!({
+                "resourceType": "OperationOutcome",
+                "issue": [{
+                    "severity": "error",
+                    "code": "value",
+                    "diagnostics": "Type error: Cannot add String and Integer"
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_issue_codes() {
+        assert_eq!(
+            FhirPathError::unknown_function("foo").issue_code(),
+            "not-supported"
+        );
+        assert_eq!(FhirPathError::division_by_zero().issue_code(), "processing");
+        assert_eq!(FhirPathError::generic("oops").issue_code(), "exception");
+    }
+}
@@ -0,0 +1,260 @@
+//! Streaming access to the entries of a Bundle
+//!
+//! Large Bundles do not have to be loaded as a whole to visit their entries.
+//! [`entry_resources`] reads a Bundle from any [`Read`] source and yields each
+//! `entry.resource` as soon as it has been parsed, so only one entry is held in
+//! memory at a time. Everything outside `entry` is skipped.
+//!
+//! Entries are yielded before the rest of the document has been read, so
+//! `resourceType` must come before `entry`; a Bundle whose `resourceType` is
+//! missing or only follows `entry` is rejected before any entry is yielded.
+
+use crate::error::FhirPathError;
+use serde::Deserialize;
+use serde::de::IgnoredAny;
+use serde_json::Value;
+use std::io::{BufReader, Bytes, Read};
+
+/// Iterate over the resource of every entry of the Bundle read from `reader`
+///
+/// Entries are yielded in document order, and entries without a resource are
+/// skipped. Each entry is only read once the previous one has been taken. A
+/// `resourceType` other than `Bundle`, one that is missing or follows `entry`,
+/// and invalid JSON are yielded as an error, after which the iterator ends.
+pub fn entry_resources<R: Read>(reader: R) -> EntryResources<R> {
+    EntryResources {
+        bytes: BufReader::new(reader).bytes(),
+        peeked: None,
+        state: State::Start,
+        is_bundle: false,
+    }
+}
+
+/// Iterator returned by [`entry_resources`]
+pub struct EntryResources<R> {
+    bytes: Bytes<BufReader<R>>,
+    /// Byte looked at but not yet taken
+    peeked: Option<u8>,
+    state: State,
+    /// Whether `resourceType: Bundle` has been read
+    is_bundle: bool,
+}
+
+/// Where in the Bundle the reader is
+#[derive(Clone, Copy)]
+enum State {
+    /// Before the opening brace of the Bundle
+    Start,
+    /// Between the members of the Bundle
+    Members {
+        first: bool,
+    },
+    /// Between the elements of the `entry` array
+    Entries {
+        first: bool,
+    },
+    Done,
+}
+
+/// Only the resource of an entry is kept; fullUrl, search, request, etc. are skipped
+#[derive(Deserialize)]
+struct Entry {
+    resource: Option<Value>,
+}
+
+impl<R: Read> Iterator for EntryResources<R> {
+    type Item = Result<Value, FhirPathError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_resource() {
+            Ok(resource) => resource.map(Ok),
+            Err(message) => {
+                self.state = State::Done;
+                Some(Err(FhirPathError::evaluation_error(format!(
+                    "Invalid Bundle JSON: {message}"
+                ))))
+            }
+        }
+    }
+}
+
+impl<R: Read> EntryResources<R> {
+    /// Read up to and including the next entry that has a resource
+    fn next_resource(&mut self) -> Result<Option<Value>, String> {
+        loop {
+            match self.state {
+                State::Start => {
+                    self.expect(b'{')?;
+                    self.state = State::Members { first: true };
+                }
+                State::Members { first } => {
+                    if self.peek_token()? == b'}' {
+                        self.take();
+                        if !self.is_bundle {
+                            return Err("missing field `resourceType`".into());
+                        }
+                        self.expect_end()?;
+                        self.state = State::Done;
+                        continue;
+                    }
+                    if !first {
+                        self.expect(b',')?;
+                    }
+                    let key: String = parse(&self.read_value()?)?;
+                    self.expect(b':')?;
+                    self.state = State::Members { first: false };
+                    self.read_member(&key)?;
+                }
+                State::Entries { first } => {
+                    if self.peek_token()? == b']' {
+                        self.take();
+                        self.state = State::Members { first: false };
+                        continue;
+                    }
+                    if !first {
+                        self.expect(b',')?;
+                    }
+                    self.state = State::Entries { first: false };
+                    let entry: Entry = parse(&self.read_value()?)?;
+                    if let Some(resource) = entry.resource {
+                        return Ok(Some(resource));
+                    }
+                }
+                State::Done => return Ok(None),
+            }
+        }
+    }
+
+    /// Read the value of the Bundle member `key`, stopping at the start of `entry`
+    fn read_member(&mut self, key: &str) -> Result<(), String> {
+        match key {
+            "resourceType" => {
+                let resource_type: String = parse(&self.read_value()?)?;
+                if resource_type != "Bundle" {
+                    return Err(format!("expected a Bundle, found {resource_type}"));
+                }
+                self.is_bundle = true;
+            }
+            "entry" if !self.is_bundle => {
+                return Err("expected resourceType 'Bundle' before entry".into());
+            }
+            "entry" => {
+                self.expect(b'[')?;
+                self.state = State::Entries { first: true };
+            }
+            _ => {
+                parse::<IgnoredAny>(&self.read_value()?)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The bytes of the next JSON value, to be parsed with serde_json
+    fn read_value(&mut self) -> Result<Vec<u8>, String> {
+        let mut value = Vec::new();
+        match self.peek_token()? {
+            b'"' => self.read_string(&mut value)?,
+            b'{' | b'[' => {
+                let mut depth = 0usize;
+                loop {
+                    let byte = self.peek()?.ok_or("EOF while parsing a value")?;
+                    match byte {
+                        b'"' => {
+                            self.read_string(&mut value)?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => depth -= 1,
+                        _ => {}
+                    }
+                    value.push(self.take());
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            _ => {
+                while let Some(byte) = self.peek()? {
+                    if matches!(byte, b',' | b'}' | b']') || byte.is_ascii_whitespace() {
+                        break;
+                    }
+                    value.push(self.take());
+                }
+            }
+        }
+        Ok(value)
+    }
+
+    /// Append the string starting at the next byte, quotes included, to `value`
+    fn read_string(&mut self, value: &mut Vec<u8>) -> Result<(), String> {
+        value.push(self.take());
+        loop {
+            let byte = self.peek()?.ok_or("EOF while parsing a string")?;
+            value.push(self.take());
+            match byte {
+                b'\\' => {
+                    self.peek()?.ok_or("EOF while parsing a string")?;
+                    value.push(self.take());
+                }
+                b'"' => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+
+    /// Take the next byte after whitespace, failing if it is not `expected`
+    fn expect(&mut self, expected: u8) -> Result<(), String> {
+        let byte = self.peek_token()?;
+        if byte != expected {
+            return Err(format!(
+                "expected `{}`, found `{}`",
+                expected as char, byte as char
+            ));
+        }
+        self.take();
+        Ok(())
+    }
+
+    /// Fail unless only whitespace is left
+    fn expect_end(&mut self) -> Result<(), String> {
+        self.skip_whitespace()?;
+        match self.peek()? {
+            Some(_) => Err("trailing characters".into()),
+            None => Ok(()),
+        }
+    }
+
+    /// The next byte after whitespace, without taking it
+    fn peek_token(&mut self) -> Result<u8, String> {
+        self.skip_whitespace()?;
+        self.peek()?
+            .ok_or_else(|| "EOF while parsing the Bundle".into())
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), String> {
+        while self.peek()?.is_some_and(|byte| byte.is_ascii_whitespace()) {
+            self.take();
+        }
+        Ok(())
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, String> {
+        if self.peeked.is_none() {
+            self.peeked = self
+                .bytes
+                .next()
+                .transpose()
+                .map_err(|error| error.to_string())?;
+        }
+        Ok(self.peeked)
+    }
+
+    /// Take the byte returned by the last [`peek`](Self::peek)
+    fn take(&mut self) -> u8 {
+        self.peeked.take().expect("a byte has been peeked")
+    }
+}
+
+fn parse<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<T, String> {
+    serde_json::from_slice(bytes).map_err(|error| error.to_string())
+}
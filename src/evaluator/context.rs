@@ -1,7 +1,11 @@
 // Evaluation context for FHIRPath expressions
 
+use super::navigation::ErrorTrace;
+use crate::diagnostics::WarningSink;
 use crate::model::FhirPathValue;
+use crate::registry::functions::{ReferenceSource, ResolutionCache};
 use crate::registry::{FunctionRegistry, OperatorRegistry};
+use chrono::{DateTime, FixedOffset, Utc};
 use rustc_hash::FxHashMap;
 use std::borrow::Cow;
 use std::collections::VecDeque;
@@ -124,9 +128,16 @@ pub struct EvaluationContext {
     /// Current input value being evaluated
     pub input: FhirPathValue,
 
-    /// Root input value (for %context and $resource variables)
+    /// Root input value (for %context and %rootResource variables)
     pub root: FhirPathValue,
 
+    /// The resource containing the node being evaluated (for %resource)
+    pub resource: FhirPathValue,
+
+    /// The `%resource` of each item of `input`, when its items were reached
+    /// through different resources, such as the entries of a Bundle
+    pub input_resources: Option<Arc<[FhirPathValue]>>,
+
     /// Variable scope stack for proper scoping
     pub variable_scope: VariableScope,
 
@@ -135,6 +146,21 @@ pub struct EvaluationContext {
 
     /// Operator registry for evaluating operations
     pub operators: Arc<OperatorRegistry>,
+
+    /// When the evaluation started, so `now()` is the same throughout it
+    pub now: DateTime<FixedOffset>,
+
+    /// Where `resolve()` looks for references it cannot find in the data
+    pub reference_resolver: Option<ReferenceSource>,
+
+    /// Bundle lookups `resolve()` has made so far in the evaluation
+    pub resolution_cache: Option<Arc<ResolutionCache>>,
+
+    /// Where non-fatal warnings raised during the evaluation are sent
+    pub warnings: Option<WarningSink>,
+
+    /// Records where an error arose, when the caller wants its navigation stack
+    pub error_trace: Option<ErrorTrace>,
 }
 
 impl EvaluationContext {
@@ -146,10 +172,17 @@ impl EvaluationContext {
     ) -> Self {
         Self {
             root: input.clone(),
+            resource: input.clone(),
+            input_resources: None,
             input,
             variable_scope: VariableScope::new(),
             functions,
             operators,
+            now: Utc::now().fixed_offset(),
+            reference_resolver: None,
+            resolution_cache: None,
+            warnings: None,
+            error_trace: None,
         }
     }
 
@@ -158,9 +191,16 @@ impl EvaluationContext {
         Self {
             input,
             root: self.root.clone(),
+            resource: self.resource.clone(),
+            input_resources: None,
             variable_scope: self.variable_scope.clone(),
             functions: self.functions.clone(),
             operators: self.operators.clone(),
+            now: self.now,
+            reference_resolver: self.reference_resolver.clone(),
+            resolution_cache: self.resolution_cache.clone(),
+            warnings: self.warnings.clone(),
+            error_trace: self.error_trace.clone(),
         }
     }
 
@@ -169,9 +209,16 @@ impl EvaluationContext {
         Self {
             input: self.input.clone(),
             root: self.root.clone(),
+            resource: self.resource.clone(),
+            input_resources: self.input_resources.clone(),
             variable_scope: VariableScope::new(),
             functions: self.functions.clone(),
             operators: self.operators.clone(),
+            now: self.now,
+            reference_resolver: self.reference_resolver.clone(),
+            resolution_cache: self.resolution_cache.clone(),
+            warnings: self.warnings.clone(),
+            error_trace: self.error_trace.clone(),
         }
     }
 
@@ -180,9 +227,87 @@ impl EvaluationContext {
         Self {
             input,
             root: self.root.clone(),
+            resource: self.resource.clone(),
+            input_resources: None,
             variable_scope: VariableScope::child_from_shared(Arc::new(self.variable_scope.clone())),
             functions: self.functions.clone(),
             operators: self.operators.clone(),
+            now: self.now,
+            reference_resolver: self.reference_resolver.clone(),
+            resolution_cache: self.resolution_cache.clone(),
+            warnings: self.warnings.clone(),
+            error_trace: self.error_trace.clone(),
+        }
+    }
+
+    /// Create the context function implementations are called with
+    pub fn to_registry_context(&self) -> crate::registry::function::EvaluationContext {
+        let mut context = crate::registry::function::EvaluationContext::new(self.input.clone());
+        context.root = self.root.clone();
+        context.now = self.now;
+        context.reference_resolver = self.reference_resolver.clone();
+        context.resolution_cache = self.resolution_cache.clone();
+        context.warnings = self.warnings.clone();
+        context
+            .variables
+            .extend(self.variable_scope.collect_all_variables());
+        context
+    }
+
+    /// The `%resource` of `item`, the item at `index` of `input`
+    ///
+    /// Falls back to the context's `%resource` for items that are not from
+    /// `input`, such as those `repeat()` reaches.
+    pub fn resource_of(&self, index: usize, item: &FhirPathValue) -> &FhirPathValue {
+        let from_input = input_items(&self.input)
+            .get(index)
+            .is_some_and(|candidate| same_item(candidate, item));
+        self.input_resources
+            .as_ref()
+            .filter(|_| from_input)
+            .and_then(|resources| resources.get(index))
+            .unwrap_or(&self.resource)
+    }
+
+    /// Create a child context for the item at `index` of `input`, with that
+    /// item's `%resource`
+    pub fn with_input_item(&self, index: usize, item: FhirPathValue) -> Self {
+        let resource = self.resource_of(index, &item).clone();
+        let mut context = self.with_input(item);
+        context.resource = resource;
+        context
+    }
+
+    /// The `%resource` of each item of `result`, for a function such as
+    /// `where()` or `first()` that returns some of its input items
+    ///
+    /// Items are matched to `input` in order. Gives `None` once an item is not
+    /// one of the input items, as for the results of `select()`.
+    pub fn resources_kept_in(&self, result: &FhirPathValue) -> Option<Arc<[FhirPathValue]>> {
+        let resources = self.input_resources.as_ref()?;
+        let input = input_items(&self.input);
+        let mut next = 0;
+        input_items(result)
+            .iter()
+            .map(|item| {
+                let offset = input[next..]
+                    .iter()
+                    .position(|candidate| same_item(candidate, item))?;
+                next += offset + 1;
+                resources.get(next - 1).cloned()
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Arc::from)
+    }
+
+    /// Make `value` the current `%resource` if it is a single FHIR resource
+    pub fn enter_resource(&mut self, value: &FhirPathValue) {
+        let resource = match value {
+            FhirPathValue::Collection(items) if items.len() == 1 => items.first(),
+            other => Some(other),
+        };
+        if let Some(resource) = resource.filter(|value| is_resource(value)) {
+            self.resource = resource.clone();
         }
     }
 
@@ -197,6 +322,25 @@ impl EvaluationContext {
     }
 }
 
+/// The items of `value`, a collection or a single item
+pub(crate) fn input_items(value: &FhirPathValue) -> &[FhirPathValue] {
+    match value {
+        FhirPathValue::Collection(items) => items.as_arc(),
+        FhirPathValue::Empty => &[],
+        item => std::slice::from_ref(item),
+    }
+}
+
+/// Whether `value` is a FHIR resource rather than an element of one
+pub(crate) fn is_resource(value: &FhirPathValue) -> bool {
+    matches!(value, FhirPathValue::Resource(r) if r.resource_type().is_some())
+}
+
+/// Whether `a` and `b` are the same item, checking for shared storage first
+fn same_item(a: &FhirPathValue, b: &FhirPathValue) -> bool {
+    a.shares_memory_with(b) || a == b
+}
+
 impl VariableScope {
     /// Collect all variables from this scope and parent scopes into a flat map
     pub fn collect_all_variables(&self) -> FxHashMap<String, FhirPathValue> {
@@ -327,6 +471,10 @@ impl ContextPool {
             if let Some(mut context) = pool.pop_front() {
                 // Reset the context for reuse
                 context.input = input.clone();
+                context.resource = input.clone();
+                context.input_resources = None;
+                context.warnings = None;
+                context.error_trace = None;
                 context.root = input;
                 context.variable_scope = VariableScope::new();
                 context
@@ -406,6 +554,10 @@ impl Drop for PooledContext {
             self.context.variable_scope = VariableScope::new();
             self.context.input = FhirPathValue::Empty;
             self.context.root = FhirPathValue::Empty;
+            self.context.resource = FhirPathValue::Empty;
+            self.context.input_resources = None;
+            self.context.warnings = None;
+            self.context.error_trace = None;
 
             // Clone the registries before the replace operation
             let functions = self.context.functions.clone();
@@ -485,6 +637,7 @@ impl<'a> StackContext<'a> {
             Arc::new(self.operators.clone()),
         );
         context.root = self.root.clone();
+        context.resource = self.root.clone();
 
         // Convert variables to owned form
         for (name, value) in &self.variables {
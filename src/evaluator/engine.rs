@@ -1,16 +1,24 @@
 //\! Main FHIRPath evaluation engine
 
+use super::navigation::ErrorTrace;
 use super::{
-    context::EvaluationContext,
+    context::{EvaluationContext, input_items, is_resource},
     error::{EvaluationError, EvaluationResult},
 };
 use crate::ast::{BinaryOperator, ExpressionNode, LiteralValue, UnaryOperator};
-use crate::model::FhirPathValue;
-use crate::registry::{FunctionRegistry, OperatorRegistry};
+use crate::diagnostics::WarningSink;
+use crate::model::{
+    FhirPathValue, PrecisionDate, PrecisionDateTime, PrecisionTime, is_value_of_type,
+};
+use crate::registry::functions::collection::union_of;
+use crate::registry::functions::{DEFAULT_RESOLUTION_CACHE_SIZE, ReferenceSource, ResolutionCache};
+use crate::registry::{ArgumentEvaluation, FunctionRegistry, OperatorRegistry};
+use chrono::{FixedOffset, Local, Utc};
 // Lambda functions are not yet fully implemented
 // use crate::registry::function::{AllFunction, AnyFunction, ExistsFunction};
 use rust_decimal::Decimal;
 use std::hash::BuildHasherDefault;
+use std::num::NonZeroUsize;
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -28,8 +36,24 @@ pub struct FhirPathEngine {
     operators: Arc<OperatorRegistry>,
     /// Reusable virtual machine for bytecode execution
     vm: crate::compiler::VirtualMachine,
+    /// Timezone of `now()`, `today()` and `timeOfDay()`, the local one if unset
+    timezone: Option<FixedOffset>,
+    /// Where `resolve()` looks for references it cannot find in the data
+    reference_resolver: Option<ReferenceSource>,
+    /// How many Bundle lookups `resolve()` remembers per evaluation, 0 for none
+    resolution_cache_size: usize,
+    /// Caller-supplied `%` variables every evaluation starts with
+    variables: Arc<VarMap>,
 }
 
+/// A value with the `%resource` of each of its items, when known, and the
+/// context threaded through its evaluation
+type Focus = (
+    FhirPathValue,
+    Option<Arc<[FhirPathValue]>>,
+    EvaluationContext,
+);
+
 impl FhirPathEngine {
     /// Create a new engine with default built-in functions and operators
     pub fn new() -> Self {
@@ -41,6 +65,10 @@ impl FhirPathEngine {
             vm: crate::compiler::VirtualMachine::new(functions.clone(), operators.clone()),
             functions,
             operators,
+            timezone: None,
+            reference_resolver: None,
+            resolution_cache_size: DEFAULT_RESOLUTION_CACHE_SIZE,
+            variables: Arc::default(),
         }
     }
 
@@ -53,12 +81,91 @@ impl FhirPathEngine {
             vm: crate::compiler::VirtualMachine::new(functions.clone(), operators.clone()),
             functions,
             operators,
+            timezone: None,
+            reference_resolver: None,
+            resolution_cache_size: DEFAULT_RESOLUTION_CACHE_SIZE,
+            variables: Arc::default(),
         }
     }
 
+    /// The function and operator registries used by this engine
+    pub fn registries(&self) -> (Arc<FunctionRegistry>, Arc<OperatorRegistry>) {
+        (self.functions.clone(), self.operators.clone())
+    }
+
+    /// Set the timezone `now()`, `today()` and `timeOfDay()` report in, or
+    /// `None` for the local timezone
+    pub fn with_timezone(mut self, timezone: Option<FixedOffset>) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// The timezone `now()`, `today()` and `timeOfDay()` report in, `None`
+    /// for the local timezone
+    pub fn timezone(&self) -> Option<FixedOffset> {
+        self.timezone
+    }
+
+    /// Set where `resolve()` looks for references it cannot find in the data
+    pub fn with_reference_resolver(mut self, resolver: Option<ReferenceSource>) -> Self {
+        self.reference_resolver = resolver;
+        self
+    }
+
+    /// Where `resolve()` looks for references it cannot find in the data
+    pub fn reference_resolver(&self) -> Option<ReferenceSource> {
+        self.reference_resolver.clone()
+    }
+
+    /// Set how many Bundle lookups `resolve()` remembers per evaluation, 0 to
+    /// search the Bundle every time
+    pub fn with_resolution_cache_size(mut self, size: usize) -> Self {
+        self.resolution_cache_size = size;
+        self
+    }
+
+    /// How many Bundle lookups `resolve()` remembers per evaluation
+    pub fn resolution_cache_size(&self) -> usize {
+        self.resolution_cache_size
+    }
+
+    /// Make `value` available to every evaluation as `%name`
+    pub fn set_variable(&mut self, name: impl Into<String>, value: FhirPathValue) {
+        Arc::make_mut(&mut self.variables).insert(name.into(), value);
+    }
+
+    /// Replace the `%` variables every evaluation starts with
+    pub fn with_variables(mut self, variables: Arc<VarMap>) -> Self {
+        self.variables = variables;
+        self
+    }
+
+    /// The `%` variables every evaluation starts with
+    pub fn variables(&self) -> Arc<VarMap> {
+        self.variables.clone()
+    }
+
+    /// Create the context for a new evaluation of `input`, fixing the time
+    /// `now()` returns for the rest of it
+    fn new_context(&self, input: FhirPathValue) -> EvaluationContext {
+        let mut context =
+            EvaluationContext::new(input, self.functions.clone(), self.operators.clone());
+        context.now = match self.timezone {
+            Some(timezone) => Utc::now().with_timezone(&timezone),
+            None => Local::now().fixed_offset(),
+        };
+        context.reference_resolver = self.reference_resolver.clone();
+        context.resolution_cache = NonZeroUsize::new(self.resolution_cache_size)
+            .map(|size| Arc::new(ResolutionCache::new(size)));
+        for (name, value) in self.variables.iter() {
+            context.set_variable(name.clone(), value.clone());
+        }
+        context
+    }
+
     /// Extract a type name from an expression node (for handling 'is' function arguments)
     /// Returns the full dotted path as a string for identifiers and path expressions
-    fn extract_type_name(&self, expr: &ExpressionNode) -> Option<String> {
+    pub(super) fn extract_type_name(&self, expr: &ExpressionNode) -> Option<String> {
         match expr {
             ExpressionNode::Identifier(name) => Some(name.clone()),
             ExpressionNode::Path { base, path } => {
@@ -97,7 +204,27 @@ impl FhirPathEngine {
         }
 
         // Use traditional AST interpretation (simple expressions or VM fallback)
-        self.evaluate_traditional_async(expression, input).await
+        self.evaluate_traditional_async(expression, self.new_context(input))
+            .await
+    }
+
+    /// Evaluate an FHIRPath expression against input data, sending the
+    /// non-fatal warnings raised along the way to `warnings` and recording
+    /// where an error arose in `error_trace`
+    ///
+    /// The bytecode VM neither raises warnings nor records errors, so it is
+    /// skipped.
+    pub async fn evaluate_reporting(
+        &self,
+        expression: &ExpressionNode,
+        input: FhirPathValue,
+        warnings: Option<WarningSink>,
+        error_trace: Option<ErrorTrace>,
+    ) -> EvaluationResult<FhirPathValue> {
+        let mut context = self.new_context(input);
+        context.warnings = warnings;
+        context.error_trace = error_trace;
+        self.evaluate_traditional_async(expression, context).await
     }
 
     /// Async version of evaluate - supports async function calls
@@ -121,7 +248,8 @@ impl FhirPathEngine {
         }
 
         // Use traditional AST interpretation with async support
-        self.evaluate_traditional_async(expression, input).await
+        self.evaluate_traditional_async(expression, self.new_context(input))
+            .await
     }
 
     /// Traditional AST interpretation (internal method)
@@ -130,7 +258,7 @@ impl FhirPathEngine {
         expression: &ExpressionNode,
         input: FhirPathValue,
     ) -> EvaluationResult<FhirPathValue> {
-        let context = EvaluationContext::new(input, self.functions.clone(), self.operators.clone());
+        let context = self.new_context(input);
 
         // Check if expression needs variable scoping - if so, use threaded evaluation
         if self.needs_variable_scoping(expression) {
@@ -145,10 +273,8 @@ impl FhirPathEngine {
     async fn evaluate_traditional_async(
         &self,
         expression: &ExpressionNode,
-        input: FhirPathValue,
+        context: EvaluationContext,
     ) -> EvaluationResult<FhirPathValue> {
-        let context = EvaluationContext::new(input, self.functions.clone(), self.operators.clone());
-
         // Check if expression needs variable scoping - if so, use threaded evaluation
         if self.needs_variable_scoping(expression) {
             let (result, _) = self
@@ -209,23 +335,7 @@ impl FhirPathEngine {
                 let name_value = self
                     .evaluate_with_context(&data.args[0], &define_context)
                     .await?;
-                let var_name = match name_value {
-                    FhirPathValue::String(name) => name,
-                    FhirPathValue::Collection(items) if items.len() == 1 => match items.get(0) {
-                        Some(FhirPathValue::String(name)) => name.clone(),
-                        _ => {
-                            return Err(EvaluationError::InvalidOperation {
-                                message: "defineVariable first argument must be a string"
-                                    .to_string(),
-                            });
-                        }
-                    },
-                    _ => {
-                        return Err(EvaluationError::InvalidOperation {
-                            message: "defineVariable first argument must be a string".to_string(),
-                        });
-                    }
-                };
+                let var_name = self.definable_name(name_value, context)?;
 
                 let var_value = if data.args.len() == 2 {
                     self.evaluate_with_context(&data.args[1], &define_context)
@@ -249,6 +359,123 @@ impl FhirPathEngine {
         }
     }
 
+    /// Evaluate a navigation step or method call chain like
+    /// [`evaluate_with_context_threaded_async`](Self::evaluate_with_context_threaded_async),
+    /// also returning the `%resource` of each item of the result
+    ///
+    /// An item that is itself a resource, such as a Bundle entry's `resource`
+    /// or a `contained` element, is the `%resource` of everything reached from
+    /// it, so in `entry.resource.id.where(%resource.id = $this)` each id is
+    /// compared with the entry resource holding it. Navigating into a resource
+    /// returned by a function such as `resolve()`, which is not part of the
+    /// data, keeps the `%resource` it was reached from.
+    fn evaluate_focus_async<'a>(
+        &'a self,
+        expression: &'a ExpressionNode,
+        context: EvaluationContext,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = EvaluationResult<Focus>> + Send + 'a>>
+    {
+        Box::pin(async move {
+            let error_trace = context.error_trace.clone();
+            let result = async move {
+                match expression {
+                    ExpressionNode::Identifier(name) => {
+                        let (result, resources) = self.navigate(name, &context)?;
+                        Ok((result, resources, context))
+                    }
+
+                    ExpressionNode::Path { base, path } => {
+                        let (base_value, base_resources, updated_context) =
+                            self.evaluate_focus_async(base, context).await?;
+                        let mut path_context = updated_context.with_input(base_value);
+                        path_context.input_resources = base_resources;
+                        let (result, resources) = self.navigate(path, &path_context)?;
+                        Ok((result, resources, updated_context))
+                    }
+
+                    ExpressionNode::MethodCall(data) if data.method != "defineVariable" => {
+                        if let Some(result) = self
+                            .evaluate_lazy_input(&data.base, &data.method, &data.args, &context)
+                            .await?
+                        {
+                            return Ok((result, None, context));
+                        }
+
+                        let (base_value, base_resources, updated_context) =
+                            self.evaluate_focus_async(&data.base, context).await?;
+                        let mut method_context = updated_context.with_input(base_value);
+                        method_context.input_resources = base_resources;
+                        let result = self
+                            .evaluate_method_call_direct_async(
+                                &data.method,
+                                &data.args,
+                                &method_context,
+                            )
+                            .await?;
+                        let resources = method_context.resources_kept_in(&result);
+                        Ok((result, resources, updated_context))
+                    }
+
+                    _ => {
+                        let (result, updated_context) = self
+                            .evaluate_with_context_threaded_async(expression, context)
+                            .await?;
+                        Ok((result, None, updated_context))
+                    }
+                }
+            }
+            .await;
+            if result.is_err()
+                && let Some(trace) = &error_trace
+            {
+                trace.record(expression);
+            }
+            result
+        })
+    }
+
+    /// Navigate to the element `name` of the input, returning the `%resource`
+    /// of each item reached
+    fn navigate(
+        &self,
+        name: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<(FhirPathValue, Option<Arc<[FhirPathValue]>>)> {
+        // Namespaces and single items are navigated as a whole
+        let items = input_items(&context.input);
+        if matches!(name, "FHIR" | "System") || items.len() <= 1 {
+            let result = self.evaluate_identifier(name, context)?;
+            let resource = items
+                .first()
+                .map_or(&context.resource, |item| context.resource_of(0, item));
+            let resources = input_items(&result)
+                .iter()
+                .map(|item| if is_resource(item) { item } else { resource }.clone())
+                .collect();
+            return Ok((result, Some(resources)));
+        }
+
+        let mut results = Vec::new();
+        let mut resources = Vec::new();
+        for (index, item) in items.iter().enumerate() {
+            let item_context = context.with_input_item(index, item.clone());
+            // Like navigation over a whole collection, items without the
+            // element are skipped
+            let Ok(value) = self.evaluate_identifier(name, &item_context) else {
+                continue;
+            };
+            for reached in input_items(&value) {
+                resources.push(if is_resource(reached) {
+                    reached.clone()
+                } else {
+                    item_context.resource.clone()
+                });
+                results.push(reached.clone());
+            }
+        }
+        Ok((FhirPathValue::collection(results), Some(resources.into())))
+    }
+
     /// Evaluate with explicit context and return both result and updated context (async version)
     pub fn evaluate_with_context_threaded_async<'a>(
         &'a self,
@@ -257,214 +484,135 @@ impl FhirPathEngine {
     ) -> std::pin::Pin<
         Box<
             dyn std::future::Future<Output = EvaluationResult<(FhirPathValue, EvaluationContext)>>
+                + Send
                 + 'a,
         >,
     > {
         Box::pin(async move {
-            match expression {
-                ExpressionNode::MethodCall(data) if data.method == "defineVariable" => {
-                    // Special handling for defineVariable to thread context properly
-                    let (base_value, mut updated_context) = self
-                        .evaluate_with_context_threaded_async(&data.base, context)
-                        .await?;
-
-                    if data.args.is_empty() || data.args.len() > 2 {
-                        return Err(EvaluationError::InvalidOperation {
-                            message:
-                                "defineVariable requires 1-2 arguments: name and optional value"
-                                    .to_string(),
-                        });
-                    }
-
-                    // Create context with base value as input
-                    let define_context = updated_context.with_input(base_value.clone());
-
-                    // Evaluate variable name and value
-                    let (name_value, _) = self
-                        .evaluate_with_context_threaded_async(&data.args[0], define_context.clone())
-                        .await?;
-                    let var_name = match name_value {
-                        FhirPathValue::String(name) => name,
-                        FhirPathValue::Collection(items) if items.len() == 1 => {
-                            match items.get(0) {
-                                Some(FhirPathValue::String(name)) => name.clone(),
-                                _ => {
-                                    return Err(EvaluationError::InvalidOperation {
-                                        message: "defineVariable first argument must be a string"
-                                            .to_string(),
-                                    });
-                                }
-                            }
-                        }
-                        _ => {
+            let error_trace = context.error_trace.clone();
+            let result = async move {
+                match expression {
+                    ExpressionNode::MethodCall(data) if data.method == "defineVariable" => {
+                        // Special handling for defineVariable to thread context properly
+                        let (base_value, mut updated_context) = self
+                            .evaluate_with_context_threaded_async(&data.base, context)
+                            .await?;
+
+                        if data.args.is_empty() || data.args.len() > 2 {
                             return Err(EvaluationError::InvalidOperation {
-                                message: "defineVariable first argument must be a string"
-                                    .to_string(),
+                                message:
+                                    "defineVariable requires 1-2 arguments: name and optional value"
+                                        .to_string(),
                             });
                         }
-                    };
-
-                    // Check for protected system variables
-                    if self.is_protected_variable(&var_name) {
-                        return Err(EvaluationError::InvalidOperation {
-                            message: format!("Cannot redefine system variable '{var_name}'"),
-                        });
-                    }
-
-                    let (var_value, _) = if data.args.len() == 2 {
-                        self.evaluate_with_context_threaded_async(&data.args[1], define_context)
-                            .await?
-                    } else {
-                        // If no value provided, use current base value
-                        (base_value.clone(), updated_context.clone())
-                    };
-
-                    // Store the variable in the context
-                    updated_context.set_variable(var_name.to_string(), var_value.clone());
-
-                    // Return the input value with updated context (defineVariable returns its input, not the variable value)
-                    Ok((base_value, updated_context))
-                }
 
-                ExpressionNode::FunctionCall(data) if data.name == "defineVariable" => {
-                    // Special handling for defineVariable function call
-                    if data.args.is_empty() || data.args.len() > 2 {
-                        return Err(EvaluationError::InvalidOperation {
-                            message:
-                                "defineVariable requires 1-2 arguments: name and optional value"
-                                    .to_string(),
-                        });
+                        // Create context with base value as input
+                        let define_context = updated_context.with_input(base_value.clone());
+
+                        // Evaluate variable name and value
+                        let (name_value, _) = self
+                            .evaluate_with_context_threaded_async(
+                                &data.args[0],
+                                define_context.clone(),
+                            )
+                            .await?;
+                        let var_name = self.definable_name(name_value, &updated_context)?;
+
+                        let (var_value, _) = if data.args.len() == 2 {
+                            self.evaluate_with_context_threaded_async(&data.args[1], define_context)
+                                .await?
+                        } else {
+                            // If no value provided, use current base value
+                            (base_value.clone(), updated_context.clone())
+                        };
+
+                        // Store the variable in the context
+                        updated_context.set_variable(var_name.to_string(), var_value.clone());
+
+                        // Return the input value with updated context (defineVariable returns its input, not the variable value)
+                        Ok((base_value, updated_context))
                     }
 
-                    // Evaluate variable name and value
-                    let (name_value, _) = self
-                        .evaluate_with_context_threaded_async(&data.args[0], context.clone())
-                        .await?;
-                    let var_name = match name_value {
-                        FhirPathValue::String(name) => name,
-                        FhirPathValue::Collection(items) if items.len() == 1 => {
-                            match items.get(0) {
-                                Some(FhirPathValue::String(name)) => name.clone(),
-                                _ => {
-                                    return Err(EvaluationError::InvalidOperation {
-                                        message: "defineVariable first argument must be a string"
-                                            .to_string(),
-                                    });
-                                }
-                            }
-                        }
-                        _ => {
+                    ExpressionNode::FunctionCall(data) if data.name == "defineVariable" => {
+                        // Special handling for defineVariable function call
+                        if data.args.is_empty() || data.args.len() > 2 {
                             return Err(EvaluationError::InvalidOperation {
-                                message: "defineVariable first argument must be a string"
-                                    .to_string(),
+                                message:
+                                    "defineVariable requires 1-2 arguments: name and optional value"
+                                        .to_string(),
                             });
                         }
-                    };
 
-                    // Check for protected system variables
-                    if self.is_protected_variable(&var_name) {
-                        return Err(EvaluationError::InvalidOperation {
-                            message: format!("Cannot redefine system variable '{var_name}'"),
-                        });
-                    }
-
-                    let (var_value, mut updated_context) = if data.args.len() == 2 {
-                        self.evaluate_with_context_threaded_async(&data.args[1], context.clone())
+                        // Evaluate variable name and value
+                        let (name_value, _) = self
+                            .evaluate_with_context_threaded_async(&data.args[0], context.clone())
+                            .await?;
+                        let var_name = self.definable_name(name_value, &context)?;
+
+                        let (var_value, mut updated_context) = if data.args.len() == 2 {
+                            self.evaluate_with_context_threaded_async(
+                                &data.args[1],
+                                context.clone(),
+                            )
                             .await?
-                    } else {
-                        // If no value provided, use current input
-                        (context.input.clone(), context.clone())
-                    };
-
-                    // Store the variable in the context
-                    updated_context.set_variable(var_name.to_string(), var_value.clone());
+                        } else {
+                            // If no value provided, use current input
+                            (context.input.clone(), context.clone())
+                        };
 
-                    // Return the input value with updated context (defineVariable returns its input)
-                    Ok((context.input.clone(), updated_context))
-                }
+                        // Store the variable in the context
+                        updated_context.set_variable(var_name.to_string(), var_value.clone());
 
-                ExpressionNode::Union { left, right } => {
-                    // For union operations, evaluate each side with isolated variable scopes
-                    // This prevents variables defined in one side from affecting the other
-                    let left_context = context.with_fresh_variable_scope();
-                    let right_context = context.with_fresh_variable_scope();
-
-                    let (left_val, _) = self
-                        .evaluate_with_context_threaded_async(left, left_context)
-                        .await?;
-                    let (right_val, _) = self
-                        .evaluate_with_context_threaded_async(right, right_context)
-                        .await?;
-
-                    let mut items = Vec::new();
-
-                    // Add items from left
-                    match left_val {
-                        FhirPathValue::Collection(left_items) => items.extend(left_items),
-                        FhirPathValue::Empty => {}
-                        other => items.push(other),
+                        // Return the input value with updated context (defineVariable returns its input)
+                        Ok((context.input.clone(), updated_context))
                     }
 
-                    // Add items from right, removing duplicates
-                    match right_val {
-                        FhirPathValue::Collection(right_items) => {
-                            for item in right_items {
-                                if !items.contains(&item) {
-                                    items.push(item);
-                                }
-                            }
-                        }
-                        FhirPathValue::Empty => {}
-                        other => {
-                            if !items.contains(&other) {
-                                items.push(other);
-                            }
-                        }
-                    }
+                    ExpressionNode::Union { left, right } => {
+                        // For union operations, evaluate each side with isolated variable scopes
+                        // This prevents variables defined in one side from affecting the other
+                        let left_context = context.with_fresh_variable_scope();
+                        let right_context = context.with_fresh_variable_scope();
 
-                    Ok((FhirPathValue::collection(items), context))
-                }
+                        let (left_val, _) = self
+                            .evaluate_with_context_threaded_async(left, left_context)
+                            .await?;
+                        let (right_val, _) = self
+                            .evaluate_with_context_threaded_async(right, right_context)
+                            .await?;
 
-                ExpressionNode::MethodCall(data) => {
-                    // For other method calls, thread context through base evaluation
-                    let (base_value, updated_context) = self
-                        .evaluate_with_context_threaded_async(&data.base, context)
-                        .await?;
-                    let method_context = updated_context.with_input(base_value);
-                    let result = self
-                        .evaluate_method_call_direct_async(
-                            &data.method,
-                            &data.args,
-                            &method_context,
-                        )
-                        .await?;
-                    Ok((result, updated_context))
-                }
+                        Ok((union_of(left_val, right_val), context))
+                    }
 
-                ExpressionNode::Path { base, path } => {
-                    // Thread context through path navigation
-                    let (base_value, updated_context) = self
-                        .evaluate_with_context_threaded_async(base, context)
-                        .await?;
-                    let path_context = updated_context.with_input(base_value);
-                    let result = self.evaluate_identifier(path, &path_context)?;
-                    Ok((result, updated_context))
-                }
+                    ExpressionNode::MethodCall(_) | ExpressionNode::Path { .. } => {
+                        // Thread context through the base, keeping track of the
+                        // resource each item was reached through
+                        let (result, _, updated_context) =
+                            self.evaluate_focus_async(expression, context).await?;
+                        Ok((result, updated_context))
+                    }
 
-                ExpressionNode::Variable(name) => {
-                    // Variable evaluation uses current context
-                    let result = self.evaluate_variable(name, &context)?;
-                    Ok((result, context))
-                }
+                    ExpressionNode::Variable(name) => {
+                        // Variable evaluation uses current context
+                        let result = self.evaluate_variable(name, &context)?;
+                        Ok((result, context))
+                    }
 
-                _ => {
-                    // For other expressions, use the old evaluation method and wrap the result
-                    let result = self
-                        .evaluate_with_context_old_async(expression, &context)
-                        .await?;
-                    Ok((result, context))
+                    _ => {
+                        // For other expressions, use the old evaluation method and wrap the result
+                        let result = self
+                            .evaluate_with_context_old_async(expression, &context)
+                            .await?;
+                        Ok((result, context))
+                    }
                 }
             }
+            .await;
+            if result.is_err()
+                && let Some(trace) = &error_trace
+            {
+                trace.record(expression);
+            }
+            result
         })
     }
 
@@ -493,30 +641,7 @@ impl FhirPathEngine {
                 // Evaluate variable name and value
                 let (name_value, _) =
                     self.evaluate_with_context_threaded(&data.args[0], define_context.clone())?;
-                let var_name = match name_value {
-                    FhirPathValue::String(name) => name,
-                    FhirPathValue::Collection(items) if items.len() == 1 => match items.get(0) {
-                        Some(FhirPathValue::String(name)) => name.clone(),
-                        _ => {
-                            return Err(EvaluationError::InvalidOperation {
-                                message: "defineVariable first argument must be a string"
-                                    .to_string(),
-                            });
-                        }
-                    },
-                    _ => {
-                        return Err(EvaluationError::InvalidOperation {
-                            message: "defineVariable first argument must be a string".to_string(),
-                        });
-                    }
-                };
-
-                // Check for protected system variables
-                if self.is_protected_variable(&var_name) {
-                    return Err(EvaluationError::InvalidOperation {
-                        message: format!("Cannot redefine system variable '{var_name}'"),
-                    });
-                }
+                let var_name = self.definable_name(name_value, &updated_context)?;
 
                 let (var_value, _) = if data.args.len() == 2 {
                     self.evaluate_with_context_threaded(&data.args[1], define_context)?
@@ -544,30 +669,7 @@ impl FhirPathEngine {
                 // Evaluate variable name and value
                 let (name_value, _) =
                     self.evaluate_with_context_threaded(&data.args[0], context.clone())?;
-                let var_name = match name_value {
-                    FhirPathValue::String(name) => name,
-                    FhirPathValue::Collection(items) if items.len() == 1 => match items.get(0) {
-                        Some(FhirPathValue::String(name)) => name.clone(),
-                        _ => {
-                            return Err(EvaluationError::InvalidOperation {
-                                message: "defineVariable first argument must be a string"
-                                    .to_string(),
-                            });
-                        }
-                    },
-                    _ => {
-                        return Err(EvaluationError::InvalidOperation {
-                            message: "defineVariable first argument must be a string".to_string(),
-                        });
-                    }
-                };
-
-                // Check for protected system variables
-                if self.is_protected_variable(&var_name) {
-                    return Err(EvaluationError::InvalidOperation {
-                        message: format!("Cannot redefine system variable '{var_name}'"),
-                    });
-                }
+                let var_name = self.definable_name(name_value, &context)?;
 
                 let (var_value, mut updated_context) = if data.args.len() == 2 {
                     self.evaluate_with_context_threaded(&data.args[1], context.clone())?
@@ -592,33 +694,7 @@ impl FhirPathEngine {
                 let (left_val, _) = self.evaluate_with_context_threaded(left, left_context)?;
                 let (right_val, _) = self.evaluate_with_context_threaded(right, right_context)?;
 
-                let mut items = Vec::new();
-
-                // Add items from left
-                match left_val {
-                    FhirPathValue::Collection(left_items) => items.extend(left_items),
-                    FhirPathValue::Empty => {}
-                    other => items.push(other),
-                }
-
-                // Add items from right, removing duplicates
-                match right_val {
-                    FhirPathValue::Collection(right_items) => {
-                        for item in right_items {
-                            if !items.contains(&item) {
-                                items.push(item);
-                            }
-                        }
-                    }
-                    FhirPathValue::Empty => {}
-                    other => {
-                        if !items.contains(&other) {
-                            items.push(other);
-                        }
-                    }
-                }
-
-                Ok((FhirPathValue::collection(items), context))
+                Ok((union_of(left_val, right_val), context))
             }
 
             ExpressionNode::MethodCall(data) => {
@@ -659,247 +735,187 @@ impl FhirPathEngine {
         &'a self,
         expression: &'a ExpressionNode,
         context: &'a EvaluationContext,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = EvaluationResult<FhirPathValue>> + 'a>>
-    {
+    ) -> std::pin::Pin<
+        Box<dyn std::future::Future<Output = EvaluationResult<FhirPathValue>> + Send + 'a>,
+    > {
         Box::pin(async move {
-            match expression {
-                ExpressionNode::Literal(literal) => self.evaluate_literal(literal),
+            let error_trace = context.error_trace.clone();
+            let result = async move {
+                match expression {
+                    ExpressionNode::Literal(literal) => self.evaluate_literal(literal),
 
-                ExpressionNode::Identifier(name) => self.evaluate_identifier(name, context),
+                    ExpressionNode::Identifier(name) => self.evaluate_identifier(name, context),
 
-                ExpressionNode::Variable(name) => self.evaluate_variable(name, context),
+                    ExpressionNode::Variable(name) => self.evaluate_variable(name, context),
 
-                ExpressionNode::FunctionCall(data) => {
-                    self.evaluate_function_call_async(&data.name, &data.args, context)
-                        .await
-                }
+                    ExpressionNode::FunctionCall(data) => {
+                        self.evaluate_function_call_async(&data.name, &data.args, context)
+                            .await
+                    }
 
-                ExpressionNode::MethodCall(data) => {
-                    self.evaluate_method_call_async(&data.base, &data.method, &data.args, context)
+                    ExpressionNode::MethodCall(data) => {
+                        self.evaluate_method_call_async(
+                            &data.base,
+                            &data.method,
+                            &data.args,
+                            context,
+                        )
                         .await
-                }
+                    }
 
-                ExpressionNode::BinaryOp(data) => {
-                    self.evaluate_binary_op_async(&data.op, &data.left, &data.right, context)
-                        .await
-                }
+                    ExpressionNode::BinaryOp(data) => {
+                        self.evaluate_binary_op_async(&data.op, &data.left, &data.right, context)
+                            .await
+                    }
 
-                ExpressionNode::UnaryOp { op, operand } => {
-                    self.evaluate_unary_op_async(op, operand, context).await
-                }
+                    ExpressionNode::UnaryOp { op, operand } => {
+                        self.evaluate_unary_op_async(op, operand, context).await
+                    }
 
-                ExpressionNode::Path { base, path } => {
-                    let base_val = self.evaluate_with_context(base, context).await?;
-                    let new_context = context.with_input(base_val);
-                    self.evaluate_identifier(path, &new_context)
-                }
+                    ExpressionNode::Path { base, path } => {
+                        let base_val = self.evaluate_with_context(base, context).await?;
+                        let new_context = context.with_input(base_val);
+                        self.evaluate_identifier(path, &new_context)
+                    }
 
-                ExpressionNode::Index { base, index } => {
-                    let base_val = self.evaluate_with_context(base, context).await?;
-                    let index_val = self.evaluate_with_context(index, context).await?;
-
-                    let index_num = match &index_val {
-                        FhirPathValue::Integer(i) => *i,
-                        FhirPathValue::Collection(items) if items.len() == 1 => {
-                            match items.get(0) {
-                                Some(FhirPathValue::Integer(i)) => *i,
-                                _ => {
-                                    return Err(EvaluationError::TypeError {
-                                        expected: "Integer".to_string(),
-                                        actual: index_val.type_name().to_string(),
-                                    });
+                    ExpressionNode::Index { base, index } => {
+                        let base_val = self.evaluate_with_context(base, context).await?;
+                        let index_val = self.evaluate_with_context(index, context).await?;
+
+                        let index_num = match &index_val {
+                            FhirPathValue::Integer(i) => *i,
+                            FhirPathValue::Collection(items) if items.len() == 1 => {
+                                match items.get(0) {
+                                    Some(FhirPathValue::Integer(i)) => *i,
+                                    _ => {
+                                        return Err(EvaluationError::TypeError {
+                                            expected: "Integer".to_string(),
+                                            actual: index_val.type_name().to_string(),
+                                        });
+                                    }
                                 }
                             }
-                        }
-                        _ => {
-                            return Err(EvaluationError::TypeError {
-                                expected: "Integer".to_string(),
-                                actual: index_val.type_name().to_string(),
-                            });
-                        }
-                    };
-
-                    match base_val {
-                        FhirPathValue::Collection(items) => {
-                            // Handle negative indexing (from end of collection)
-                            let effective_index = if index_num < 0 {
-                                let len = items.len() as i64;
-                                len + index_num
-                            } else {
-                                index_num
-                            };
-
-                            // Return empty collection for out-of-bounds access (FHIRPath spec)
-                            if effective_index < 0 || effective_index as usize >= items.len() {
-                                Ok(FhirPathValue::Collection(vec![].into()))
-                            } else {
-                                Ok(items.get(effective_index as usize).unwrap().clone())
+                            _ => {
+                                return Err(EvaluationError::TypeError {
+                                    expected: "Integer".to_string(),
+                                    actual: index_val.type_name().to_string(),
+                                });
                             }
-                        }
-                        _ => {
-                            // Single item is treated as single-item collection for indexing
-                            let single_item_collection = [base_val];
-
-                            // Handle negative indexing
-                            let effective_index = if index_num < 0 {
-                                1 + index_num // Length is 1 for single item
-                            } else {
-                                index_num
-                            };
-
-                            // Return empty collection for out-of-bounds access
-                            if effective_index < 0 || effective_index as usize >= 1 {
-                                Ok(FhirPathValue::Collection(vec![].into()))
-                            } else {
-                                Ok(single_item_collection
-                                    .get(effective_index as usize)
-                                    .unwrap()
-                                    .clone())
-                            }
-                        }
+                        };
+
+                        Ok(item_at(base_val, index_num))
                     }
-                }
 
-                ExpressionNode::Filter { base, condition } => {
-                    let base_val = self.evaluate_with_context(base, context).await?;
+                    ExpressionNode::Filter { base, condition } => {
+                        let base_val = self.evaluate_with_context(base, context).await?;
 
-                    match base_val {
-                        FhirPathValue::Collection(items) => {
-                            let mut results = Vec::new();
+                        match base_val {
+                            FhirPathValue::Collection(items) => {
+                                let mut results = Vec::new();
 
-                            for item in items {
-                                let item_context = context.with_input(item.clone());
-                                let condition_result =
-                                    self.evaluate_with_context(condition, &item_context).await?;
+                                for item in items {
+                                    let item_context = context.with_input(item.clone());
+                                    let condition_result = self
+                                        .evaluate_with_context(condition, &item_context)
+                                        .await?;
 
-                                if let FhirPathValue::Boolean(true) = condition_result {
-                                    results.push(item)
+                                    if let FhirPathValue::Boolean(true) = condition_result {
+                                        results.push(item)
+                                    }
                                 }
+
+                                Ok(FhirPathValue::collection(results))
                             }
+                            other => {
+                                // For non-collections, treat as single-item collection
+                                let item_context = context.with_input(other.clone());
+                                let condition_result =
+                                    self.evaluate_with_context(condition, &item_context).await?;
 
-                            Ok(FhirPathValue::collection(results))
-                        }
-                        other => {
-                            // For non-collections, treat as single-item collection
-                            let item_context = context.with_input(other.clone());
-                            let condition_result =
-                                self.evaluate_with_context(condition, &item_context).await?;
-
-                            match condition_result {
-                                FhirPathValue::Boolean(true) => Ok(other),
-                                _ => Ok(FhirPathValue::Empty),
+                                match condition_result {
+                                    FhirPathValue::Boolean(true) => Ok(other),
+                                    _ => Ok(FhirPathValue::Empty),
+                                }
                             }
                         }
                     }
-                }
 
-                ExpressionNode::Union { left, right } => {
-                    // For union operations, each side should be evaluated with a fresh variable context
-                    // to ensure proper variable scoping as per FHIRPath specification
-                    let left_context = context.with_fresh_variable_scope();
-                    let right_context = context.with_fresh_variable_scope();
+                    ExpressionNode::Union { left, right } => {
+                        // For union operations, each side should be evaluated with a fresh variable context
+                        // to ensure proper variable scoping as per FHIRPath specification
+                        let left_context = context.with_fresh_variable_scope();
+                        let right_context = context.with_fresh_variable_scope();
 
-                    let left_val = self.evaluate_with_context(left, &left_context).await?;
-                    let right_val = self.evaluate_with_context(right, &right_context).await?;
+                        let left_val = self.evaluate_with_context(left, &left_context).await?;
+                        let right_val = self.evaluate_with_context(right, &right_context).await?;
 
-                    let mut items = Vec::new();
-
-                    // Add items from left
-                    match left_val {
-                        FhirPathValue::Collection(left_items) => items.extend(left_items),
-                        FhirPathValue::Empty => {}
-                        other => items.push(other),
+                        Ok(union_of(left_val, right_val))
                     }
 
-                    // Add items from right, removing duplicates
-                    match right_val {
-                        FhirPathValue::Collection(right_items) => {
-                            for item in right_items {
-                                if !items.contains(&item) {
-                                    items.push(item);
+                    ExpressionNode::TypeCheck {
+                        expression,
+                        type_name,
+                    } => {
+                        let value = self.evaluate_with_context(expression, context).await?;
+
+                        let matches = match &value {
+                            FhirPathValue::Collection(items) => {
+                                // For collections, check if it has exactly one item of the specified type
+                                if items.len() == 1 {
+                                    is_value_of_type(items.get(0).unwrap(), type_name)
+                                } else {
+                                    false
                                 }
                             }
-                        }
-                        FhirPathValue::Empty => {}
-                        other => {
-                            if !items.contains(&other) {
-                                items.push(other);
-                            }
-                        }
-                    }
-
-                    Ok(FhirPathValue::collection(items))
-                }
-
-                ExpressionNode::TypeCheck {
-                    expression,
-                    type_name,
-                } => {
-                    let value = self.evaluate_with_context(expression, context).await?;
-
-                    let matches = match &value {
-                        FhirPathValue::Collection(items) => {
-                            // For collections, check if it has exactly one item of the specified type
-                            if items.len() == 1 {
-                                check_value_type(items.get(0).unwrap(), type_name)
-                            } else {
-                                false
-                            }
-                        }
-                        single_value => check_value_type(single_value, type_name),
-                    };
+                            single_value => is_value_of_type(single_value, type_name),
+                        };
 
-                    Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
-                        matches,
-                    )]))
-                }
+                        Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
+                            matches,
+                        )]))
+                    }
 
-                ExpressionNode::TypeCast {
-                    expression,
-                    type_name,
-                } => {
-                    let value = self.evaluate_with_context(expression, context).await?;
-
-                    // Basic type casting - can be enhanced later
-                    match (type_name.as_str(), &value) {
-                        ("String", _) => {
-                            if let Some(s) = value.to_string_value() {
-                                Ok(FhirPathValue::collection(vec![FhirPathValue::String(
-                                    s.into(),
-                                )]))
-                            } else {
-                                Ok(FhirPathValue::collection(vec![]))
-                            }
-                        }
-                        _ => Ok(FhirPathValue::collection(vec![value])), // For now, just return the value as-is
+                    ExpressionNode::TypeCast {
+                        expression,
+                        type_name,
+                    } => {
+                        let value = self.evaluate_with_context(expression, context).await?;
+                        Ok(cast_value(value, type_name))
                     }
-                }
 
-                ExpressionNode::Lambda(data) => {
-                    // Lambda expressions are context-dependent
-                    // For now, evaluate body directly
-                    self.evaluate_with_context_old_async(&data.body, context)
-                        .await
-                }
+                    ExpressionNode::Lambda(data) => {
+                        // Lambda expressions are context-dependent
+                        // For now, evaluate body directly
+                        self.evaluate_with_context_old_async(&data.body, context)
+                            .await
+                    }
 
-                ExpressionNode::Conditional(data) => {
-                    let condition_val =
-                        self.evaluate_with_context(&data.condition, context).await?;
+                    ExpressionNode::Conditional(data) => {
+                        let condition_val =
+                            self.evaluate_with_context(&data.condition, context).await?;
 
-                    match condition_val {
-                        FhirPathValue::Boolean(true) => {
-                            self.evaluate_with_context(&data.then_expr, context).await
-                        }
-                        _ => {
-                            if let Some(else_branch) = data.else_expr.as_deref() {
-                                self.evaluate_with_context(else_branch, context).await
-                            } else {
-                                Ok(FhirPathValue::collection(vec![]))
+                        match condition_val {
+                            FhirPathValue::Boolean(true) => {
+                                self.evaluate_with_context(&data.then_expr, context).await
+                            }
+                            _ => {
+                                if let Some(else_branch) = data.else_expr.as_deref() {
+                                    self.evaluate_with_context(else_branch, context).await
+                                } else {
+                                    Ok(FhirPathValue::collection(vec![]))
+                                }
                             }
                         }
                     }
                 }
             }
+            .await;
+            if result.is_err()
+                && let Some(trace) = &error_trace
+            {
+                trace.record(expression);
+            }
+            result
         })
     }
 
@@ -1047,24 +1063,24 @@ impl FhirPathEngine {
             },
             LiteralValue::String(s) => FhirPathValue::String(s.clone().into()),
             LiteralValue::Date(s) => match parse_fhirpath_date(s) {
-                Ok(date) => FhirPathValue::Date(date),
-                Err(_) => {
+                Some(date) => FhirPathValue::Date(date),
+                None => {
                     return Err(EvaluationError::InvalidOperation {
                         message: format!("Invalid date literal: {s}"),
                     });
                 }
             },
             LiteralValue::DateTime(s) => match parse_fhirpath_datetime(s) {
-                Ok(datetime) => FhirPathValue::DateTime(datetime),
-                Err(_) => {
+                Some(datetime) => FhirPathValue::DateTime(datetime),
+                None => {
                     return Err(EvaluationError::InvalidOperation {
                         message: format!("Invalid datetime literal: {s}"),
                     });
                 }
             },
             LiteralValue::Time(s) => match parse_fhirpath_time(s) {
-                Ok(time) => FhirPathValue::Time(time),
-                Err(_) => {
+                Some(time) => FhirPathValue::Time(time),
+                None => {
                     return Err(EvaluationError::InvalidOperation {
                         message: format!("Invalid time literal: {s}"),
                     });
@@ -1121,15 +1137,19 @@ impl FhirPathEngine {
                 }
 
                 // Otherwise try to get the property
-                match resource.get_property(name) {
-                    Some(value) => {
+                match resource.get_typed_property(name) {
+                    Some((value, choice_type)) => {
                         // Convert values properly handling arrays and objects
                         match value {
                             serde_json::Value::Object(_) => {
                                 // Wrap JSON objects as FhirResource so functions like resolve() can inspect fields
-                                Ok(FhirPathValue::Resource(Arc::new(
-                                    crate::model::FhirResource::from_json(value.clone()),
-                                )))
+                                let mut element =
+                                    crate::model::FhirResource::from_json(value.clone());
+                                if let Some(choice_type) = choice_type {
+                                    // A choice element's key names its type, e.g. valueQuantity
+                                    element = element.with_element_type(choice_type);
+                                }
+                                Ok(FhirPathValue::Resource(Arc::new(element)))
                             }
                             serde_json::Value::Array(arr) => {
                                 // Convert array elements, wrapping objects as FhirResources
@@ -1240,7 +1260,11 @@ impl FhirPathEngine {
                     Ok(context.root.clone())
                 }
             }
-            "$$" | "$resource" | "resource" => Ok(context.root.clone()),
+            // The resource the current node was reached through, e.g. the entry
+            // resource while iterating over a Bundle's entries
+            "$$" | "$resource" | "resource" => Ok(context.resource.clone()),
+            // The evaluation input, which is also the outermost container
+            "context" | "$context" | "rootResource" | "$rootResource" => Ok(context.root.clone()),
             "$total" | "total" => {
                 // $total is used in aggregate functions - check for it in variables
                 if let Some(value) = context.get_variable("total") {
@@ -1263,9 +1287,14 @@ impl FhirPathEngine {
                 // Environment variables parsed as Variable("name") where % is stripped by parser
                 if let Some(value) = context.get_variable(name) {
                     Ok(value.clone())
+                } else if let Some(url) = code_system_url(name) {
+                    Ok(FhirPathValue::String(url.into()))
+                } else if let Some(url) = expand_abbreviated_url(name) {
+                    Ok(FhirPathValue::String(url.into()))
                 } else {
-                    // Variable not found - return empty per FHIRPath spec
-                    Ok(FhirPathValue::Empty)
+                    Err(EvaluationError::VariableNotFound {
+                        name: format!("%{name}"),
+                    })
                 }
             }
         }
@@ -1279,17 +1308,14 @@ impl FhirPathEngine {
         context: &EvaluationContext,
     ) -> EvaluationResult<FhirPathValue> {
         // Get function from registry
-        let function =
-            context
-                .functions
-                .get(name)
-                .ok_or_else(|| EvaluationError::InvalidOperation {
-                    message: format!("Unknown function: {name}"),
-                })?;
-
-        // Check if this is a lambda function that needs special evaluation
-        if is_lambda_function(name) {
-            // For lambda functions, we don't evaluate arguments first - we pass the expressions
+        let function = context
+            .functions
+            .get(name)
+            .ok_or_else(|| unknown_function(&context.functions, name))?;
+        context.functions.validate_call_shape(name, args)?;
+
+        // Functions with lazy parameters get those arguments as expressions
+        if function.signature().has_lazy_parameters() {
             return self
                 .evaluate_lambda_function_async(function, args, context)
                 .await;
@@ -1298,21 +1324,16 @@ impl FhirPathEngine {
         // For regular functions, evaluate arguments normally
         let mut arg_values = Vec::new();
         for arg in args {
-            let value = self.evaluate_with_context(arg, context).await?;
-
-            // Special handling for type-related functions: if an argument evaluates to Empty,
-            // check if it represents a type name (identifier or dotted path) and treat as string literal
-            if (name == "is" || name == "as" || name == "ofType")
-                && matches!(value, FhirPathValue::Empty)
+            // Type-related functions take a type specifier: an identifier or dotted
+            // path is a type name, not navigation (`ofType(Patient)` must not pick up
+            // the Patient resources in the input)
+            if matches!(name, "is" | "as" | "ofType")
+                && let Some(type_name) = self.extract_type_name(arg)
             {
-                if let Some(type_name) = self.extract_type_name(arg) {
-                    arg_values.push(FhirPathValue::String(type_name.into()));
-                } else {
-                    arg_values.push(value);
-                }
-            } else {
-                arg_values.push(value);
+                arg_values.push(FhirPathValue::String(type_name.into()));
+                continue;
             }
+            arg_values.push(self.evaluate_with_context(arg, context).await?);
         }
 
         // Unwrap single-item collections for function arguments
@@ -1320,12 +1341,7 @@ impl FhirPathEngine {
         let unwrapped_args = unwrap_function_arguments(arg_values);
 
         // Create a compatible context for the function registry
-        let mut registry_context =
-            crate::registry::function::EvaluationContext::new(context.input.clone());
-        registry_context
-            .variables
-            .extend(context.variable_scope.collect_all_variables());
-        registry_context.root = context.root.clone();
+        let registry_context = context.to_registry_context();
 
         // Evaluate function with async support
         let result = function
@@ -1342,17 +1358,13 @@ impl FhirPathEngine {
         context: &EvaluationContext,
     ) -> EvaluationResult<FhirPathValue> {
         // Get function from registry
-        let function =
-            context
-                .functions
-                .get(name)
-                .ok_or_else(|| EvaluationError::InvalidOperation {
-                    message: format!("Unknown function: {name}"),
-                })?;
+        let function = context
+            .functions
+            .get(name)
+            .ok_or_else(|| unknown_function(&context.functions, name))?;
 
-        // Check if this is a lambda function that needs special evaluation
-        if is_lambda_function(name) {
-            // For lambda functions, we don't evaluate arguments first - we pass the expressions
+        // Functions with lazy parameters get those arguments as expressions
+        if function.signature().has_lazy_parameters() {
             // Note: This sync function should not be used - prefer async version
             panic!("Sync lambda evaluation not supported - use async version");
         }
@@ -1360,21 +1372,16 @@ impl FhirPathEngine {
         // For regular functions, evaluate arguments normally
         let mut arg_values = Vec::new();
         for arg in args {
-            let value = self.evaluate_with_context_old(arg, context)?;
-
-            // Special handling for type-related functions: if an argument evaluates to Empty,
-            // check if it represents a type name (identifier or dotted path) and treat as string literal
-            if (name == "is" || name == "as" || name == "ofType")
-                && matches!(value, FhirPathValue::Empty)
+            // Type-related functions take a type specifier: an identifier or dotted
+            // path is a type name, not navigation (`ofType(Patient)` must not pick up
+            // the Patient resources in the input)
+            if matches!(name, "is" | "as" | "ofType")
+                && let Some(type_name) = self.extract_type_name(arg)
             {
-                if let Some(type_name) = self.extract_type_name(arg) {
-                    arg_values.push(FhirPathValue::String(type_name.into()));
-                } else {
-                    arg_values.push(value);
-                }
-            } else {
-                arg_values.push(value);
+                arg_values.push(FhirPathValue::String(type_name.into()));
+                continue;
             }
+            arg_values.push(self.evaluate_with_context_old(arg, context)?);
         }
 
         // Unwrap single-item collections for function arguments
@@ -1382,12 +1389,7 @@ impl FhirPathEngine {
         let unwrapped_args = unwrap_function_arguments(arg_values);
 
         // Create a compatible context for the function registry
-        let mut registry_context =
-            crate::registry::function::EvaluationContext::new(context.input.clone());
-        registry_context
-            .variables
-            .extend(context.variable_scope.collect_all_variables());
-        registry_context.root = context.root.clone();
+        let registry_context = context.to_registry_context();
 
         // Evaluate function
         let result = function.evaluate(&unwrapped_args, &registry_context)?;
@@ -1409,8 +1411,10 @@ impl FhirPathEngine {
             let context_clone = context.clone();
 
             Box::pin(async move {
-                // Create a new evaluation context with the item as input
-                let mut item_eval_context = context_clone.with_input(item_context_clone.clone());
+                // Evaluate against the item, in a new scope for defineVariable
+                let mut item_eval_context =
+                    context_clone.with_inherited_scope(item_context_clone.clone());
+                item_eval_context.enter_resource(&item_context_clone);
 
                 // Explicitly set $this to the current item for lambda functions
                 item_eval_context.set_variable("this".to_string(), item_context_clone.clone());
@@ -1434,131 +1438,109 @@ impl FhirPathEngine {
                                     crate::model::FhirPathValue,
                                     crate::registry::function::FunctionError,
                                 >,
-                            > + '_,
+                            > + Send
+                            + '_,
                     >,
                 >
         };
 
         // Create an enhanced async lambda evaluator that supports additional variables
-        let enhanced_evaluator = |expr: &ExpressionNode,
-                                  item_context: &FhirPathValue,
-                                  additional_vars: &VarMap| {
-            let expr_clone = expr.clone();
-            let item_context_clone = item_context.clone();
-            let additional_vars_clone = additional_vars.clone();
-            let self_clone = self.clone();
-            let context_clone = context.clone();
+        let enhanced_evaluator =
+            |expr: &ExpressionNode, item_context: &FhirPathValue, additional_vars: &VarMap| {
+                let expr_clone = expr.clone();
+                let item_context_clone = item_context.clone();
+                let additional_vars_clone = additional_vars.clone();
+                let self_clone = self.clone();
+                let context_clone = context.clone();
+                let index = match additional_vars.get("index") {
+                    Some(FhirPathValue::Integer(index)) => Some(*index as usize),
+                    _ => None,
+                };
+                // The position of an input item tells the resource it was reached through
+                let resource = match index {
+                    Some(index) => context.resource_of(index, item_context).clone(),
+                    None => context.resource.clone(),
+                };
 
-            Box::pin(async move {
-                // Create a new evaluation context with the item as input
-                let mut item_eval_context = context_clone.with_input(item_context_clone.clone());
+                Box::pin(async move {
+                    // Evaluate against the item, in a new scope for defineVariable
+                    let mut item_eval_context =
+                        context_clone.with_inherited_scope(item_context_clone.clone());
+                    item_eval_context.resource = resource;
+                    item_eval_context.enter_resource(&item_context_clone);
 
-                // Explicitly set $this to the current item for lambda functions
-                item_eval_context.set_variable("this".to_string(), item_context_clone.clone());
+                    // Explicitly set $this to the current item for lambda functions
+                    item_eval_context.set_variable("this".to_string(), item_context_clone.clone());
 
-                // Inject additional variables into the context
-                for (name, value) in &additional_vars_clone {
-                    item_eval_context.set_variable(name.clone(), value.clone());
-                }
+                    // Inject additional variables into the context
+                    for (name, value) in &additional_vars_clone {
+                        item_eval_context.set_variable(name.clone(), value.clone());
+                    }
 
-                // Always use async evaluation
-                self_clone
-                    .evaluate_with_context_threaded_async(&expr_clone, item_eval_context)
-                    .await
-                    .map(|(result, _)| result)
-                    .map_err(
-                        |e| crate::registry::function::FunctionError::EvaluationError {
-                            name: "enhanced_lambda".to_string(),
-                            message: format!("Enhanced lambda evaluation error: {e}"),
-                        },
-                    )
-            })
-                as std::pin::Pin<
-                    Box<
-                        dyn std::future::Future<
-                                Output = Result<
-                                    crate::model::FhirPathValue,
-                                    crate::registry::function::FunctionError,
-                                >,
-                            > + '_,
-                    >,
-                >
+                    // Always use async evaluation
+                    let error_trace = item_eval_context.error_trace.clone();
+                    self_clone
+                        .evaluate_with_context_threaded_async(&expr_clone, item_eval_context)
+                        .await
+                        .map(|(result, _)| result)
+                        .map_err(|e| {
+                            if let (Some(trace), Some(index)) = (&error_trace, index) {
+                                trace.record_item(index);
+                            }
+                            crate::registry::function::FunctionError::EvaluationError {
+                                name: "enhanced_lambda".to_string(),
+                                message: format!("Enhanced lambda evaluation error: {e}"),
+                            }
+                        })
+                })
+                    as std::pin::Pin<
+                        Box<
+                            dyn std::future::Future<
+                                    Output = Result<
+                                        crate::model::FhirPathValue,
+                                        crate::registry::function::FunctionError,
+                                    >,
+                                > + Send
+                                + '_,
+                        >,
+                    >
+            };
+
+        // Functions declaring lazy parameters without a lambda implementation
+        // get all their arguments evaluated
+        let Some(lambda_function) = context.functions.get_lambda(function.name()) else {
+            return self
+                .evaluate_function_call_regular_async(function, args, context)
+                .await;
         };
 
-        // Try to cast to LambdaFunction and use lambda evaluation
-        use crate::registry::function::LambdaFunction;
+        // Eager arguments are evaluated against the input before the call
+        let signature = function.signature();
+        let mut eager_args = Vec::with_capacity(args.len());
+        for (index, arg) in args.iter().enumerate() {
+            eager_args.push(match signature.argument_evaluation(index) {
+                ArgumentEvaluation::Eager => {
+                    let value = self.evaluate_with_context(arg, context).await?;
+                    unwrap_function_arguments(vec![value]).pop()
+                }
+                ArgumentEvaluation::Lazy => None,
+            });
+        }
 
         // Create lambda evaluation context
-        let mut registry_context =
-            crate::registry::function::EvaluationContext::new(context.input.clone());
-        registry_context
-            .variables
-            .extend(context.variable_scope.collect_all_variables());
-        registry_context.root = context.root.clone();
+        let registry_context = context.to_registry_context();
 
         let lambda_context = crate::registry::function::LambdaEvaluationContext {
             context: &registry_context,
             evaluator: &evaluator,
             enhanced_evaluator: Some(&enhanced_evaluator),
+            eager_args: &eager_args,
         };
 
-        // Check if function implements LambdaFunction trait
-        // For now, we'll handle known lambda functions explicitly
-        match function.name() {
-            "all" => {
-                use crate::registry::functions::boolean::AllFunction;
-                let all_fn = AllFunction;
-                all_fn
-                    .evaluate_with_lambda(args, &lambda_context)
-                    .await
-                    .map_err(EvaluationError::Function)
-            }
-            "select" => {
-                use crate::registry::functions::filtering::SelectFunction;
-                let select_fn = SelectFunction;
-                select_fn
-                    .evaluate_with_lambda(args, &lambda_context)
-                    .await
-                    .map_err(EvaluationError::Function)
-            }
-            "where" => {
-                use crate::registry::functions::filtering::WhereFunction;
-                let where_fn = WhereFunction;
-                where_fn
-                    .evaluate_with_lambda(args, &lambda_context)
-                    .await
-                    .map_err(EvaluationError::Function)
-            }
-            "aggregate" => {
-                use crate::registry::functions::collection::AggregateFunction;
-                let aggregate_fn = AggregateFunction;
-                aggregate_fn
-                    .evaluate_with_lambda(args, &lambda_context)
-                    .await
-                    .map_err(EvaluationError::Function)
-            }
-            "sort" => {
-                use crate::registry::functions::collection::SortFunction;
-                let sort_fn = SortFunction;
-                sort_fn
-                    .evaluate_with_lambda(args, &lambda_context)
-                    .await
-                    .map_err(EvaluationError::Function)
-            }
-            "exists" => {
-                use crate::registry::functions::collection::ExistsFunction;
-                let exists_fn = ExistsFunction;
-                exists_fn
-                    .evaluate_with_lambda(args, &lambda_context)
-                    .await
-                    .map_err(EvaluationError::Function)
-            }
-            _ => {
-                // Fall back to regular function evaluation for other functions
-                self.evaluate_function_call_regular_async(function, args, context)
-                    .await
-            }
-        }
+        lambda_function
+            .evaluate_with_lambda(args, &lambda_context)
+            .await
+            .map_err(EvaluationError::Function)
     }
 
     /// Regular function evaluation for functions that don't support lambdas (async version)
@@ -1579,12 +1561,7 @@ impl FhirPathEngine {
         let unwrapped_args = unwrap_function_arguments(arg_values);
 
         // Create a compatible context for the function registry
-        let mut registry_context =
-            crate::registry::function::EvaluationContext::new(context.input.clone());
-        registry_context
-            .variables
-            .extend(context.variable_scope.collect_all_variables());
-        registry_context.root = context.root.clone();
+        let registry_context = context.to_registry_context();
 
         // Evaluate function with async support
         let result = function
@@ -1611,12 +1588,7 @@ impl FhirPathEngine {
         let unwrapped_args = unwrap_function_arguments(arg_values);
 
         // Create a compatible context for the function registry
-        let mut registry_context =
-            crate::registry::function::EvaluationContext::new(context.input.clone());
-        registry_context
-            .variables
-            .extend(context.variable_scope.collect_all_variables());
-        registry_context.root = context.root.clone();
+        let registry_context = context.to_registry_context();
 
         // Evaluate function
         let result = function.evaluate(&unwrapped_args, &registry_context)?;
@@ -1631,6 +1603,27 @@ impl FhirPathEngine {
         args: &[ExpressionNode],
         context: &EvaluationContext,
     ) -> EvaluationResult<FhirPathValue> {
+        // `exists().not()` is the same test as `empty()`
+        let (base, method) = match base {
+            ExpressionNode::MethodCall(data)
+                if method == "not"
+                    && args.is_empty()
+                    && data.method == "exists"
+                    && data.args.is_empty() =>
+            {
+                (&data.base, "empty")
+            }
+            _ => (base, method),
+        };
+
+        // `descendants()...all()` and the like stop reading nodes once decided
+        if let Some(result) = self
+            .evaluate_lazy_input(base, method, args, context)
+            .await?
+        {
+            return Ok(result);
+        }
+
         // Check if we need to thread context through the method call chain
         if self.needs_variable_scoping(base) {
             // Use threaded context evaluation to preserve variables from defineVariable calls
@@ -1673,7 +1666,7 @@ impl FhirPathEngine {
     }
 
     /// Evaluate a method call with already-evaluated base value (async version)
-    async fn evaluate_method_call_direct_async(
+    pub(super) async fn evaluate_method_call_direct_async(
         &self,
         method: &str,
         args: &[ExpressionNode],
@@ -1686,10 +1679,13 @@ impl FhirPathEngine {
             "allTrue" | "anyTrue" | "allFalse" | "anyFalse" | "aggregate" |
             "select" | "where" | "all" | "any" |  // Lambda functions should operate on collections
             "first" | "last" | "tail" | "skip" | "take" |  // Collection navigation functions
-            "join" | // String functions that operate on collections
-            "subsetOf" | "supersetOf" | "intersect" | "exclude" | "combine" | // Set operations
+            "join" | "substring" | "replaceMatches" | // String functions that operate on collections
+            "subsetOf" | "supersetOf" | "intersect" | "exclude" | "combine" | "union" | // Set operations
             "sort" | // Sort function should operate on the entire collection
-            "repeat" // Repeat function should operate on the entire collection
+            "repeat" | // Repeat function should operate on the entire collection
+            "trace" | // trace() logs the whole collection
+            "iif" | // iif() rejects inputs of more than one item
+            "resolve" // resolve() fetches unresolved references together
         );
 
         // For collection-level functions, always operate on the entire collection
@@ -1705,14 +1701,14 @@ impl FhirPathEngine {
                 let items_vec: Vec<FhirPathValue> = items.iter().cloned().collect();
                 // For single-element collections, unwrap and call method on the element
                 if items_vec.len() == 1 {
-                    let method_context = context.with_input(items_vec[0].clone());
+                    let method_context = context.with_input_item(0, items_vec[0].clone());
                     self.evaluate_function_call_async(method, args, &method_context)
                         .await
                 } else {
                     // For multi-element collections, call method on each element and collect results
                     let mut results = Vec::new();
-                    for item in items_vec {
-                        let method_context = context.with_input(item.clone());
+                    for (index, item) in items_vec.into_iter().enumerate() {
+                        let method_context = context.with_input_item(index, item);
                         match self
                             .evaluate_function_call_async(method, args, &method_context)
                             .await
@@ -1754,10 +1750,13 @@ impl FhirPathEngine {
             "allTrue" | "anyTrue" | "allFalse" | "anyFalse" | "aggregate" |
             "select" | "where" | "all" | "any" |  // Lambda functions should operate on collections
             "first" | "last" | "tail" | "skip" | "take" |  // Collection navigation functions
-            "join" | // String functions that operate on collections
-            "subsetOf" | "supersetOf" | "intersect" | "exclude" | "combine" | // Set operations
+            "join" | "substring" | "replaceMatches" | // String functions that operate on collections
+            "subsetOf" | "supersetOf" | "intersect" | "exclude" | "combine" | "union" | // Set operations
             "sort" | // Sort function should operate on the entire collection
-            "repeat" // Repeat function should operate on the entire collection
+            "repeat" | // Repeat function should operate on the entire collection
+            "trace" | // trace() logs the whole collection
+            "iif" | // iif() rejects inputs of more than one item
+            "resolve" // resolve() fetches unresolved references together
         );
 
         // For collection-level functions, always operate on the entire collection
@@ -1771,13 +1770,13 @@ impl FhirPathEngine {
                 let items_vec: Vec<FhirPathValue> = items.iter().cloned().collect();
                 // For single-element collections, unwrap and call method on the element
                 if items_vec.len() == 1 {
-                    let method_context = context.with_input(items_vec[0].clone());
+                    let method_context = context.with_input_item(0, items_vec[0].clone());
                     self.evaluate_function_call(method, args, &method_context)
                 } else {
                     // For multi-element collections, call method on each element and collect results
                     let mut results = Vec::new();
-                    for item in items_vec {
-                        let method_context = context.with_input(item.clone());
+                    for (index, item) in items_vec.into_iter().enumerate() {
+                        let method_context = context.with_input_item(index, item);
                         match self.evaluate_function_call(method, args, &method_context) {
                             Ok(result) => match result {
                                 FhirPathValue::Collection(sub_items) => {
@@ -1809,7 +1808,23 @@ impl FhirPathEngine {
         right: &ExpressionNode,
         context: &EvaluationContext,
     ) -> EvaluationResult<FhirPathValue> {
+        // `exists() = false` is the same test as `empty()`, `exists() = true` as `exists()`
+        if *op == BinaryOperator::Equal
+            && let ExpressionNode::MethodCall(data) = left
+            && data.method == "exists"
+            && data.args.is_empty()
+            && let ExpressionNode::Literal(LiteralValue::Boolean(expected)) = right
+        {
+            let method = if *expected { "exists" } else { "empty" };
+            return self
+                .evaluate_method_call_async(&data.base, method, &[], context)
+                .await;
+        }
+
         let left_val = self.evaluate_with_context(left, context).await?;
+        if let Some(result) = short_circuit(op, &left_val) {
+            return Ok(result);
+        }
         let right_val = self.evaluate_with_context(right, context).await?;
 
         // Use operator registry
@@ -1829,6 +1844,7 @@ impl FhirPathEngine {
             FhirPathValue::Collection(items) if items.len() == 1 => items.get(0).unwrap().clone(),
             _ => right_val.clone(),
         };
+        let (left_operand, right_operand) = lift_quantity_elements(op, left_operand, right_operand);
 
         operator
             .evaluate_binary(&left_operand, &right_operand)
@@ -1844,6 +1860,9 @@ impl FhirPathEngine {
         context: &EvaluationContext,
     ) -> EvaluationResult<FhirPathValue> {
         let left_val = self.evaluate_with_context_old(left, context)?;
+        if let Some(result) = short_circuit(op, &left_val) {
+            return Ok(result);
+        }
         let right_val = self.evaluate_with_context_old(right, context)?;
 
         // Use operator registry
@@ -1863,6 +1882,7 @@ impl FhirPathEngine {
             FhirPathValue::Collection(items) if items.len() == 1 => items.get(0).unwrap().clone(),
             _ => right_val.clone(),
         };
+        let (left_operand, right_operand) = lift_quantity_elements(op, left_operand, right_operand);
 
         operator
             .evaluate_binary(&left_operand, &right_operand)
@@ -2034,45 +2054,7 @@ impl FhirPathEngine {
             }
         };
 
-        match base_val {
-            FhirPathValue::Collection(items) => {
-                // Handle negative indexing (from end of collection)
-                let effective_index = if index_num < 0 {
-                    let len = items.len() as i64;
-                    len + index_num
-                } else {
-                    index_num
-                };
-
-                // Return empty collection for out-of-bounds access (FHIRPath spec)
-                if effective_index < 0 || effective_index as usize >= items.len() {
-                    Ok(FhirPathValue::Collection(vec![].into()))
-                } else {
-                    Ok(items.get(effective_index as usize).unwrap().clone())
-                }
-            }
-            _ => {
-                // Single item is treated as single-item collection for indexing
-                let single_item_collection = [base_val];
-
-                // Handle negative indexing
-                let effective_index = if index_num < 0 {
-                    1 + index_num // Length is 1 for single item
-                } else {
-                    index_num
-                };
-
-                // Return empty collection for out-of-bounds access
-                if effective_index < 0 || effective_index as usize >= 1 {
-                    Ok(FhirPathValue::Collection(vec![].into()))
-                } else {
-                    Ok(single_item_collection
-                        .get(effective_index as usize)
-                        .unwrap()
-                        .clone())
-                }
-            }
-        }
+        Ok(item_at(base_val, index_num))
     }
 
     /// Evaluate filter expression
@@ -2129,33 +2111,7 @@ impl FhirPathEngine {
         let left_val = self.evaluate_with_context_old(left, &left_context)?;
         let right_val = self.evaluate_with_context_old(right, &right_context)?;
 
-        let mut items = Vec::new();
-
-        // Add items from left
-        match left_val {
-            FhirPathValue::Collection(left_items) => items.extend(left_items),
-            FhirPathValue::Empty => {}
-            other => items.push(other),
-        }
-
-        // Add items from right, removing duplicates
-        match right_val {
-            FhirPathValue::Collection(right_items) => {
-                for item in right_items {
-                    if !items.contains(&item) {
-                        items.push(item);
-                    }
-                }
-            }
-            FhirPathValue::Empty => {}
-            other => {
-                if !items.contains(&other) {
-                    items.push(other);
-                }
-            }
-        }
-
-        Ok(FhirPathValue::collection(items))
+        Ok(union_of(left_val, right_val))
     }
 
     /// Evaluate type check
@@ -2171,12 +2127,12 @@ impl FhirPathEngine {
             FhirPathValue::Collection(items) => {
                 // For collections, check if it has exactly one item of the specified type
                 if items.len() == 1 {
-                    check_value_type(items.get(0).unwrap(), type_name)
+                    is_value_of_type(items.get(0).unwrap(), type_name)
                 } else {
                     false
                 }
             }
-            single_value => check_value_type(single_value, type_name),
+            single_value => is_value_of_type(single_value, type_name),
         };
 
         Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
@@ -2192,20 +2148,7 @@ impl FhirPathEngine {
         context: &EvaluationContext,
     ) -> EvaluationResult<FhirPathValue> {
         let value = self.evaluate_with_context_old(expression, context)?;
-
-        // Basic type casting - can be enhanced later
-        match (type_name, &value) {
-            ("String", _) => {
-                if let Some(s) = value.to_string_value() {
-                    Ok(FhirPathValue::collection(vec![FhirPathValue::String(
-                        s.into(),
-                    )]))
-                } else {
-                    Ok(FhirPathValue::collection(vec![]))
-                }
-            }
-            _ => Ok(FhirPathValue::collection(vec![value])), // For now, just return the value as-is
-        }
+        Ok(cast_value(value, type_name))
     }
 
     /// Evaluate conditional expression
@@ -2237,76 +2180,98 @@ impl Default for FhirPathEngine {
     }
 }
 
-/// Helper function to check if a value matches a type name
-fn check_value_type(value: &FhirPathValue, type_name: &str) -> bool {
-    match value {
-        FhirPathValue::Boolean(_) => {
-            matches!(
-                type_name,
-                "Boolean" | "System.Boolean" | "boolean" | "FHIR.boolean"
-            )
-        }
-        FhirPathValue::Integer(_) => {
-            matches!(
-                type_name,
-                "Integer" | "System.Integer" | "integer" | "FHIR.integer"
-            )
-        }
-        FhirPathValue::Decimal(_) => {
-            matches!(
-                type_name,
-                "Decimal" | "System.Decimal" | "decimal" | "FHIR.decimal"
-            )
-        }
-        FhirPathValue::String(_) => {
-            matches!(
-                type_name,
-                "String"
-                    | "System.String"
-                    | "string"
-                    | "FHIR.string"
-                    | "uri"
-                    | "FHIR.uri"
-                    | "uuid"
-                    | "FHIR.uuid"
-            )
-        }
-        FhirPathValue::Date(_) => {
-            matches!(type_name, "Date" | "System.Date" | "date" | "FHIR.date")
-        }
-        FhirPathValue::DateTime(_) => {
-            matches!(
-                type_name,
-                "DateTime" | "System.DateTime" | "dateTime" | "FHIR.dateTime"
-            )
-        }
-        FhirPathValue::Time(_) => {
-            matches!(type_name, "Time" | "System.Time" | "time" | "FHIR.time")
-        }
-        FhirPathValue::Quantity(_) => {
-            matches!(type_name, "Quantity" | "System.Quantity" | "FHIR.Quantity")
-        }
-        FhirPathValue::Empty => false,
-        FhirPathValue::Resource(resource) => {
-            // Check FHIR resource type - support both with and without FHIR prefix
-            if let Some(resource_type) = resource.resource_type() {
-                resource_type == type_name
-                    || type_name == format!("FHIR.{resource_type}")
-                    || type_name == format!("FHIR.`{resource_type}`")
-            } else {
-                false
-            }
-        }
-        FhirPathValue::Collection(_) => {
-            matches!(type_name, "Collection")
-        }
-        FhirPathValue::TypeInfoObject { .. } => {
-            matches!(type_name, "TypeInfo" | "System.TypeInfo")
-        }
-        FhirPathValue::JsonValue(_) => {
-            // JsonValue can match various types depending on content
-            matches!(type_name, "JsonValue" | "Object" | "Any")
-        }
+/// Cast `value` to `type_name` for the `as` operator: a single value of that
+/// type is returned as is, anything else gives an empty collection
+fn cast_value(value: FhirPathValue, type_name: &str) -> FhirPathValue {
+    let single = match value {
+        FhirPathValue::Collection(items) if items.len() == 1 => items.get(0).unwrap().clone(),
+        FhirPathValue::Collection(_) | FhirPathValue::Empty => return FhirPathValue::Empty,
+        single => single,
+    };
+    if is_value_of_type(&single, type_name) {
+        FhirPathValue::collection(vec![single])
+    } else {
+        FhirPathValue::collection(vec![])
+    }
+}
+
+/// The item at zero-based `index` of `base`, or empty if there is none
+///
+/// A negative index gives empty, as one past the end does, rather than
+/// counting from the end. A single value is indexed as a one-item collection.
+fn item_at(base: FhirPathValue, index: i64) -> FhirPathValue {
+    let item = usize::try_from(index).ok().and_then(|index| match base {
+        FhirPathValue::Collection(items) => items.get(index).cloned(),
+        FhirPathValue::Empty => None,
+        single => (index == 0).then_some(single),
+    });
+    item.unwrap_or_else(|| FhirPathValue::Collection(vec![].into()))
+}
+
+/// The result of `and`, `or` or `implies` when the left operand alone decides it
+///
+/// `false and x` is false, and `true or x` and `false implies x` are true,
+/// whatever `x` is, so `x` need not be evaluated and any error in it does
+/// not surface.
+fn short_circuit(op: &BinaryOperator, left: &FhirPathValue) -> Option<FhirPathValue> {
+    let left = match left {
+        FhirPathValue::Collection(items) if items.len() == 1 => items.first()?,
+        other => other,
+    };
+    let result = match (op, left) {
+        (BinaryOperator::And, FhirPathValue::Boolean(false)) => false,
+        (BinaryOperator::Or, FhirPathValue::Boolean(true)) => true,
+        (BinaryOperator::Implies, FhirPathValue::Boolean(false)) => true,
+        _ => return None,
+    };
+    Some(FhirPathValue::collection(vec![FhirPathValue::Boolean(
+        result,
+    )]))
+}
+
+/// Treat FHIR Quantity elements as System Quantities in quantity arithmetic
+/// and comparison
+///
+/// An element is lifted when the other operand is a Quantity, a number or
+/// another Quantity element, so `Observation.valueQuantity > 4 'mg'` and
+/// `Observation.valueQuantity * 2` work. Other operators, such as `|`, see the
+/// element unchanged.
+fn lift_quantity_elements(
+    op: &BinaryOperator,
+    left: FhirPathValue,
+    right: FhirPathValue,
+) -> (FhirPathValue, FhirPathValue) {
+    if !matches!(
+        op,
+        BinaryOperator::Add
+            | BinaryOperator::Subtract
+            | BinaryOperator::Multiply
+            | BinaryOperator::Divide
+            | BinaryOperator::Equal
+            | BinaryOperator::NotEqual
+            | BinaryOperator::Equivalent
+            | BinaryOperator::NotEquivalent
+            | BinaryOperator::LessThan
+            | BinaryOperator::LessThanOrEqual
+            | BinaryOperator::GreaterThan
+            | BinaryOperator::GreaterThanOrEqual
+    ) {
+        return (left, right);
+    }
+
+    let as_quantity = |value: &FhirPathValue| match value {
+        FhirPathValue::Quantity(quantity) => Some(quantity.clone()),
+        other => other.quantity_element(),
+    };
+    let is_number = |value: &FhirPathValue| {
+        matches!(value, FhirPathValue::Integer(_) | FhirPathValue::Decimal(_))
+    };
+
+    match (as_quantity(&left), as_quantity(&right)) {
+        (Some(l), Some(r)) => (FhirPathValue::Quantity(l), FhirPathValue::Quantity(r)),
+        (Some(l), None) if is_number(&right) => (FhirPathValue::Quantity(l), right),
+        (None, Some(r)) if is_number(&left) => (left, FhirPathValue::Quantity(r)),
+        _ => (left, right),
     }
 }
 
@@ -2323,149 +2288,115 @@ fn unwrap_function_arguments(args: Vec<FhirPathValue>) -> Vec<FhirPathValue> {
         .collect()
 }
 
-/// Parse a FHIRPath date literal (supports partial dates: @YYYY, @YYYY-MM, @YYYY-MM-DD)
-fn parse_fhirpath_date(s: &str) -> Result<chrono::NaiveDate, chrono::ParseError> {
-    // Remove the @ prefix
-    let date_str = s.strip_prefix('@').unwrap_or(s);
-
-    // Try full date format first
-    if let Ok(date) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-        return Ok(date);
-    }
-
-    // Try year-month format (default to first day of month)
-    if let Ok(date) = chrono::NaiveDate::parse_from_str(&format!("{date_str}-01"), "%Y-%m-%d") {
-        return Ok(date);
-    }
-
-    // Try year-only format (default to January 1st)
-    if let Ok(date) = chrono::NaiveDate::parse_from_str(&format!("{date_str}-01-01"), "%Y-%m-%d") {
-        return Ok(date);
-    }
-
-    // If none work, return error with the original string for better error reporting
-    chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+/// The error for a call to a function that is not registered, suggesting the
+/// closest registered name
+fn unknown_function(functions: &FunctionRegistry, name: &str) -> EvaluationError {
+    let message = match functions.suggest_similar(name) {
+        Some(suggestion) => format!("Unknown function: {name}, did you mean '{suggestion}'?"),
+        None => format!("Unknown function: {name}"),
+    };
+    EvaluationError::InvalidOperation { message }
 }
 
-/// Parse a FHIRPath datetime literal (supports partial: @YYYY, @YYYY-MM, @YYYY-MM-DD, @YYYY-MM-DDTHH, etc.)
-fn parse_fhirpath_datetime(
-    s: &str,
-) -> Result<chrono::DateTime<chrono::FixedOffset>, chrono::ParseError> {
-    use chrono::TimeZone;
-
-    // Remove the @ prefix
-    let datetime_str = s.strip_prefix('@').unwrap_or(s);
-
-    // Try different datetime formats
-    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(datetime_str) {
-        return Ok(dt.fixed_offset());
-    }
-
-    // Try format with timezone offset
-    if let Ok(dt) = chrono::DateTime::parse_from_str(datetime_str, "%Y-%m-%dT%H:%M:%S%.3f%z") {
-        return Ok(dt.fixed_offset());
-    }
-
-    // Try format with timezone offset (hour:minute only, no seconds)
-    if let Ok(dt) = chrono::DateTime::parse_from_str(datetime_str, "%Y-%m-%dT%H:%M%z") {
-        return Ok(dt.fixed_offset());
-    }
-
-    // Try format without timezone (assume UTC)
-    if let Ok(naive_dt) =
-        chrono::NaiveDateTime::parse_from_str(datetime_str, "%Y-%m-%dT%H:%M:%S%.3f")
-    {
-        return Ok(chrono::Utc.from_utc_datetime(&naive_dt).fixed_offset());
-    }
-
-    // Try basic format without milliseconds
-    if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(datetime_str, "%Y-%m-%dT%H:%M:%S") {
-        return Ok(chrono::Utc.from_utc_datetime(&naive_dt).fixed_offset());
-    }
-
-    // Try format with just hour and minute
-    if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(datetime_str, "%Y-%m-%dT%H:%M") {
-        return Ok(chrono::Utc.from_utc_datetime(&naive_dt).fixed_offset());
-    }
-
-    // Try format with just hour
-    if let Ok(naive_dt) =
-        chrono::NaiveDateTime::parse_from_str(&format!("{datetime_str}:00:00"), "%Y-%m-%dT%H:%M:%S")
-    {
-        return Ok(chrono::Utc.from_utc_datetime(&naive_dt).fixed_offset());
-    }
-
-    // Handle partial datetime formats ending with T
-    if datetime_str.ends_with('T') {
-        let date_part = datetime_str.trim_end_matches('T');
-
-        // Try to parse as a date and convert to datetime
-        if let Ok(date) = parse_fhirpath_date(&format!("@{date_part}")) {
-            let naive_dt = date.and_hms_opt(0, 0, 0).unwrap();
-            return Ok(chrono::Utc.from_utc_datetime(&naive_dt).fixed_offset());
-        }
-    }
-
-    // Handle date-only parts as datetime (midnight)
-    if let Ok(date) = parse_fhirpath_date(&format!("@{datetime_str}")) {
-        let naive_dt = date.and_hms_opt(0, 0, 0).unwrap();
-        return Ok(chrono::Utc.from_utc_datetime(&naive_dt).fixed_offset());
-    }
+/// Parse a FHIRPath date literal (supports partial dates: @YYYY, @YYYY-MM, @YYYY-MM-DD)
+fn parse_fhirpath_date(s: &str) -> Option<PrecisionDate> {
+    PrecisionDate::parse(s.strip_prefix('@').unwrap_or(s))
+}
 
-    // Return a simple parse error by trying an invalid format to get a real ParseError
-    chrono::NaiveDateTime::parse_from_str("invalid", "%Y-%m-%d").map(|_| {
-        chrono::Utc
-            .from_utc_datetime(&chrono::NaiveDateTime::new(
-                chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
-                chrono::NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
-            ))
-            .fixed_offset()
-    })
+/// Parse a FHIRPath datetime literal (supports partial: @YYYYT, @YYYY-MM-DDTHH, etc.)
+fn parse_fhirpath_datetime(s: &str) -> Option<PrecisionDateTime> {
+    PrecisionDateTime::parse(s.strip_prefix('@').unwrap_or(s))
 }
 
 /// Parse a FHIRPath time literal (supports partial: @T14, @T14:30, @THH:MM:SS.sss)
-fn parse_fhirpath_time(s: &str) -> Result<chrono::NaiveTime, chrono::ParseError> {
+fn parse_fhirpath_time(s: &str) -> Option<PrecisionTime> {
     // Remove the @T prefix
     let time_str = s
         .strip_prefix('@')
         .and_then(|s| s.strip_prefix('T'))
         .unwrap_or(s);
 
-    // Try format with milliseconds
-    if let Ok(time) = chrono::NaiveTime::parse_from_str(time_str, "%H:%M:%S%.3f") {
-        return Ok(time);
-    }
+    PrecisionTime::parse(time_str)
+}
 
-    // Try basic format without milliseconds
-    if let Ok(time) = chrono::NaiveTime::parse_from_str(time_str, "%H:%M:%S") {
-        return Ok(time);
+/// The URLs of the code systems the specification defines variables for,
+/// `%ucum`, `%sct` and `%loinc`
+fn code_system_url(name: &str) -> Option<&'static str> {
+    match name {
+        "ucum" => Some("http://unitsofmeasure.org"),
+        "sct" => Some("http://snomed.info/sct"),
+        "loinc" => Some("http://loinc.org"),
+        _ => None,
     }
+}
 
-    // Try hour and minute format (default seconds to 0)
-    if let Ok(time) = chrono::NaiveTime::parse_from_str(time_str, "%H:%M") {
-        return Ok(time);
+/// Expand the FHIR `%vs-[name]` and `%ext-[name]` abbreviation variables
+///
+/// ``%`vs-administrative-gender` `` names the value set
+/// `http://hl7.org/fhir/ValueSet/administrative-gender` and
+/// ``%`ext-patient-birthTime` `` the extension definition
+/// `http://hl7.org/fhir/StructureDefinition/patient-birthTime`.
+fn expand_abbreviated_url(name: &str) -> Option<String> {
+    if let Some(value_set) = name.strip_prefix("vs-").filter(|n| !n.is_empty()) {
+        Some(format!("http://hl7.org/fhir/ValueSet/{value_set}"))
+    } else {
+        name.strip_prefix("ext-")
+            .filter(|n| !n.is_empty())
+            .map(|extension| format!("http://hl7.org/fhir/StructureDefinition/{extension}"))
     }
+}
 
-    // Try hour-only format (default minutes and seconds to 0)
-    if let Ok(hour) = time_str.parse::<u32>() {
-        if let Some(time) = chrono::NaiveTime::from_hms_opt(hour, 0, 0) {
-            return Ok(time);
+impl FhirPathEngine {
+    /// The name defineVariable was given, checked to be definable in the scope
+    /// of `context`
+    ///
+    /// Every path that evaluates defineVariable takes its name from here, so
+    /// they all apply the same redefinition rules.
+    fn definable_name(
+        &self,
+        name_value: FhirPathValue,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<String> {
+        let name = match name_value {
+            FhirPathValue::String(name) => Some(name.to_string()),
+            FhirPathValue::Collection(items) if items.len() == 1 => match items.get(0) {
+                Some(FhirPathValue::String(name)) => Some(name.to_string()),
+                _ => None,
+            },
+            _ => None,
         }
+        .ok_or_else(|| EvaluationError::InvalidOperation {
+            message: "defineVariable first argument must be a string".to_string(),
+        })?;
+        self.check_definable(&name, context)?;
+        Ok(name)
     }
 
-    // If none work, return error with the original format for better error reporting
-    chrono::NaiveTime::parse_from_str(time_str, "%H:%M:%S")
-}
+    /// Check that defineVariable may define `name` in the scope of `context`
+    ///
+    /// System variables can never be redefined, and a name can be defined
+    /// only once per scope. The expression arguments of functions such as
+    /// `select()` start a new scope, in which outer names can be redefined.
+    fn check_definable(&self, name: &str, context: &EvaluationContext) -> EvaluationResult<()> {
+        let message = if self.is_protected_variable(name)
+            || code_system_url(name).is_some()
+            || expand_abbreviated_url(name).is_some()
+        {
+            format!("cannot redefine system variable '%{name}'")
+        } else if context.variable_scope.contains_local(name) {
+            format!("variable '%{name}' is already defined")
+        } else {
+            return Ok(());
+        };
 
-/// Check if a function name corresponds to a lambda function
-fn is_lambda_function(name: &str) -> bool {
-    matches!(
-        name,
-        "all" | "any" | "exists" | "select" | "where" | "aggregate" | "sort"
-    )
-}
+        Err(EvaluationError::Function(
+            crate::registry::function::FunctionError::EvaluationError {
+                name: "defineVariable".to_string(),
+                message,
+            },
+        ))
+    }
 
-impl FhirPathEngine {
     /// Check if a variable name is protected (system variable that cannot be redefined)
     fn is_protected_variable(&self, name: &str) -> bool {
         matches!(
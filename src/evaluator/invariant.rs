@@ -0,0 +1,145 @@
+//! Locating the items an `all()` invariant fails for
+//!
+//! `contact.all(telecom.exists())` only tells whether every contact passes.
+//! To report which ones do not, the collection before `all()` is evaluated one
+//! step at a time while keeping the path of every item, e.g. `contact[1]`, and
+//! the criteria is then evaluated against each item on its own.
+
+use super::context::input_items;
+use super::engine::FhirPathEngine;
+use super::error::EvaluationResult;
+use super::navigation::{describe, invocation_chain, push_frame, rebase, this};
+use crate::ast::ExpressionNode;
+use crate::model::FhirPathValue;
+
+impl FhirPathEngine {
+    /// Paths of the items an `<items>.all(<criteria>)` expression fails for
+    ///
+    /// An item fails when the criteria does not evaluate to `true` for it.
+    /// Paths index into every collection on the way, like
+    /// `Patient.contact[1]`. Returns `None` if `expression` is not an `all()`
+    /// call with a single criteria argument.
+    pub async fn failing_items(
+        &self,
+        expression: &ExpressionNode,
+        input: FhirPathValue,
+    ) -> EvaluationResult<Option<Vec<String>>> {
+        let (base, criteria) = match expression {
+            ExpressionNode::MethodCall(data) if data.method == "all" && data.args.len() == 1 => {
+                (Some(&data.base), &data.args[0])
+            }
+            ExpressionNode::FunctionCall(data) if data.name == "all" && data.args.len() == 1 => {
+                (None, &data.args[0])
+            }
+            _ => return Ok(None),
+        };
+        let criteria = match criteria {
+            ExpressionNode::Lambda(data) => &data.body,
+            other => other,
+        };
+
+        let items = match base {
+            Some(base) => self.items_with_paths(base, input).await?,
+            None => input_items(&input)
+                .iter()
+                .enumerate()
+                .map(|(index, item)| (format!("[{index}]"), item.clone()))
+                .collect(),
+        };
+
+        let mut failing = Vec::new();
+        for (path, item) in items {
+            let result = self.evaluate(criteria, item).await?;
+            if !is_true(&result) {
+                failing.push(path);
+            }
+        }
+        Ok(Some(failing))
+    }
+
+    /// Evaluate a chain of steps, keeping the path that leads to each item
+    async fn items_with_paths(
+        &self,
+        expression: &ExpressionNode,
+        input: FhirPathValue,
+    ) -> EvaluationResult<Vec<(String, FhirPathValue)>> {
+        let chain = invocation_chain(expression);
+        let mut current: Vec<(String, FhirPathValue)> = vec![(String::new(), input)];
+
+        for (step, node) in chain.iter().enumerate() {
+            let step_node = if step == 0 {
+                (*node).clone()
+            } else {
+                rebase(node, this())
+            };
+
+            current = match node {
+                // Navigation is evaluated per item so every result knows its parent
+                ExpressionNode::Identifier(_) | ExpressionNode::Path { .. } => {
+                    let mut next = Vec::new();
+                    for (path, item) in current {
+                        let value = self.evaluate(&step_node, item).await?;
+                        let frame = join(&path, &frame_of(node));
+                        match value {
+                            FhirPathValue::Collection(items) => {
+                                next.extend(items.iter().enumerate().map(|(index, item)| {
+                                    (format!("{frame}[{index}]"), item.clone())
+                                }))
+                            }
+                            FhirPathValue::Empty => {}
+                            item => next.push((frame, item)),
+                        }
+                    }
+                    next
+                }
+                // Other steps see the whole collection; the items they return keep
+                // the path of the input item they came from, if any
+                _ => {
+                    let values: Vec<FhirPathValue> =
+                        current.iter().map(|(_, item)| item.clone()).collect();
+                    let result = self
+                        .evaluate(&step_node, FhirPathValue::collection(values))
+                        .await?;
+                    let mut unused = current;
+                    input_items(&result)
+                        .iter()
+                        .cloned()
+                        .enumerate()
+                        .map(
+                            |(index, item)| match unused.iter().position(|(_, v)| *v == item) {
+                                Some(position) => unused.remove(position),
+                                None => (format!("{}[{index}]", describe(node)), item),
+                            },
+                        )
+                        .collect()
+                }
+            };
+        }
+
+        Ok(current)
+    }
+}
+
+fn frame_of(node: &ExpressionNode) -> String {
+    let mut frames = Vec::new();
+    push_frame(&mut frames, node);
+    frames.pop().unwrap_or_else(|| describe(node))
+}
+
+fn join(path: &str, frame: &str) -> String {
+    if path.is_empty() {
+        frame.to_string()
+    } else {
+        format!("{path}.{frame}")
+    }
+}
+
+fn is_true(value: &FhirPathValue) -> bool {
+    match value {
+        FhirPathValue::Boolean(b) => *b,
+        FhirPathValue::Collection(items) if items.len() == 1 => {
+            matches!(items.first(), Some(FhirPathValue::Boolean(true)))
+        }
+        _ => false,
+    }
+}
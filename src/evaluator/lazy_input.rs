@@ -0,0 +1,171 @@
+//! Handing lazily produced nodes to functions that can stop early
+//!
+//! `descendants().ofType(Reference).all(reference.exists())` is decided by the
+//! first Reference without a reference, so the rest of the resource does not
+//! have to be visited. Functions that produce their nodes lazily, such as
+//! `descendants()`, are therefore read one node at a time. Each node goes
+//! through the item-wise steps that follow, e.g. `ofType()` and `where()`, and
+//! what is left is handed to a function that can decide its result from a
+//! single item, such as `all()` or `exists()`.
+//!
+//! Which functions take part is a property of each function, so any chain of
+//! them is evaluated this way, e.g. `children().select(active).anyTrue()`.
+//! Expressions that define variables or refer to `$index` or `$total` are
+//! evaluated eagerly, as those would otherwise restart at every node;
+//! variables fixed for the whole evaluation, such as `%resource`, are not.
+
+use super::context::{EvaluationContext, input_items};
+use super::engine::FhirPathEngine;
+use super::error::EvaluationResult;
+use super::navigation::sub_expressions;
+use crate::ast::ExpressionNode;
+use crate::model::FhirPathValue;
+use crate::registry::functions::{ChildrenFunction, DescendantsFunction};
+
+/// A function applied to the collection its nodes come from
+type Step<'a> = (&'a str, &'a [ExpressionNode]);
+
+impl FhirPathEngine {
+    /// Evaluate `<base>.<method>(<args>)` by reading the nodes of `base` one at
+    /// a time, stopping as soon as `method` has decided its result
+    ///
+    /// Returns `None` if `method` cannot decide from a single item, or if
+    /// `base` does not start with a lazy source followed by item-wise steps.
+    pub(super) async fn evaluate_lazy_input(
+        &self,
+        base: &ExpressionNode,
+        method: &str,
+        args: &[ExpressionNode],
+        context: &EvaluationContext,
+    ) -> EvaluationResult<Option<FhirPathValue>> {
+        let Some(deciding) = deciding_result(method) else {
+            return Ok(None);
+        };
+        if depends_on_collection(base) || args.iter().any(depends_on_collection) {
+            return Ok(None);
+        }
+        let Some((source, source_base, steps)) = lazy_chain(base) else {
+            return Ok(None);
+        };
+
+        // Nodes are no items of the source's input, so with_input_item() gives
+        // them the %resource of the source
+        let source_context = match source_base {
+            Some(source_base) => {
+                context.with_input(self.evaluate_with_context(source_base, context).await?)
+            }
+            None => context.clone(),
+        };
+
+        for node in lazy_nodes(source, &source_context.input) {
+            let mut items = vec![node];
+            for (step, step_args) in &steps {
+                let mut next = Vec::new();
+                for item in items {
+                    let item_context = source_context.with_input_item(0, item);
+                    let result = self
+                        .evaluate_method_call_direct_async(step, step_args, &item_context)
+                        .await?;
+                    next.extend_from_slice(input_items(&result));
+                }
+                items = next;
+            }
+
+            for item in items {
+                let item_context = source_context.with_input_item(0, item);
+                let result = self
+                    .evaluate_method_call_direct_async(method, args, &item_context)
+                    .await?;
+                if result == boolean(deciding) {
+                    return Ok(Some(result));
+                }
+                // A result that cannot be combined is left to eager evaluation
+                if result != boolean(!deciding) {
+                    return Ok(None);
+                }
+            }
+        }
+
+        Ok(Some(boolean(!deciding)))
+    }
+}
+
+/// The result a function gives for a whole collection as soon as it gives it
+/// for one of its items, e.g. `false` for all()
+///
+/// Once no item decides, the result is the opposite, which is also the result
+/// for an empty collection.
+fn deciding_result(method: &str) -> Option<bool> {
+    match method {
+        "all" | "allTrue" | "allFalse" | "empty" => Some(false),
+        "exists" | "anyTrue" | "anyFalse" => Some(true),
+        _ => None,
+    }
+}
+
+/// Whether `expression` defines variables or refers to `$index` or `$total`,
+/// which depend on the whole collection being evaluated at once
+fn depends_on_collection(expression: &ExpressionNode) -> bool {
+    let depends = match expression {
+        ExpressionNode::Variable(name) => {
+            matches!(name.as_str(), "index" | "$index" | "total" | "$total")
+        }
+        ExpressionNode::MethodCall(data) => data.method == "defineVariable",
+        ExpressionNode::FunctionCall(data) => data.name == "defineVariable",
+        _ => false,
+    };
+    depends
+        || sub_expressions(expression)
+            .into_iter()
+            .any(depends_on_collection)
+}
+
+/// Whether the function gives for a collection what it gives for each of its
+/// items in turn
+fn is_item_wise(method: &str) -> bool {
+    matches!(method, "where" | "select" | "ofType")
+}
+
+/// Whether the function can produce its nodes one at a time
+fn is_lazy_source(method: &str, args: &[ExpressionNode]) -> bool {
+    matches!(method, "children" | "descendants") && args.is_empty()
+}
+
+/// The nodes of the lazy source `method`, visited as they are taken
+fn lazy_nodes(
+    method: &str,
+    input: &FhirPathValue,
+) -> Box<dyn Iterator<Item = FhirPathValue> + Send> {
+    match method {
+        "children" => Box::new(ChildrenFunction::lazy_children(input)),
+        _ => Box::new(DescendantsFunction::lazy_descendants(input)),
+    }
+}
+
+/// Split `base` into a lazy source, the expression it is called on, if any,
+/// and the item-wise steps after it in the order they apply
+fn lazy_chain(base: &ExpressionNode) -> Option<(&str, Option<&ExpressionNode>, Vec<Step<'_>>)> {
+    let mut steps = Vec::new();
+    let mut current = base;
+    loop {
+        match current {
+            ExpressionNode::MethodCall(data) if is_lazy_source(&data.method, &data.args) => {
+                steps.reverse();
+                return Some((&data.method, Some(&data.base), steps));
+            }
+            ExpressionNode::FunctionCall(data) if is_lazy_source(&data.name, &data.args) => {
+                steps.reverse();
+                return Some((&data.name, None, steps));
+            }
+            ExpressionNode::MethodCall(data) if is_item_wise(&data.method) => {
+                steps.push((data.method.as_str(), data.args.as_slice()));
+                current = &data.base;
+            }
+            _ => return None,
+        }
+    }
+}
+
+fn boolean(value: bool) -> FhirPathValue {
+    FhirPathValue::collection(vec![FhirPathValue::Boolean(value)])
+}
@@ -5,17 +5,22 @@
 //\! with automatic hybrid strategy selection for optimal performance.
 
 pub mod bundle_arc;
+pub mod bundle_stream;
 pub mod collections;
 #[warn(missing_docs)]
 mod context;
 mod engine;
 mod error;
+mod invariant;
+mod lazy_input;
+mod navigation;
 mod shared_context;
 
 // Essential evaluation functionality - clean and focused
 pub use context::{EvaluationContext, VariableScope};
 pub use engine::FhirPathEngine;
 pub use error::{EvaluationError, EvaluationResult};
+pub use navigation::ErrorTrace;
 pub use shared_context::{
     ContextInheritance, FunctionClosureOptimizer, SharedContextBuilder, SharedEvaluationContext,
 };
@@ -0,0 +1,305 @@
+//! Navigation stack capture for evaluation errors
+//!
+//! While an expression is evaluated with an [`ErrorTrace`], every node an error
+//! passes through on its way out is recorded, together with the input item a
+//! lambda argument failed for. Once the evaluation has failed, the recorded
+//! nodes are walked from the outermost in to describe the failing step, so the
+//! stack reads like `Bundle.entry.resource[3].select(value.as(Quantity))`
+//! without evaluating anything again.
+
+use crate::ast::{ExpressionNode, LiteralValue};
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// The nodes an evaluation error propagated through
+///
+/// Clones share the same record, so lambda arguments evaluated in their own
+/// contexts report into the trace of the whole evaluation.
+#[derive(Debug, Clone, Default)]
+pub struct ErrorTrace {
+    failures: Arc<Mutex<Vec<Failure>>>,
+}
+
+/// A node whose evaluation failed
+#[derive(Debug)]
+struct Failure {
+    node: ExpressionNode,
+    /// The input item a lambda argument failed for
+    item: Option<usize>,
+}
+
+impl ErrorTrace {
+    /// Create an empty trace
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that evaluating `node` failed
+    ///
+    /// Failures are recorded innermost first. An error that was recovered from
+    /// leaves failures outside `node`, which are dropped.
+    pub(crate) fn record(&self, node: &ExpressionNode) {
+        let mut failures = self.failures.lock();
+        match failures.last() {
+            Some(last) if last.node == *node => return,
+            Some(last) if !contains(node, &last.node) => failures.clear(),
+            _ => {}
+        }
+        failures.push(Failure {
+            node: node.clone(),
+            item: None,
+        });
+    }
+
+    /// Record that the lambda argument that failed last did so for the input
+    /// item at `index`
+    pub(crate) fn record_item(&self, index: usize) {
+        if let Some(last) = self.failures.lock().last_mut() {
+            last.item = Some(index);
+        }
+    }
+
+    /// Describe the navigation path that led to the recorded error
+    ///
+    /// Returns `None` if no failure was recorded.
+    pub fn navigation_stack(&self) -> Option<Vec<String>> {
+        let failures = self.failures.lock();
+        let outermost = failures.last()?;
+        let mut frames = Vec::new();
+        locate(&outermost.node, &failures, &mut frames);
+        Some(frames)
+    }
+}
+
+/// Push the frames leading to the failing step of `expression` onto `frames`
+fn locate(expression: &ExpressionNode, failures: &[Failure], frames: &mut Vec<String>) {
+    for step in invocation_chain(expression) {
+        if failed(step, failures).is_some() {
+            return locate_in_step(step, failures, frames);
+        }
+        push_frame(frames, step);
+    }
+}
+
+/// Push the frame for a failing step, descending into an operand or
+/// argument when that is where the error comes from
+fn locate_in_step(step: &ExpressionNode, failures: &[Failure], frames: &mut Vec<String>) {
+    let (name, args) = match step {
+        ExpressionNode::MethodCall(data) => (&data.method, &data.args),
+        ExpressionNode::FunctionCall(data) => (&data.name, &data.args),
+        _ => {
+            let operand = operands(step)
+                .into_iter()
+                .find(|operand| failed(operand, failures).is_some());
+            match operand {
+                Some(operand) => locate(operand, failures, frames),
+                None => push_frame(frames, step),
+            }
+            return;
+        }
+    };
+
+    // Lambda arguments are evaluated once per input item
+    for arg in args {
+        let arg = match arg {
+            ExpressionNode::Lambda(data) => &data.body,
+            other => other,
+        };
+        if let Some(failure) = failed(arg, failures) {
+            let mut inner = Vec::new();
+            locate(arg, failures, &mut inner);
+            if let Some(index) = failure.item {
+                mark_index(frames, index);
+            }
+            frames.push(format!("{name}({})", inner.join(".")));
+            return;
+        }
+    }
+    push_frame(frames, step);
+}
+
+/// The recorded failure of `node`, if it failed
+fn failed<'a>(node: &ExpressionNode, failures: &'a [Failure]) -> Option<&'a Failure> {
+    failures.iter().find(|failure| failure.node == *node)
+}
+
+/// The operands of an operator, which are evaluated against its own input
+fn operands(node: &ExpressionNode) -> Vec<&ExpressionNode> {
+    match node {
+        ExpressionNode::BinaryOp(data) => vec![&data.left, &data.right],
+        ExpressionNode::Union { left, right } => vec![left, right],
+        ExpressionNode::UnaryOp { operand, .. }
+        | ExpressionNode::TypeCheck {
+            expression: operand,
+            ..
+        }
+        | ExpressionNode::TypeCast {
+            expression: operand,
+            ..
+        } => vec![operand],
+        _ => Vec::new(),
+    }
+}
+
+/// Check whether `inner` is `node` or one of its descendants
+fn contains(node: &ExpressionNode, inner: &ExpressionNode) -> bool {
+    node == inner
+        || sub_expressions(node)
+            .into_iter()
+            .any(|child| contains(child, inner))
+}
+
+/// The expressions `node` is made of, such as the base and arguments of a
+/// method call
+pub(super) fn sub_expressions(node: &ExpressionNode) -> Vec<&ExpressionNode> {
+    match node {
+        ExpressionNode::Literal(_)
+        | ExpressionNode::Identifier(_)
+        | ExpressionNode::Variable(_) => Vec::new(),
+        ExpressionNode::Path { base, .. } => vec![base],
+        ExpressionNode::Index { base, index } => vec![base, index],
+        ExpressionNode::Filter { base, condition } => vec![base, condition],
+        ExpressionNode::MethodCall(data) => std::iter::once(&data.base).chain(&data.args).collect(),
+        ExpressionNode::FunctionCall(data) => data.args.iter().collect(),
+        ExpressionNode::Lambda(data) => vec![&data.body],
+        ExpressionNode::Conditional(data) => [&data.condition, &data.then_expr]
+            .into_iter()
+            .chain(data.else_expr.as_deref())
+            .collect(),
+        other => operands(other),
+    }
+}
+
+/// Split an expression into its invocation chain, head first
+pub(super) fn invocation_chain(expression: &ExpressionNode) -> Vec<&ExpressionNode> {
+    let mut chain = Vec::new();
+    let mut node = expression;
+    loop {
+        chain.push(node);
+        node = match node {
+            ExpressionNode::Path { base, .. } | ExpressionNode::Index { base, .. } => base,
+            ExpressionNode::MethodCall(data) => &data.base,
+            _ => break,
+        };
+    }
+    chain.reverse();
+    chain
+}
+
+/// Replace the base of a chain step
+pub(super) fn rebase(step: &ExpressionNode, base: ExpressionNode) -> ExpressionNode {
+    let mut step = step.clone();
+    match &mut step {
+        ExpressionNode::Path { base: old, .. } | ExpressionNode::Index { base: old, .. } => {
+            **old = base;
+        }
+        ExpressionNode::MethodCall(data) => data.base = base,
+        _ => {}
+    }
+    step
+}
+
+pub(super) fn this() -> ExpressionNode {
+    ExpressionNode::variable("this")
+}
+
+/// Record the index of the item selected from the last frame's collection
+fn mark_index(frames: &mut [String], index: usize) {
+    if let Some(last) = frames.last_mut() {
+        last.push_str(&format!("[{index}]"));
+    }
+}
+
+/// Push the frame describing a single chain step
+pub(super) fn push_frame(frames: &mut Vec<String>, step: &ExpressionNode) {
+    match step {
+        ExpressionNode::Path { path, .. } => frames.push(path.clone()),
+        ExpressionNode::Index { index, .. } => match frames.last_mut() {
+            Some(last) => last.push_str(&format!("[{}]", describe(index))),
+            None => frames.push(format!("[{}]", describe(index))),
+        },
+        ExpressionNode::MethodCall(data) => {
+            frames.push(format!("{}({})", data.method, describe_args(&data.args)))
+        }
+        other => frames.push(describe(other)),
+    }
+}
+
+pub(super) fn describe_args(args: &[ExpressionNode]) -> String {
+    args.iter().map(describe).collect::<Vec<_>>().join(", ")
+}
+
+/// Render an expression back to FHIRPath-like text for display in a frame
+pub(super) fn describe(node: &ExpressionNode) -> String {
+    match node {
+        ExpressionNode::Literal(literal) => match literal {
+            LiteralValue::Boolean(b) => b.to_string(),
+            LiteralValue::Integer(i) => i.to_string(),
+            LiteralValue::Decimal(d) => d.clone(),
+            LiteralValue::String(s) => format!("'{s}'"),
+            LiteralValue::Date(s) | LiteralValue::DateTime(s) | LiteralValue::Time(s) => {
+                if s.starts_with('@') {
+                    s.clone()
+                } else {
+                    format!("@{s}")
+                }
+            }
+            LiteralValue::Quantity { value, unit } => format!("{value} '{unit}'"),
+            LiteralValue::Null => "{}".to_string(),
+        },
+        ExpressionNode::Identifier(name) => name.clone(),
+        ExpressionNode::Variable(name) => match name.as_str() {
+            "this" | "index" | "total" => format!("${name}"),
+            _ if name.starts_with('$') || name.starts_with('%') => name.clone(),
+            _ => format!("%{name}"),
+        },
+        ExpressionNode::Path { base, path } => format!("{}.{path}", describe(base)),
+        ExpressionNode::Index { base, index } => {
+            format!("{}[{}]", describe(base), describe(index))
+        }
+        ExpressionNode::MethodCall(data) => format!(
+            "{}.{}({})",
+            describe(&data.base),
+            data.method,
+            describe_args(&data.args)
+        ),
+        ExpressionNode::FunctionCall(data) => {
+            format!("{}({})", data.name, describe_args(&data.args))
+        }
+        ExpressionNode::BinaryOp(data) => format!(
+            "{} {} {}",
+            describe(&data.left),
+            data.op.as_str(),
+            describe(&data.right)
+        ),
+        ExpressionNode::UnaryOp { op, operand } => format!("{}{}", op.as_str(), describe(operand)),
+        ExpressionNode::Union { left, right } => {
+            format!("{} | {}", describe(left), describe(right))
+        }
+        ExpressionNode::TypeCheck {
+            expression,
+            type_name,
+        } => format!("{} is {type_name}", describe(expression)),
+        ExpressionNode::TypeCast {
+            expression,
+            type_name,
+        } => format!("{} as {type_name}", describe(expression)),
+        ExpressionNode::Filter { base, condition } => {
+            format!("{}.where({})", describe(base), describe(condition))
+        }
+        ExpressionNode::Lambda(data) => describe(&data.body),
+        ExpressionNode::Conditional(data) => match &data.else_expr {
+            Some(else_expr) => format!(
+                "iif({}, {}, {})",
+                describe(&data.condition),
+                describe(&data.then_expr),
+                describe(else_expr)
+            ),
+            None => format!(
+                "iif({}, {})",
+                describe(&data.condition),
+                describe(&data.then_expr)
+            ),
+        },
+    }
+}
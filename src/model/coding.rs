@@ -0,0 +1,140 @@
+//! Coding values for terminology operations
+//!
+//! Terminology functions such as `memberOf()` operate on codes, Codings and
+//! CodeableConcepts. This module lifts any of those shapes into a uniform
+//! [`Coding`] so the terminology layer only has to deal with one type.
+
+use serde_json::Value;
+
+use super::value::FhirPathValue;
+
+/// A code with an optional code system, as used by terminology operations
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Coding {
+    /// Code system URI (absent for bare codes)
+    pub system: Option<String>,
+    /// Code system version
+    pub version: Option<String>,
+    /// The code itself
+    pub code: String,
+    /// Human readable display text
+    pub display: Option<String>,
+}
+
+impl Coding {
+    /// Create a coding for a bare code with no system
+    pub fn new(code: impl Into<String>) -> Self {
+        Self {
+            system: None,
+            version: None,
+            code: code.into(),
+            display: None,
+        }
+    }
+
+    /// Create a coding for a code within a code system
+    pub fn with_system(system: impl Into<String>, code: impl Into<String>) -> Self {
+        Self {
+            system: Some(system.into()),
+            ..Self::new(code)
+        }
+    }
+
+    /// Lift a FHIRPath value into the codings it represents
+    ///
+    /// A plain string is treated as a code with no system. A Coding object yields
+    /// itself, and a CodeableConcept yields each of its codings. Anything else
+    /// (including objects without a `code`) yields nothing.
+    pub fn from_value(value: &FhirPathValue) -> Vec<Coding> {
+        match value {
+            FhirPathValue::String(code) => vec![Self::new(code.as_ref())],
+            FhirPathValue::Resource(resource) => Self::from_json(resource.as_json()),
+            FhirPathValue::JsonValue(json) => Self::from_json(json.as_json()),
+            FhirPathValue::Collection(items) => items.iter().flat_map(Self::from_value).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Lift a JSON Coding or CodeableConcept into codings
+    pub fn from_json(json: &Value) -> Vec<Coding> {
+        match json {
+            Value::String(code) => vec![Self::new(code.as_str())],
+            Value::Object(obj) => {
+                if let Some(Value::Array(codings)) = obj.get("coding") {
+                    return codings.iter().filter_map(Self::from_coding_json).collect();
+                }
+                Self::from_coding_json(json).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn from_coding_json(json: &Value) -> Option<Coding> {
+        let obj = json.as_object()?;
+        let code = obj.get("code")?.as_str()?;
+        let text = |key: &str| obj.get(key).and_then(Value::as_str).map(str::to_string);
+
+        Some(Self {
+            system: text("system"),
+            version: text("version"),
+            code: code.to_string(),
+            display: text("display"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_string_lifts_to_code_without_system() {
+        let codings = Coding::from_value(&FhirPathValue::String("male".into()));
+        assert_eq!(codings, vec![Coding::new("male")]);
+        assert!(codings[0].system.is_none());
+    }
+
+    #[test]
+    fn test_coding_object() {
+        let value = FhirPathValue::resource_from_json(json!({
+            "system": "http://loinc.org",
+            "code": "1234-5",
+            "display": "Test"
+        }));
+        let codings = Coding::from_value(&value);
+
+        assert_eq!(codings.len(), 1);
+        assert_eq!(codings[0].system.as_deref(), Some("http://loinc.org"));
+        assert_eq!(codings[0].code, "1234-5");
+        assert_eq!(codings[0].display.as_deref(), Some("Test"));
+    }
+
+    #[test]
+    fn test_codeable_concept_yields_each_coding() {
+        let concept = json!({
+            "coding": [
+                {"system": "http://loinc.org", "code": "1234-5"},
+                {"system": "http://snomed.info/sct", "code": "9876"},
+                {"system": "http://example.org", "display": "no code"}
+            ],
+            "text": "Concept"
+        });
+        let codings = Coding::from_json(&concept);
+
+        assert_eq!(
+            codings,
+            vec![
+                Coding::with_system("http://loinc.org", "1234-5"),
+                Coding::with_system("http://snomed.info/sct", "9876"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_non_coding_values() {
+        assert!(Coding::from_value(&FhirPathValue::Integer(1)).is_empty());
+        assert!(Coding::from_value(&FhirPathValue::Empty).is_empty());
+        assert!(Coding::from_json(&json!({"text": "no code"})).is_empty());
+    }
+}
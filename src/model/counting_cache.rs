@@ -0,0 +1,87 @@
+//! Bounded least-recently-used cache that counts its hits and misses
+//!
+//! Shared by the engine's parsed expression cache and resolve()'s Bundle
+//! lookups, so both report the same [`CacheStats`].
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Thread-safe LRU cache keeping hit and miss counts
+///
+/// The least recently used entries are evicted once `capacity` is reached.
+pub struct CountingCache<K: Hash + Eq, V> {
+    entries: Mutex<LruCache<K, V>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Statistics about a [`CountingCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered from the cache
+    pub hits: u64,
+    /// Lookups that had to compute their value
+    pub misses: u64,
+    /// Number of entries currently cached
+    pub entries: usize,
+}
+
+impl<K: Hash + Eq, V> CountingCache<K, V> {
+    /// Create a cache holding up to `capacity` entries
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Get cache statistics
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries.lock().len(),
+        }
+    }
+
+    /// Look `key` up, counting a hit or a miss
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        self.lookup(|entries| entries.get(key).cloned())
+    }
+
+    /// Answer a lookup from the locked entries, counting a hit when `lookup`
+    /// returns a value and a miss otherwise
+    ///
+    /// For lookups that need more than one key, such as trying a normalized
+    /// key before the original one.
+    pub fn lookup<R>(&self, lookup: impl FnOnce(&mut LruCache<K, V>) -> Option<R>) -> Option<R> {
+        let found = lookup(&mut self.entries.lock());
+        let counter = if found.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    /// Cache `value` under `key`, evicting the least recently used entry if full
+    pub fn put(&self, key: K, value: V) {
+        self.entries.lock().put(key, value);
+    }
+
+    /// Remove all entries, keeping the counts
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+}
@@ -6,6 +6,8 @@
 #![warn(missing_docs)]
 
 pub mod arc_pool;
+pub mod coding;
+pub mod counting_cache;
 pub mod error;
 pub mod json_arc;
 pub mod lazy;
@@ -14,6 +16,8 @@ pub mod quantity;
 pub mod resource;
 pub mod smart_collection;
 pub mod string_intern;
+pub mod temporal;
+pub mod type_compatibility;
 pub mod types;
 pub mod value;
 pub mod value_pool;
@@ -22,6 +26,8 @@ pub use arc_pool::{
     ArcPoolConfig, ArcPoolStats, CombinedArcPoolStats, FragmentationStats, GlobalArcPoolManager,
     TypedArcPool, get_pooled_collection, get_pooled_fhir_value, global_arc_pool,
 };
+pub use coding::Coding;
+pub use counting_cache::{CacheStats, CountingCache};
 pub use error::{ModelError, Result};
 pub use json_arc::{ArcJsonValue, ArrayView};
 pub use lazy::{LazyCollection, LazyIterator, ToLazy};
@@ -30,8 +36,13 @@ pub use quantity::Quantity;
 pub use resource::FhirResource;
 pub use smart_collection::{SmartCollection, SmartCollectionBuilder, SmartCollectionIter};
 pub use string_intern::{
-    InternerStats, clear_global_interner, global_interner_stats, global_interner_stats_compat,
-    intern_string, is_interned,
+    InternerStats, JSON_STRING_INTERNER_CAPACITY, clear_global_interner, global_interner_stats,
+    global_interner_stats_compat, intern_string, is_interned,
+};
+pub use temporal::{PrecisionDate, PrecisionDateTime, PrecisionTime, TemporalPrecision};
+pub use type_compatibility::{
+    choice_element_type, choice_type, is_of_type, is_value_of_type, parse_type_specifier,
+    type_specifier_of, value_type_info,
 };
 pub use types::TypeInfo;
 pub use value::{Collection, FhirPathValue, ValueRef};
@@ -10,6 +10,24 @@ use octofhir_ucum::{self, OwnedUnitExpr};
 
 use super::error::{ModelError, Result};
 
+/// Calendar duration keywords and the UCUM units they correspond to
+const CALENDAR_UNITS: [(&str, &str); 8] = [
+    ("year", "a"),
+    ("month", "mo"),
+    ("week", "wk"),
+    ("day", "d"),
+    ("hour", "h"),
+    ("minute", "min"),
+    ("second", "s"),
+    ("millisecond", "ms"),
+];
+
+/// Significant digits kept by canonical conversion
+///
+/// UCUM gives conversion factors as f64, good to about 15 digits, so results
+/// are rounded there to drop noise like `373.1500000000001`.
+const CANONICAL_SIGNIFICANT_DIGITS: u32 = 15;
+
 /// Quantity value with optional unit
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Quantity {
@@ -17,6 +35,10 @@ pub struct Quantity {
     pub value: Decimal,
     /// Unit string (UCUM)
     pub unit: Option<String>,
+    /// Whether the unit was written as a calendar duration keyword such as
+    /// `year`, rather than as a UCUM unit such as `'a'`
+    #[serde(default)]
+    calendar: bool,
     /// Cached parsed UCUM unit expression for performance
     #[serde(skip)]
     ucum_expr: Option<Arc<OwnedUnitExpr>>,
@@ -24,43 +46,61 @@ pub struct Quantity {
 
 impl Quantity {
     /// Create a new quantity
+    ///
+    /// A calendar duration keyword (`year`, `months`, ...) as the unit makes a
+    /// calendar duration, stored with the corresponding UCUM unit.
     pub fn new(value: Decimal, unit: Option<String>) -> Self {
-        // Normalize unit name to UCUM format
-        let normalized_unit = unit.as_ref().map(|u| Self::normalize_unit_name(u));
-        let ucum_expr = normalized_unit
-            .as_ref()
-            .and_then(|u| Self::parse_ucum_unit(u));
+        let calendar_unit = unit.as_deref().and_then(Self::calendar_unit);
+        let unit = match calendar_unit {
+            Some(ucum) => Some(ucum.to_string()),
+            None => unit,
+        };
+        let ucum_expr = unit.as_ref().and_then(|u| Self::parse_ucum_unit(u));
         Self {
             value,
-            unit: normalized_unit,
+            unit,
+            calendar: calendar_unit.is_some(),
             ucum_expr,
         }
     }
 
-    /// Normalize common unit names to UCUM equivalents
-    fn normalize_unit_name(unit: &str) -> String {
-        // First check calendar units for FHIRPath compatibility
-        match unit {
-            // Time units - FHIRPath calendar units to UCUM
-            "day" | "days" => "d".to_string(),
-            "hour" | "hours" => "h".to_string(),
-            "minute" | "minutes" => "min".to_string(),
-            "second" | "seconds" => "s".to_string(),
-            "week" | "weeks" => "wk".to_string(),
-            "month" | "months" => "mo".to_string(),
-            "year" | "years" => "a".to_string(),
-            "millisecond" | "milliseconds" => "ms".to_string(),
+    /// The UCUM unit for a calendar duration keyword, singular or plural
+    pub fn calendar_unit(keyword: &str) -> Option<&'static str> {
+        let singular = keyword.strip_suffix('s').unwrap_or(keyword);
+        CALENDAR_UNITS
+            .iter()
+            .find(|(calendar, _)| *calendar == singular)
+            .map(|(_, ucum)| *ucum)
+    }
 
-            _ => {
-                // Check if it's already a valid UCUM unit - if so, keep it as-is
-                // This preserves units like "g", "mg", "m", "cm", etc. without
-                // converting them to base SI units
-                match octofhir_ucum::validate(unit) {
-                    Ok(_) => unit.to_string(),  // Valid UCUM unit, keep as-is
-                    Err(_) => unit.to_string(), // Invalid or unknown, keep original
-                }
-            }
+    /// Whether this is a calendar duration such as `1 year`
+    pub fn is_calendar_duration(&self) -> bool {
+        self.calendar
+    }
+
+    /// Whether comparing with `other` has a definite result
+    ///
+    /// Calendar years and months vary in length, so they cannot be compared
+    /// with the UCUM units `'a'` and `'mo'`, which are fixed averages. Other
+    /// calendar durations equal their UCUM units, so `1 week = 1 'wk'`.
+    pub fn is_comparable_with(&self, other: &Quantity) -> bool {
+        let varies = |q: &Quantity| matches!(q.unit.as_deref(), Some("a" | "mo"));
+        self.calendar == other.calendar || !(varies(self) || varies(other))
+    }
+
+    /// The calendar keyword this quantity was written with, pluralised to
+    /// match its value
+    fn calendar_keyword(&self) -> Option<String> {
+        if !self.calendar {
+            return None;
         }
+        let unit = self.unit.as_deref()?;
+        let (keyword, _) = CALENDAR_UNITS.iter().find(|(_, ucum)| *ucum == unit)?;
+        Some(if self.value.abs() == Decimal::ONE {
+            keyword.to_string()
+        } else {
+            format!("{keyword}s")
+        })
     }
 
     /// Create a unitless quantity
@@ -68,6 +108,7 @@ impl Quantity {
         Self {
             value,
             unit: None,
+            calendar: false,
             ucum_expr: None,
         }
     }
@@ -256,17 +297,58 @@ impl Quantity {
         }
     }
 
-    /// Multiply by a scalar
+    /// Multiply by a scalar, keeping the unit so `2 * 1 year` is `2 years`
     pub fn multiply_scalar(&self, scalar: Decimal) -> Quantity {
-        Quantity::new(self.value * scalar, self.unit.clone())
+        Quantity {
+            value: self.value * scalar,
+            ..self.clone()
+        }
     }
 
-    /// Divide by a scalar
+    /// Divide by a scalar, keeping the unit
     pub fn divide_scalar(&self, scalar: Decimal) -> Option<Quantity> {
         if scalar.is_zero() {
             None
         } else {
-            Some(Quantity::new(self.value / scalar, self.unit.clone()))
+            Some(Quantity {
+                value: self.value / scalar,
+                ..self.clone()
+            })
+        }
+    }
+
+    /// Express this quantity in canonical UCUM base units
+    ///
+    /// `1000 'mg'` becomes `1 'g'` and `2 'km'` becomes `2000 'm'`. Unitless
+    /// quantities are returned unchanged.
+    pub fn to_canonical(&self) -> Result<Quantity> {
+        let Some(unit) = &self.unit else {
+            return Ok(self.clone());
+        };
+        let analysis =
+            octofhir_ucum::analyse(unit).map_err(|_| ModelError::invalid_unit(unit.as_str()))?;
+
+        let value = Decimal::from_f64(analysis.factor)
+            .zip(Decimal::from_f64(analysis.offset))
+            .and_then(|(factor, offset)| self.value.checked_mul(factor)?.checked_add(offset))
+            .and_then(|value| value.round_sf(CANONICAL_SIGNIFICANT_DIGITS))
+            .map(|value| value.normalize())
+            .ok_or_else(|| ModelError::conversion_error(unit.as_str(), "canonical units"))?;
+
+        Ok(Quantity::new(
+            value,
+            Some(canonical_unit_string(&analysis.dimension)),
+        ))
+    }
+
+    /// Format as a FHIRPath quantity literal, e.g. `1000 'mg'` or `4 days`
+    pub fn to_literal_string(&self) -> String {
+        if let Some(keyword) = self.calendar_keyword() {
+            return format!("{} {}", self.value, keyword);
+        }
+        match &self.unit {
+            Some(unit) => format!("{} '{}'", self.value, unit),
+            None => self.value.to_string(),
         }
     }
 
@@ -295,9 +377,35 @@ impl Quantity {
     }
 }
 
+/// Build the canonical unit for a dimension vector
+///
+/// UCUM conversion factors are relative to the gram, so mass is expressed in
+/// `g` rather than the SI `kg`.
+fn canonical_unit_string(dimension: &octofhir_ucum::Dimension) -> String {
+    const BASE_UNITS: [&str; 7] = ["g", "m", "s", "A", "K", "mol", "cd"];
+
+    let parts: Vec<String> = BASE_UNITS
+        .iter()
+        .zip(dimension.0.iter())
+        .filter(|(_, exponent)| **exponent != 0)
+        .map(|(unit, exponent)| match exponent {
+            1 => unit.to_string(),
+            _ => format!("{unit}{exponent}"),
+        })
+        .collect();
+
+    if parts.is_empty() {
+        "1".to_string()
+    } else {
+        parts.join(".")
+    }
+}
+
 impl fmt::Display for Quantity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if let Some(unit) = &self.unit {
+        if let Some(keyword) = self.calendar_keyword() {
+            write!(f, "{} {}", self.value, keyword)
+        } else if let Some(unit) = &self.unit {
             write!(f, "{} {}", self.value, unit)
         } else {
             write!(f, "{}", self.value)
@@ -320,6 +428,28 @@ mod tests {
         assert_eq!(q2.unit, None);
     }
 
+    #[test]
+    fn test_calendar_durations() {
+        let year = Quantity::new(Decimal::from(1), Some("year".to_string()));
+        assert!(year.is_calendar_duration());
+        assert_eq!(year.unit, Some("a".to_string()));
+        assert_eq!(year.to_literal_string(), "1 year");
+
+        let days = Quantity::new(Decimal::from(4), Some("days".to_string()));
+        assert_eq!(days.unit, Some("d".to_string()));
+        assert_eq!(days.to_literal_string(), "4 days");
+
+        let ucum_year = Quantity::new(Decimal::from(1), Some("a".to_string()));
+        assert!(!ucum_year.is_calendar_duration());
+        assert!(!year.is_comparable_with(&ucum_year));
+        assert!(year.is_comparable_with(&year));
+
+        let week = Quantity::new(Decimal::from(1), Some("week".to_string()));
+        let ucum_week = Quantity::new(Decimal::from(1), Some("wk".to_string()));
+        assert!(week.is_comparable_with(&ucum_week));
+        assert_eq!(Quantity::calendar_unit("wk"), None);
+    }
+
     #[test]
     fn test_quantity_arithmetic() {
         let q1 = Quantity::new(Decimal::from(5), Some("mg".to_string()));
@@ -343,6 +473,38 @@ mod tests {
         assert!(q1.subtract(&q2).is_err());
     }
 
+    #[test]
+    fn test_to_canonical() {
+        let q = Quantity::new(Decimal::from(1000), Some("mg".to_string()));
+        let canonical = q.to_canonical().unwrap();
+        assert_eq!(canonical.value, Decimal::from(1));
+        assert_eq!(canonical.unit, Some("g".to_string()));
+        assert_eq!(q.to_literal_string(), "1000 'mg'");
+        assert_eq!(canonical.to_literal_string(), "1 'g'");
+
+        // Offsets apply, without floating point noise
+        let boiling = Quantity::new(Decimal::from(212), Some("[degF]".to_string()));
+        assert_eq!(
+            boiling.to_canonical().unwrap().to_literal_string(),
+            "373.15 'K'"
+        );
+
+        // The value itself never goes through f64
+        let precise = Quantity::new("0.1234567890123".parse().unwrap(), Some("kg".to_string()));
+        assert_eq!(
+            precise.to_canonical().unwrap().to_literal_string(),
+            "123.4567890123 'g'"
+        );
+
+        // Values out of Decimal range after conversion are an error, not 0
+        let huge = Quantity::new(Decimal::MAX, Some("km".to_string()));
+        assert!(huge.to_canonical().is_err());
+
+        let unitless = Quantity::unitless(Decimal::from(7));
+        assert_eq!(unitless.to_canonical().unwrap(), unitless);
+        assert_eq!(unitless.to_literal_string(), "7");
+    }
+
     #[test]
     fn test_ucum_conversion_debug() {
         // Test the g to mg conversion like in the failing test
@@ -1,16 +1,28 @@
 //! FHIR resource wrapper types
 
 use super::json_arc::ArcJsonValue;
+use super::type_compatibility::{choice_element_type, choice_type};
+use super::types::TypeInfo;
+use super::value::FhirPathValue;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 /// Represents a FHIR resource or complex object
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct FhirResource {
     /// The JSON representation of the resource (Arc-wrapped for efficiency)
     data: ArcJsonValue,
     /// Optional resource type for optimization
     resource_type: Option<String>,
+    /// FHIR type of a complex element, when navigation determined it
+    element_type: Option<String>,
+}
+
+/// Resources are equal when their JSON is, whatever is known about their type
+impl PartialEq for FhirResource {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
 }
 
 impl FhirResource {
@@ -25,6 +37,7 @@ impl FhirResource {
         Self {
             data: ArcJsonValue::new(data),
             resource_type,
+            element_type: None,
         }
     }
 
@@ -39,6 +52,7 @@ impl FhirResource {
         Self {
             data,
             resource_type,
+            element_type: None,
         }
     }
 
@@ -62,31 +76,48 @@ impl FhirResource {
         self.resource_type.as_deref()
     }
 
+    /// Record the FHIR type of a complex element, e.g. `Quantity` for the
+    /// object found under `valueQuantity`
+    pub fn with_element_type(mut self, element_type: impl Into<String>) -> Self {
+        self.element_type = Some(element_type.into());
+        self
+    }
+
+    /// Get the FHIR type of a complex element, if known
+    pub fn element_type(&self) -> Option<&str> {
+        self.element_type.as_deref()
+    }
+
+    /// Get the type of this resource or element, if known
+    pub fn type_info(&self) -> Option<TypeInfo> {
+        match (self.resource_type(), self.element_type()) {
+            (Some(resource_type), _) => Some(TypeInfo::Resource(resource_type.to_string())),
+            (None, Some(element_type)) => Some(TypeInfo::named("FHIR", element_type)),
+            (None, None) => None,
+        }
+    }
+
     /// Get a property value by path
     pub fn get_property(&self, path: &str) -> Option<&Value> {
-        // Handle simple property access on JSON objects
-        match self.data.as_json() {
-            Value::Object(obj) => {
-                // First try direct property access
-                if let Some(value) = obj.get(path) {
-                    return Some(value);
-                }
-
-                // Handle FHIR polymorphic properties (e.g., value -> valueString, valueInteger, etc.)
-                if path == "value" {
-                    // Look for value[x] properties
-                    for key in obj.keys() {
-                        if key.starts_with("value") && key.len() > 5 {
-                            // Found a value[x] property (like valueString, valueInteger, etc.)
-                            return obj.get(key);
-                        }
-                    }
-                }
+        self.get_typed_property(path).map(|(value, _)| value)
+    }
 
-                None
-            }
-            _ => None,
+    /// Get a property value by name, with its type if it is a choice element
+    ///
+    /// A choice element `value[x]` is stored under a key naming its type, so
+    /// `value` finds `valueQuantity` with type `Quantity` and `valueString`
+    /// with type `string`. Only known choice elements are looked up this way,
+    /// so `birth` does not find `birthDate`.
+    pub fn get_typed_property(&self, name: &str) -> Option<(&Value, Option<String>)> {
+        let Value::Object(obj) = self.data.as_json() else {
+            return None;
+        };
+        if let Some(value) = obj.get(name) {
+            return Some((value, None));
         }
+
+        obj.iter()
+            .find_map(|(key, value)| Some((value, Some(choice_of(key, name)?))))
     }
 
     /// Get a property value by path (Arc-optimized version)
@@ -94,16 +125,12 @@ impl FhirResource {
         // Use the efficient Arc-based property access
         let result = self.data.get_property(path);
 
-        // Handle FHIR polymorphic properties if direct access failed
-        if result.is_none() && path == "value" {
-            if let Value::Object(obj) = self.data.as_json() {
-                // Look for value[x] properties
-                for key in obj.keys() {
-                    if key.starts_with("value") && key.len() > 5 {
-                        return self.data.get_property(key);
-                    }
-                }
-            }
+        // Choice elements are stored under a key naming their type
+        if result.is_none()
+            && let Value::Object(obj) = self.data.as_json()
+        {
+            let key = obj.keys().find(|key| choice_of(key, path).is_some())?;
+            return self.data.get_property(key);
         }
 
         result
@@ -135,6 +162,24 @@ impl FhirResource {
         }
     }
 
+    /// Get the value at a simple dotted path such as `name.0.given.0`
+    ///
+    /// Each segment is either an object key or, on arrays, a zero-based index.
+    /// This is plain JSON navigation without FHIRPath semantics: arrays are not
+    /// flattened, and choice types must be named in full (`valueString`).
+    /// Returns `None` if any segment is missing.
+    pub fn get_path(&self, path: &str) -> Option<FhirPathValue> {
+        let mut current = self.data.as_json();
+        for segment in path.split('.') {
+            current = match current {
+                Value::Object(obj) => obj.get(segment)?,
+                Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+        Some(FhirPathValue::from(current.clone()))
+    }
+
     /// Check if this resource has a specific property
     pub fn has_property(&self, path: &str) -> bool {
         self.get_property(path).is_some()
@@ -199,6 +244,13 @@ impl From<Value> for FhirResource {
     }
 }
 
+/// The type of the choice element `name` if it is stored under `key`, e.g.
+/// `Quantity` for `value` under `valueQuantity`
+fn choice_of(key: &str, name: &str) -> Option<String> {
+    let suffix = key.strip_prefix(name)?;
+    choice_element_type(key).filter(|choice| choice_type(suffix).as_ref() == Some(choice))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,6 +292,43 @@ mod tests {
         assert!(!resource.has_property("nonexistent"));
     }
 
+    #[test]
+    fn test_choice_property_access() {
+        let json = json!({
+            "resourceType": "Observation",
+            "valueQuantity": {"value": 185, "unit": "lbs"},
+            "valueSet": "not a choice element"
+        });
+
+        let resource = FhirResource::from_json(json);
+
+        let (value, choice_type) = resource.get_typed_property("value").unwrap();
+        assert_eq!(value, &json!({"value": 185, "unit": "lbs"}));
+        assert_eq!(choice_type.as_deref(), Some("Quantity"));
+        assert_eq!(
+            resource.get_typed_property("valueSet"),
+            Some((&json!("not a choice element"), None))
+        );
+        assert!(resource.get_typed_property("effective").is_none());
+
+        // Keys that merely start with the name are no choice elements
+        let patient = FhirResource::from_json(json!({
+            "resourceType": "Patient",
+            "birthDate": "1974-12-25",
+            "managingOrganization": {"reference": "Organization/1"}
+        }));
+        assert!(patient.get_typed_property("birth").is_none());
+        assert!(patient.get_typed_property("managing").is_none());
+        assert!(patient.get_property_arc("birth").is_none());
+
+        let quantity = FhirResource::from_json(value.clone()).with_element_type("Quantity");
+        assert_eq!(
+            quantity.type_info(),
+            Some(TypeInfo::named("FHIR", "Quantity"))
+        );
+        assert_eq!(quantity, FhirResource::from_json(value.clone()));
+    }
+
     #[test]
     fn test_nested_property_access() {
         let json = json!({
@@ -261,6 +350,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_path() {
+        let json = json!({
+            "resourceType": "Patient",
+            "id": "123",
+            "name": [
+                {"given": ["John", "Jacob"], "family": "Doe"},
+                {"given": ["Johnny"]}
+            ]
+        });
+
+        let resource = FhirResource::from_json(json);
+
+        assert_eq!(resource.get_path("id"), Some(FhirPathValue::from("123")));
+        assert_eq!(
+            resource.get_path("name.0.given.1"),
+            Some(FhirPathValue::from("Jacob"))
+        );
+        assert_eq!(
+            resource.get_path("name.1.given.0"),
+            Some(FhirPathValue::from("Johnny"))
+        );
+        assert!(matches!(
+            resource.get_path("name.0.given"),
+            Some(FhirPathValue::Collection(items)) if items.len() == 2
+        ));
+        assert!(matches!(
+            resource.get_path("name.0"),
+            Some(FhirPathValue::JsonValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_path_missing() {
+        let json = json!({
+            "resourceType": "Patient",
+            "name": [{"given": ["John"], "family": "Doe"}]
+        });
+
+        let resource = FhirResource::from_json(json);
+
+        assert_eq!(resource.get_path("birthDate"), None);
+        assert_eq!(resource.get_path("name.1.given"), None);
+        assert_eq!(resource.get_path("name.0.given.5"), None);
+        // Arrays are only indexed, never flattened
+        assert_eq!(resource.get_path("name.given"), None);
+        // Primitives have no children
+        assert_eq!(resource.get_path("name.0.family.text"), None);
+        assert_eq!(resource.get_path(""), None);
+    }
+
     #[test]
     fn test_primitive_extensions() {
         let json = json!({
@@ -3,8 +3,14 @@
 //! This module provides string interning to reduce memory allocation overhead
 //! for commonly used strings like property names, function names, and literals.
 
+use super::counting_cache::CountingCache;
 use dashmap::DashMap;
+use std::cell::RefCell;
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
 
 /// Thread-safe string interner using Arc for shared ownership
 pub struct StringInterner {
@@ -269,6 +275,87 @@ pub fn clear_global_interner() {
     GLOBAL_INTERNER.clear();
 }
 
+/// How many distinct strings one evaluation's JSON string interner keeps
+pub const JSON_STRING_INTERNER_CAPACITY: usize = 4096;
+
+thread_local! {
+    /// The interner of the evaluation being polled on this thread, if any
+    static CURRENT_JSON_INTERNER: RefCell<Option<Arc<JsonStringInterner>>> =
+        const { RefCell::new(None) };
+}
+
+/// Interner for the string values lifted from the JSON input of one evaluation
+///
+/// Large Bundles repeat the same systems, codes and urls many times; interned,
+/// those values share one allocation instead of allocating a fresh string each
+/// time they are navigated. Only the most recently used strings are kept, up
+/// to the capacity, so a large input cannot grow the interner without bound.
+pub(crate) struct JsonStringInterner {
+    strings: CountingCache<Arc<str>, Arc<str>>,
+}
+
+impl JsonStringInterner {
+    /// Create an interner keeping up to `capacity` strings
+    pub(crate) fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            strings: CountingCache::new(capacity),
+        }
+    }
+
+    /// Return the shared copy of `s`, adding it if not yet held
+    fn intern(&self, s: &str) -> Arc<str> {
+        if let Some(interned) = self.strings.get(s) {
+            return interned;
+        }
+        let interned: Arc<str> = Arc::from(s);
+        self.strings.put(interned.clone(), interned.clone());
+        interned
+    }
+
+    /// Run `future`, interning the JSON strings lifted while it is polled
+    pub(crate) fn scope<F: Future>(self: Arc<Self>, future: F) -> Interning<F> {
+        Interning {
+            interner: self,
+            future: Box::pin(future),
+        }
+    }
+}
+
+/// Future returned by [`JsonStringInterner::scope`]
+pub(crate) struct Interning<F> {
+    interner: Arc<JsonStringInterner>,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Interning<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Restores the interner polled before this one, even on panic
+        struct Restore(Option<Arc<JsonStringInterner>>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT_JSON_INTERNER.with(|current| current.replace(self.0.take()));
+            }
+        }
+
+        let this = &mut *self;
+        let _restore = Restore(
+            CURRENT_JSON_INTERNER.with(|current| current.replace(Some(this.interner.clone()))),
+        );
+        this.future.as_mut().poll(cx)
+    }
+}
+
+/// Convert a JSON string into a shared string, interning it when the
+/// evaluation being polled interns JSON strings
+pub(crate) fn json_string(s: &str) -> Arc<str> {
+    CURRENT_JSON_INTERNER.with(|current| match &*current.borrow() {
+        Some(interner) => interner.intern(s),
+        None => Arc::from(s),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,611 @@
+//! Partial-precision temporal values
+//!
+//! FHIRPath temporal literals may be written to any precision (`@2013`,
+//! `@2013-01`, `@2013-01-01T12:00:00Z`, `@T10:30`). The precision matters for
+//! comparison: two values that agree on every component they both specify but
+//! differ in precision cannot be ordered, and the comparison yields empty.
+
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike,
+};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// The finest component specified by a temporal value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TemporalPrecision {
+    /// Year only (`@2013`)
+    Year,
+    /// Year and month (`@2013-01`)
+    Month,
+    /// Full date (`@2013-01-01`)
+    Day,
+    /// Hour (`@T10`)
+    Hour,
+    /// Hour and minute (`@T10:30`)
+    Minute,
+    /// Whole seconds (`@T10:30:00`)
+    Second,
+    /// Fractional seconds (`@T10:30:00.000`)
+    Millisecond,
+}
+
+impl TemporalPrecision {
+    /// Precision used for comparisons
+    ///
+    /// Seconds and fractional seconds are a single precision for comparison
+    /// purposes, so `@T10:30:00 = @T10:30:00.000` is true.
+    fn comparison_precision(self) -> Self {
+        match self {
+            Self::Millisecond => Self::Second,
+            other => other,
+        }
+    }
+}
+
+/// A time of day together with the precision it was specified to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PrecisionTime {
+    /// The time of day, with unspecified components set to zero
+    pub time: NaiveTime,
+    /// The finest component that was specified
+    pub precision: TemporalPrecision,
+}
+
+impl PrecisionTime {
+    /// Create a time with the given precision
+    pub fn new(time: NaiveTime, precision: TemporalPrecision) -> Self {
+        Self { time, precision }
+    }
+
+    /// Create a fully specified time, with fractional precision if it has a fraction
+    pub fn from_time(time: NaiveTime) -> Self {
+        let precision = if time.nanosecond() == 0 {
+            TemporalPrecision::Second
+        } else {
+            TemporalPrecision::Millisecond
+        };
+        Self::new(time, precision)
+    }
+
+    /// Parse a FHIRPath time (`HH`, `HH:MM`, `HH:MM:SS` or `HH:MM:SS.fff`)
+    ///
+    /// Each component must be two digits and in range; fractional seconds may
+    /// have any number of digits. Returns `None` for anything else.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (hms, fraction) = match s.split_once('.') {
+            Some((hms, fraction)) => (hms, Some(fraction)),
+            None => (s, None),
+        };
+
+        let parts: Vec<&str> = hms.split(':').collect();
+        if parts.is_empty() || parts.len() > 3 || (fraction.is_some() && parts.len() != 3) {
+            return None;
+        }
+
+        let mut components = [0u32; 3];
+        for (component, part) in components.iter_mut().zip(&parts) {
+            if part.len() != 2 || !part.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            *component = part.parse().ok()?;
+        }
+
+        let nanos = match fraction {
+            Some(digits) => {
+                if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                // Keep nanosecond resolution, dropping any extra digits
+                let padded = format!("{digits:0<9}");
+                padded[..9].parse().ok()?
+            }
+            None => 0,
+        };
+
+        let [hour, minute, second] = components;
+        let time = NaiveTime::from_hms_nano_opt(hour, minute, second, nanos)?;
+        let precision = match (parts.len(), fraction) {
+            (1, _) => TemporalPrecision::Hour,
+            (2, _) => TemporalPrecision::Minute,
+            (_, None) => TemporalPrecision::Second,
+            (_, Some(_)) => TemporalPrecision::Millisecond,
+        };
+
+        Some(Self::new(time, precision))
+    }
+
+    /// Compare two times honouring precision
+    ///
+    /// Components are compared from the hour down to the coarser of the two
+    /// precisions. Returns `None` when the times agree on every shared
+    /// component but are specified to different precisions.
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        compare_to_shared_precision(
+            NaiveDate::MIN.and_time(self.time),
+            self.precision,
+            NaiveDate::MIN.and_time(other.time),
+            other.precision,
+        )
+    }
+
+    /// Hash the components [`compare`](Self::compare) looks at, so times
+    /// comparing equal hash alike
+    ///
+    /// `@T10:30:00` and `@T10:30:00.000` compare equal, so seconds and
+    /// fractional seconds hash as one precision.
+    pub(crate) fn hash_compared<H: Hasher>(&self, state: &mut H) {
+        self.precision.comparison_precision().hash(state);
+        self.time.hash(state);
+    }
+}
+
+impl fmt::Display for PrecisionTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.precision {
+            TemporalPrecision::Hour => write!(f, "{}", self.time.format("%H")),
+            TemporalPrecision::Minute => write!(f, "{}", self.time.format("%H:%M")),
+            TemporalPrecision::Millisecond => write!(f, "{}", self.time.format("%H:%M:%S%.3f")),
+            _ => write!(f, "{}", self.time.format("%H:%M:%S")),
+        }
+    }
+}
+
+/// A calendar date together with the precision it was specified to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PrecisionDate {
+    /// The date, with unspecified components set to the first month or day
+    pub date: NaiveDate,
+    /// The finest component that was specified: year, month or day
+    pub precision: TemporalPrecision,
+}
+
+impl PrecisionDate {
+    /// Create a date with the given precision
+    pub fn new(date: NaiveDate, precision: TemporalPrecision) -> Self {
+        Self { date, precision }
+    }
+
+    /// Create a fully specified date
+    pub fn from_date(date: NaiveDate) -> Self {
+        Self::new(date, TemporalPrecision::Day)
+    }
+
+    /// Parse a FHIRPath date (`YYYY`, `YYYY-MM` or `YYYY-MM-DD`)
+    ///
+    /// The year must be four digits and the month and day two, all in range.
+    /// Returns `None` for anything else.
+    pub fn parse(s: &str) -> Option<Self> {
+        let parts: Vec<&str> = s.split('-').collect();
+        if parts.len() > 3 {
+            return None;
+        }
+
+        let mut components = [0u32, 1, 1];
+        for (i, (component, part)) in components.iter_mut().zip(&parts).enumerate() {
+            let width = if i == 0 { 4 } else { 2 };
+            if part.len() != width || !part.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            *component = part.parse().ok()?;
+        }
+
+        let [year, month, day] = components;
+        let date = NaiveDate::from_ymd_opt(year as i32, month, day)?;
+        let precision = match parts.len() {
+            1 => TemporalPrecision::Year,
+            2 => TemporalPrecision::Month,
+            _ => TemporalPrecision::Day,
+        };
+
+        Some(Self::new(date, precision))
+    }
+
+    /// Compare two dates honouring precision
+    ///
+    /// Returns `None` when the dates agree on every shared component but are
+    /// specified to different precisions, as `@2013` and `@2013-01` are.
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        compare_to_shared_precision(
+            self.date.into(),
+            self.precision,
+            other.date.into(),
+            other.precision,
+        )
+    }
+
+    /// The date as a date time at its own precision, without a timezone
+    pub fn to_datetime(&self) -> PrecisionDateTime {
+        let midnight = FixedOffset::east_opt(0)
+            .unwrap()
+            .from_utc_datetime(&self.date.into());
+        PrecisionDateTime::new(midnight, self.precision, false)
+    }
+}
+
+impl fmt::Display for PrecisionDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.precision {
+            TemporalPrecision::Year => write!(f, "{}", self.date.format("%Y")),
+            TemporalPrecision::Month => write!(f, "{}", self.date.format("%Y-%m")),
+            _ => write!(f, "{}", self.date.format("%Y-%m-%d")),
+        }
+    }
+}
+
+/// A point in time together with the precision it was specified to
+///
+/// A date time written without a timezone is stored as UTC, with
+/// `timezone_specified` false so it still prints as it was written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PrecisionDateTime {
+    /// The date and time, with unspecified components set to their minimum
+    pub datetime: DateTime<FixedOffset>,
+    /// The finest component that was specified, from year to millisecond
+    pub precision: TemporalPrecision,
+    /// Whether a timezone offset or `Z` was given
+    pub timezone_specified: bool,
+}
+
+impl PrecisionDateTime {
+    /// Create a date time with the given precision
+    pub fn new(
+        datetime: DateTime<FixedOffset>,
+        precision: TemporalPrecision,
+        timezone_specified: bool,
+    ) -> Self {
+        Self {
+            datetime,
+            precision,
+            timezone_specified,
+        }
+    }
+
+    /// Create a fully specified date time with a timezone, with fractional
+    /// precision if it has a fraction
+    pub fn from_datetime(datetime: DateTime<FixedOffset>) -> Self {
+        let precision = PrecisionTime::from_time(datetime.time()).precision;
+        Self::new(datetime, precision, true)
+    }
+
+    /// Parse a FHIRPath date time: a date, `T`, and optionally a time and a
+    /// timezone (`Z` or `+HH:MM`/`-HH:MM`), as in `2013T`, `2013-01-01T12` or
+    /// `2013-01-01T12:00:00.000+10:00`
+    ///
+    /// A time may only follow a full date. Returns `None` for anything else.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (date, rest) = s.split_once('T')?;
+        let date = PrecisionDate::parse(date)?;
+
+        let (time, offset) = if let Some(time) = rest.strip_suffix('Z') {
+            (time, Some(FixedOffset::east_opt(0)?))
+        } else if let Some(sign_at) = rest.rfind(['+', '-']) {
+            let (time, offset) = rest.split_at(sign_at);
+            (time, Some(parse_offset(offset)?))
+        } else {
+            (rest, None)
+        };
+
+        // A timezone needs a time, and a time needs a full date
+        let (time, valid) = if time.is_empty() {
+            let midnight = PrecisionTime::new(NaiveTime::MIN, date.precision);
+            (midnight, offset.is_none())
+        } else {
+            let time = PrecisionTime::parse(time)?;
+            (time, date.precision == TemporalPrecision::Day)
+        };
+        if !valid {
+            return None;
+        }
+
+        let naive = date.date.and_time(time.time);
+        let datetime = match offset {
+            Some(offset) => offset.from_local_datetime(&naive).single()?,
+            None => FixedOffset::east_opt(0)?.from_utc_datetime(&naive),
+        };
+        Some(Self::new(datetime, time.precision, offset.is_some()))
+    }
+
+    /// Compare two date times honouring precision
+    ///
+    /// Both are normalized to UTC first. Returns `None` when they agree on
+    /// every shared component but are specified to different precisions, and
+    /// when both give a time but only one a timezone, as the other's offset
+    /// from UTC is unknown.
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        let both_timed =
+            self.precision >= TemporalPrecision::Hour && other.precision >= TemporalPrecision::Hour;
+        if both_timed && self.timezone_specified != other.timezone_specified {
+            return None;
+        }
+        compare_to_shared_precision(
+            self.datetime.naive_utc(),
+            self.precision,
+            other.datetime.naive_utc(),
+            other.precision,
+        )
+    }
+
+    /// Hash the components [`compare`](Self::compare) looks at, so date times
+    /// comparing equal hash alike
+    ///
+    /// `@2018-01-01T10+05:30` and `@2018-01-01T04Z` compare equal at hour
+    /// precision although their minutes in UTC differ, so only components
+    /// down to the precision are hashed.
+    pub(crate) fn hash_compared<H: Hasher>(&self, state: &mut H) {
+        let precision = self.precision.comparison_precision();
+        let utc = self.datetime.naive_utc();
+        let components = [
+            (TemporalPrecision::Year, i64::from(utc.year())),
+            (TemporalPrecision::Month, i64::from(utc.month())),
+            (TemporalPrecision::Day, i64::from(utc.day())),
+            (TemporalPrecision::Hour, i64::from(utc.hour())),
+            (TemporalPrecision::Minute, i64::from(utc.minute())),
+            (
+                TemporalPrecision::Second,
+                i64::from(utc.second()) * 1_000_000_000 + i64::from(utc.nanosecond()),
+            ),
+        ];
+        precision.hash(state);
+        for (component_precision, component) in components {
+            if component_precision > precision {
+                break;
+            }
+            component.hash(state);
+        }
+    }
+}
+
+/// Compare the components two values both specify, from the year down
+///
+/// Seconds and fractional seconds are compared together. Returns `None` when
+/// every shared component is equal but the precisions differ.
+fn compare_to_shared_precision(
+    left: NaiveDateTime,
+    left_precision: TemporalPrecision,
+    right: NaiveDateTime,
+    right_precision: TemporalPrecision,
+) -> Option<Ordering> {
+    let left_precision = left_precision.comparison_precision();
+    let right_precision = right_precision.comparison_precision();
+    let shared = left_precision.min(right_precision);
+
+    let seconds = |dt: &NaiveDateTime| (dt.second(), dt.nanosecond());
+    let components = [
+        (TemporalPrecision::Year, left.year().cmp(&right.year())),
+        (TemporalPrecision::Month, left.month().cmp(&right.month())),
+        (TemporalPrecision::Day, left.day().cmp(&right.day())),
+        (TemporalPrecision::Hour, left.hour().cmp(&right.hour())),
+        (
+            TemporalPrecision::Minute,
+            left.minute().cmp(&right.minute()),
+        ),
+        (
+            TemporalPrecision::Second,
+            seconds(&left).cmp(&seconds(&right)),
+        ),
+    ];
+    for (precision, ordering) in components {
+        if precision > shared {
+            break;
+        }
+        if ordering.is_ne() {
+            return Some(ordering);
+        }
+    }
+
+    (left_precision == right_precision).then_some(Ordering::Equal)
+}
+
+/// Parse a timezone offset such as `+10:00` or `-05:30`
+fn parse_offset(s: &str) -> Option<FixedOffset> {
+    let (sign, hh_mm) = match s.split_at_checked(1)? {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let (hours, minutes) = hh_mm.split_once(':')?;
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+impl fmt::Display for PrecisionDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let date = PrecisionDate::new(
+            self.datetime.date_naive(),
+            self.precision.min(TemporalPrecision::Day),
+        );
+        if self.precision <= TemporalPrecision::Day {
+            return write!(f, "{date}");
+        }
+
+        let time = PrecisionTime::new(self.datetime.time(), self.precision);
+        write!(f, "{date}T{time}")?;
+        if self.timezone_specified {
+            write!(f, "{}", self.datetime.format("%:z"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> PrecisionTime {
+        PrecisionTime::parse(s).unwrap_or_else(|| panic!("'{s}' should parse"))
+    }
+
+    #[test]
+    fn test_parse_precisions() {
+        assert_eq!(time("14").precision, TemporalPrecision::Hour);
+        assert_eq!(time("14:30").precision, TemporalPrecision::Minute);
+        assert_eq!(time("14:30:00").precision, TemporalPrecision::Second);
+
+        let fractional = time("14:30:00.25");
+        assert_eq!(fractional.precision, TemporalPrecision::Millisecond);
+        assert_eq!(fractional.time.nanosecond(), 250_000_000);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        for input in [
+            "",
+            "1",
+            "24:00",
+            "14:60",
+            "14:30:",
+            "14:30.5",
+            "14:30:00.",
+            "2pm",
+            " 14:30",
+        ] {
+            assert!(
+                PrecisionTime::parse(input).is_none(),
+                "'{input}' should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for input in ["14", "14:30", "14:30:00", "14:30:00.123"] {
+            assert_eq!(time(input).to_string(), input);
+        }
+    }
+
+    #[test]
+    fn test_date_precisions() {
+        let date =
+            |s: &str| PrecisionDate::parse(s).unwrap_or_else(|| panic!("'{s}' should parse"));
+        assert_eq!(date("2013").precision, TemporalPrecision::Year);
+        assert_eq!(date("2013-01").precision, TemporalPrecision::Month);
+        assert_eq!(date("2013-01-02").precision, TemporalPrecision::Day);
+        for input in ["2013", "2013-01", "2013-01-02"] {
+            assert_eq!(date(input).to_string(), input);
+        }
+
+        for input in ["", "13", "2013-1", "2013-13", "2013-02-30", "2013-01-01-01"] {
+            assert!(
+                PrecisionDate::parse(input).is_none(),
+                "'{input}' should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_datetime_precisions() {
+        let datetime =
+            |s: &str| PrecisionDateTime::parse(s).unwrap_or_else(|| panic!("'{s}' should parse"));
+        assert_eq!(datetime("2013T").precision, TemporalPrecision::Year);
+        assert_eq!(datetime("2013-01-02T").precision, TemporalPrecision::Day);
+        assert_eq!(datetime("2013-01-02T10").precision, TemporalPrecision::Hour);
+        assert_eq!(
+            datetime("2013-01-02T10:30:00.5Z").precision,
+            TemporalPrecision::Millisecond
+        );
+
+        let with_offset = datetime("2013-01-02T10:30+10:00");
+        assert!(with_offset.timezone_specified);
+        assert_eq!(with_offset.datetime.offset().local_minus_utc(), 36000);
+        assert!(!datetime("2013-01-02T10:30").timezone_specified);
+
+        for input in [
+            "2013-01-02T10:30",
+            "2013-01-02T10:30:00.123",
+            "2013-01-02T10:30:00-05:00",
+        ] {
+            assert_eq!(datetime(input).to_string(), input);
+        }
+        assert_eq!(datetime("2013T").to_string(), "2013");
+        assert_eq!(
+            datetime("2013-01-02T10:30Z").to_string(),
+            "2013-01-02T10:30+00:00"
+        );
+
+        for input in [
+            "2013",
+            "2013TZ",
+            "2013-01T10",
+            "2013-01-02T25",
+            "2013-01-02T10+1000",
+        ] {
+            assert!(
+                PrecisionDateTime::parse(input).is_none(),
+                "'{input}' should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(time("10:30").compare(&time("10:31")), Some(Ordering::Less));
+        assert_eq!(
+            time("11").compare(&time("10:59:59")),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            time("10:30:00.5").compare(&time("10:30:00.25")),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            time("10:30:00").compare(&time("10:30:00.000")),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(time("10:30").compare(&time("10:30:00")), None);
+        assert_eq!(time("10").compare(&time("10:00")), None);
+
+        // Times comparing equal hash alike, though `==` is structural
+        let hash = |t: PrecisionTime| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            t.hash_compared(&mut hasher);
+            hasher.finish()
+        };
+        assert_ne!(time("10:30:00"), time("10:30:00.000"));
+        assert_eq!(hash(time("10:30:00")), hash(time("10:30:00.000")));
+    }
+
+    #[test]
+    fn test_compare_dates() {
+        let date = |s: &str| PrecisionDate::parse(s).unwrap();
+        assert_eq!(date("2012").compare(&date("2013")), Some(Ordering::Less));
+        assert_eq!(
+            date("2013-02").compare(&date("2013-01-31")),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(date("2013").compare(&date("2013-01")), None);
+        assert_eq!(date("2013-01").compare(&date("2013-01-02")), None);
+
+        let datetime = |s: &str| PrecisionDateTime::parse(s).unwrap();
+        assert_eq!(
+            datetime("2012-04-15T15:00:00Z").compare(&datetime("2012-04-15T10:00:00-05:00")),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            datetime("2012-04-15T10:00+10:00").compare(&datetime("2012-04-15T01:00Z")),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            date("2012-04-15")
+                .to_datetime()
+                .compare(&datetime("2012-04-15T10:00")),
+            None
+        );
+        assert_eq!(
+            datetime("2012-04-15T15:00:00Z").compare(&datetime("2012-04-15T10:00:00")),
+            None
+        );
+        assert_eq!(
+            datetime("2012-04-15T15:00:00").compare(&datetime("2012-04-15T15:00:00")),
+            Some(Ordering::Equal)
+        );
+    }
+}
@@ -0,0 +1,271 @@
+//! Type compatibility for type tests such as `ofType()`
+//!
+//! A type specifier names either a system type (`System.String`) or a FHIR
+//! type (`FHIR.string`, `FHIR.Patient`); unqualified names may be either. A
+//! value is of a type when its own type is that type or one of its subtypes in
+//! the FHIR type hierarchy, so a Patient is of type `DomainResource` and
+//! `Resource`.
+//!
+//! Primitive values do not remember which FHIR primitive they were read from,
+//! so a `System.String` value is of every FHIR type represented as a string
+//! (`string`, `code`, `uri`, ...), and a `System.DateTime` value is of type
+//! `dateTime` and `instant`.
+//!
+//! `is`, `as`, `ofType()` and `type()` all resolve type names here, so a
+//! qualified name and its unqualified form always mean the same type.
+
+use super::types::TypeInfo;
+use super::value::FhirPathValue;
+use crate::types::FhirTypeRegistry;
+use serde_json::Value;
+use std::sync::LazyLock;
+
+static FHIR_TYPES: LazyLock<FhirTypeRegistry> = LazyLock::new(FhirTypeRegistry::new);
+
+/// Check whether a single value is of the type named by `type_specifier`
+///
+/// Collections and empty values are of no type; objects of unknown type are
+/// only of type `Any`.
+pub fn is_value_of_type(value: &FhirPathValue, type_specifier: &str) -> bool {
+    match value_type_info(value) {
+        Some(type_info) => is_of_type(&type_info, type_specifier),
+        None => {
+            let (namespace, name) = parse_type_specifier(type_specifier);
+            name == "Any"
+                && namespace != Some("FHIR")
+                && !matches!(value, FhirPathValue::Collection(_) | FhirPathValue::Empty)
+        }
+    }
+}
+
+/// The type of a single value, if known
+///
+/// Resources know their resource type and choice elements their element
+/// type; other objects, collections and empty values have none.
+pub fn value_type_info(value: &FhirPathValue) -> Option<TypeInfo> {
+    match value {
+        FhirPathValue::Resource(resource) => match resource.as_json() {
+            Value::Bool(_) => Some(TypeInfo::Boolean),
+            Value::String(_) => Some(TypeInfo::String),
+            Value::Number(n) if n.is_i64() => Some(TypeInfo::Integer),
+            Value::Number(_) => Some(TypeInfo::Decimal),
+            _ => resource.type_info(),
+        },
+        FhirPathValue::TypeInfoObject { .. } => Some(TypeInfo::TypeInfo),
+        FhirPathValue::Collection(_) | FhirPathValue::Empty | FhirPathValue::JsonValue(_) => None,
+        other => Some(other.to_type_info()),
+    }
+}
+
+/// The namespace and name `type()` reports for a type
+pub fn type_specifier_of(type_info: &TypeInfo) -> (String, String) {
+    match type_info {
+        TypeInfo::Resource(name) => ("FHIR".to_string(), name.clone()),
+        TypeInfo::Named { namespace, name } => (namespace.clone(), name.clone()),
+        other => ("System".to_string(), other.type_name()),
+    }
+}
+
+/// Check whether a value of type `type_info` is of the type named by
+/// `type_specifier`
+pub fn is_of_type(type_info: &TypeInfo, type_specifier: &str) -> bool {
+    let (namespace, name) = parse_type_specifier(type_specifier);
+    let system = namespace != Some("FHIR");
+    let fhir = namespace != Some("System");
+
+    if name == "Any" && system {
+        return !matches!(type_info, TypeInfo::Collection(_));
+    }
+
+    match type_info {
+        TypeInfo::Boolean
+        | TypeInfo::Integer
+        | TypeInfo::Decimal
+        | TypeInfo::String
+        | TypeInfo::Date
+        | TypeInfo::DateTime
+        | TypeInfo::Time
+        | TypeInfo::Quantity => {
+            (system && type_info.type_name() == name)
+                || (fhir && fhir_system_type(name).as_ref() == Some(type_info))
+        }
+        TypeInfo::TypeInfo => system && name == "TypeInfo",
+        TypeInfo::Resource(resource_type) => fhir && is_resource_of_type(resource_type, name),
+        TypeInfo::Named {
+            namespace: type_namespace,
+            name: type_name,
+        } => match type_namespace.as_str() {
+            "System" => system && type_name == name,
+            _ => fhir && FHIR_TYPES.is_type_compatible(type_name, name),
+        },
+        TypeInfo::Optional(inner) => is_of_type(inner, type_specifier),
+        _ => false,
+    }
+}
+
+/// The system type that values of the FHIR type `name` are represented as
+pub fn fhir_system_type(name: &str) -> Option<TypeInfo> {
+    let type_info = match name {
+        "boolean" => TypeInfo::Boolean,
+        "integer" | "unsignedInt" | "positiveInt" | "integer64" => TypeInfo::Integer,
+        "decimal" => TypeInfo::Decimal,
+        "string" | "code" | "id" | "markdown" | "uri" | "url" | "canonical" | "oid" | "uuid"
+        | "base64Binary" | "xhtml" => TypeInfo::String,
+        "date" => TypeInfo::Date,
+        "dateTime" | "instant" => TypeInfo::DateTime,
+        "time" => TypeInfo::Time,
+        "Quantity" => TypeInfo::Quantity,
+        _ => return None,
+    };
+    Some(type_info)
+}
+
+/// The FHIR type named by the suffix of a choice element, e.g. `Quantity` for
+/// `valueQuantity` and `string` for `valueString`
+///
+/// Returns `None` if the suffix does not name a FHIR type, as for `valueSet`.
+pub fn choice_type(suffix: &str) -> Option<String> {
+    let mut chars = suffix.chars();
+    let first = chars.next().filter(char::is_ascii_uppercase)?;
+    let primitive = format!("{}{}", first.to_ascii_lowercase(), chars.as_str());
+    if fhir_system_type(&primitive).is_some_and(|t| t != TypeInfo::Quantity) {
+        Some(primitive)
+    } else if FHIR_TYPES.is_known_type(suffix) {
+        Some(suffix.to_string())
+    } else {
+        None
+    }
+}
+
+/// The FHIR type of the choice element stored under `key`, e.g. `Reference`
+/// for `valueReference`
+///
+/// Only keys made of a known choice element and one of its type suffixes
+/// count, so `managingOrganization` is not taken for an `Organization`.
+pub fn choice_element_type(key: &str) -> Option<String> {
+    key.char_indices()
+        .filter(|(_, c)| c.is_ascii_uppercase())
+        .find_map(|(split, _)| {
+            let (element, suffix) = key.split_at(split);
+            FHIR_TYPES
+                .get_polymorphic_suffixes(element)?
+                .iter()
+                .any(|allowed| allowed == suffix)
+                .then(|| choice_type(suffix))?
+        })
+}
+
+/// Resources missing from the type registry are still resources, and assumed
+/// to be domain resources like most
+fn is_resource_of_type(resource_type: &str, name: &str) -> bool {
+    if FHIR_TYPES.is_known_type(resource_type) {
+        FHIR_TYPES.is_type_compatible(resource_type, name)
+    } else {
+        matches!(name, "DomainResource" | "Resource") || resource_type == name
+    }
+}
+
+/// Split a type specifier into its namespace and name, dropping backticks
+///
+/// `FHIR.Quantity`, `` FHIR.`Quantity` `` and `Quantity` all give the name
+/// `Quantity`; only the first two restrict it to the FHIR namespace.
+pub fn parse_type_specifier(type_specifier: &str) -> (Option<&str>, &str) {
+    let type_specifier = type_specifier.trim();
+    match type_specifier.split_once('.') {
+        Some((namespace, name)) => {
+            let namespace = namespace.trim_matches('`');
+            match namespace {
+                "System" | "FHIR" => (Some(namespace), name.trim_matches('`')),
+                _ => (None, type_specifier.trim_matches('`')),
+            }
+        }
+        _ => (None, type_specifier.trim_matches('`')),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_types() {
+        assert!(is_of_type(&TypeInfo::String, "String"));
+        assert!(is_of_type(&TypeInfo::String, "System.String"));
+        assert!(is_of_type(&TypeInfo::Integer, "System.Any"));
+        assert!(!is_of_type(&TypeInfo::String, "System.Integer"));
+        assert!(!is_of_type(&TypeInfo::String, "FHIR.String"));
+    }
+
+    #[test]
+    fn test_fhir_primitive_types() {
+        assert!(is_of_type(&TypeInfo::String, "FHIR.string"));
+        assert!(is_of_type(&TypeInfo::String, "code"));
+        assert!(is_of_type(&TypeInfo::DateTime, "instant"));
+        assert!(is_of_type(&TypeInfo::DateTime, "FHIR.dateTime"));
+        assert!(!is_of_type(&TypeInfo::Date, "dateTime"));
+        assert!(!is_of_type(&TypeInfo::Integer, "System.integer"));
+    }
+
+    #[test]
+    fn test_parse_type_specifier() {
+        assert_eq!(parse_type_specifier("Quantity"), (None, "Quantity"));
+        assert_eq!(
+            parse_type_specifier("FHIR.Quantity"),
+            (Some("FHIR"), "Quantity")
+        );
+        assert_eq!(
+            parse_type_specifier("FHIR.`Quantity`"),
+            (Some("FHIR"), "Quantity")
+        );
+        assert_eq!(
+            parse_type_specifier("`System`.Integer"),
+            (Some("System"), "Integer")
+        );
+        assert_eq!(parse_type_specifier("`Quantity`"), (None, "Quantity"));
+    }
+
+    #[test]
+    fn test_value_types() {
+        let integer = FhirPathValue::Integer(1);
+        assert!(is_value_of_type(&integer, "Integer"));
+        assert!(is_value_of_type(&integer, "FHIR.integer"));
+        assert!(is_value_of_type(&integer, "Any"));
+        assert!(!is_value_of_type(&integer, "FHIR.Integer"));
+        assert_eq!(
+            type_specifier_of(&value_type_info(&integer).unwrap()),
+            ("System".to_string(), "Integer".to_string())
+        );
+
+        let empty = FhirPathValue::collection(vec![]);
+        assert!(value_type_info(&empty).is_none());
+        assert!(!is_value_of_type(&empty, "Any"));
+    }
+
+    #[test]
+    fn test_choice_type() {
+        assert_eq!(choice_type("Quantity").as_deref(), Some("Quantity"));
+        assert_eq!(choice_type("String").as_deref(), Some("string"));
+        assert_eq!(choice_type("DateTime").as_deref(), Some("dateTime"));
+        assert_eq!(
+            choice_type("CodeableConcept").as_deref(),
+            Some("CodeableConcept")
+        );
+        assert_eq!(choice_type("Set"), None);
+        assert_eq!(choice_type("quantity"), None);
+    }
+
+    #[test]
+    fn test_resource_hierarchy() {
+        let patient = TypeInfo::Resource("Patient".to_string());
+        assert!(is_of_type(&patient, "Patient"));
+        assert!(is_of_type(&patient, "FHIR.`Patient`"));
+        assert!(is_of_type(&patient, "DomainResource"));
+        assert!(is_of_type(&patient, "FHIR.Resource"));
+        assert!(!is_of_type(&patient, "Observation"));
+        assert!(!is_of_type(&patient, "System.Patient"));
+
+        let bundle = TypeInfo::Resource("Bundle".to_string());
+        assert!(is_of_type(&bundle, "Resource"));
+        assert!(!is_of_type(&bundle, "DomainResource"));
+    }
+}
@@ -1,6 +1,6 @@
 //! Core value types for FHIRPath expressions
 
-use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime};
+use chrono::{DateTime, NaiveDate};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -11,6 +11,8 @@ use std::sync::Arc;
 use super::json_arc::ArcJsonValue;
 use super::quantity::Quantity;
 use super::resource::FhirResource;
+use super::string_intern::json_string;
+use super::temporal::{PrecisionDate, PrecisionDateTime, PrecisionTime, TemporalPrecision};
 use super::types::TypeInfo;
 
 /// Core value type for FHIRPath expressions
@@ -18,6 +20,10 @@ use super::types::TypeInfo;
 /// This enum represents all possible values that can be produced by FHIRPath expressions.
 /// All values in FHIRPath are conceptual collections, but single values are represented
 /// directly for performance reasons.
+///
+/// The derived `PartialEq` is structural: `Integer(1)` and `Decimal(1.0)` differ,
+/// as do `1 'cm'` and `10 'mm'`. Use [`fhirpath_eq`](Self::fhirpath_eq) for the
+/// equality of the FHIRPath `=` operator.
 #[derive(Clone, PartialEq)]
 pub enum FhirPathValue {
     /// Boolean value
@@ -32,14 +38,14 @@ pub enum FhirPathValue {
     /// String value
     String(Arc<str>),
 
-    /// Date value (without time)
-    Date(NaiveDate),
+    /// Date value (without time), with the precision it was specified to
+    Date(PrecisionDate),
 
-    /// DateTime value with timezone
-    DateTime(DateTime<FixedOffset>),
+    /// DateTime value, with the precision it was specified to
+    DateTime(PrecisionDateTime),
 
-    /// Time value (without date)
-    Time(NaiveTime),
+    /// Time value (without date), with the precision it was specified to
+    Time(PrecisionTime),
 
     /// Quantity value with optional unit
     Quantity(Arc<Quantity>),
@@ -448,6 +454,24 @@ impl FhirPathValue {
         Self::Quantity(Arc::new(Quantity::new(value, unit)))
     }
 
+    /// Read a FHIR Quantity element (an object with `value` and `code` or `unit`)
+    /// as a System Quantity
+    ///
+    /// Navigation keeps such elements as JSON so `code`, `system`, `comparator`
+    /// and extensions stay reachable; operators use this to treat them as
+    /// quantities. Returns `None` for anything that is not a quantity element.
+    pub fn quantity_element(&self) -> Option<Arc<Quantity>> {
+        let json = match self {
+            Self::Resource(resource) => resource.as_json(),
+            Self::JsonValue(json) => json.as_json(),
+            _ => return None,
+        };
+        match json {
+            Value::Object(obj) => quantity_from_json(obj).map(Arc::new),
+            _ => None,
+        }
+    }
+
     /// Create an interned string value (more memory efficient for common strings)
     pub fn interned_string<S: AsRef<str>>(s: S) -> Self {
         use super::intern_string;
@@ -520,6 +544,15 @@ impl FhirPathValue {
         }
     }
 
+    /// Whether two values are equal under the FHIRPath `=` operator
+    ///
+    /// Integers equal decimals of the same value, quantities are compared
+    /// across compatible units and elements by their content. Values for which
+    /// `=` is empty, such as dates of different precision, are not equal.
+    pub fn fhirpath_eq(&self, other: &FhirPathValue) -> bool {
+        crate::registry::operators::values_equal(self, other)
+    }
+
     /// Get the first item from a collection, or the value itself if single
     pub fn first(&self) -> Option<&FhirPathValue> {
         match self {
@@ -558,9 +591,9 @@ impl FhirPathValue {
             Self::Boolean(b) => Some(b.to_string()),
             Self::Integer(i) => Some(i.to_string()),
             Self::Decimal(d) => Some(d.to_string()),
-            Self::Date(d) => Some(d.format("%Y-%m-%d").to_string()),
-            Self::DateTime(dt) => Some(dt.to_rfc3339()),
-            Self::Time(t) => Some(t.format("%H:%M:%S").to_string()),
+            Self::Date(d) => Some(d.to_string()),
+            Self::DateTime(dt) => Some(dt.to_string()),
+            Self::Time(t) => Some(t.to_string()),
             Self::Quantity(q) => Some(q.to_string()),
             Self::JsonValue(json) => match json.as_json() {
                 Value::String(s) => Some(s.clone()),
@@ -592,6 +625,20 @@ impl FhirPathValue {
         }
     }
 
+    /// Check whether this value is an instance of the named System type
+    ///
+    /// `type_name` is the unqualified name (`Boolean`, `Decimal`, ...). Each
+    /// System primitive corresponds to exactly one variant, so `1` is an
+    /// `Integer` but not a `Decimal`. `Any` matches every non-empty value.
+    pub fn is_system_type(&self, type_name: &str) -> bool {
+        let type_name = type_name.trim_matches('`');
+        match self {
+            Self::Empty => false,
+            Self::Collection(_) | Self::Resource(_) | Self::JsonValue(_) => type_name == "Any",
+            _ => type_name == "Any" || self.type_name() == type_name,
+        }
+    }
+
     /// Get the TypeInfo for this value
     pub fn to_type_info(&self) -> TypeInfo {
         match self {
@@ -630,13 +677,56 @@ impl FhirPathValue {
     }
 
     /// Try to convert to a decimal
+    ///
+    /// Like the other typed accessors below, this also accepts a collection
+    /// holding exactly one decimal.
     pub fn as_decimal(&self) -> Option<&Decimal> {
-        match self {
+        match self.singleton_item() {
             Self::Decimal(d) => Some(d),
             _ => None,
         }
     }
 
+    /// Get a boolean from a boolean or a single-element collection of one
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.singleton_item() {
+            Self::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Get an integer from an integer or a single-element collection of one
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.singleton_item() {
+            Self::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// Get a string slice from a string or a single-element collection of one
+    pub fn as_str(&self) -> Option<&str> {
+        match self.singleton_item() {
+            Self::String(s) => Some(s.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Get the resource from a resource or a single-element collection of one
+    pub fn as_resource(&self) -> Option<&FhirResource> {
+        match self.singleton_item() {
+            Self::Resource(resource) => Some(resource),
+            _ => None,
+        }
+    }
+
+    /// The only item of a single-element collection, or the value itself
+    fn singleton_item(&self) -> &FhirPathValue {
+        match self {
+            Self::Collection(items) if items.len() == 1 => items.get(0).unwrap_or(self),
+            _ => self,
+        }
+    }
+
     /// Try to convert to a string
     pub fn as_string(&self) -> Option<&str> {
         match self {
@@ -717,6 +807,20 @@ impl FhirPathValue {
     }
 }
 
+/// Read a JSON object with a numeric `value` and a `code` or `unit` as a Quantity
+fn quantity_from_json(obj: &serde_json::Map<String, Value>) -> Option<Quantity> {
+    if !obj.contains_key("unit") && !obj.contains_key("code") {
+        return None;
+    }
+    let value = Decimal::try_from(obj.get("value")?.as_f64()?).ok()?;
+    let unit = obj
+        .get("code")
+        .or_else(|| obj.get("unit"))
+        .and_then(|u| u.as_str())
+        .map(|s| s.to_string());
+    Some(Quantity::new(value, unit))
+}
+
 /// Convert from serde_json::Value to FhirPathValue with CoW optimization
 impl From<Value> for FhirPathValue {
     fn from(value: Value) -> Self {
@@ -738,15 +842,15 @@ impl From<Value> for FhirPathValue {
             Value::String(s) => {
                 // Try to parse as date/datetime/time first
                 if let Ok(date) = NaiveDate::parse_from_str(&s, "%Y-%m-%d") {
-                    Self::Date(date)
+                    Self::Date(PrecisionDate::from_date(date))
                 } else if let Ok(datetime) = DateTime::parse_from_rfc3339(&s) {
-                    Self::DateTime(datetime.fixed_offset())
-                } else if let Ok(time) = NaiveTime::parse_from_str(&s, "%H:%M:%S") {
-                    Self::Time(time)
-                } else if let Ok(time) = NaiveTime::parse_from_str(&s, "%H:%M:%S%.f") {
+                    Self::DateTime(PrecisionDateTime::from_datetime(datetime))
+                } else if let Some(time) =
+                    PrecisionTime::parse(&s).filter(|t| t.precision >= TemporalPrecision::Second)
+                {
                     Self::Time(time)
                 } else {
-                    Self::String(Arc::from(s.as_str()))
+                    Self::String(json_string(&s))
                 }
             }
             Value::Array(arr) => {
@@ -755,25 +859,8 @@ impl From<Value> for FhirPathValue {
             }
             Value::Object(ref obj) => {
                 // Check if this looks like a Quantity
-                if obj.contains_key("value")
-                    && (obj.contains_key("unit") || obj.contains_key("code"))
-                {
-                    if let Some(value_json) = obj.get("value") {
-                        if let Some(value_num) = value_json.as_f64() {
-                            let unit = obj
-                                .get("code")
-                                .or_else(|| obj.get("unit"))
-                                .and_then(|u| u.as_str())
-                                .map(|s| s.to_string());
-
-                            if let Ok(decimal_value) = Decimal::try_from(value_num) {
-                                return Self::Quantity(Arc::new(Quantity::new(
-                                    decimal_value,
-                                    unit,
-                                )));
-                            }
-                        }
-                    }
+                if let Some(quantity) = quantity_from_json(obj) {
+                    return Self::Quantity(Arc::new(quantity));
                 }
 
                 // Check if this looks like a TypeInfo object
@@ -821,11 +908,9 @@ impl From<FhirPathValue> for Value {
                 }
             }
             FhirPathValue::String(s) => Value::String(s.as_ref().to_string()),
-            FhirPathValue::Date(d) => Value::String(format!("@{}", d.format("%Y-%m-%d"))),
-            FhirPathValue::DateTime(dt) => {
-                Value::String(format!("@{}", dt.format("%Y-%m-%dT%H:%M:%S%.3f%z")))
-            }
-            FhirPathValue::Time(t) => Value::String(format!("@T{}", t.format("%H:%M:%S"))),
+            FhirPathValue::Date(d) => Value::String(format!("@{d}")),
+            FhirPathValue::DateTime(dt) => Value::String(format!("@{dt}")),
+            FhirPathValue::Time(t) => Value::String(format!("@T{t}")),
             FhirPathValue::Quantity(q) => q.to_json(),
             FhirPathValue::Collection(items) => {
                 let json_items: Vec<Value> = items.into_iter().map(Value::from).collect();
@@ -855,23 +940,11 @@ impl fmt::Display for FhirPathValue {
             Self::Boolean(b) => write!(f, "{b}"),
             Self::Integer(i) => write!(f, "{i}"),
             Self::Decimal(d) => write!(f, "{d}"),
-            Self::Date(d) => write!(f, "@{}", d.format("%Y-%m-%d")),
-            Self::DateTime(dt) => {
-                let formatted = dt.format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string();
-                // Convert timezone format from +0000 to +00:00
-                let formatted = if formatted.len() >= 5 {
-                    let (main, tz) = formatted.split_at(formatted.len() - 5);
-                    if tz.len() == 5 && (tz.starts_with('+') || tz.starts_with('-')) {
-                        format!("{}{}:{}", main, &tz[..3], &tz[3..])
-                    } else {
-                        formatted
-                    }
-                } else {
-                    formatted
-                };
-                write!(f, "@{formatted}")
-            }
-            Self::Time(t) => write!(f, "@T{}", t.format("%H:%M:%S")),
+            Self::Date(d) => write!(f, "@{d}"),
+            // A date time without a time still needs its `T`, as in `@2013T`
+            Self::DateTime(dt) if dt.precision <= TemporalPrecision::Day => write!(f, "@{dt}T"),
+            Self::DateTime(dt) => write!(f, "@{dt}"),
+            Self::Time(t) => write!(f, "@T{t}"),
             Self::Quantity(q) => write!(f, "{q}"),
             Self::Collection(items) => {
                 let item_strings: Vec<String> = items.iter().map(|item| item.to_string()).collect();
@@ -908,9 +981,9 @@ impl fmt::Debug for FhirPathValue {
             Self::Boolean(b) => write!(f, "Boolean({b})"),
             Self::Integer(i) => write!(f, "Integer({i})"),
             Self::Decimal(d) => write!(f, "Decimal({d})"),
-            Self::Date(d) => write!(f, "Date({})", d.format("%Y-%m-%d")),
-            Self::DateTime(dt) => write!(f, "DateTime({})", dt.to_rfc3339()),
-            Self::Time(t) => write!(f, "Time({})", t.format("%H:%M:%S")),
+            Self::Date(d) => write!(f, "Date({d})"),
+            Self::DateTime(dt) => write!(f, "DateTime({dt})"),
+            Self::Time(t) => write!(f, "Time({t})"),
             Self::Quantity(q) => write!(f, "Quantity({q})"),
             Self::Collection(items) => {
                 // Show the collection contents without nested Collection wrapper
@@ -1342,4 +1415,51 @@ mod tests {
             assert_eq!(name.as_ref(), "String");
         }
     }
+
+    #[test]
+    fn test_typed_accessors() {
+        let singleton = |value: FhirPathValue| FhirPathValue::collection(vec![value]);
+
+        let boolean = FhirPathValue::Boolean(true);
+        assert_eq!(boolean.as_bool(), Some(true));
+        assert_eq!(singleton(boolean).as_bool(), Some(true));
+
+        let integer = FhirPathValue::Integer(42);
+        assert_eq!(integer.as_i64(), Some(42));
+        assert_eq!(singleton(integer.clone()).as_i64(), Some(42));
+        assert_eq!(integer.as_bool(), None);
+
+        let decimal = FhirPathValue::Decimal(Decimal::new(314, 2));
+        assert_eq!(decimal.as_decimal(), Some(&Decimal::new(314, 2)));
+        assert_eq!(singleton(decimal).as_decimal(), Some(&Decimal::new(314, 2)));
+
+        let string = FhirPathValue::String("hello".into());
+        assert_eq!(string.as_str(), Some("hello"));
+        assert_eq!(singleton(string.clone()).as_str(), Some("hello"));
+        assert_eq!(string.as_i64(), None);
+
+        let resource = FhirPathValue::resource_from_json(serde_json::json!({
+            "resourceType": "Patient",
+            "id": "example"
+        }));
+        assert_eq!(
+            resource.as_resource().and_then(|r| r.resource_type()),
+            Some("Patient")
+        );
+        assert_eq!(
+            singleton(resource)
+                .as_resource()
+                .and_then(|r| r.resource_type()),
+            Some("Patient")
+        );
+    }
+
+    #[test]
+    fn test_typed_accessors_reject_non_singletons() {
+        let pair =
+            FhirPathValue::collection(vec![FhirPathValue::Integer(1), FhirPathValue::Integer(2)]);
+        assert_eq!(pair.as_i64(), None);
+        assert_eq!(FhirPathValue::Empty.as_bool(), None);
+        assert_eq!(FhirPathValue::collection(vec![]).as_str(), None);
+    }
 }
@@ -3,6 +3,7 @@
 use crate::diagnostics::{Diagnostic, DiagnosticBuilder, DiagnosticCode};
 use nom::error::{ErrorKind, ParseError as NomParseError};
 use std::borrow::Cow;
+use std::ops::Range;
 use thiserror::Error;
 
 /// Pre-allocated common error messages for performance
@@ -89,6 +90,10 @@ pub enum ParseError {
     #[error("Unexpected end of input")]
     UnexpectedEof,
 
+    /// The expression is empty or only whitespace and comments
+    #[error("Empty expression")]
+    EmptyExpression,
+
     /// Expected token
     #[error("Expected {expected} at position {position}")]
     ExpectedToken {
@@ -173,6 +178,7 @@ impl std::fmt::Debug for ParseError {
                 .field("position", position)
                 .finish(),
             Self::UnexpectedEof => write!(f, "UnexpectedEof"),
+            Self::EmptyExpression => write!(f, "EmptyExpression"),
             Self::ExpectedToken { expected, position } => f
                 .debug_struct("ExpectedToken")
                 .field("expected", expected)
@@ -235,6 +241,7 @@ impl Clone for ParseError {
                 position: *position,
             },
             Self::UnexpectedEof => Self::UnexpectedEof,
+            Self::EmptyExpression => Self::EmptyExpression,
             Self::ExpectedToken { expected, position } => Self::ExpectedToken {
                 expected: expected.clone(),
                 position: *position,
@@ -307,6 +314,7 @@ impl PartialEq for ParseError {
                 },
             ) => t1 == t2 && p1 == p2,
             (Self::UnexpectedEof, Self::UnexpectedEof) => true,
+            (Self::EmptyExpression, Self::EmptyExpression) => true,
             (
                 Self::ExpectedToken {
                     expected: e1,
@@ -385,6 +393,40 @@ impl PartialEq for ParseError {
 }
 
 impl ParseError {
+    /// Byte offset in the expression where the error occurred, if known
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            Self::SyntaxError { position, .. }
+            | Self::UnexpectedToken { position, .. }
+            | Self::ExpectedToken { position, .. }
+            | Self::UnexpectedEndOfInput { position }
+            | Self::InvalidLiteral { position, .. }
+            | Self::InvalidEscape { position, .. }
+            | Self::UnclosedString { position }
+            | Self::InvalidIdentifier { position, .. }
+            | Self::NomError { position, .. }
+            | Self::LazyFormatted { position, .. } => Some(*position),
+            Self::UnexpectedEof | Self::EmptyExpression => None,
+        }
+    }
+
+    /// Set the byte offset reported by [`position`](Self::position)
+    pub(crate) fn set_position(&mut self, offset: usize) {
+        match self {
+            Self::SyntaxError { position, .. }
+            | Self::UnexpectedToken { position, .. }
+            | Self::ExpectedToken { position, .. }
+            | Self::UnexpectedEndOfInput { position }
+            | Self::InvalidLiteral { position, .. }
+            | Self::InvalidEscape { position, .. }
+            | Self::UnclosedString { position }
+            | Self::InvalidIdentifier { position, .. }
+            | Self::NomError { position, .. }
+            | Self::LazyFormatted { position, .. } => *position = offset,
+            Self::UnexpectedEof | Self::EmptyExpression => {}
+        }
+    }
+
     /// Convert to a diagnostic
     pub fn to_diagnostic(&self) -> Diagnostic {
         match self {
@@ -403,6 +445,11 @@ impl ParseError {
             ParseError::UnexpectedEof => DiagnosticBuilder::error(DiagnosticCode::UnexpectedToken)
                 .with_message("Unexpected end of input")
                 .build(),
+            ParseError::EmptyExpression => {
+                DiagnosticBuilder::error(DiagnosticCode::UnexpectedToken)
+                    .with_message("Empty expression")
+                    .build()
+            }
             ParseError::ExpectedToken { expected, position } => {
                 DiagnosticBuilder::error(DiagnosticCode::ExpectedToken(expected.to_string()))
                     .with_message(format!("Expected {expected}"))
@@ -609,6 +656,70 @@ impl ParseError {
     }
 }
 
+/// A [`ParseError`] together with the expression it occurred in
+///
+/// `span` is the byte range of the token the parser stopped at, or an empty
+/// range at the end of the expression when more input was expected. The
+/// `Display` form underlines it:
+///
+/// ```text
+/// Expected identifier at position 13
+///   Patient.name.
+///                ^
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedParseError {
+    /// What went wrong
+    pub error: ParseError,
+    /// Byte range in `expression` the error refers to
+    pub span: Range<usize>,
+    /// The expression that failed to parse
+    pub expression: String,
+}
+
+impl SpannedParseError {
+    /// Locate `error` at `span` of `expression`
+    pub fn new(error: ParseError, span: Range<usize>, expression: impl Into<String>) -> Self {
+        Self {
+            error,
+            span,
+            expression: expression.into(),
+        }
+    }
+
+    /// The line of the expression containing the span, and the caret line
+    /// underlining the span within it
+    pub fn caret_lines(&self) -> (&str, String) {
+        let start = self.span.start.min(self.expression.len());
+        let line_start = self.expression[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.expression[start..]
+            .find('\n')
+            .map_or(self.expression.len(), |i| start + i);
+        let line = &self.expression[line_start..line_end];
+
+        let indent: String = self.expression[line_start..start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let end = self.span.end.clamp(start, line_end);
+        let width = self.expression[start..end].chars().count().max(1);
+        (line, format!("{indent}{}", "^".repeat(width)))
+    }
+}
+
+impl std::fmt::Display for SpannedParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (line, carets) = self.caret_lines();
+        write!(f, "{}\n  {line}\n  {carets}", self.error)
+    }
+}
+
+impl std::error::Error for SpannedParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
 /// Implement nom's ParseError trait
 impl<I> NomParseError<I> for ParseError {
     fn from_error_kind(_input: I, kind: ErrorKind) -> Self {
@@ -17,12 +17,12 @@ pub use ast_cache::{
     AstCache, AstCacheConfig, AstCacheStats, SharedAst, cache_ast, get_cached_ast,
     global_ast_cache, global_ast_cache_stats,
 };
-pub use error::{ParseError, ParseResult};
+pub use error::{ParseError, ParseResult, SpannedParseError};
 pub use error_recovery::{
     RecoveryAnalysis, RecoveryResult, RecoveryStrategy, analyze_recovery_potential,
     parse_with_recovery,
 };
-pub use pratt::parse_expression_pratt;
+pub use pratt::{parse_expression_pratt, parse_expression_spanned};
 pub use span::{Span, Spanned};
 
 // Re-export parser function for compatibility
@@ -7,9 +7,11 @@
 //! - Minimal allocations during parsing
 //! - Cache-efficient memory layout
 
-use super::error::{ParseError, ParseResult};
+use super::error::{ParseError, ParseResult, SpannedParseError, common_messages};
 use super::tokenizer::{Token, Tokenizer};
 use crate::ast::{BinaryOperator, ExpressionNode, LiteralValue, UnaryOperator};
+use crate::model::{PrecisionDate, PrecisionDateTime, PrecisionTime, Quantity};
+use std::ops::Range;
 
 /// Operator precedence levels (higher = tighter binding)
 /// Designed for optimal branch prediction with sequential spacing
@@ -308,8 +310,11 @@ fn token_to_binary_op<'input>(token: &Token<'input>) -> Option<BinaryOperator> {
 /// - Zero-allocation parsing with lifetime parameters
 /// - Direct pattern matching for O(1) precedence lookups
 pub struct PrattParser<'input> {
+    input: &'input str,
     tokenizer: Tokenizer<'input>,
     current_token: Option<Token<'input>>,
+    /// Byte range of `current_token`, or of the text the tokenizer rejected
+    current_span: Range<usize>,
 }
 
 impl<'input> PrattParser<'input> {
@@ -317,8 +322,10 @@ impl<'input> PrattParser<'input> {
     #[inline]
     pub fn new(input: &'input str) -> Self {
         Self {
+            input,
             tokenizer: Tokenizer::new(input),
             current_token: None,
+            current_span: 0..0,
         }
     }
 
@@ -326,7 +333,9 @@ impl<'input> PrattParser<'input> {
     /// Optimized for hot path with minimal allocations
     #[inline(always)]
     fn advance(&mut self) -> ParseResult<()> {
-        self.current_token = self.tokenizer.next_token()?;
+        let next = self.tokenizer.next_token();
+        self.current_span = self.tokenizer.token_span();
+        self.current_token = next?;
         Ok(())
     }
 
@@ -357,6 +366,15 @@ impl<'input> PrattParser<'input> {
         }
     }
 
+    /// Error for a literal token whose text is not a valid value of its type
+    fn invalid_literal(literal_type: &'static str, value: &str) -> ParseError {
+        ParseError::InvalidLiteral {
+            literal_type: literal_type.into(),
+            value: value.to_string().into(),
+            position: 0,
+        }
+    }
+
     /// Fast token type matching using direct pattern matching
     #[inline(always)]
     fn tokens_match(token: &Token<'input>, expected: &Token<'input>) -> bool {
@@ -442,6 +460,86 @@ impl<'input> PrattParser<'input> {
         }
     }
 
+    /// Parse the type name after `is` or `as`, as in `is Quantity`,
+    /// `as FHIR.Quantity` or `` is(System.`Integer`) ``
+    ///
+    /// Delimited parts lose their backticks; qualified and unqualified names
+    /// are resolved later by [`crate::model::parse_type_specifier`].
+    fn parse_type_specifier(
+        &mut self,
+        operator: &str,
+        precedence: Precedence,
+    ) -> ParseResult<String> {
+        let parenthesized = matches!(self.current(), Some(Token::LeftParen));
+        if parenthesized {
+            self.advance()?;
+        }
+
+        let mut type_name = self.parse_type_name_part(operator, precedence)?;
+        while let Some(Token::Dot) = self.current() {
+            self.advance()?;
+            type_name.push('.');
+            type_name.push_str(&self.parse_type_name_part(operator, precedence)?);
+        }
+
+        if parenthesized {
+            self.expect(Token::RightParen)?;
+        }
+        Ok(type_name)
+    }
+
+    /// Parse one identifier of a type name, plain or delimited by backticks
+    fn parse_type_name_part(
+        &mut self,
+        operator: &str,
+        precedence: Precedence,
+    ) -> ParseResult<String> {
+        let delimited = matches!(self.current(), Some(Token::Backtick));
+        if delimited {
+            self.advance()?;
+        }
+
+        let Some(name) = self.current().and_then(|token| token.as_identifier()) else {
+            return Err(ParseError::UnexpectedToken {
+                token: format!(
+                    "Expected type name after '{operator}' operator, got: {:?}. Context: {}",
+                    self.current(),
+                    Self::precedence_context(precedence)
+                )
+                .into(),
+                position: 0,
+            });
+        };
+        let name = name.to_string();
+        self.advance()?;
+
+        if delimited {
+            self.expect(Token::Backtick)?;
+        }
+        Ok(name)
+    }
+
+    /// Parse the unit after a number into a quantity literal, if there is one
+    ///
+    /// The unit is a UCUM code in quotes or a calendar duration keyword such
+    /// as `days`, the same grammar toQuantity() accepts in strings. Any other
+    /// identifier is not a unit, so `5 mg` is not a quantity.
+    fn parse_quantity_unit(&mut self, value: String) -> ParseResult<Option<ExpressionNode>> {
+        let unit = match self.current() {
+            Some(Token::String(unit)) => unit.to_string(),
+            Some(token) => match token.as_identifier() {
+                Some(unit) if Quantity::calendar_unit(unit).is_some() => unit.to_string(),
+                _ => return Ok(None),
+            },
+            None => return Ok(None),
+        };
+        self.advance()?;
+        Ok(Some(ExpressionNode::literal(LiteralValue::Quantity {
+            value,
+            unit,
+        })))
+    }
+
     /// Get precedence information for error messages
     fn precedence_context(precedence: Precedence) -> &'static str {
         match precedence {
@@ -507,35 +605,17 @@ impl<'input> PrattParser<'input> {
             Some(Token::Integer(value)) => {
                 let value = *value;
                 self.advance()?;
-                // Check for quantity (number followed by unit - string or identifier)
-                match self.current() {
-                    Some(Token::String(unit)) => {
-                        let unit_str = *unit;
-                        self.advance()?;
-                        Ok(ExpressionNode::literal(LiteralValue::Quantity {
-                            value: value.to_string(),
-                            unit: unit_str.to_string(),
-                        }))
-                    }
-                    Some(Token::Identifier(unit)) => {
-                        let unit_str = *unit;
-                        self.advance()?;
-                        Ok(ExpressionNode::literal(LiteralValue::Quantity {
-                            value: value.to_string(),
-                            unit: unit_str.to_string(),
-                        }))
-                    }
-                    _ => Ok(ExpressionNode::literal(LiteralValue::Integer(value))),
+                match self.parse_quantity_unit(value.to_string())? {
+                    Some(quantity) => Ok(quantity),
+                    None => Ok(ExpressionNode::literal(LiteralValue::Integer(value))),
                 }
             }
 
             // String literals
             Some(Token::String(value)) => {
-                let value = *value;
-                self.advance()?;
-
                 // Process escape sequences including Unicode escapes
                 let processed_string = Self::process_string_escapes(value)?;
+                self.advance()?;
 
                 Ok(ExpressionNode::literal(LiteralValue::String(
                     processed_string,
@@ -546,25 +626,9 @@ impl<'input> PrattParser<'input> {
             Some(Token::Decimal(value)) => {
                 let value = *value;
                 self.advance()?;
-                // Check for quantity (decimal followed by unit - string or identifier)
-                match self.current() {
-                    Some(Token::String(unit)) => {
-                        let unit_str = *unit;
-                        self.advance()?;
-                        Ok(ExpressionNode::literal(LiteralValue::Quantity {
-                            value: value.to_string(),
-                            unit: unit_str.to_string(),
-                        }))
-                    }
-                    Some(Token::Identifier(unit)) => {
-                        let unit_str = *unit;
-                        self.advance()?;
-                        Ok(ExpressionNode::literal(LiteralValue::Quantity {
-                            value: value.to_string(),
-                            unit: unit_str.to_string(),
-                        }))
-                    }
-                    _ => Ok(ExpressionNode::literal(LiteralValue::Decimal(
+                match self.parse_quantity_unit(value.to_string())? {
+                    Some(quantity) => Ok(quantity),
+                    None => Ok(ExpressionNode::literal(LiteralValue::Decimal(
                         value.to_string(),
                     ))),
                 }
@@ -580,9 +644,13 @@ impl<'input> PrattParser<'input> {
                 Ok(ExpressionNode::literal(LiteralValue::Boolean(false)))
             }
 
-            // Date/time literals
+            // Date/time literals, parsed here so that a malformed literal such
+            // as `@2013-02-30` is a syntax error rather than an evaluation one
             Some(Token::Date(value)) => {
                 let value = *value;
+                if PrecisionDate::parse(&value[1..]).is_none() {
+                    return Err(Self::invalid_literal(common_messages::DATE, value));
+                }
                 self.advance()?;
                 Ok(ExpressionNode::literal(LiteralValue::Date(
                     value.to_string(),
@@ -590,6 +658,9 @@ impl<'input> PrattParser<'input> {
             }
             Some(Token::DateTime(value)) => {
                 let value = *value;
+                if PrecisionDateTime::parse(&value[1..]).is_none() {
+                    return Err(Self::invalid_literal(common_messages::DATETIME, value));
+                }
                 self.advance()?;
                 Ok(ExpressionNode::literal(LiteralValue::DateTime(
                     value.to_string(),
@@ -597,6 +668,9 @@ impl<'input> PrattParser<'input> {
             }
             Some(Token::Time(value)) => {
                 let value = *value;
+                if PrecisionTime::parse(&value[2..]).is_none() {
+                    return Err(Self::invalid_literal(common_messages::TIME, value));
+                }
                 self.advance()?;
                 Ok(ExpressionNode::literal(LiteralValue::Time(
                     value.to_string(),
@@ -709,8 +783,8 @@ impl<'input> PrattParser<'input> {
             // Variable references
             Some(Token::Dollar) => {
                 self.advance()?;
-                if let Some(Token::Identifier(name)) = self.current() {
-                    let var_name = *name;
+                if let Some(name) = self.current().and_then(|token| token.as_identifier()) {
+                    let var_name = name.to_string();
                     self.advance()?;
                     Ok(ExpressionNode::variable(var_name))
                 } else {
@@ -741,8 +815,8 @@ impl<'input> PrattParser<'input> {
             Some(Token::Percent) => {
                 self.advance()?;
                 match self.current() {
-                    Some(Token::Identifier(name)) => {
-                        let var_name = *name;
+                    Some(token) if token.is_identifier() => {
+                        let var_name = token.as_identifier().unwrap_or_default().to_string();
                         self.advance()?;
                         Ok(ExpressionNode::variable(var_name))
                     }
@@ -836,6 +910,8 @@ impl<'input> PrattParser<'input> {
                     Some(Token::Tail) => "tail",
                     Some(Token::True) => "true",
                     Some(Token::False) => "false",
+                    Some(Token::Div) => "div",
+                    Some(Token::Mod) => "mod",
                     _ => {
                         return Err(ParseError::UnexpectedToken {
                             token: std::borrow::Cow::Borrowed("Expected identifier after backtick"),
@@ -873,6 +949,16 @@ impl<'input> PrattParser<'input> {
                 position: 0,
             }),
 
+            // `contains` only starts an expression when the left operand is missing
+            Some(Token::Contains) => Err(ParseError::SyntaxError {
+                position: 0,
+                message: std::borrow::Cow::Borrowed(
+                    "'contains' operator is missing its left operand; use \
+                     `collection contains item` for membership or `value.contains(substring)` \
+                     for the string function",
+                ),
+            }),
+
             Some(token) => Err(ParseError::UnexpectedToken {
                 token: format!("Unexpected token: {token:?}").into(),
                 position: 0,
@@ -972,9 +1058,13 @@ impl<'input> PrattParser<'input> {
             Some(Token::Take) => "take".to_string(),
             Some(Token::Skip) => "skip".to_string(),
             Some(Token::Distinct) => "distinct".to_string(),
+            Some(Token::Union) => "union".to_string(),
             Some(Token::Is) => "is".to_string(),
             Some(Token::Contains) => "contains".to_string(),
             Some(Token::Not) => "not".to_string(),
+            // Narrative.div: after a dot `div` and `mod` can only be element names
+            Some(Token::Div) => "div".to_string(),
+            Some(Token::Mod) => "mod".to_string(),
             Some(Token::OfType) => "ofType".to_string(),
             Some(Token::As) => "as".to_string(),
             Some(Token::Backtick) => {
@@ -992,6 +1082,8 @@ impl<'input> PrattParser<'input> {
                     Some(Token::Tail) => "tail".to_string(),
                     Some(Token::True) => "true".to_string(),
                     Some(Token::False) => "false".to_string(),
+                    Some(Token::Div) => "div".to_string(),
+                    Some(Token::Mod) => "mod".to_string(),
                     _ => {
                         return Err(ParseError::UnexpectedToken {
                             token: std::borrow::Cow::Borrowed("Expected identifier after backtick"),
@@ -1003,12 +1095,7 @@ impl<'input> PrattParser<'input> {
                 self.expect(Token::Backtick)?;
                 return self.parse_method_or_path(base, &backtick_name);
             }
-            _ => {
-                return Err(ParseError::UnexpectedToken {
-                    token: format!("Expected identifier after dot: {:?}", self.current()).into(),
-                    position: 0,
-                });
-            }
+            _ => return Err(ParseError::expected_identifier(0)),
         };
 
         self.advance()?;
@@ -1085,108 +1172,7 @@ impl<'input> PrattParser<'input> {
             match current_token {
                 Token::Is => {
                     self.advance()?;
-
-                    // Handle parenthesized type names like is(DateTime) or plain type names like is DateTime
-                    let type_name = if let Some(Token::LeftParen) = self.current() {
-                        // Handle is(Type) form
-                        self.advance()?; // consume (
-                        if let Some(token) = self.current() {
-                            if let Some(first_part) = token.as_identifier() {
-                                let mut type_name = first_part.to_string();
-                                self.advance()?;
-
-                                // Handle qualified names with dots (e.g., System.Boolean)
-                                while let Some(Token::Dot) = self.current() {
-                                    self.advance()?; // consume dot
-                                    if let Some(token) = self.current() {
-                                        if let Some(next_part) = token.as_identifier() {
-                                            type_name.push('.');
-                                            type_name.push_str(next_part);
-                                            self.advance()?;
-                                        } else {
-                                            break;
-                                        }
-                                    } else {
-                                        break;
-                                    }
-                                }
-
-                                self.expect(Token::RightParen)?; // consume )
-                                type_name
-                            } else {
-                                return Err(ParseError::UnexpectedToken {
-                                    token: format!(
-                                        "Expected identifier in 'is(Type)', got: {:?}",
-                                        self.current()
-                                    )
-                                    .into(),
-                                    position: 0,
-                                });
-                            }
-                        } else {
-                            return Err(ParseError::UnexpectedToken {
-                                token: format!(
-                                    "Expected type name in parentheses after 'is' operator, got: {:?}",
-                                    self.current()
-                                ).into(),
-                                position: 0,
-                            });
-                        }
-                    } else if let Some(token) = self.current() {
-                        if let Some(first_part) = token.as_identifier() {
-                            // Handle is Type form
-                            let mut type_name = first_part.to_string();
-                            self.advance()?;
-
-                            // Handle qualified names with dots (e.g., System.Boolean)
-                            while let Some(Token::Dot) = self.current() {
-                                self.advance()?; // consume dot
-                                if let Some(token) = self.current() {
-                                    if let Some(next_part) = token.as_identifier() {
-                                        type_name.push('.');
-                                        type_name.push_str(next_part);
-                                        self.advance()?;
-                                    } else {
-                                        return Err(ParseError::UnexpectedToken {
-                                        token: format!(
-                                            "Expected identifier after '.' in qualified type name, got: {:?}",
-                                            self.current()
-                                        ).into(),
-                                        position: 0,
-                                    });
-                                    }
-                                } else {
-                                    return Err(ParseError::UnexpectedToken {
-                                    token: format!(
-                                        "Expected identifier after '.' in qualified type name, got: {:?}",
-                                        self.current()
-                                    ).into(),
-                                    position: 0,
-                                });
-                                }
-                            }
-
-                            type_name
-                        } else {
-                            return Err(ParseError::UnexpectedToken {
-                                token: format!(
-                                    "Expected identifier after 'is', got: {:?}",
-                                    self.current()
-                                )
-                                .into(),
-                                position: 0,
-                            });
-                        }
-                    } else {
-                        return Err(ParseError::UnexpectedToken {
-                            token: format!(
-                                "Expected type name after 'is' operator in type check expression, got: {:?}. Context: {}",
-                                self.current(),
-                                Self::precedence_context(precedence)
-                            ).into(),
-                            position: 0,
-                        });
-                    };
+                    let type_name = self.parse_type_specifier("is", precedence)?;
 
                     left = ExpressionNode::TypeCheck {
                         expression: Box::new(left),
@@ -1196,108 +1182,7 @@ impl<'input> PrattParser<'input> {
                 }
                 Token::As => {
                     self.advance()?;
-
-                    // Handle parenthesized type names like as(Type) or plain type names like as Type
-                    let type_name = if let Some(Token::LeftParen) = self.current() {
-                        // Handle as(Type) form
-                        self.advance()?; // consume (
-                        if let Some(token) = self.current() {
-                            if let Some(first_part) = token.as_identifier() {
-                                let mut type_name = first_part.to_string();
-                                self.advance()?;
-
-                                // Handle qualified names with dots (e.g., System.Boolean)
-                                while let Some(Token::Dot) = self.current() {
-                                    self.advance()?; // consume dot
-                                    if let Some(token) = self.current() {
-                                        if let Some(next_part) = token.as_identifier() {
-                                            type_name.push('.');
-                                            type_name.push_str(next_part);
-                                            self.advance()?;
-                                        } else {
-                                            break;
-                                        }
-                                    } else {
-                                        break;
-                                    }
-                                }
-
-                                self.expect(Token::RightParen)?; // consume )
-                                type_name
-                            } else {
-                                return Err(ParseError::UnexpectedToken {
-                                    token: format!(
-                                        "Expected identifier in 'as(Type)', got: {:?}",
-                                        self.current()
-                                    )
-                                    .into(),
-                                    position: 0,
-                                });
-                            }
-                        } else {
-                            return Err(ParseError::UnexpectedToken {
-                                token: format!(
-                                    "Expected type name in parentheses after 'as' operator, got: {:?}",
-                                    self.current()
-                                ).into(),
-                                position: 0,
-                            });
-                        }
-                    } else if let Some(token) = self.current() {
-                        if let Some(first_part) = token.as_identifier() {
-                            // Handle as Type form
-                            let mut type_name = first_part.to_string();
-                            self.advance()?;
-
-                            // Handle qualified names with dots (e.g., System.Boolean)
-                            while let Some(Token::Dot) = self.current() {
-                                self.advance()?; // consume dot
-                                if let Some(token) = self.current() {
-                                    if let Some(next_part) = token.as_identifier() {
-                                        type_name.push('.');
-                                        type_name.push_str(next_part);
-                                        self.advance()?;
-                                    } else {
-                                        return Err(ParseError::UnexpectedToken {
-                                        token: format!(
-                                            "Expected identifier after '.' in qualified type name, got: {:?}",
-                                            self.current()
-                                        ).into(),
-                                        position: 0,
-                                    });
-                                    }
-                                } else {
-                                    return Err(ParseError::UnexpectedToken {
-                                    token: format!(
-                                        "Expected identifier after '.' in qualified type name, got: {:?}",
-                                        self.current()
-                                    ).into(),
-                                    position: 0,
-                                });
-                                }
-                            }
-
-                            type_name
-                        } else {
-                            return Err(ParseError::UnexpectedToken {
-                                token: format!(
-                                    "Expected identifier after 'as', got: {:?}",
-                                    self.current()
-                                )
-                                .into(),
-                                position: 0,
-                            });
-                        }
-                    } else {
-                        return Err(ParseError::UnexpectedToken {
-                            token: format!(
-                                "Expected type name after 'as' operator in type cast expression, got: {:?}. Context: {}",
-                                self.current(),
-                                Self::precedence_context(precedence)
-                            ).into(),
-                            position: 0,
-                        });
-                    };
+                    let type_name = self.parse_type_specifier("as", precedence)?;
 
                     left = ExpressionNode::TypeCast {
                         expression: Box::new(left),
@@ -1317,6 +1202,16 @@ impl<'input> PrattParser<'input> {
 
             self.advance()?;
 
+            if op == BinaryOperator::Contains && self.current().is_none() {
+                return Err(ParseError::SyntaxError {
+                    position: 0,
+                    message: std::borrow::Cow::Borrowed(
+                        "'contains' operator is missing its right operand; use \
+                         `collection contains item`",
+                    ),
+                });
+            }
+
             // Calculate next minimum precedence (handles associativity)
             // For left associative operators, use next higher precedence level
             let next_min_precedence = if precedence.is_right_associative() {
@@ -1341,12 +1236,36 @@ impl<'input> PrattParser<'input> {
         self.parse_expression_with_precedence(Precedence::Implies)
     }
 
+    /// Parse complete input, locating any error at the token where parsing stopped
+    pub fn parse_spanned(&mut self) -> Result<ExpressionNode, SpannedParseError> {
+        self.parse().map_err(|mut error| {
+            let span = self.current_span.clone();
+            error.set_position(span.start);
+            SpannedParseError::new(error, span, self.input)
+        })
+    }
+
     /// Parse complete input
     #[inline]
     pub fn parse(&mut self) -> ParseResult<ExpressionNode> {
+        self.advance()?;
+        if self.current_token.is_none() {
+            return Err(ParseError::EmptyExpression);
+        }
         let expr = self.parse_expression()?;
 
         // Ensure we consumed all input
+        if let Some(token) = &self.current_token
+            && token.as_identifier() == Some("matches")
+        {
+            // matches() has no operator form, unlike contains
+            return Err(ParseError::SyntaxError {
+                position: 0,
+                message: std::borrow::Cow::Borrowed(
+                    "'matches' is a function, not an operator; use `value.matches(regex)`",
+                ),
+            });
+        }
         if self.current_token.is_some() {
             return Err(ParseError::UnexpectedToken {
                 token: format!("Unexpected token: {:?}", self.current_token).into(),
@@ -1429,10 +1348,17 @@ impl<'input> PrattParser<'input> {
 }
 
 /// High-performance parsing function (public API)
+///
+/// Error positions are byte offsets into `input`; use
+/// [`parse_expression_spanned`] to also get the span of the offending token.
 #[inline]
 pub fn parse_expression_pratt(input: &str) -> ParseResult<ExpressionNode> {
-    let mut parser = PrattParser::new(input);
-    parser.parse()
+    parse_expression_spanned(input).map_err(|e| e.error)
+}
+
+/// Parse an expression, reporting errors with the span they refer to
+pub fn parse_expression_spanned(input: &str) -> Result<ExpressionNode, SpannedParseError> {
+    PrattParser::new(input).parse_spanned()
 }
 
 #[cfg(test)]
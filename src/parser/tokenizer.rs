@@ -450,6 +450,8 @@ pub struct Tokenizer<'input> {
     bytes: &'input [u8],
     pos: usize,
     end: usize,
+    /// Start of the token most recently read by `next_token`
+    token_start: usize,
     /// Enable string interning for identifiers (default: true)
     enable_interning: bool,
     /// Threshold for interning (identifiers used more than this get interned)
@@ -470,6 +472,7 @@ impl<'input> Tokenizer<'input> {
             bytes,
             pos: 0,
             end: bytes.len(),
+            token_start: 0,
             enable_interning: true,
             interning_threshold: 1,
             enable_streaming,
@@ -486,6 +489,7 @@ impl<'input> Tokenizer<'input> {
             bytes,
             pos: 0,
             end: bytes.len(),
+            token_start: 0,
             enable_interning: enable,
             interning_threshold: 1,
             enable_streaming,
@@ -501,6 +505,7 @@ impl<'input> Tokenizer<'input> {
             bytes,
             pos: 0,
             end: bytes.len(),
+            token_start: 0,
             enable_interning: true,
             interning_threshold: 1,
             enable_streaming: true,
@@ -682,6 +687,7 @@ impl<'input> Tokenizer<'input> {
     #[inline]
     pub fn next_token(&mut self) -> ParseResult<Option<Token<'input>>> {
         self.skip_whitespace();
+        self.token_start = self.pos;
 
         if self.pos >= self.end {
             return Ok(None);
@@ -886,8 +892,13 @@ impl<'input> Tokenizer<'input> {
 
             // Unknown character - fast error path
             ch => {
+                let ch = self
+                    .slice(self.pos, self.end)
+                    .chars()
+                    .next()
+                    .unwrap_or(ch as char);
                 return Err(ParseError::UnexpectedToken {
-                    token: format!("{}", ch as char).into(),
+                    token: ch.to_string().into(),
                     position: self.pos,
                 });
             }
@@ -1002,6 +1013,25 @@ impl<'input> Tokenizer<'input> {
             return Ok(false);
         }
 
+        // FHIRPath dates are calendar dates, so say so rather than misreading
+        // week and ordinal dates
+        if let Some(notation) = unsupported_date_notation(&self.bytes[self.pos..]) {
+            let start = self.pos - count - 1;
+            while self.pos < self.end
+                && (self.bytes[self.pos].is_ascii_alphanumeric() || self.bytes[self.pos] == b'-')
+            {
+                self.pos += 1;
+            }
+            return Err(ParseError::SyntaxError {
+                position: start,
+                message: format!(
+                    "{notation} '{}' is not supported, use a calendar date such as @2018-01-01",
+                    self.slice(start, self.pos)
+                )
+                .into(),
+            });
+        }
+
         // Fast month check (-MM)
         let remaining = &self.bytes[self.pos..];
         if remaining.len() >= 3
@@ -1058,8 +1088,11 @@ impl<'input> Tokenizer<'input> {
             {
                 self.pos += 3;
 
-                // Milliseconds (.sss)
-                if self.pos < self.end && self.bytes[self.pos] == b'.' {
+                // Milliseconds (.sss) - a '.' without digits is a method call
+                if self.pos + 1 < self.end
+                    && self.bytes[self.pos] == b'.'
+                    && self.bytes[self.pos + 1].is_ascii_digit()
+                {
                     self.pos += 1;
                     while self.pos < self.end && self.bytes[self.pos].is_ascii_digit() {
                         self.pos += 1;
@@ -1105,6 +1138,21 @@ impl<'input> Tokenizer<'input> {
         self.pos
     }
 
+    /// Byte range of the token most recently read by [`next_token`](Self::next_token)
+    ///
+    /// After `next_token` fails this covers the text it could not read, at
+    /// least one character. At the end of input the range is empty.
+    pub fn token_span(&self) -> std::ops::Range<usize> {
+        let mut end = self.pos.max(self.token_start);
+        if end == self.token_start && end < self.end {
+            end += 1;
+            while end < self.end && (self.bytes[end] & 0xC0) == 0x80 {
+                end += 1;
+            }
+        }
+        self.token_start..end
+    }
+
     /// Check if identifier should be interned based on patterns
     #[inline]
     fn should_intern_identifier(&self, ident: &str) -> bool {
@@ -1280,6 +1328,18 @@ pub fn create_streaming_tokenizer_with_buffer(input: &str, buffer_size: usize) -
     Tokenizer::with_streaming(input, buffer_size)
 }
 
+/// Name the ISO 8601 date notation FHIRPath does not support that `rest`,
+/// the input after a year, is written in
+fn unsupported_date_notation(rest: &[u8]) -> Option<&'static str> {
+    let rest = rest.strip_prefix(b"-").unwrap_or(rest);
+    let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+    match rest {
+        [b'W', w1, w2, ..] if w1.is_ascii_digit() && w2.is_ascii_digit() => Some("ISO week date"),
+        _ if digits == 3 => Some("ISO ordinal date"),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
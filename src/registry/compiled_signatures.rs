@@ -3,7 +3,7 @@
 //! This module provides a pre-compilation system for function signatures that eliminates
 //! runtime type checking and enables faster function dispatch through generated code.
 
-use crate::model::{FhirPathValue, TypeInfo};
+use crate::model::{FhirPathValue, PrecisionDate, PrecisionDateTime, PrecisionTime, TypeInfo};
 use crate::registry::function::{FunctionError, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 use rustc_hash::FxHashMap;
@@ -439,9 +439,11 @@ impl CompiledSignatureRegistry {
             TypeInfo::Decimal => FhirPathValue::Decimal("0.0".parse().unwrap()),
             TypeInfo::String => FhirPathValue::String("".into()),
             TypeInfo::Boolean => FhirPathValue::Boolean(false),
-            TypeInfo::Date => FhirPathValue::Date("2000-01-01".parse().unwrap()),
-            TypeInfo::DateTime => FhirPathValue::DateTime("2000-01-01T00:00:00Z".parse().unwrap()),
-            TypeInfo::Time => FhirPathValue::Time("00:00:00".parse().unwrap()),
+            TypeInfo::Date => FhirPathValue::Date(PrecisionDate::parse("2000-01-01").unwrap()),
+            TypeInfo::DateTime => {
+                FhirPathValue::DateTime(PrecisionDateTime::parse("2000-01-01T00:00:00Z").unwrap())
+            }
+            TypeInfo::Time => FhirPathValue::Time(PrecisionTime::parse("00:00:00").unwrap()),
             TypeInfo::Quantity => {
                 use crate::model::quantity::Quantity;
                 use rust_decimal::Decimal;
@@ -4,7 +4,9 @@ use crate::registry::extension::{
     ExtensionMetadata, ExtensionRegistry, ExtensionResult, FhirPathExtension,
 };
 use crate::registry::function::FunctionImpl;
-use crate::registry::functions::fhir_types::{ExtensionFunction, ResolveFunction};
+use crate::registry::functions::fhir_types::{
+    CodingForFunction, ExtensionFunction, ResolveFunction,
+};
 use std::sync::Arc;
 
 /// FHIR extension providing FHIR-specific functions
@@ -55,9 +57,13 @@ impl FhirPathExtension for FhirExtension {
         registry.register_function("fhir", "extension", extension_fn)?;
 
         // Register resolve function
-        let resolve_fn = FunctionImpl::Async(Arc::new(ResolveFunction));
+        let resolve_fn = FunctionImpl::Async(Arc::new(ResolveFunction::new()));
         registry.register_function("fhir", "resolve", resolve_fn)?;
 
+        // Register codingFor function
+        let coding_for_fn = FunctionImpl::Trait(Arc::new(CodingForFunction));
+        registry.register_function("fhir", "codingFor", coding_for_fn)?;
+
         Ok(())
     }
 
@@ -100,6 +106,9 @@ mod tests {
 
         let resolution = manager.resolve_function("fhir:resolve");
         assert!(resolution.is_found());
+
+        let resolution = manager.resolve_function("fhir:codingFor");
+        assert!(resolution.is_found());
     }
 
     #[test]
@@ -22,7 +22,9 @@ use async_trait::async_trait;
 // Note: Lambda evaluation is not yet fully implemented
 // pub use crate::registry::functions::boolean::{AllFunction, AnyFunction};
 // pub use crate::registry::functions::collection::ExistsFunction;
+use crate::diagnostics::WarningSink;
 use crate::model::{FhirPathValue, TypeInfo};
+use chrono::{DateTime, FixedOffset, Utc};
 use rustc_hash::FxHashMap;
 use std::hash::BuildHasherDefault;
 use std::sync::Arc;
@@ -66,6 +68,17 @@ pub enum FunctionError {
         actual: String,
     },
 
+    /// Input item of the wrong type
+    #[error("Function '{name}' expects {expected} input items, got {actual}")]
+    TypeError {
+        /// Function name
+        name: String,
+        /// Expected type
+        expected: String,
+        /// Actual type
+        actual: String,
+    },
+
     /// Runtime evaluation error
     #[error("Function '{name}' evaluation error: {message}")]
     EvaluationError {
@@ -81,8 +94,10 @@ pub type LambdaEvaluator<'a> = dyn Fn(
         &ExpressionNode,
         &FhirPathValue,
     ) -> std::pin::Pin<
-        Box<dyn std::future::Future<Output = Result<FhirPathValue, FunctionError>> + 'a>,
-    > + 'a;
+        Box<dyn std::future::Future<Output = Result<FhirPathValue, FunctionError>> + Send + 'a>,
+    > + Send
+    + Sync
+    + 'a;
 
 /// Enhanced lambda evaluator type that supports additional variables injection (async)
 pub type EnhancedLambdaEvaluator<'a> = dyn Fn(
@@ -90,11 +105,13 @@ pub type EnhancedLambdaEvaluator<'a> = dyn Fn(
         &FhirPathValue,
         &VarMap,
     ) -> std::pin::Pin<
-        Box<dyn std::future::Future<Output = Result<FhirPathValue, FunctionError>> + 'a>,
-    > + 'a;
+        Box<dyn std::future::Future<Output = Result<FhirPathValue, FunctionError>> + Send + 'a>,
+    > + Send
+    + Sync
+    + 'a;
 
 /// Context for function evaluation
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct EvaluationContext {
     /// Current input value
     pub input: FhirPathValue,
@@ -102,6 +119,31 @@ pub struct EvaluationContext {
     pub root: FhirPathValue,
     /// Variables in scope
     pub variables: FxHashMap<String, FhirPathValue>,
+    /// When the evaluation started, as returned by `now()`
+    pub now: DateTime<FixedOffset>,
+    /// Where `resolve()` looks for references it cannot find in the data
+    pub reference_resolver: Option<ReferenceSource>,
+    /// Bundle lookups `resolve()` has made so far in the evaluation
+    pub resolution_cache: Option<Arc<ResolutionCache>>,
+    /// Where non-fatal warnings raised during the evaluation are sent
+    pub warnings: Option<WarningSink>,
+}
+
+impl std::fmt::Debug for EvaluationContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EvaluationContext")
+            .field("input", &self.input)
+            .field("root", &self.root)
+            .field("variables", &self.variables)
+            .field("now", &self.now)
+            .field("reference_resolver", &self.reference_resolver.is_some())
+            .field(
+                "resolution_cache",
+                &self.resolution_cache.as_ref().map(|cache| cache.stats()),
+            )
+            .field("warnings", &self.warnings.is_some())
+            .finish()
+    }
 }
 
 /// Extended context for lambda-supporting functions
@@ -112,6 +154,35 @@ pub struct LambdaEvaluationContext<'a> {
     pub evaluator: &'a LambdaEvaluator<'a>,
     /// Enhanced lambda expression evaluator with variable injection support
     pub enhanced_evaluator: Option<&'a EnhancedLambdaEvaluator<'a>>,
+    /// Values of the eager arguments by position, `None` for lazy arguments
+    pub eager_args: &'a [Option<FhirPathValue>],
+}
+
+impl LambdaEvaluationContext<'_> {
+    /// Get the value of the eager argument at `index`
+    pub fn eager_arg(&self, index: usize) -> Option<&FhirPathValue> {
+        self.eager_args.get(index).and_then(Option::as_ref)
+    }
+
+    /// Evaluate `expr` for the item at `index` of the collection being iterated
+    ///
+    /// `$this` is bound to `item` and `$index` to `index`, hiding the `$index`
+    /// of any enclosing iteration.
+    pub async fn evaluate_item(
+        &self,
+        expr: &ExpressionNode,
+        item: &FhirPathValue,
+        index: usize,
+    ) -> FunctionResult<FhirPathValue> {
+        match self.enhanced_evaluator {
+            Some(enhanced_evaluator) => {
+                let mut variables = VarMap::default();
+                variables.insert("index".to_string(), FhirPathValue::Integer(index as i64));
+                enhanced_evaluator(expr, item, &variables).await
+            }
+            None => (self.evaluator)(expr, item).await,
+        }
+    }
 }
 
 impl EvaluationContext {
@@ -121,6 +192,10 @@ impl EvaluationContext {
             root: input.clone(),
             input,
             variables: FxHashMap::default(),
+            now: Utc::now().fixed_offset(),
+            reference_resolver: None,
+            resolution_cache: None,
+            warnings: None,
         }
     }
 }
@@ -491,6 +566,34 @@ pub trait FhirPathFunction: Send + Sync {
 
         Ok(())
     }
+
+    /// The single item of `input`, or `None` if it is empty
+    ///
+    /// Functions defined on a single input item fail for larger collections.
+    fn single_input<'a>(
+        &self,
+        input: &'a FhirPathValue,
+    ) -> FunctionResult<Option<&'a FhirPathValue>> {
+        single_input(self.name(), input)
+    }
+}
+
+/// The single item of `input` for the function `name`, or `None` if it is empty
+fn single_input<'a>(
+    name: &str,
+    input: &'a FhirPathValue,
+) -> FunctionResult<Option<&'a FhirPathValue>> {
+    match input {
+        FhirPathValue::Collection(items) if items.len() > 1 => {
+            Err(FunctionError::EvaluationError {
+                name: name.to_string(),
+                message: "Input collection contains multiple items".to_string(),
+            })
+        }
+        FhirPathValue::Collection(items) => Ok(items.first()),
+        FhirPathValue::Empty => Ok(None),
+        item => Ok(Some(item)),
+    }
 }
 
 /// Async trait for implementing FHIRPath functions
@@ -567,6 +670,16 @@ pub trait AsyncFhirPathFunction: Send + Sync {
 
         Ok(())
     }
+
+    /// The single item of `input`, or `None` if it is empty
+    ///
+    /// Functions defined on a single input item fail for larger collections.
+    fn single_input<'a>(
+        &self,
+        input: &'a FhirPathValue,
+    ) -> FunctionResult<Option<&'a FhirPathValue>> {
+        single_input(self.name(), input)
+    }
 }
 
 /// Backward compatibility wrapper trait for existing synchronous function implementations
@@ -645,7 +758,7 @@ pub trait SyncFhirPathFunction: Send + Sync {
 }
 
 /// Trait for functions that need to evaluate lambda expressions
-#[async_trait(?Send)]
+#[async_trait]
 pub trait LambdaFunction: FhirPathFunction {
     /// Evaluate function with lambda expressions
     async fn evaluate_with_lambda(
@@ -828,6 +941,8 @@ impl FunctionImpl {
 pub struct FunctionRegistry {
     functions: FxHashMap<String, FunctionImpl>,
     signatures: FxHashMap<String, Vec<FunctionSignature>>,
+    /// Functions that take some of their arguments as unevaluated expressions
+    lambda_functions: FxHashMap<String, Arc<dyn LambdaFunction>>,
     /// Cache for resolved functions by name and argument types
     resolution_cache: Arc<FunctionResolutionCache>,
     /// Cache for pure function results
@@ -877,6 +992,7 @@ impl FunctionRegistry {
         Self {
             functions: FxHashMap::default(),
             signatures: FxHashMap::default(),
+            lambda_functions: FxHashMap::default(),
             resolution_cache,
             result_cache,
             cache_config: Arc::new(config),
@@ -886,9 +1002,13 @@ impl FunctionRegistry {
 
     /// Register a trait-based function
     pub fn register<F: FhirPathFunction + 'static>(&mut self, function: F) {
+        self.register_trait(Arc::new(function));
+    }
+
+    fn register_trait(&mut self, function: Arc<dyn FhirPathFunction>) {
         let name = function.name().to_string();
         let signature = function.signature().clone();
-        let func_impl = FunctionImpl::Trait(Arc::new(function));
+        let func_impl = FunctionImpl::Trait(function);
 
         self.functions.insert(name.clone(), func_impl);
         self.signatures
@@ -902,6 +1022,20 @@ impl FunctionRegistry {
         }
     }
 
+    /// Register a function that takes arguments as unevaluated expressions
+    ///
+    /// The parameters marked lazy in its signature are passed to
+    /// [`LambdaFunction::evaluate_with_lambda`] unevaluated, the others are
+    /// evaluated before the call. Lambda functions such as `where()` and
+    /// `select()` have their call shape checked before evaluation, see
+    /// [`Self::validate_call_shape`].
+    pub fn register_lambda<F: LambdaFunction + 'static>(&mut self, function: F) {
+        let function = Arc::new(function);
+        self.lambda_functions
+            .insert(function.name().to_string(), function.clone());
+        self.register_trait(function);
+    }
+
     /// Register a synchronous function
     pub fn register_sync<F: SyncFhirPathFunction + 'static>(&mut self, function: F) {
         let name = function.name().to_string();
@@ -1064,11 +1198,78 @@ impl FunctionRegistry {
         self.functions.contains_key(name)
     }
 
+    /// Check if a function takes arguments as unevaluated expressions
+    pub fn is_lambda_function(&self, name: &str) -> bool {
+        self.lambda_functions.contains_key(name)
+    }
+
+    /// Get the lambda implementation of a function by name
+    pub fn get_lambda(&self, name: &str) -> Option<&Arc<dyn LambdaFunction>> {
+        self.lambda_functions.get(name)
+    }
+
+    /// Validate the shape of a call before any argument is evaluated
+    ///
+    /// Lambda functions must be called with an argument count their signature
+    /// accepts, and lambda syntax (`x => ...`) is only allowed as an argument
+    /// to a lambda function. Unknown functions are left to the caller.
+    pub fn validate_call_shape(&self, name: &str, args: &[ExpressionNode]) -> FunctionResult<()> {
+        if !self.contains(name) {
+            return Ok(());
+        }
+
+        if !self.is_lambda_function(name) {
+            if args
+                .iter()
+                .any(|arg| matches!(arg, ExpressionNode::Lambda(_)))
+            {
+                return Err(FunctionError::EvaluationError {
+                    name: name.to_string(),
+                    message: "lambda expressions are only allowed as arguments to lambda functions"
+                        .to_string(),
+                });
+            }
+            return Ok(());
+        }
+
+        let accepts = |sig: &FunctionSignature| {
+            args.len() >= sig.min_arity && sig.max_arity.is_none_or(|max| args.len() <= max)
+        };
+        match self.get_signatures(name) {
+            Some(signatures) if !signatures.iter().any(accepts) => {
+                let signature = &signatures[0];
+                Err(FunctionError::InvalidArity {
+                    name: name.to_string(),
+                    min: signature.min_arity,
+                    max: signature.max_arity,
+                    actual: args.len(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Get all registered function names
     pub fn function_names(&self) -> Vec<&str> {
         self.functions.keys().map(|s| s.as_str()).collect()
     }
 
+    /// Find the registered function name closest to an unknown `name`
+    ///
+    /// Names are compared ignoring case by edit distance, counting a swap of
+    /// adjacent letters as one edit. Only names within a third of the length
+    /// of `name` (and at least one edit) are suggested.
+    pub fn suggest_similar(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        let max_distance = (name.chars().count() / 3).max(1);
+        self.functions
+            .keys()
+            .map(|candidate| (edit_distance(&name, &candidate.to_lowercase()), candidate))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min()
+            .map(|(_, candidate)| candidate.as_str())
+    }
+
     /// Get all signatures for a function name
     pub fn get_signatures(&self, name: &str) -> Option<&[FunctionSignature]> {
         self.signatures.get(name).map(|v| v.as_slice())
@@ -1433,6 +1634,32 @@ impl Default for FunctionRegistry {
     }
 }
 
+/// Optimal string alignment distance: insertions, deletions, substitutions
+/// and swaps of adjacent characters each count as one edit
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
 /// Register all built-in FHIRPath functions
 pub fn register_builtin_functions(registry: &mut FunctionRegistry) {
     // Collection functions - async converted
@@ -1448,21 +1675,22 @@ pub fn register_builtin_functions(registry: &mut FunctionRegistry) {
     registry.register_async(IntersectFunction);
     registry.register_async(ExcludeFunction);
     registry.register_async(CombineFunction);
+    registry.register_async(UnionFunction);
     registry.register_async(TailFunction);
     registry.register_async(SubsetOfFunction);
     registry.register_async(SupersetOfFunction);
 
     // Collection functions - still using old trait (lambda functions)
-    registry.register(ExistsFunction);
-    registry.register(AggregateFunction);
-    registry.register(SortFunction);
+    registry.register_lambda(ExistsFunction);
+    registry.register_lambda(AggregateFunction);
+    registry.register_lambda(SortFunction);
     registry.register_async(TakeFunction);
     registry.register_async(SkipFunction);
 
     // Boolean functions
-    registry.register(AllFunction);
+    registry.register_lambda(AllFunction);
     registry.register_async(AllTrueFunction);
-    registry.register_async(AnyFunction);
+    registry.register_lambda(AnyFunction);
     registry.register_async(IsDistinctFunction);
     registry.register_async(NotFunction);
 
@@ -1480,6 +1708,7 @@ pub fn register_builtin_functions(registry: &mut FunctionRegistry) {
     registry.register_async(TrimFunction);
     registry.register_async(ToCharsFunction);
     registry.register_async(IndexOfFunction);
+    registry.register_async(LastIndexOfFunction);
     registry.register_async(UpperFunction);
     registry.register_async(LowerFunction);
     registry.register_async(EncodeFunction);
@@ -1509,9 +1738,11 @@ pub fn register_builtin_functions(registry: &mut FunctionRegistry) {
     // Type conversion functions
     registry.register_async(AsFunction);
     registry.register_async(ToStringFunction);
+    registry.register_async(ToCanonicalStringFunction);
     registry.register_async(ToIntegerFunction);
     registry.register_async(ToDecimalFunction);
     registry.register_async(ToBooleanFunction);
+    registry.register_async(ToTimeFunction);
     registry.register_async(TypeFunction);
     registry.register(ConvertsToIntegerFunction);
     registry.register(ConvertsToDecimalFunction);
@@ -1524,29 +1755,31 @@ pub fn register_builtin_functions(registry: &mut FunctionRegistry) {
     registry.register(ConvertsToQuantityFunction);
 
     // Filtering functions
-    registry.register(WhereFunction);
-    registry.register(SelectFunction);
+    registry.register_lambda(WhereFunction);
+    registry.register_lambda(SelectFunction);
     registry.register_async(OfTypeFunction);
 
     // DateTime functions
     registry.register_async(NowFunction);
     registry.register_async(TodayFunction);
+    registry.register_async(TimeOfDayFunction);
     registry.register_async(LowBoundaryFunction);
     registry.register_async(HighBoundaryFunction);
 
     // Utility functions
-    registry.register_async(IifFunction);
-    registry.register_async(TraceFunction);
+    registry.register_lambda(IifFunction);
+    registry.register_lambda(TraceFunction::new());
     registry.register_async(ConformsToFunction::new());
-    registry.register_async(DefineVariableFunction);
+    registry.register_lambda(DefineVariableFunction);
     registry.register_async(HasValueFunction);
-    registry.register_async(RepeatFunction);
+    registry.register_lambda(RepeatFunction);
 
     // FHIR type functions
     registry.register_async(IsFunction);
     registry.register_async(ComparableFunction);
     registry.register_async(ExtensionFunction);
-    registry.register_async(ResolveFunction);
+    registry.register_async(ResolveFunction::new());
+    registry.register_async(MemberOfFunction::new());
 
     // CDA functions
     registry.register(HasTemplateIdOfFunction);
@@ -2199,4 +2432,31 @@ mod tests {
                 .is_err()
         );
     }
+
+    #[test]
+    fn test_suggest_similar() {
+        let mut registry = FunctionRegistry::new();
+        register_builtin_functions(&mut registry);
+
+        for (typo, expected) in [
+            ("whre", "where"),
+            ("frist", "first"),
+            ("Count", "count"),
+            ("exist", "exists"),
+            ("toInterger", "toInteger"),
+        ] {
+            assert_eq!(registry.suggest_similar(typo), Some(expected), "{typo}");
+        }
+        assert_eq!(registry.suggest_similar("xyz"), None);
+        assert_eq!(registry.suggest_similar("substr"), None);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("where", "where"), 0);
+        assert_eq!(edit_distance("whre", "where"), 1);
+        assert_eq!(edit_distance("frist", "first"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
 }
@@ -22,7 +22,7 @@ impl FhirPathFunction for AllFunction {
         static SIG: std::sync::LazyLock<FunctionSignature> = std::sync::LazyLock::new(|| {
             FunctionSignature::new(
                 "all",
-                vec![ParameterInfo::optional("criteria", TypeInfo::Any)],
+                vec![ParameterInfo::optional("criteria", TypeInfo::Any).lazy()],
                 TypeInfo::Boolean,
             )
         });
@@ -54,7 +54,7 @@ impl FhirPathFunction for AllFunction {
     }
 }
 
-#[async_trait::async_trait(?Send)]
+#[async_trait::async_trait]
 impl LambdaFunction for AllFunction {
     async fn evaluate_with_lambda(
         &self,
@@ -82,8 +82,8 @@ impl LambdaFunction for AllFunction {
         };
 
         // Check if criteria is true for all items
-        for item in items {
-            let result = (context.evaluator)(criteria, item).await?;
+        for (index, item) in items.into_iter().enumerate() {
+            let result = context.evaluate_item(criteria, item, index).await?;
 
             // Convert result to boolean
             let is_true = match result {
@@ -1,17 +1,17 @@
 //! any() function - returns true if criteria is true for any item
 
+use crate::ast::ExpressionNode;
 use crate::model::{FhirPathValue, TypeInfo};
 use crate::registry::function::{
-    AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
+    EvaluationContext, FhirPathFunction, FunctionError, FunctionResult, LambdaEvaluationContext,
+    LambdaFunction,
 };
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
-use async_trait::async_trait;
 
 /// any() function - returns true if criteria is true for any item
 pub struct AnyFunction;
 
-#[async_trait]
-impl AsyncFhirPathFunction for AnyFunction {
+impl FhirPathFunction for AnyFunction {
     fn name(&self) -> &str {
         "any"
     }
@@ -22,7 +22,7 @@ impl AsyncFhirPathFunction for AnyFunction {
         static SIG: std::sync::LazyLock<FunctionSignature> = std::sync::LazyLock::new(|| {
             FunctionSignature::new(
                 "any",
-                vec![ParameterInfo::optional("criteria", TypeInfo::Any)],
+                vec![ParameterInfo::optional("criteria", TypeInfo::Any).lazy()],
                 TypeInfo::Boolean,
             )
         });
@@ -33,7 +33,7 @@ impl AsyncFhirPathFunction for AnyFunction {
         "Returns `true` if the criteria evaluates to `true` for any element in the input collection, otherwise `false`. If the input collection is empty (`{ }`), the result is `false`."
     }
 
-    async fn evaluate(
+    fn evaluate(
         &self,
         args: &[FhirPathValue],
         context: &EvaluationContext,
@@ -45,11 +45,63 @@ impl AsyncFhirPathFunction for AnyFunction {
                 !context.input.is_empty(),
             )]))
         } else {
-            // TODO: Implement any with criteria - need lambda evaluation
+            // This should not be called for lambda functions - use evaluate_with_lambda instead
             Err(FunctionError::EvaluationError {
                 name: self.name().to_string(),
-                message: "any() with criteria requires lambda evaluation support".to_string(),
+                message: "any() with criteria should use lambda evaluation".to_string(),
             })
         }
     }
 }
+
+#[async_trait::async_trait]
+impl LambdaFunction for AnyFunction {
+    async fn evaluate_with_lambda(
+        &self,
+        args: &[ExpressionNode],
+        context: &LambdaEvaluationContext<'_>,
+    ) -> FunctionResult<FhirPathValue> {
+        if args.is_empty() {
+            // No criteria - check if any items exist (non-empty means some exist)
+            return Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
+                !context.context.input.is_empty(),
+            )]));
+        }
+
+        let criteria = &args[0];
+
+        // Get the collection to iterate over
+        let items = match &context.context.input {
+            FhirPathValue::Collection(items) => items.iter().collect::<Vec<_>>(),
+            FhirPathValue::Empty => {
+                return Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
+                    false,
+                )]));
+            }
+            single => vec![single], // Single item treated as collection
+        };
+
+        // Check if criteria is true for any item
+        for (index, item) in items.into_iter().enumerate() {
+            let result = context.evaluate_item(criteria, item, index).await?;
+
+            let is_true = match result {
+                FhirPathValue::Boolean(b) => b,
+                FhirPathValue::Collection(ref coll) if coll.len() == 1 => {
+                    matches!(coll.get(0), Some(FhirPathValue::Boolean(true)))
+                }
+                _ => false,
+            };
+
+            if is_true {
+                return Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
+                    true,
+                )]));
+            }
+        }
+
+        Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
+            false,
+        )]))
+    }
+}
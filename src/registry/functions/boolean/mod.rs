@@ -16,9 +16,9 @@ use crate::registry::function::FunctionRegistry;
 
 /// Register all boolean functions
 pub fn register_boolean_functions(registry: &mut FunctionRegistry) {
-    registry.register(AllFunction);
+    registry.register_lambda(AllFunction);
     registry.register_async(AllTrueFunction);
-    registry.register_async(AnyFunction);
+    registry.register_lambda(AnyFunction);
     registry.register_async(IsDistinctFunction);
     registry.register_async(NotFunction);
 }
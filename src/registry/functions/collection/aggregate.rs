@@ -13,6 +13,10 @@ type VarMap =
     std::collections::HashMap<String, FhirPathValue, BuildHasherDefault<rustc_hash::FxHasher>>;
 
 /// aggregate() function - aggregates values using a lambda expression
+///
+/// The aggregator is evaluated once per input item with `$this`, `$index` and
+/// `$total` bound; `$total` starts at `init` (or empty), so an empty input
+/// aggregates to `init`.
 pub struct AggregateFunction;
 
 impl FhirPathFunction for AggregateFunction {
@@ -27,7 +31,7 @@ impl FhirPathFunction for AggregateFunction {
             FunctionSignature::new(
                 "aggregate",
                 vec![
-                    ParameterInfo::required("aggregator", TypeInfo::Any),
+                    ParameterInfo::required("aggregator", TypeInfo::Any).lazy(),
                     ParameterInfo::optional("init", TypeInfo::Any),
                 ],
                 TypeInfo::Any,
@@ -48,7 +52,7 @@ impl FhirPathFunction for AggregateFunction {
     }
 }
 
-#[async_trait::async_trait(?Send)]
+#[async_trait::async_trait]
 impl LambdaFunction for AggregateFunction {
     async fn evaluate_with_lambda(
         &self,
@@ -66,31 +70,21 @@ impl LambdaFunction for AggregateFunction {
 
         let aggregator_expr = &args[0];
 
+        // The initial value is an eager argument, evaluated before the call
+        let mut total = context
+            .eager_arg(1)
+            .cloned()
+            .unwrap_or(FhirPathValue::Empty);
+
         // Get the collection to aggregate
         let items = match &context.context.input {
             FhirPathValue::Collection(items) => items.iter().collect::<Vec<_>>(),
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
+            FhirPathValue::Empty => return Ok(total),
             single => vec![single],
         };
 
-        // Get initial value (second argument or empty)
-        let mut total = if args.len() > 1 {
-            // Evaluate the initial value expression
-            let init_result = (context.evaluator)(&args[1], &context.context.input).await?;
-
-            // Unwrap single-item collections (FHIRPath semantics)
-            match init_result {
-                FhirPathValue::Collection(ref items) if items.len() == 1 => {
-                    items.first().unwrap().clone()
-                }
-                other => other,
-            }
-        } else {
-            FhirPathValue::Empty
-        };
-
         // Aggregate over each item
-        for item in items.iter() {
+        for (index, item) in items.iter().enumerate() {
             // Create enhanced evaluator with $this and $total variables
             let result = if let Some(enhanced_evaluator) = context.enhanced_evaluator {
                 let mut additional_vars: VarMap =
@@ -103,8 +97,10 @@ impl LambdaFunction for AggregateFunction {
                     additional_vars.insert(name.clone(), value.clone());
                 }
 
-                // Set $this to current item and $total to accumulated value (parser strips $ prefix)
+                // Set $this to current item, $index to its position and $total to
+                // accumulated value (parser strips $ prefix)
                 additional_vars.insert("this".to_string(), (*item).clone());
+                additional_vars.insert("index".to_string(), FhirPathValue::Integer(index as i64));
                 additional_vars.insert("total".to_string(), total.clone());
 
                 enhanced_evaluator(aggregator_expr, item, &additional_vars).await?
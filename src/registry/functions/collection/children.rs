@@ -1,13 +1,33 @@
 //! children() function implementation
 
+use super::element_paths::PathNode;
 use crate::model::{FhirPathValue, TypeInfo};
 use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 
 /// children() function - returns direct children of nodes in the collection
+///
+/// Array elements are children in their own right; `resourceType` and the
+/// `_name` shadow properties of primitives are not children.
 pub struct ChildrenFunction;
 
+impl ChildrenFunction {
+    /// Direct children of `input` paired with their FHIRPath paths
+    ///
+    /// This is an opt-in traversal for tooling: it yields the same nodes as
+    /// `children()`, each located relative to `base_path`, e.g.
+    /// `Patient.name[0].given[1]` for `base_path` `Patient`.
+    pub fn children_with_paths(input: &FhirPathValue, base_path: &str) -> Vec<PathNode> {
+        super::element_paths::children_with_paths(input, base_path)
+    }
+
+    /// Direct children of `input`, each one visited only once it is taken
+    pub fn lazy_children(input: &FhirPathValue) -> impl Iterator<Item = FhirPathValue> + use<> {
+        super::element_paths::lazy_children(input)
+    }
+}
+
 #[async_trait]
 impl AsyncFhirPathFunction for ChildrenFunction {
     fn name(&self) -> &str {
@@ -41,72 +61,8 @@ impl AsyncFhirPathFunction for ChildrenFunction {
         context: &EvaluationContext,
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
-        let items = context.input.clone().to_collection();
-        let mut result = Vec::new();
-
-        fn collect_children(value: &FhirPathValue, result: &mut Vec<FhirPathValue>) {
-            match value {
-                FhirPathValue::Resource(resource) => {
-                    // Collect all direct child values from the resource
-                    for (_key, field_value) in resource.properties() {
-                        // Convert JSON Value to FhirPathValue
-                        let fhir_path_value = value_to_fhir_path_value(field_value);
-
-                        // Add only the direct child value (no recursion)
-                        match &fhir_path_value {
-                            FhirPathValue::Empty => {} // Skip empty values
-                            _ => {
-                                result.push(fhir_path_value);
-                            }
-                        }
-                    }
-                }
-                FhirPathValue::Collection(items) => {
-                    // For collections, the children are the items themselves
-                    for item in items.iter() {
-                        result.push(item.clone());
-                    }
-                }
-                _ => {} // Primitives have no children
-            }
-        }
-
-        fn value_to_fhir_path_value(value: &serde_json::Value) -> FhirPathValue {
-            use crate::model::FhirResource;
-
-            match value {
-                serde_json::Value::Array(arr) => {
-                    let mut collection = Vec::new();
-                    for item in arr {
-                        collection.push(value_to_fhir_path_value(item));
-                    }
-                    FhirPathValue::collection(collection)
-                }
-                serde_json::Value::Object(_) => {
-                    let resource = FhirResource::from_json(value.clone());
-                    FhirPathValue::Resource(resource.into())
-                }
-                serde_json::Value::String(s) => FhirPathValue::String(s.clone().into()),
-                serde_json::Value::Number(n) => {
-                    if let Some(i) = n.as_i64() {
-                        FhirPathValue::Integer(i)
-                    } else if let Some(f) = n.as_f64() {
-                        FhirPathValue::Decimal(
-                            rust_decimal::Decimal::from_f64_retain(f).unwrap_or_default(),
-                        )
-                    } else {
-                        FhirPathValue::Empty
-                    }
-                }
-                serde_json::Value::Bool(b) => FhirPathValue::Boolean(*b),
-                serde_json::Value::Null => FhirPathValue::Empty,
-            }
-        }
-
-        for item in items.iter() {
-            collect_children(item, &mut result);
-        }
-
-        Ok(FhirPathValue::collection(result))
+        Ok(FhirPathValue::collection(
+            Self::lazy_children(&context.input).collect(),
+        ))
     }
 }
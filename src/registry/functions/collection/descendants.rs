@@ -1,13 +1,34 @@
 //! descendants() function implementation
 
+use super::element_paths::PathNode;
 use crate::model::{FhirPathValue, TypeInfo};
 use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 
 /// descendants() function - returns all descendants of nodes in the collection
+///
+/// Nodes are visited depth-first in document order. The id and extensions of a
+/// primitive (its `_name` shadow property) are descendants of that primitive.
 pub struct DescendantsFunction;
 
+impl DescendantsFunction {
+    /// Descendants of `input` in document order, paired with their FHIRPath paths
+    ///
+    /// This is an opt-in traversal for tooling: it yields the same nodes as
+    /// `descendants()`, each located relative to `base_path`, e.g.
+    /// `Patient.name[0].given[1]` for `base_path` `Patient`.
+    pub fn descendants_with_paths(input: &FhirPathValue, base_path: &str) -> Vec<PathNode> {
+        super::element_paths::descendants_with_paths(input, base_path)
+    }
+
+    /// Descendants of `input` in document order, each one visited only once it
+    /// is taken, so callers that stop early skip the rest of the tree
+    pub fn lazy_descendants(input: &FhirPathValue) -> impl Iterator<Item = FhirPathValue> + use<> {
+        super::element_paths::lazy_descendants(input)
+    }
+}
+
 #[async_trait]
 impl AsyncFhirPathFunction for DescendantsFunction {
     fn name(&self) -> &str {
@@ -41,74 +62,8 @@ impl AsyncFhirPathFunction for DescendantsFunction {
         context: &EvaluationContext,
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
-        let items = context.input.clone().to_collection();
-        let mut result = Vec::new();
-
-        fn collect_descendants(value: &FhirPathValue, result: &mut Vec<FhirPathValue>) {
-            match value {
-                FhirPathValue::Resource(resource) => {
-                    // Collect all nested values from the resource
-                    for (_key, field_value) in resource.properties() {
-                        // Convert JSON Value to FhirPathValue
-                        let fhir_path_value = value_to_fhir_path_value(field_value);
-
-                        // Add the field value itself
-                        match &fhir_path_value {
-                            FhirPathValue::Empty => {} // Skip empty values
-                            _ => {
-                                result.push(fhir_path_value.clone());
-                                // Recursively collect descendants
-                                collect_descendants(&fhir_path_value, result);
-                            }
-                        }
-                    }
-                }
-                FhirPathValue::Collection(items) => {
-                    for item in items.iter() {
-                        result.push(item.clone());
-                        collect_descendants(item, result);
-                    }
-                }
-                _ => {} // Primitives have no descendants
-            }
-        }
-
-        fn value_to_fhir_path_value(value: &serde_json::Value) -> FhirPathValue {
-            use crate::model::FhirResource;
-
-            match value {
-                serde_json::Value::Array(arr) => {
-                    let mut collection = Vec::new();
-                    for item in arr {
-                        collection.push(value_to_fhir_path_value(item));
-                    }
-                    FhirPathValue::collection(collection)
-                }
-                serde_json::Value::Object(_) => {
-                    let resource = FhirResource::from_json(value.clone());
-                    FhirPathValue::Resource(resource.into())
-                }
-                serde_json::Value::String(s) => FhirPathValue::String(s.clone().into()),
-                serde_json::Value::Number(n) => {
-                    if let Some(i) = n.as_i64() {
-                        FhirPathValue::Integer(i)
-                    } else if let Some(f) = n.as_f64() {
-                        FhirPathValue::Decimal(
-                            rust_decimal::Decimal::from_f64_retain(f).unwrap_or_default(),
-                        )
-                    } else {
-                        FhirPathValue::Empty
-                    }
-                }
-                serde_json::Value::Bool(b) => FhirPathValue::Boolean(*b),
-                serde_json::Value::Null => FhirPathValue::Empty,
-            }
-        }
-
-        for item in items.iter() {
-            collect_descendants(item, &mut result);
-        }
-
-        Ok(FhirPathValue::collection(result))
+        Ok(FhirPathValue::collection(
+            Self::lazy_descendants(&context.input).collect(),
+        ))
     }
 }
@@ -1,11 +1,15 @@
 //! distinct() function - returns unique items in the collection
 
+use super::UniqueValues;
 use crate::model::{FhirPathValue, TypeInfo};
 use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 
 /// distinct() function - returns unique items in the collection
+///
+/// Items are compared with `=` semantics, so `1` and `1.0` are duplicates, and
+/// the first occurrence of each item is kept in place.
 pub struct DistinctFunction;
 
 #[async_trait]
@@ -41,13 +45,10 @@ impl AsyncFhirPathFunction for DistinctFunction {
         context: &EvaluationContext,
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
-        let items = context.input.clone().to_collection();
-        let mut unique = Vec::new();
-        for item in items.into_iter() {
-            if !unique.iter().any(|u| u == &item) {
-                unique.push(item);
-            }
+        let mut unique = UniqueValues::default();
+        for item in context.input.clone().to_collection().iter() {
+            unique.insert(item);
         }
-        Ok(FhirPathValue::collection(unique))
+        Ok(FhirPathValue::collection(unique.into_vec()))
     }
 }
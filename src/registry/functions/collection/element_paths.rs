@@ -0,0 +1,327 @@
+//! Shared traversal for children() and descendants()
+//!
+//! Tooling such as invariant failure reporting needs to know where each node
+//! came from, so the `*_with_paths` helpers yield the same nodes as the
+//! functions paired with their FHIRPath location, e.g. `Patient.name[0].given[1]`.
+//! Paths are only built when asked for.
+//!
+//! Object properties are children in JSON field order, with array elements as
+//! children in their own right. `resourceType` is not an element and is skipped.
+//! The `_name` shadow properties that carry the id and extensions of primitive
+//! `name` are not children themselves: their contents are descendants of the
+//! primitive, e.g. `Patient.birthDate.extension[0]`.
+//!
+//! Objects stored under a choice element key know their type, so the node of
+//! `valueReference` is a `Reference` for `ofType(Reference)`.
+
+use crate::model::{FhirPathValue, FhirResource, choice_element_type};
+use serde_json::Value;
+
+/// A node together with the FHIRPath path that locates it
+pub type PathNode = (String, FhirPathValue);
+
+/// A node and its path, if paths are being tracked
+type Node = (Option<String>, FhirPathValue);
+
+/// Direct children of every item in `input`, visited as they are taken
+pub(super) fn lazy_children(input: &FhirPathValue) -> impl Iterator<Item = FhirPathValue> + use<> {
+    values(Traversal::new(input, None, false))
+}
+
+/// All descendants of every item in `input` in document order, visited as
+/// they are taken
+pub(super) fn lazy_descendants(
+    input: &FhirPathValue,
+) -> impl Iterator<Item = FhirPathValue> + use<> {
+    values(Traversal::new(input, None, true))
+}
+
+/// Direct children of every item in `input`, located relative to `base_path`
+pub(super) fn children_with_paths(input: &FhirPathValue, base_path: &str) -> Vec<PathNode> {
+    with_paths(Traversal::new(input, Some(base_path), false))
+}
+
+/// All descendants of every item in `input` in document order, located
+/// relative to `base_path`
+pub(super) fn descendants_with_paths(input: &FhirPathValue, base_path: &str) -> Vec<PathNode> {
+    with_paths(Traversal::new(input, Some(base_path), true))
+}
+
+fn values(nodes: Traversal) -> impl Iterator<Item = FhirPathValue> {
+    nodes.map(|(_, value)| value)
+}
+
+fn with_paths(nodes: Traversal) -> Vec<PathNode> {
+    nodes
+        .map(|(path, value)| (path.unwrap_or_default(), value))
+        .collect()
+}
+
+/// Work left for a [`Traversal`]
+enum Pending {
+    /// The fields of an object, whose own node has been yielded already
+    Fields(Value, Option<String>),
+    /// A node still to be yielded, with its type if it is a choice element
+    Node(Value, Option<String>, Option<String>),
+}
+
+/// Depth-first traversal yielding one node at a time in document order
+///
+/// Nothing below the last yielded node is visited until the iterator is
+/// advanced again, so callers that stop early skip the rest of the tree.
+struct Traversal {
+    /// Work left to do, the next step on top
+    stack: Vec<Pending>,
+    /// Whether to go below the direct children
+    recurse: bool,
+}
+
+impl Traversal {
+    fn new(input: &FhirPathValue, base_path: Option<&str>, recurse: bool) -> Self {
+        let mut stack: Vec<Pending> = input_items(input, base_path)
+            .into_iter()
+            .map(|(path, json)| Pending::Fields(json, path))
+            .collect();
+        stack.reverse();
+        Self { stack, recurse }
+    }
+
+    /// Queue the nodes found in the fields of `json`
+    fn push_fields(&mut self, json: Value, path: Option<String>) {
+        let Value::Object(fields) = json else {
+            return; // Primitives have no children
+        };
+
+        let mut pending = Vec::new();
+        for (key, field_value) in fields {
+            if key == "resourceType" {
+                continue;
+            }
+
+            // Id and extensions of a primitive are descendants of that primitive,
+            // one object per primitive value (`null` for values without either)
+            if let Some(primitive) = key.strip_prefix('_') {
+                if self.recurse {
+                    match field_value {
+                        Value::Array(items) => {
+                            for (index, item) in items.into_iter().enumerate() {
+                                let item_path = join(&path, &format!("{primitive}[{index}]"));
+                                pending.push(Pending::Fields(item, item_path));
+                            }
+                        }
+                        shadow => pending.push(Pending::Fields(shadow, join(&path, primitive))),
+                    }
+                }
+                continue;
+            }
+
+            let element_type = choice_element_type(&key);
+            match field_value {
+                Value::Array(items) => {
+                    for (index, item) in items.into_iter().enumerate() {
+                        let item_path = join(&path, &format!("{key}[{index}]"));
+                        pending.push(Pending::Node(item, item_path, element_type.clone()));
+                    }
+                }
+                field_value => {
+                    pending.push(Pending::Node(field_value, join(&path, &key), element_type))
+                }
+            }
+        }
+        self.stack.extend(pending.into_iter().rev());
+    }
+}
+
+impl Iterator for Traversal {
+    type Item = Node;
+
+    fn next(&mut self) -> Option<Node> {
+        while let Some(pending) = self.stack.pop() {
+            match pending {
+                Pending::Fields(json, path) => self.push_fields(json, path),
+                Pending::Node(Value::Null, _, _) => {}
+                // Nested arrays are flattened, so only their elements are nodes
+                Pending::Node(Value::Array(items), path, element_type) => {
+                    let nodes: Vec<_> = (items.into_iter().enumerate())
+                        .map(|(index, item)| {
+                            let item_path = path.as_ref().map(|path| format!("{path}[{index}]"));
+                            Pending::Node(item, item_path, element_type.clone())
+                        })
+                        .collect();
+                    self.stack.extend(nodes.into_iter().rev());
+                }
+                Pending::Node(json, path, element_type) => {
+                    #[cfg(test)]
+                    tests::VISITED.with(|visited| visited.set(visited.get() + 1));
+                    let value = node_value(&json, element_type);
+                    if self.recurse {
+                        self.stack.push(Pending::Fields(json, path.clone()));
+                    }
+                    return Some((path, value));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// The path of `step` below `path`, if paths are being tracked
+fn join(path: &Option<String>, step: &str) -> Option<String> {
+    path.as_ref().map(|path| format!("{path}.{step}"))
+}
+
+/// Index the items of a multi-item input so each gets a distinct path
+fn input_items(input: &FhirPathValue, base_path: Option<&str>) -> Vec<(Option<String>, Value)> {
+    match input {
+        FhirPathValue::Collection(items) if items.len() > 1 => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let path = base_path.map(|base| format!("{base}[{index}]"));
+                (path, Value::from(item.clone()))
+            })
+            .collect(),
+        FhirPathValue::Collection(items) => items
+            .iter()
+            .map(|item| (base_path.map(str::to_string), Value::from(item.clone())))
+            .collect(),
+        FhirPathValue::Empty => Vec::new(),
+        single => vec![(base_path.map(str::to_string), Value::from(single.clone()))],
+    }
+}
+
+/// Convert a JSON node into the value children() and descendants() yield
+fn node_value(json: &Value, element_type: Option<String>) -> FhirPathValue {
+    match json {
+        Value::Object(_) => {
+            let resource = FhirResource::from_json(json.clone());
+            let resource = match element_type {
+                Some(element_type) => resource.with_element_type(element_type),
+                None => resource,
+            };
+            FhirPathValue::Resource(resource.into())
+        }
+        Value::String(s) => FhirPathValue::String(s.clone().into()),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => FhirPathValue::Integer(i),
+            None => n
+                .as_f64()
+                .and_then(rust_decimal::Decimal::from_f64_retain)
+                .map_or(FhirPathValue::Empty, FhirPathValue::Decimal),
+        },
+        Value::Bool(b) => FhirPathValue::Boolean(*b),
+        Value::Array(_) | Value::Null => FhirPathValue::Empty,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::FhirPathEngine;
+    use crate::model::FhirPathValue;
+    use serde_json::{Value, json};
+    use std::cell::Cell;
+
+    thread_local! {
+        /// Nodes yielded by traversals on this thread
+        pub(super) static VISITED: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Evaluate `expression` against `resource` with the number of nodes visited
+    async fn eval_counting(expression: &str, resource: Value) -> (FhirPathValue, usize) {
+        VISITED.with(|visited| visited.set(0));
+        let result = FhirPathEngine::new()
+            .evaluate(expression, resource)
+            .await
+            .unwrap_or_else(|e| panic!("'{expression}' should evaluate: {e}"));
+        (result, VISITED.with(Cell::get))
+    }
+
+    fn boolean(value: bool) -> FhirPathValue {
+        FhirPathValue::collection(vec![FhirPathValue::Boolean(value)])
+    }
+
+    /// A Patient whose second extension holds a Reference without a reference
+    fn patient() -> Value {
+        json!({
+            "resourceType": "Patient",
+            "extension": [
+                {
+                    "url": "http://example.org/referrer",
+                    "valueReference": {"reference": "Practitioner/1", "display": "Dr Who"}
+                },
+                {"url": "http://example.org/insurer", "valueReference": {"display": "Acme"}},
+                {
+                    "url": "http://example.org/pharmacy",
+                    "valueReference": {"reference": "Organization/3"}
+                }
+            ],
+            "name": [{"family": "Doe", "given": ["Jane", "Ann"]}]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_all_stops_at_first_violating_node() {
+        let (count, total) = eval_counting("descendants().count()", patient()).await;
+        assert_eq!(
+            count,
+            FhirPathValue::collection(vec![FhirPathValue::Integer(17)])
+        );
+        assert_eq!(total, 17);
+
+        // extension[0] with its 4 descendants, then extension[1], its url and
+        // the violating valueReference; nothing below or after it
+        let (result, visited) = eval_counting(
+            "descendants().ofType(Reference).all(reference.exists())",
+            patient(),
+        )
+        .await;
+        assert_eq!(result, boolean(false));
+        assert_eq!(visited, 8);
+    }
+
+    #[tokio::test]
+    async fn test_all_visits_every_node_when_it_holds() {
+        let (result, visited) = eval_counting(
+            "descendants().ofType(Reference).all(display.exists() or reference.exists())",
+            patient(),
+        )
+        .await;
+        assert_eq!(result, boolean(true));
+        assert_eq!(visited, 17);
+    }
+
+    #[tokio::test]
+    async fn test_exists_stops_at_first_kept_node() {
+        let (result, visited) =
+            eval_counting("descendants().ofType(Reference).exists()", patient()).await;
+        assert_eq!(result, boolean(true));
+        assert_eq!(visited, 3);
+
+        let (result, visited) = eval_counting(
+            "children().where(family.exists()).select(given).exists().not()",
+            patient(),
+        )
+        .await;
+        assert_eq!(result, boolean(false));
+        assert_eq!(visited, 4);
+    }
+
+    #[tokio::test]
+    async fn test_lazy_nodes_see_resource() {
+        let (result, visited) = eval_counting(
+            "descendants().ofType(Reference).all(%resource.name.exists() and reference.exists())",
+            patient(),
+        )
+        .await;
+        assert_eq!(result, boolean(false));
+        assert_eq!(visited, 8);
+
+        let (result, visited) = eval_counting(
+            "children().where(%resource.name.family = 'Doe').exists()",
+            patient(),
+        )
+        .await;
+        assert_eq!(result, boolean(true));
+        assert_eq!(visited, 1);
+    }
+}
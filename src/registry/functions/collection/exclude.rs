@@ -47,7 +47,7 @@ impl AsyncFhirPathFunction for ExcludeFunction {
 
         let mut result = Vec::new();
         for item in left.into_iter() {
-            if !right.iter().any(|r| r == &item) {
+            if !right.iter().any(|r| r.fhirpath_eq(&item)) {
                 result.push(item);
             }
         }
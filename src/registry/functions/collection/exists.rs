@@ -22,7 +22,7 @@ impl FhirPathFunction for ExistsFunction {
         static SIG: std::sync::LazyLock<FunctionSignature> = std::sync::LazyLock::new(|| {
             FunctionSignature::new(
                 "exists",
-                vec![ParameterInfo::optional("condition", TypeInfo::Any)],
+                vec![ParameterInfo::optional("condition", TypeInfo::Any).lazy()],
                 TypeInfo::Boolean,
             )
         });
@@ -60,7 +60,7 @@ impl FhirPathFunction for ExistsFunction {
     }
 }
 
-#[async_trait::async_trait(?Send)]
+#[async_trait::async_trait]
 impl LambdaFunction for ExistsFunction {
     async fn evaluate_with_lambda(
         &self,
@@ -103,8 +103,8 @@ impl LambdaFunction for ExistsFunction {
         };
 
         // Check if any item satisfies the condition
-        for item in items.iter() {
-            let result = (context.evaluator)(condition_expr, item).await?;
+        for (index, item) in items.iter().enumerate() {
+            let result = context.evaluate_item(condition_expr, item, index).await?;
 
             // Check if result is truthy
             let is_truthy = match result {
@@ -6,6 +6,9 @@ use crate::registry::signature::{FunctionSignature, ParameterInfo};
 use async_trait::async_trait;
 
 /// intersect() function - returns the intersection of two collections
+///
+/// Items are compared with `=` semantics; the result keeps the first occurrence
+/// of each matching input item, in input order.
 pub struct IntersectFunction;
 
 #[async_trait]
@@ -47,7 +50,11 @@ impl AsyncFhirPathFunction for IntersectFunction {
 
         let mut result = Vec::new();
         for item in left.into_iter() {
-            if right.iter().any(|r| r == &item) && !result.iter().any(|res| res == &item) {
+            if right.iter().any(|r| r.fhirpath_eq(&item))
+                && !result
+                    .iter()
+                    .any(|res: &FhirPathValue| res.fhirpath_eq(&item))
+            {
                 result.push(item);
             }
         }
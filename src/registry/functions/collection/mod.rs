@@ -6,6 +6,7 @@ mod combine;
 mod count;
 mod descendants;
 mod distinct;
+mod element_paths;
 mod empty;
 mod exclude;
 mod exists;
@@ -18,6 +19,8 @@ mod sort;
 mod subset_of;
 mod superset_of;
 mod tail;
+mod union;
+mod unique_values;
 
 pub use aggregate::AggregateFunction;
 pub use children::ChildrenFunction;
@@ -25,6 +28,7 @@ pub use combine::CombineFunction;
 pub use count::CountFunction;
 pub use descendants::DescendantsFunction;
 pub use distinct::DistinctFunction;
+pub use element_paths::PathNode;
 pub use empty::EmptyFunction;
 pub use exclude::ExcludeFunction;
 pub use exists::ExistsFunction;
@@ -37,15 +41,17 @@ pub use sort::SortFunction;
 pub use subset_of::SubsetOfFunction;
 pub use superset_of::SupersetOfFunction;
 pub use tail::TailFunction;
+pub use union::UnionFunction;
+pub(crate) use unique_values::{UniqueValues, union_of};
 
 use crate::registry::function::FunctionRegistry;
 
 /// Register all collection functions
 pub fn register_collection_functions(registry: &mut FunctionRegistry) {
     // Lambda functions (still using old trait)
-    registry.register(AggregateFunction);
-    registry.register(ExistsFunction);
-    registry.register(SortFunction);
+    registry.register_lambda(AggregateFunction);
+    registry.register_lambda(ExistsFunction);
+    registry.register_lambda(SortFunction);
 
     // Async collection functions
     registry.register_async(ChildrenFunction);
@@ -63,4 +69,5 @@ pub fn register_collection_functions(registry: &mut FunctionRegistry) {
     registry.register_async(SubsetOfFunction);
     registry.register_async(SupersetOfFunction);
     registry.register_async(TailFunction);
+    registry.register_async(UnionFunction);
 }
@@ -1,11 +1,15 @@
 //! single() function - returns the single item if collection has exactly one item
 
 use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
+use crate::registry::function::{
+    AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
+};
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 
 /// single() function - returns the single item if collection has exactly one item
+///
+/// Unlike first() and last(), an input of more than one item is an error.
 pub struct SingleFunction;
 
 #[async_trait]
@@ -27,7 +31,7 @@ impl AsyncFhirPathFunction for SingleFunction {
     }
 
     fn documentation(&self) -> &str {
-        "Returns the single item in the input collection. If the input collection does not contain exactly one item, an empty collection is returned."
+        "Returns the single item in the input collection, or an empty collection if the input is empty. An input of more than one item is an error."
     }
 
     async fn evaluate(
@@ -38,13 +42,14 @@ impl AsyncFhirPathFunction for SingleFunction {
         self.validate_args(args)?;
         match &context.input {
             FhirPathValue::Empty => Ok(FhirPathValue::Empty),
-            FhirPathValue::Collection(items) => {
-                if items.len() == 1 {
-                    Ok(items.iter().next().unwrap().clone())
-                } else {
-                    Ok(FhirPathValue::Empty)
-                }
-            }
+            FhirPathValue::Collection(items) => match items.len() {
+                0 => Ok(FhirPathValue::Empty),
+                1 => Ok(items.iter().next().unwrap().clone()),
+                count => Err(FunctionError::EvaluationError {
+                    name: self.name().to_string(),
+                    message: format!("expected at most one item, got {count}"),
+                }),
+            },
             other => Ok(other.clone()), // Single value returns itself
         }
     }
@@ -129,7 +129,7 @@ impl FhirPathFunction for SortFunction {
         static SIG: std::sync::LazyLock<FunctionSignature> = std::sync::LazyLock::new(|| {
             FunctionSignature::variadic(
                 "sort",
-                vec![ParameterInfo::optional("expressions", TypeInfo::Any)],
+                vec![ParameterInfo::optional("expressions", TypeInfo::Any).lazy()],
                 TypeInfo::Collection(Box::new(TypeInfo::Any)),
             )
         });
@@ -158,7 +158,7 @@ impl FhirPathFunction for SortFunction {
     }
 }
 
-#[async_trait::async_trait(?Send)]
+#[async_trait::async_trait]
 impl LambdaFunction for SortFunction {
     async fn evaluate_with_lambda(
         &self,
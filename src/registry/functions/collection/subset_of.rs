@@ -6,6 +6,8 @@ use crate::registry::signature::{FunctionSignature, ParameterInfo};
 use async_trait::async_trait;
 
 /// subsetOf() function - returns true if the input collection is a subset of the argument collection
+///
+/// Items are compared with `=` semantics, as in intersect().
 pub struct SubsetOfFunction;
 
 #[async_trait]
@@ -47,9 +49,11 @@ impl AsyncFhirPathFunction for SubsetOfFunction {
         }
 
         // Check if every element in subset exists in superset
-        let is_subset = subset
-            .iter()
-            .all(|item| superset.iter().any(|super_item| super_item == item));
+        let is_subset = subset.iter().all(|item| {
+            superset
+                .iter()
+                .any(|super_item| super_item.fhirpath_eq(item))
+        });
 
         Ok(FhirPathValue::Boolean(is_subset))
     }
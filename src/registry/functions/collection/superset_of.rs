@@ -6,6 +6,8 @@ use crate::registry::signature::{FunctionSignature, ParameterInfo};
 use async_trait::async_trait;
 
 /// supersetOf() function - returns true if the input collection is a superset of the argument collection
+///
+/// Items are compared with `=` semantics, as in intersect().
 pub struct SupersetOfFunction;
 
 #[async_trait]
@@ -47,9 +49,11 @@ impl AsyncFhirPathFunction for SupersetOfFunction {
         }
 
         // Check if every element in subset exists in superset
-        let is_superset = subset
-            .iter()
-            .all(|item| superset.iter().any(|super_item| super_item == item));
+        let is_superset = subset.iter().all(|item| {
+            superset
+                .iter()
+                .any(|super_item| super_item.fhirpath_eq(item))
+        });
 
         Ok(FhirPathValue::Boolean(is_superset))
     }
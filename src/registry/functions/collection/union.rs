@@ -0,0 +1,46 @@
+//! union() function - returns the union of two collections
+
+use crate::model::{FhirPathValue, TypeInfo};
+use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
+use crate::registry::signature::{FunctionSignature, ParameterInfo};
+use async_trait::async_trait;
+
+/// union() function - returns the union of two collections
+pub struct UnionFunction;
+
+#[async_trait]
+impl AsyncFhirPathFunction for UnionFunction {
+    fn name(&self) -> &str {
+        "union"
+    }
+    fn human_friendly_name(&self) -> &str {
+        "Union"
+    }
+    fn signature(&self) -> &FunctionSignature {
+        static SIG: std::sync::LazyLock<FunctionSignature> = std::sync::LazyLock::new(|| {
+            FunctionSignature::new(
+                "union",
+                vec![ParameterInfo::required("other", TypeInfo::Any)],
+                TypeInfo::Collection(Box::new(TypeInfo::Any)),
+            )
+        });
+        &SIG
+    }
+
+    fn is_pure(&self) -> bool {
+        true // union() is a pure collection function
+    }
+
+    fn documentation(&self) -> &str {
+        "Merges the input collection and the other collection into a single collection, eliminating duplicates. The items may be of different types, e.g. resources and primitives."
+    }
+
+    async fn evaluate(
+        &self,
+        args: &[FhirPathValue],
+        context: &EvaluationContext,
+    ) -> FunctionResult<FhirPathValue> {
+        self.validate_args(args)?;
+        Ok(super::union_of(context.input.clone(), args[0].clone()))
+    }
+}
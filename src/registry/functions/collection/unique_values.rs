@@ -0,0 +1,149 @@
+//! Insertion-ordered de-duplication with `=` semantics
+//!
+//! Shared by distinct(), isDistinct(), repeat() and union. Each value is hashed by a
+//! normalized key that equal values share, so only values in the same bucket
+//! are compared with `=`.
+
+use crate::model::{FhirPathValue, Quantity};
+use crate::registry::operators::{element_json, implicit_quantity, json_number};
+use rust_decimal::Decimal;
+use rustc_hash::{FxHashMap, FxHasher};
+use serde_json::Value;
+use std::hash::{Hash, Hasher};
+
+/// Insertion-ordered set of values compared with `=` semantics
+///
+/// Values are bucketed by a hash that equal values always share, and only
+/// compared within their bucket.
+#[derive(Default)]
+pub(crate) struct UniqueValues {
+    values: Vec<FhirPathValue>,
+    by_hash: FxHashMap<u64, Vec<usize>>,
+}
+
+impl UniqueValues {
+    /// Add a value, returning `false` if an equal value is already present
+    pub(crate) fn insert(&mut self, value: &FhirPathValue) -> bool {
+        let indices = self.by_hash.entry(bucket_of(value)).or_default();
+        if indices
+            .iter()
+            .any(|&index| self.values[index].fhirpath_eq(value))
+        {
+            return false;
+        }
+
+        indices.push(self.values.len());
+        self.values.push(value.clone());
+        true
+    }
+
+    /// The values in insertion order
+    pub(crate) fn into_vec(self) -> Vec<FhirPathValue> {
+        self.values
+    }
+}
+
+/// The union of two collections: their items in order, without duplicates
+pub(crate) fn union_of(left: FhirPathValue, right: FhirPathValue) -> FhirPathValue {
+    let mut unique = UniqueValues::default();
+    for item in left
+        .to_collection()
+        .into_iter()
+        .chain(right.to_collection())
+    {
+        unique.insert(&item);
+    }
+    FhirPathValue::collection(unique.into_vec())
+}
+
+/// Hash a value so that values equal under `=` land in the same bucket
+///
+/// Values equal across representations hash a normalized form: numbers and
+/// quantities (including FHIR Quantity elements) their canonical UCUM
+/// quantity, so `1 = 1.0` and `1000 'mg' = 1 'g'`, and dates and date times
+/// the UTC components their precision specifies.
+fn bucket_of(value: &FhirPathValue) -> u64 {
+    let mut hasher = FxHasher::default();
+    match value {
+        FhirPathValue::String(s) => (0u8, s.as_ref()).hash(&mut hasher),
+        FhirPathValue::Boolean(b) => (1u8, b).hash(&mut hasher),
+        // Numbers equal quantities with unit '1'
+        FhirPathValue::Integer(i) => {
+            hash_quantity(&implicit_quantity(Decimal::from(*i)), &mut hasher)
+        }
+        FhirPathValue::Decimal(d) => hash_quantity(&implicit_quantity(*d), &mut hasher),
+        FhirPathValue::Quantity(q) => hash_quantity(q, &mut hasher),
+        FhirPathValue::Resource(_) | FhirPathValue::JsonValue(_) => {
+            match value.quantity_element() {
+                Some(q) => hash_quantity(&q, &mut hasher),
+                None => {
+                    2u8.hash(&mut hasher);
+                    if let Some(json) = element_json(value) {
+                        hash_canonical(json, &mut hasher);
+                    }
+                }
+            }
+        }
+        FhirPathValue::Date(date) => {
+            3u8.hash(&mut hasher);
+            date.to_datetime().hash_compared(&mut hasher);
+        }
+        FhirPathValue::DateTime(datetime) => {
+            3u8.hash(&mut hasher);
+            datetime.hash_compared(&mut hasher);
+        }
+        FhirPathValue::Time(time) => {
+            4u8.hash(&mut hasher);
+            time.hash_compared(&mut hasher);
+        }
+        // Collections, type infos and empty values share a bucket
+        _ => 5u8.hash(&mut hasher),
+    }
+    hasher.finish()
+}
+
+/// Hash a quantity by its canonical form
+///
+/// The canonical value is rounded well past f64 noise, so quantities whose
+/// conversion factors only agree to floating point precision still share a
+/// bucket. Units without a UCUM form, such as calendar durations, share one
+/// bucket.
+fn hash_quantity(quantity: &Quantity, hasher: &mut FxHasher) {
+    6u8.hash(hasher);
+    if quantity.unit.is_none() {
+        (0u8, quantity.value.normalize()).hash(hasher);
+        return;
+    }
+    if let Ok(canonical) = quantity.to_canonical() {
+        let value = canonical.value.round_sf(10).unwrap_or(canonical.value);
+        (1u8, canonical.unit, value.normalize()).hash(hasher);
+    }
+}
+
+/// Hash JSON independently of object key order and number formatting
+fn hash_canonical(value: &Value, hasher: &mut FxHasher) {
+    match value {
+        Value::Null => 0u8.hash(hasher),
+        Value::Bool(b) => (1u8, b).hash(hasher),
+        Value::Number(n) => match json_number(n) {
+            Some(number) => (2u8, number.normalize().to_string()).hash(hasher),
+            None => (2u8, n.to_string()).hash(hasher),
+        },
+        Value::String(s) => (3u8, s).hash(hasher),
+        Value::Array(items) => {
+            (4u8, items.len()).hash(hasher);
+            for item in items {
+                hash_canonical(item, hasher);
+            }
+        }
+        Value::Object(map) => {
+            (5u8, map.len()).hash(hasher);
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            for (key, item) in entries {
+                key.hash(hasher);
+                hash_canonical(item, hasher);
+            }
+        }
+    }
+}
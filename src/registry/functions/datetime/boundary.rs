@@ -1,6 +1,6 @@
 //! Boundary functions - lowBoundary() and highBoundary() for precision-based bounds
 
-use crate::model::{FhirPathValue, TypeInfo};
+use crate::model::{FhirPathValue, PrecisionDateTime, TemporalPrecision, TypeInfo};
 use crate::registry::function::{
     AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
 };
@@ -99,8 +99,12 @@ impl AsyncFhirPathFunction for LowBoundaryFunction {
                 }
             }
             FhirPathValue::Date(d) => {
-                let low_bound = calculate_date_low_boundary(d)?;
-                Ok(FhirPathValue::DateTime(low_bound))
+                let low_bound = calculate_date_low_boundary(&d.date)?;
+                Ok(FhirPathValue::DateTime(PrecisionDateTime::new(
+                    low_bound,
+                    TemporalPrecision::Millisecond,
+                    false,
+                )))
             }
             FhirPathValue::Quantity(q) => {
                 match calculate_low_boundary(&q.value, precision) {
@@ -124,8 +128,12 @@ impl AsyncFhirPathFunction for LowBoundaryFunction {
                 }
             }
             FhirPathValue::DateTime(dt) => {
-                let low_bound = calculate_datetime_low_boundary(dt)?;
-                Ok(FhirPathValue::DateTime(low_bound))
+                let low_bound = calculate_datetime_low_boundary(&dt.datetime)?;
+                Ok(FhirPathValue::DateTime(PrecisionDateTime::new(
+                    low_bound,
+                    TemporalPrecision::Millisecond,
+                    dt.timezone_specified,
+                )))
             }
             FhirPathValue::Empty => Ok(FhirPathValue::Empty),
             _ => Ok(FhirPathValue::Empty),
@@ -222,8 +230,12 @@ impl AsyncFhirPathFunction for HighBoundaryFunction {
                 }
             }
             FhirPathValue::Date(d) => {
-                let high_bound = calculate_date_high_boundary(d)?;
-                Ok(FhirPathValue::DateTime(high_bound))
+                let high_bound = calculate_date_high_boundary(&d.date)?;
+                Ok(FhirPathValue::DateTime(PrecisionDateTime::new(
+                    high_bound,
+                    TemporalPrecision::Millisecond,
+                    false,
+                )))
             }
             FhirPathValue::Quantity(q) => {
                 match calculate_high_boundary(&q.value, precision) {
@@ -247,8 +259,12 @@ impl AsyncFhirPathFunction for HighBoundaryFunction {
                 }
             }
             FhirPathValue::DateTime(dt) => {
-                let high_bound = calculate_datetime_high_boundary(dt)?;
-                Ok(FhirPathValue::DateTime(high_bound))
+                let high_bound = calculate_datetime_high_boundary(&dt.datetime)?;
+                Ok(FhirPathValue::DateTime(PrecisionDateTime::new(
+                    high_bound,
+                    TemporalPrecision::Millisecond,
+                    dt.timezone_specified,
+                )))
             }
             FhirPathValue::Empty => Ok(FhirPathValue::Empty),
             _ => Ok(FhirPathValue::Empty),
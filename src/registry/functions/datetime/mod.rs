@@ -2,10 +2,12 @@
 
 mod boundary;
 mod now;
+mod time_of_day;
 mod today;
 
 pub use boundary::{HighBoundaryFunction, LowBoundaryFunction};
 pub use now::NowFunction;
+pub use time_of_day::TimeOfDayFunction;
 pub use today::TodayFunction;
 
 use crate::registry::function::FunctionRegistry;
@@ -14,6 +16,7 @@ use crate::registry::function::FunctionRegistry;
 pub fn register_datetime_functions(registry: &mut FunctionRegistry) {
     registry.register_async(NowFunction);
     registry.register_async(TodayFunction);
+    registry.register_async(TimeOfDayFunction);
     registry.register_async(LowBoundaryFunction);
     registry.register_async(HighBoundaryFunction);
 }
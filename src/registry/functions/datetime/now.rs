@@ -1,12 +1,13 @@
 //! now() function - returns current date/time
 
-use crate::model::{FhirPathValue, TypeInfo};
+use crate::model::{FhirPathValue, PrecisionDateTime, TypeInfo};
 use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
-use chrono::Utc;
 
 /// now() function - returns current date/time
+///
+/// The time is read once when an evaluation starts, so `now() = now()` holds.
 pub struct NowFunction;
 
 #[async_trait]
@@ -30,12 +31,11 @@ impl AsyncFhirPathFunction for NowFunction {
     async fn evaluate(
         &self,
         args: &[FhirPathValue],
-        _context: &EvaluationContext,
+        context: &EvaluationContext,
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
-        let now = Utc::now();
-        Ok(FhirPathValue::DateTime(
-            now.with_timezone(&chrono::FixedOffset::east_opt(0).unwrap()),
-        ))
+        Ok(FhirPathValue::DateTime(PrecisionDateTime::from_datetime(
+            context.now,
+        )))
     }
 }
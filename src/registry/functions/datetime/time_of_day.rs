@@ -0,0 +1,42 @@
+//! timeOfDay() function - returns current time
+
+use crate::model::{FhirPathValue, PrecisionTime, TypeInfo};
+use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
+use crate::registry::signature::FunctionSignature;
+use async_trait::async_trait;
+
+/// timeOfDay() function - returns current time
+///
+/// The time of day of the time `now()` returns in the same evaluation.
+pub struct TimeOfDayFunction;
+
+#[async_trait]
+impl AsyncFhirPathFunction for TimeOfDayFunction {
+    fn name(&self) -> &str {
+        "timeOfDay"
+    }
+    fn human_friendly_name(&self) -> &str {
+        "Time Of Day"
+    }
+    fn signature(&self) -> &FunctionSignature {
+        static SIG: std::sync::LazyLock<FunctionSignature> = std::sync::LazyLock::new(|| {
+            FunctionSignature::new("timeOfDay", vec![], TypeInfo::Time)
+        });
+        &SIG
+    }
+
+    fn documentation(&self) -> &str {
+        "Returns the current time of day, without a timezone."
+    }
+
+    async fn evaluate(
+        &self,
+        args: &[FhirPathValue],
+        context: &EvaluationContext,
+    ) -> FunctionResult<FhirPathValue> {
+        self.validate_args(args)?;
+        Ok(FhirPathValue::Time(PrecisionTime::from_time(
+            context.now.time(),
+        )))
+    }
+}
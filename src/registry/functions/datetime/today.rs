@@ -1,12 +1,13 @@
 //! today() function - returns current date
 
-use crate::model::{FhirPathValue, TypeInfo};
+use crate::model::{FhirPathValue, PrecisionDate, TypeInfo};
 use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
-use chrono::Local;
 
 /// today() function - returns current date
+///
+/// The date of the time `now()` returns in the same evaluation.
 pub struct TodayFunction;
 
 #[async_trait]
@@ -25,10 +26,11 @@ impl AsyncFhirPathFunction for TodayFunction {
     async fn evaluate(
         &self,
         args: &[FhirPathValue],
-        _context: &EvaluationContext,
+        context: &EvaluationContext,
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
-        let today = Local::now().date_naive();
-        Ok(FhirPathValue::Date(today))
+        Ok(FhirPathValue::Date(PrecisionDate::from_date(
+            context.now.date_naive(),
+        )))
     }
 }
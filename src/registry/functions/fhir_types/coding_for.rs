@@ -0,0 +1,69 @@
+//! codingFor() function - codes of the codings from a given code system
+//!
+//! This is an extension, not part of the FHIRPath specification. It is a
+//! shorthand for `coding.where(system = %loinc).code` that also accepts
+//! Codings as input. It is only registered as `fhir:codingFor` by
+//! `FhirExtension`, never as a core function.
+
+use crate::model::{Coding, FhirPathValue, TypeInfo};
+use crate::registry::function::{
+    EvaluationContext, FhirPathFunction, FunctionError, FunctionResult,
+};
+use crate::registry::signature::{FunctionSignature, ParameterInfo};
+
+/// codingFor() function - codes of the codings from a given code system
+///
+/// Each input item may be a CodeableConcept, whose codings are searched, or a
+/// Coding. Codes are returned in input order; codings without a system never
+/// match.
+pub struct CodingForFunction;
+
+impl FhirPathFunction for CodingForFunction {
+    fn name(&self) -> &str {
+        "codingFor"
+    }
+    fn human_friendly_name(&self) -> &str {
+        "Coding For"
+    }
+    fn signature(&self) -> &FunctionSignature {
+        static SIG: std::sync::LazyLock<FunctionSignature> = std::sync::LazyLock::new(|| {
+            FunctionSignature::new(
+                "codingFor",
+                vec![ParameterInfo::required("system", TypeInfo::String)],
+                TypeInfo::Collection(Box::new(TypeInfo::String)),
+            )
+        });
+        &SIG
+    }
+    fn documentation(&self) -> &str {
+        "Extension: returns the codes of the codings in the input CodeableConcepts or Codings whose system is `system`, e.g. `code.codingFor(%loinc)`."
+    }
+    fn evaluate(
+        &self,
+        args: &[FhirPathValue],
+        context: &EvaluationContext,
+    ) -> FunctionResult<FhirPathValue> {
+        self.validate_args(args)?;
+
+        let system = match &args[0] {
+            FhirPathValue::String(system) => system.as_ref(),
+            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
+            other => {
+                return Err(FunctionError::InvalidArgumentType {
+                    name: self.name().to_string(),
+                    index: 0,
+                    expected: "String".to_string(),
+                    actual: format!("{other:?}"),
+                });
+            }
+        };
+
+        let codes = Coding::from_value(&context.input)
+            .into_iter()
+            .filter(|coding| coding.system.as_deref() == Some(system))
+            .map(|coding| FhirPathValue::String(coding.code.into()))
+            .collect();
+
+        Ok(FhirPathValue::collection(codes))
+    }
+}
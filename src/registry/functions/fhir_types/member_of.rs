@@ -0,0 +1,112 @@
+//! memberOf() function - checks value set membership through a terminology provider
+
+use crate::model::{Coding, FhirPathValue, TypeInfo};
+use crate::registry::function::{
+    AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
+};
+use crate::registry::signature::{FunctionSignature, ParameterInfo};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Terminology service used to answer value set membership questions
+#[async_trait]
+pub trait TerminologyProvider: Send + Sync {
+    /// Check whether a coding is a member of the value set identified by `value_set_url`
+    ///
+    /// Codings lifted from bare strings have no `system`; providers should match them
+    /// against codes of any system in the value set.
+    async fn member_of(&self, value_set_url: &str, coding: &Coding) -> FunctionResult<bool>;
+}
+
+/// memberOf() function - checks value set membership through a terminology provider
+///
+/// The input may be a code string, a Coding or a CodeableConcept. Strings are treated
+/// as a code with no system. A concept is a member if any of its codings is a member.
+/// Without a configured [`TerminologyProvider`] the result is always empty.
+pub struct MemberOfFunction {
+    provider: Option<Arc<dyn TerminologyProvider>>,
+}
+
+impl Default for MemberOfFunction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemberOfFunction {
+    /// Create a new MemberOfFunction without a terminology provider
+    pub fn new() -> Self {
+        Self { provider: None }
+    }
+
+    /// Create a new MemberOfFunction backed by a custom terminology provider
+    pub fn with_provider(provider: Arc<dyn TerminologyProvider>) -> Self {
+        Self {
+            provider: Some(provider),
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncFhirPathFunction for MemberOfFunction {
+    fn name(&self) -> &str {
+        "memberOf"
+    }
+
+    fn human_friendly_name(&self) -> &str {
+        "Member Of"
+    }
+
+    fn signature(&self) -> &FunctionSignature {
+        static SIG: std::sync::LazyLock<FunctionSignature> = std::sync::LazyLock::new(|| {
+            FunctionSignature::new(
+                "memberOf",
+                vec![ParameterInfo::required("valueset", TypeInfo::String)],
+                TypeInfo::Boolean,
+            )
+        });
+        &SIG
+    }
+
+    fn documentation(&self) -> &str {
+        "Returns true if the input code, Coding or CodeableConcept is a member of the given value set. A plain string is treated as a code with no system."
+    }
+
+    async fn evaluate(
+        &self,
+        args: &[FhirPathValue],
+        context: &EvaluationContext,
+    ) -> FunctionResult<FhirPathValue> {
+        self.validate_args(args)?;
+
+        let value_set_url = match &args[0] {
+            FhirPathValue::String(s) => s,
+            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
+            other => {
+                return Err(FunctionError::InvalidArgumentType {
+                    name: self.name().to_string(),
+                    index: 0,
+                    expected: "String".to_string(),
+                    actual: other.type_name().to_string(),
+                });
+            }
+        };
+
+        let Some(provider) = &self.provider else {
+            return Ok(FhirPathValue::Empty);
+        };
+
+        let codings = Coding::from_value(&context.input);
+        if codings.is_empty() {
+            return Ok(FhirPathValue::Empty);
+        }
+
+        for coding in &codings {
+            if provider.member_of(value_set_url, coding).await? {
+                return Ok(FhirPathValue::Boolean(true));
+            }
+        }
+
+        Ok(FhirPathValue::Boolean(false))
+    }
+}
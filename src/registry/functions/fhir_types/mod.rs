@@ -1,11 +1,18 @@
 //! FHIR-specific type system functions
 
+pub mod coding_for;
 pub mod comparable;
 pub mod extension;
 pub mod is;
+pub mod member_of;
 pub mod resolve;
 
+pub use coding_for::CodingForFunction;
 pub use comparable::ComparableFunction;
 pub use extension::ExtensionFunction;
 pub use is::IsFunction;
-pub use resolve::ResolveFunction;
+pub use member_of::{MemberOfFunction, TerminologyProvider};
+pub use resolve::{
+    AsyncReferenceResolver, DEFAULT_RESOLUTION_CACHE_SIZE, DanglingReferences, ReferenceResolver,
+    ReferenceSource, ResolutionCache, ResolutionCacheStats, ResolveFunction, ResolveMode,
+};
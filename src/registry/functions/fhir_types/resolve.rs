@@ -1,11 +1,169 @@
 //! resolve() function - resolves FHIR references to resources
 
-use crate::model::{FhirPathValue, FhirResource, TypeInfo};
+use crate::diagnostics::{DiagnosticBuilder, DiagnosticCode};
+use crate::model::{CacheStats, CountingCache, FhirPathValue, FhirResource, TypeInfo};
 use crate::registry::function::{
     AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
 };
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+/// Source of resources that resolve() cannot find in the data itself, such
+/// as a database or FHIR server
+pub trait ReferenceResolver: Send + Sync {
+    /// Fetch the resource a reference such as `Patient/123` points to
+    fn resolve(&self, reference: &str) -> Option<FhirResource>;
+}
+
+/// Asynchronous [`ReferenceResolver`], e.g. one making HTTP calls to a FHIR server
+#[async_trait]
+pub trait AsyncReferenceResolver: Send + Sync {
+    /// Fetch the resource a reference such as `Patient/123` points to
+    async fn resolve(&self, reference: &str) -> Option<FhirResource>;
+}
+
+/// A caller's resolver together with how many references resolve() may fetch
+/// from it at once
+#[derive(Clone)]
+pub struct ReferenceSource {
+    resolver: Arc<dyn AsyncReferenceResolver>,
+    max_concurrent: usize,
+}
+
+impl ReferenceSource {
+    /// Fetch from `resolver`, with up to `max_concurrent` requests in flight
+    pub fn new(resolver: Arc<dyn AsyncReferenceResolver>, max_concurrent: usize) -> Self {
+        Self {
+            resolver,
+            max_concurrent: max_concurrent.max(1),
+        }
+    }
+
+    /// Fetch from a synchronous `resolver`, one reference at a time
+    pub fn blocking(resolver: Arc<dyn ReferenceResolver>) -> Self {
+        Self::new(Arc::new(BlockingResolver(resolver)), 1)
+    }
+
+    /// Fetch each of `references`, keeping their order
+    pub async fn resolve_all(&self, references: &[&str]) -> Vec<Option<FhirResource>> {
+        let references: Vec<String> = references.iter().map(|r| r.to_string()).collect();
+        stream::iter(references)
+            .map(|reference| {
+                let resolver = self.resolver.clone();
+                async move { resolver.resolve(&reference).await }
+            })
+            .buffered(self.max_concurrent)
+            .collect()
+            .await
+    }
+}
+
+/// Adapts a [`ReferenceResolver`] to [`AsyncReferenceResolver`]
+struct BlockingResolver(Arc<dyn ReferenceResolver>);
+
+#[async_trait]
+impl AsyncReferenceResolver for BlockingResolver {
+    async fn resolve(&self, reference: &str) -> Option<FhirResource> {
+        self.0.resolve(reference)
+    }
+}
+
+/// How many Bundle lookups an evaluation remembers unless configured otherwise
+pub const DEFAULT_RESOLUTION_CACHE_SIZE: usize = 1024;
+
+/// Bundle lookups made by resolve(), kept for the rest of one evaluation
+///
+/// `Bundle.entry.resource.where(subject.resolve() is Patient)` resolves the
+/// same few references once per entry; with the cache each distinct
+/// reference is searched for once. An absolute reference ending in
+/// `Type/id` is stored under the relative reference `Type/id`, so
+/// `Patient/123` and `http://example.org/fhir/Patient/123` share an entry.
+/// The least recently used entries are evicted once `capacity` is reached.
+pub struct ResolutionCache {
+    entries: CountingCache<String, Option<BundleMatch>>,
+}
+
+/// Statistics about the Bundle lookups of one evaluation
+pub type ResolutionCacheStats = CacheStats;
+
+/// The Bundle entry a reference resolved to
+#[derive(Clone)]
+struct BundleMatch {
+    full_url: String,
+    resource: FhirPathValue,
+}
+
+impl ResolutionCache {
+    /// Create a cache holding up to `capacity` references
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: CountingCache::new(capacity),
+        }
+    }
+
+    /// Get cache statistics
+    pub fn stats(&self) -> ResolutionCacheStats {
+        self.entries.stats()
+    }
+
+    /// Look `reference` up, calling `search` to find its Bundle entry on a miss
+    ///
+    /// An absolute reference only matches the entry with that exact fullUrl,
+    /// so a cached entry found through another base does not answer it, and
+    /// not finding it says nothing about the relative form.
+    fn get_or_search(
+        &self,
+        reference: &str,
+        search: impl FnOnce() -> Option<BundleMatch>,
+    ) -> Option<FhirPathValue> {
+        let key = cache_key(reference);
+        let absolute = key.len() != reference.len();
+        let cached = self.entries.lookup(|entries| match entries.get(key) {
+            Some(Some(found)) if !absolute || found.full_url == reference => {
+                Some(Some(found.resource.clone()))
+            }
+            Some(None) if !absolute => Some(None),
+            _ if absolute => entries
+                .get(reference)
+                .map(|cached| cached.as_ref().map(|found| found.resource.clone())),
+            _ => None,
+        });
+        if let Some(resolved) = cached {
+            return resolved;
+        }
+
+        let found = search();
+        let resolved = found.as_ref().map(|found| found.resource.clone());
+        let key = if found.is_none() && absolute {
+            reference
+        } else {
+            key
+        };
+        self.entries.put(key.to_string(), found);
+        resolved
+    }
+}
+
+/// The key `reference` is cached under: `Type/id` for an absolute URL ending
+/// in one, the reference itself otherwise
+fn cache_key(reference: &str) -> &str {
+    if !(reference.starts_with("http://") || reference.starts_with("https://")) {
+        return reference;
+    }
+    let mut segments = reference.rsplitn(3, '/');
+    let (Some(id), Some(resource_type), Some(_)) =
+        (segments.next(), segments.next(), segments.next())
+    else {
+        return reference;
+    };
+    if id.is_empty() || !resource_type.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return reference;
+    }
+    &reference[reference.len() - id.len() - resource_type.len() - 1..]
+}
 
 /// resolve() function - resolves FHIR references to resources
 ///
@@ -14,7 +172,86 @@ use async_trait::async_trait;
 /// If the item does not resolve to a resource, the item is ignored and nothing is added
 /// to the output collection. The items in the collection may also represent a Reference,
 /// in which case the Reference.reference is resolved.
-pub struct ResolveFunction;
+///
+/// By default only `#id` fragment references resolve against `contained` resources.
+/// [`ResolveFunction::tolerant`] additionally matches bare ids (no `/`, scheme or `#`)
+/// against contained resource ids, for data that omits the leading `#`.
+///
+/// References that cannot be found locally are fetched together from the
+/// [`ReferenceSource`] attached to the evaluation, if any. Those it cannot
+/// find either are left out of the result, as the specification requires,
+/// unless [`DanglingReferences`] or [`ResolveMode`] say otherwise. Each one
+/// left out or replaced by a placeholder is reported to the evaluation's
+/// [`WarningSink`](crate::diagnostics::WarningSink), if it has one.
+///
+/// Resolved resources are returned in the order of their references, once per
+/// reference, so two references to the same resource yield it twice.
+#[derive(Debug, Clone, Default)]
+pub struct ResolveFunction {
+    tolerant_contained: bool,
+    dangling_references: DanglingReferences,
+    mode: ResolveMode,
+}
+
+/// How resolve() treats a reference that matches no contained or Bundle resource
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DanglingReferences {
+    /// Leave the reference out of the result
+    #[default]
+    Drop,
+    /// Fail the evaluation
+    Error,
+}
+
+/// Whether resolve() makes up resources for references it cannot find
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResolveMode {
+    /// Only return resources found in the data or fetched from a resolver;
+    /// references found nowhere are handled by [`DanglingReferences`]
+    #[default]
+    Strict,
+    /// Return a synthetic resource for each reference found nowhere, for
+    /// testing only
+    ///
+    /// The resource has the reference's type and id, and carries
+    /// `_placeholder: true` and the reference as `_originalReference`.
+    Placeholder,
+}
+
+/// What one item of resolve()'s input refers to
+enum Resolution {
+    /// A resource found in the data
+    Found(FhirPathValue),
+    /// A reference to a resource the data does not hold
+    Dangling(String),
+}
+
+impl ResolveFunction {
+    /// Create a new ResolveFunction with strict FHIR reference matching
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new ResolveFunction that also resolves bare ids against contained resources
+    pub fn tolerant() -> Self {
+        Self {
+            tolerant_contained: true,
+            ..Self::default()
+        }
+    }
+
+    /// Set how references that cannot be found locally are handled
+    pub fn with_dangling_references(mut self, policy: DanglingReferences) -> Self {
+        self.dangling_references = policy;
+        self
+    }
+
+    /// Set whether references found nowhere resolve to placeholder resources
+    pub fn with_resolve_mode(mut self, mode: ResolveMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
 
 #[async_trait]
 impl AsyncFhirPathFunction for ResolveFunction {
@@ -61,13 +298,56 @@ impl AsyncFhirPathFunction for ResolveFunction {
             single => vec![single],
         };
 
-        for item in items {
-            match self.resolve_item(item, context) {
-                Some(resolved) => resolved_resources.push(resolved),
-                None => {
-                    // Item cannot be resolved - ignore it as per spec
+        // Items that cannot be resolved are ignored as per spec
+        let local: Vec<Resolution> = items
+            .into_iter()
+            .filter_map(|item| self.resolve_item(item, context))
+            .collect();
+
+        // Fetch every reference the data does not hold from the caller's
+        // resolver at once, before falling back to the policy
+        let dangling: Vec<&str> = local
+            .iter()
+            .filter_map(|resolution| match resolution {
+                Resolution::Dangling(reference) => Some(reference.as_str()),
+                Resolution::Found(_) => None,
+            })
+            .collect();
+        let mut fetched = match &context.reference_resolver {
+            Some(source) if !dangling.is_empty() => source.resolve_all(&dangling).await,
+            _ => Vec::new(),
+        }
+        .into_iter();
+
+        for resolution in local {
+            let reference = match resolution {
+                Resolution::Found(resolved) => {
+                    resolved_resources.push(resolved);
                     continue;
                 }
+                Resolution::Dangling(reference) => reference,
+            };
+            if let Some(resource) = fetched.next().flatten() {
+                resolved_resources.push(FhirPathValue::Resource(resource.into()));
+                continue;
+            }
+            if self.mode == ResolveMode::Strict
+                && self.dangling_references == DanglingReferences::Error
+            {
+                return Err(FunctionError::EvaluationError {
+                    name: self.name().to_string(),
+                    message: format!("Reference '{reference}' could not be resolved"),
+                });
+            }
+            if self.mode == ResolveMode::Placeholder {
+                resolved_resources.push(self.create_placeholder_resource(&reference));
+            }
+            if let Some(warnings) = &context.warnings {
+                warnings.push(
+                    DiagnosticBuilder::warning(DiagnosticCode::UnresolvedReference)
+                        .with_message(format!("Unresolved reference '{reference}'"))
+                        .build(),
+                );
             }
         }
 
@@ -81,7 +361,7 @@ impl ResolveFunction {
         &self,
         item: &FhirPathValue,
         context: &EvaluationContext,
-    ) -> Option<FhirPathValue> {
+    ) -> Option<Resolution> {
         match item {
             // Handle string URIs/references
             FhirPathValue::String(uri) => self.resolve_string_reference(uri, context),
@@ -103,28 +383,90 @@ impl ResolveFunction {
 
     /// Check if a resource is a Reference type
     fn is_reference(&self, resource: &FhirResource) -> bool {
-        // Check if this is a Reference resource by looking for 'reference' field
+        // Check if this is a Reference resource by looking for a 'reference' field,
+        // or a single 'identifier' object for logical references (resources carry
+        // identifier arrays instead)
         if let Some(obj) = resource.as_json().as_object() {
             obj.contains_key("reference")
+                || obj
+                    .get("identifier")
+                    .is_some_and(|identifier| identifier.is_object())
         } else {
             false
         }
     }
 
     /// Resolve a Reference resource by extracting its reference field
+    ///
+    /// A logical reference (one with an `identifier` but no `reference`) is
+    /// resolved against Bundle entries carrying a matching identifier.
     fn resolve_reference_resource(
         &self,
         resource: &FhirResource,
         context: &EvaluationContext,
+    ) -> Option<Resolution> {
+        let obj = resource.as_json().as_object()?;
+
+        if let Some(reference_value) = obj.get("reference") {
+            return reference_value
+                .as_str()
+                .and_then(|reference_str| self.resolve_string_reference(reference_str, context));
+        }
+
+        let identifier = obj.get("identifier")?;
+        let target_type = obj.get("type").and_then(|t| t.as_str());
+        self.resolve_from_bundle_by_identifier(identifier, target_type, context)
+            .map(Resolution::Found)
+    }
+
+    /// Resolve a logical reference from a Bundle by matching entry identifiers
+    fn resolve_from_bundle_by_identifier(
+        &self,
+        identifier: &serde_json::Value,
+        target_type: Option<&str>,
+        context: &EvaluationContext,
     ) -> Option<FhirPathValue> {
-        if let Some(obj) = resource.as_json().as_object() {
-            if let Some(reference_value) = obj.get("reference") {
-                if let Some(reference_str) = reference_value.as_str() {
-                    return self.resolve_string_reference(reference_str, context);
-                }
-            }
+        let value = identifier.get("value")?.as_str()?;
+        let system = identifier.get("system").and_then(|s| s.as_str());
+
+        let bundle = self.find_bundle_in_context(context)?;
+        let entries = bundle.as_json().get("entry")?.as_array()?;
+
+        entries
+            .iter()
+            .filter_map(|entry| entry.get("resource"))
+            .find(|resource| {
+                let type_matches = target_type.is_none_or(|expected| {
+                    resource.get("resourceType").and_then(|t| t.as_str()) == Some(expected)
+                });
+                type_matches && self.has_matching_identifier(resource, system, value)
+            })
+            .map(|resource| {
+                FhirPathValue::Resource(FhirResource::from_json(resource.clone()).into())
+            })
+    }
+
+    /// Check if a resource carries an identifier with the given system and value
+    ///
+    /// When the reference omits the system, any identifier with the same value matches.
+    fn has_matching_identifier(
+        &self,
+        resource: &serde_json::Value,
+        system: Option<&str>,
+        value: &str,
+    ) -> bool {
+        let matches = |candidate: &serde_json::Value| {
+            candidate.get("value").and_then(|v| v.as_str()) == Some(value)
+                && system.is_none_or(|system| {
+                    candidate.get("system").and_then(|s| s.as_str()) == Some(system)
+                })
+        };
+
+        match resource.get("identifier") {
+            Some(serde_json::Value::Array(identifiers)) => identifiers.iter().any(matches),
+            Some(identifier) => matches(identifier),
+            None => false,
         }
-        None
     }
 
     /// Resolve a string reference (URI/URL)
@@ -132,26 +474,31 @@ impl ResolveFunction {
         &self,
         reference: &str,
         context: &EvaluationContext,
-    ) -> Option<FhirPathValue> {
+    ) -> Option<Resolution> {
         // Handle fragment references to contained resources (e.g., "#obs1")
         if let Some(contained_id) = reference.strip_prefix('#') {
             // Remove the '#' prefix
-            return self.resolve_contained_resource(contained_id, context);
+            return self
+                .resolve_contained_resource(contained_id, context)
+                .map(Resolution::Found);
         }
 
         // Try to resolve from Bundle if we're in Bundle context
         if let Some(resolved) = self.resolve_from_bundle(reference, context) {
-            return Some(resolved);
+            return Some(Resolution::Found(resolved));
         }
 
-        // Check if it looks like a FHIR reference
-        if self.is_fhir_reference(reference) {
-            // Create a placeholder resource - in a real implementation this would
-            // fetch the actual resource from a server or bundle
-            self.create_placeholder_resource(reference)
-        } else {
-            None
+        // In tolerant mode a bare id may name a contained resource without the '#'
+        if self.tolerant_contained
+            && self.is_bare_id(reference)
+            && let Some(resolved) = self.resolve_contained_resource(reference, context)
+        {
+            return Some(Resolution::Found(resolved));
         }
+
+        // A FHIR reference may still be found by the caller's resolver
+        self.is_fhir_reference(reference)
+            .then(|| Resolution::Dangling(reference.to_string()))
     }
 
     /// Resolve a contained resource by ID
@@ -199,12 +546,17 @@ impl ResolveFunction {
         reference.starts_with("urn:") // URN format
     }
 
-    /// Create a placeholder resource for testing purposes
-    /// In a real implementation, this would fetch the actual resource
-    fn create_placeholder_resource(&self, reference: &str) -> Option<FhirPathValue> {
-        // For now, return a simple placeholder resource
-        // This allows tests to pass while indicating that resolve() is working
+    /// Check if a string is a bare id: no path, no scheme and no fragment marker
+    fn is_bare_id(&self, reference: &str) -> bool {
+        !reference.is_empty()
+            && !reference.contains('/')
+            && !reference.contains(':')
+            && !reference.starts_with('#')
+    }
 
+    /// Create the placeholder [`ResolveMode::Placeholder`] returns for a
+    /// reference found nowhere
+    fn create_placeholder_resource(&self, reference: &str) -> FhirPathValue {
         // Extract resource type from reference if possible
         let resource_type = if let Some(slash_pos) = reference.find('/') {
             &reference[..slash_pos]
@@ -221,10 +573,12 @@ impl ResolveFunction {
         });
 
         let resource = FhirResource::from_json(placeholder_json);
-        Some(FhirPathValue::Resource(resource.into()))
+        FhirPathValue::Resource(resource.into())
     }
 
     /// Resolve a reference from a Bundle context
+    ///
+    /// Lookups go through the evaluation's [`ResolutionCache`], if it has one.
     fn resolve_from_bundle(
         &self,
         reference: &str,
@@ -232,7 +586,16 @@ impl ResolveFunction {
     ) -> Option<FhirPathValue> {
         // Check if we're in a Bundle context
         let bundle = self.find_bundle_in_context(context)?;
+        match &context.resolution_cache {
+            Some(cache) => cache.get_or_search(reference, || self.search_bundle(reference, bundle)),
+            None => self
+                .search_bundle(reference, bundle)
+                .map(|found| found.resource),
+        }
+    }
 
+    /// Find the Bundle entry whose fullUrl `reference` matches
+    fn search_bundle(&self, reference: &str, bundle: &FhirResource) -> Option<BundleMatch> {
         if let Some(bundle_obj) = bundle.as_json().as_object() {
             if let Some(entries) = bundle_obj.get("entry") {
                 if let Some(entry_array) = entries.as_array() {
@@ -247,9 +610,12 @@ impl ResolveFunction {
                                         if let Some(resource) = entry_obj.get("resource") {
                                             let fhir_resource =
                                                 FhirResource::from_json(resource.clone());
-                                            return Some(FhirPathValue::Resource(
-                                                fhir_resource.into(),
-                                            ));
+                                            return Some(BundleMatch {
+                                                full_url: full_url_str.to_string(),
+                                                resource: FhirPathValue::Resource(
+                                                    fhir_resource.into(),
+                                                ),
+                                            });
                                         }
                                     }
                                 }
@@ -17,8 +17,8 @@ use crate::registry::function::FunctionRegistry;
 /// Register all filtering functions
 pub fn register_filtering_functions(registry: &mut FunctionRegistry) {
     registry.register_async(OfTypeFunction);
-    registry.register(SelectFunction);
+    registry.register_lambda(SelectFunction);
     registry.register_async(SkipFunction);
     registry.register_async(TakeFunction);
-    registry.register(WhereFunction);
+    registry.register_lambda(WhereFunction);
 }
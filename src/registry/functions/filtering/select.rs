@@ -7,10 +7,6 @@ use crate::registry::function::{
     LambdaFunction,
 };
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
-use std::hash::BuildHasherDefault;
-
-type VarMap =
-    std::collections::HashMap<String, FhirPathValue, BuildHasherDefault<rustc_hash::FxHasher>>;
 
 /// select() function - transforms collection using expression
 pub struct SelectFunction;
@@ -26,7 +22,7 @@ impl FhirPathFunction for SelectFunction {
         static SIG: std::sync::LazyLock<FunctionSignature> = std::sync::LazyLock::new(|| {
             FunctionSignature::new(
                 "select",
-                vec![ParameterInfo::required("expression", TypeInfo::Any)],
+                vec![ParameterInfo::required("expression", TypeInfo::Any).lazy()],
                 TypeInfo::Collection(Box::new(TypeInfo::Any)),
             )
         });
@@ -47,7 +43,7 @@ impl FhirPathFunction for SelectFunction {
     }
 }
 
-#[async_trait::async_trait(?Send)]
+#[async_trait::async_trait]
 impl LambdaFunction for SelectFunction {
     async fn evaluate_with_lambda(
         &self,
@@ -74,28 +70,9 @@ impl LambdaFunction for SelectFunction {
 
         let mut results = Vec::new();
 
-        // Apply expression to each item with index support
+        // Apply expression to each item with $this and $index bound
         for (index, item) in items.iter().enumerate() {
-            let result = if let Some(enhanced_evaluator) = context.enhanced_evaluator {
-                // Use enhanced evaluator with $index variable injection and outer context variables
-                let mut additional_vars: VarMap =
-                    std::collections::HashMap::with_hasher(BuildHasherDefault::<
-                        rustc_hash::FxHasher,
-                    >::default());
-
-                // Include all variables from outer context
-                for (name, value) in &context.context.variables {
-                    additional_vars.insert(name.clone(), value.clone());
-                }
-
-                // Add $index variable for iteration (parser strips $ prefix)
-                additional_vars.insert("index".to_string(), FhirPathValue::Integer(index as i64));
-
-                enhanced_evaluator(expression, item, &additional_vars).await?
-            } else {
-                // Fall back to regular evaluator
-                (context.evaluator)(expression, item).await?
-            };
+            let result = context.evaluate_item(expression, item, index).await?;
 
             // Add result to collection, flattening collections
             match result {
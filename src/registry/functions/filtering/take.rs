@@ -35,7 +35,8 @@ impl AsyncFhirPathFunction for TakeFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
         let num = match &args[0] {
-            FhirPathValue::Integer(n) => *n as usize,
+            // Negative counts take nothing
+            FhirPathValue::Integer(n) => usize::try_from(*n).unwrap_or(0),
             _ => {
                 return Err(FunctionError::InvalidArgumentType {
                     name: self.name().to_string(),
@@ -7,10 +7,6 @@ use crate::registry::function::{
     LambdaFunction,
 };
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
-use std::hash::BuildHasherDefault;
-
-type VarMap =
-    std::collections::HashMap<String, FhirPathValue, BuildHasherDefault<rustc_hash::FxHasher>>;
 
 /// where() function - filters collection based on criteria
 pub struct WhereFunction;
@@ -26,7 +22,7 @@ impl FhirPathFunction for WhereFunction {
         static SIG: std::sync::LazyLock<FunctionSignature> = std::sync::LazyLock::new(|| {
             FunctionSignature::new(
                 "where",
-                vec![ParameterInfo::required("criteria", TypeInfo::Any)],
+                vec![ParameterInfo::required("criteria", TypeInfo::Any).lazy()],
                 TypeInfo::Collection(Box::new(TypeInfo::Any)),
             )
         });
@@ -47,7 +43,7 @@ impl FhirPathFunction for WhereFunction {
     }
 }
 
-#[async_trait::async_trait(?Send)]
+#[async_trait::async_trait]
 impl LambdaFunction for WhereFunction {
     async fn evaluate_with_lambda(
         &self,
@@ -74,21 +70,9 @@ impl LambdaFunction for WhereFunction {
 
         let mut results = Vec::new();
 
-        // Apply criteria to each item with index support
+        // Apply criteria to each item with $this and $index bound
         for (index, item) in items.iter().enumerate() {
-            let result = if let Some(enhanced_evaluator) = context.enhanced_evaluator {
-                // Use enhanced evaluator with $index variable injection
-                let mut additional_vars: VarMap =
-                    std::collections::HashMap::with_hasher(BuildHasherDefault::<
-                        rustc_hash::FxHasher,
-                    >::default());
-                additional_vars.insert("$index".to_string(), FhirPathValue::Integer(index as i64));
-
-                enhanced_evaluator(criteria, item, &additional_vars).await?
-            } else {
-                // Fall back to regular evaluator
-                (context.evaluator)(criteria, item).await?
-            };
+            let result = context.evaluate_item(criteria, item, index).await?;
 
             // Check if criteria evaluates to true
             let is_true = match result {
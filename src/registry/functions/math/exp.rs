@@ -8,6 +8,8 @@ use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 use rust_decimal::prelude::*;
 
+use super::{decimal_result, promote_to_decimal};
+
 /// exp() function - exponential (e^x)
 pub struct ExpFunction;
 
@@ -28,32 +30,29 @@ impl AsyncFhirPathFunction for ExpFunction {
     fn is_pure(&self) -> bool {
         true // exp() is a pure mathematical function
     }
+
+    fn documentation(&self) -> &str {
+        "Returns e raised to the power of the input as a Decimal. If the result cannot be represented as a Decimal, the result is empty."
+    }
+
     async fn evaluate(
         &self,
         args: &[FhirPathValue],
         context: &EvaluationContext,
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
-        match &context.input {
-            FhirPathValue::Integer(i) => {
-                let result = (*i as f64).exp();
-                Ok(FhirPathValue::Decimal(
-                    Decimal::from_f64(result).unwrap_or_default(),
-                ))
-            }
-            FhirPathValue::Decimal(d) => {
-                let result = d.to_f64().unwrap_or(0.0).exp();
-                Ok(FhirPathValue::Decimal(
-                    Decimal::from_f64(result).unwrap_or_default(),
-                ))
-            }
-            FhirPathValue::Empty => Ok(FhirPathValue::Empty),
-            _ => Err(FunctionError::InvalidArgumentType {
+        if context.input.is_empty() {
+            return Ok(FhirPathValue::Empty);
+        }
+        let Some(value) = promote_to_decimal(&context.input) else {
+            return Err(FunctionError::InvalidArgumentType {
                 name: self.name().to_string(),
                 index: 0,
                 expected: "Number".to_string(),
                 actual: format!("{:?}", context.input),
-            }),
-        }
+            });
+        };
+        let value = value.to_f64().unwrap_or(f64::NAN);
+        Ok(decimal_result(value.exp()))
     }
 }
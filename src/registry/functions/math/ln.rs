@@ -8,6 +8,8 @@ use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 use rust_decimal::prelude::*;
 
+use super::{decimal_result, promote_to_decimal};
+
 /// ln() function - natural logarithm
 pub struct LnFunction;
 
@@ -28,44 +30,29 @@ impl AsyncFhirPathFunction for LnFunction {
     fn is_pure(&self) -> bool {
         true // ln() is a pure mathematical function
     }
+
+    fn documentation(&self) -> &str {
+        "Returns the natural logarithm of the input as a Decimal. If the input is zero or negative, the result is empty."
+    }
+
     async fn evaluate(
         &self,
         args: &[FhirPathValue],
         context: &EvaluationContext,
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
-        match &context.input {
-            FhirPathValue::Integer(i) => {
-                if *i <= 0 {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Cannot take logarithm of non-positive number".to_string(),
-                    });
-                }
-                let result = (*i as f64).ln();
-                Ok(FhirPathValue::Decimal(
-                    Decimal::from_f64(result).unwrap_or_default(),
-                ))
-            }
-            FhirPathValue::Decimal(d) => {
-                if d.is_sign_negative() || d.is_zero() {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Cannot take logarithm of non-positive number".to_string(),
-                    });
-                }
-                let result = d.to_f64().unwrap_or(0.0).ln();
-                Ok(FhirPathValue::Decimal(
-                    Decimal::from_f64(result).unwrap_or_default(),
-                ))
-            }
-            FhirPathValue::Empty => Ok(FhirPathValue::Empty),
-            _ => Err(FunctionError::InvalidArgumentType {
+        if context.input.is_empty() {
+            return Ok(FhirPathValue::Empty);
+        }
+        let Some(value) = promote_to_decimal(&context.input) else {
+            return Err(FunctionError::InvalidArgumentType {
                 name: self.name().to_string(),
                 index: 0,
                 expected: "Number".to_string(),
                 actual: format!("{:?}", context.input),
-            }),
-        }
+            });
+        };
+        let value = value.to_f64().unwrap_or(f64::NAN);
+        Ok(decimal_result(value.ln()))
     }
 }
@@ -8,6 +8,8 @@ use crate::registry::signature::{FunctionSignature, ParameterInfo};
 use async_trait::async_trait;
 use rust_decimal::prelude::*;
 
+use super::{decimal_result, promote_to_decimal};
+
 /// log() function - logarithm with base
 pub struct LogFunction;
 
@@ -33,65 +35,42 @@ impl AsyncFhirPathFunction for LogFunction {
     fn is_pure(&self) -> bool {
         true // log() is a pure mathematical function
     }
+
+    fn documentation(&self) -> &str {
+        "Returns the logarithm of the input to the given `base` as a Decimal. If the input or base is zero or negative, or the base is 1, the result is empty."
+    }
+
     async fn evaluate(
         &self,
         args: &[FhirPathValue],
         context: &EvaluationContext,
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
-        let base = match &args[0] {
-            FhirPathValue::Integer(i) => *i as f64,
-            FhirPathValue::Decimal(d) => d.to_f64().unwrap_or(0.0),
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            _ => {
-                return Err(FunctionError::InvalidArgumentType {
-                    name: self.name().to_string(),
-                    index: 0,
-                    expected: "Number".to_string(),
-                    actual: format!("{:?}", args[0]),
-                });
-            }
-        };
-
-        if base <= 0.0 || base == 1.0 {
-            return Err(FunctionError::EvaluationError {
+        if context.input.is_empty() || args[0].is_empty() {
+            return Ok(FhirPathValue::Empty);
+        }
+        let Some(base) = promote_to_decimal(&args[0]) else {
+            return Err(FunctionError::InvalidArgumentType {
                 name: self.name().to_string(),
-                message: "Logarithm base must be positive and not equal to 1".to_string(),
+                index: 0,
+                expected: "Number".to_string(),
+                actual: format!("{:?}", args[0]),
             });
-        }
-
-        match &context.input {
-            FhirPathValue::Integer(i) => {
-                if *i <= 0 {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Cannot take logarithm of non-positive number".to_string(),
-                    });
-                }
-                let result = (*i as f64).log(base);
-                Ok(FhirPathValue::Decimal(
-                    Decimal::from_f64(result).unwrap_or_default(),
-                ))
-            }
-            FhirPathValue::Decimal(d) => {
-                if d.is_sign_negative() || d.is_zero() {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Cannot take logarithm of non-positive number".to_string(),
-                    });
-                }
-                let result = d.to_f64().unwrap_or(0.0).log(base);
-                Ok(FhirPathValue::Decimal(
-                    Decimal::from_f64(result).unwrap_or_default(),
-                ))
-            }
-            FhirPathValue::Empty => Ok(FhirPathValue::Empty),
-            _ => Err(FunctionError::InvalidArgumentType {
+        };
+        let Some(value) = promote_to_decimal(&context.input) else {
+            return Err(FunctionError::InvalidArgumentType {
                 name: self.name().to_string(),
                 index: 0,
                 expected: "Number".to_string(),
                 actual: format!("{:?}", context.input),
-            }),
+            });
+        };
+        // ln(0) is -inf, which would turn into a finite 0 once used as divisor
+        if base <= Decimal::ZERO {
+            return Ok(FhirPathValue::Empty);
         }
+        let base = base.to_f64().unwrap_or(f64::NAN);
+        let value = value.to_f64().unwrap_or(f64::NAN);
+        Ok(decimal_result(value.log(base)))
     }
 }
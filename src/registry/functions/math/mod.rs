@@ -32,7 +32,9 @@ pub use sqrt::SqrtFunction;
 pub use sum::SumFunction;
 pub use truncate::TruncateFunction;
 
+use crate::model::FhirPathValue;
 use crate::registry::function::FunctionRegistry;
+use rust_decimal::prelude::*;
 
 /// Register all math functions
 pub fn register_math_functions(registry: &mut FunctionRegistry) {
@@ -52,3 +54,23 @@ pub fn register_math_functions(registry: &mut FunctionRegistry) {
     registry.register_async(SumFunction);
     registry.register_async(TruncateFunction);
 }
+
+/// Promote an Integer or Decimal to Decimal, `None` for any other value
+fn promote_to_decimal(value: &FhirPathValue) -> Option<Decimal> {
+    match value {
+        FhirPathValue::Integer(i) => Some(Decimal::from(*i)),
+        FhirPathValue::Decimal(d) => Some(*d),
+        _ => None,
+    }
+}
+
+/// Wrap the result of a floating point computation as a Decimal
+///
+/// NaN, infinities and values outside the Decimal range cannot be represented,
+/// so they yield empty rather than a made-up number.
+fn decimal_result(result: f64) -> FhirPathValue {
+    if !result.is_finite() {
+        return FhirPathValue::Empty;
+    }
+    Decimal::from_f64(result).map_or(FhirPathValue::Empty, FhirPathValue::Decimal)
+}
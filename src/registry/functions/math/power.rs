@@ -8,6 +8,8 @@ use crate::registry::signature::{FunctionSignature, ParameterInfo};
 use async_trait::async_trait;
 use rust_decimal::prelude::*;
 
+use super::{decimal_result, promote_to_decimal};
+
 /// power() function - exponentiation
 pub struct PowerFunction;
 
@@ -33,51 +35,51 @@ impl AsyncFhirPathFunction for PowerFunction {
     fn is_pure(&self) -> bool {
         true // power() is a pure mathematical function
     }
+
+    fn documentation(&self) -> &str {
+        "Raises the input to the power of `exponent`. The result is an Integer when both are Integers and the result fits, otherwise a Decimal. If the result cannot be represented (such as `(-1).power(0.5)`), the result is empty."
+    }
+
     async fn evaluate(
         &self,
         args: &[FhirPathValue],
         context: &EvaluationContext,
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
-        let exponent = match &args[0] {
-            FhirPathValue::Integer(i) => *i as f64,
-            FhirPathValue::Decimal(d) => d.to_f64().unwrap_or(0.0),
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            _ => {
-                return Err(FunctionError::InvalidArgumentType {
-                    name: self.name().to_string(),
-                    index: 0,
-                    expected: "Number".to_string(),
-                    actual: format!("{:?}", args[0]),
-                });
-            }
-        };
+        if context.input.is_empty() || args[0].is_empty() {
+            return Ok(FhirPathValue::Empty);
+        }
+
+        if let (FhirPathValue::Integer(base), FhirPathValue::Integer(exponent)) =
+            (&context.input, &args[0])
+            && let Some(result) = u32::try_from(*exponent)
+                .ok()
+                .and_then(|exponent| base.checked_pow(exponent))
+        {
+            return Ok(FhirPathValue::Integer(result));
+        }
 
-        match &context.input {
-            FhirPathValue::Integer(i) => {
-                let result = (*i as f64).powf(exponent);
-                if exponent.fract() == 0.0 && exponent >= 0.0 {
-                    // Integer result for integer exponents
-                    Ok(FhirPathValue::Integer(result as i64))
-                } else {
-                    Ok(FhirPathValue::Decimal(
-                        Decimal::from_f64(result).unwrap_or_default(),
-                    ))
-                }
-            }
-            FhirPathValue::Decimal(d) => {
-                let result = d.to_f64().unwrap_or(0.0).powf(exponent);
-                Ok(FhirPathValue::Decimal(
-                    Decimal::from_f64(result).unwrap_or_default(),
-                ))
-            }
-            FhirPathValue::Empty => Ok(FhirPathValue::Empty),
-            _ => Err(FunctionError::InvalidArgumentType {
+        let Some(exponent) = promote_to_decimal(&args[0]) else {
+            return Err(FunctionError::InvalidArgumentType {
+                name: self.name().to_string(),
+                index: 0,
+                expected: "Number".to_string(),
+                actual: format!("{:?}", args[0]),
+            });
+        };
+        let Some(base) = promote_to_decimal(&context.input) else {
+            return Err(FunctionError::InvalidArgumentType {
                 name: self.name().to_string(),
                 index: 0,
                 expected: "Number".to_string(),
                 actual: format!("{:?}", context.input),
-            }),
-        }
+            });
+        };
+
+        // Negative exponents and overflowing Integer results end up here too;
+        // a negative base with a fractional exponent gives NaN and so empty
+        let base = base.to_f64().unwrap_or(f64::NAN);
+        let exponent = exponent.to_f64().unwrap_or(f64::NAN);
+        Ok(decimal_result(base.powf(exponent)))
     }
 }
@@ -8,6 +8,8 @@ use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 use rust_decimal::prelude::*;
 
+use super::{decimal_result, promote_to_decimal};
+
 /// sqrt() function - square root
 pub struct SqrtFunction;
 
@@ -39,35 +41,18 @@ impl AsyncFhirPathFunction for SqrtFunction {
         context: &EvaluationContext,
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
-        match &context.input {
-            FhirPathValue::Integer(i) => {
-                if *i < 0 {
-                    return Ok(FhirPathValue::Empty);
-                }
-                let result = (*i as f64).sqrt();
-                Ok(FhirPathValue::Decimal(
-                    Decimal::from_f64(result).unwrap_or_default(),
-                ))
-            }
-            FhirPathValue::Decimal(d) => {
-                if d.is_sign_negative() {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Cannot take square root of negative number".to_string(),
-                    });
-                }
-                let result = d.to_f64().unwrap_or(0.0).sqrt();
-                Ok(FhirPathValue::Decimal(
-                    Decimal::from_f64(result).unwrap_or_default(),
-                ))
-            }
-            FhirPathValue::Empty => Ok(FhirPathValue::Empty),
-            _ => Err(FunctionError::InvalidArgumentType {
+        if context.input.is_empty() {
+            return Ok(FhirPathValue::Empty);
+        }
+        let Some(value) = promote_to_decimal(&context.input) else {
+            return Err(FunctionError::InvalidArgumentType {
                 name: self.name().to_string(),
                 index: 0,
                 expected: "Number".to_string(),
                 actual: format!("{:?}", context.input),
-            }),
-        }
+            });
+        };
+        let value = value.to_f64().unwrap_or(f64::NAN);
+        Ok(decimal_result(value.sqrt()))
     }
 }
@@ -31,7 +31,7 @@ impl AsyncFhirPathFunction for TruncateFunction {
     }
 
     fn documentation(&self) -> &str {
-        "Returns the integer portion of the input."
+        "Returns the integer portion of the input, dropping the fractional part toward zero."
     }
 
     async fn evaluate(
@@ -42,9 +42,12 @@ impl AsyncFhirPathFunction for TruncateFunction {
         self.validate_args(args)?;
         match &context.input {
             FhirPathValue::Integer(i) => Ok(FhirPathValue::Integer(*i)),
-            FhirPathValue::Decimal(d) => {
-                Ok(FhirPathValue::Integer(d.trunc().to_i64().unwrap_or(0)))
-            }
+            // Rounds toward zero, so (-2.5).truncate() is -2 where floor() gives -3;
+            // values beyond the Integer range cannot be represented
+            FhirPathValue::Decimal(d) => Ok(d
+                .trunc()
+                .to_i64()
+                .map_or(FhirPathValue::Empty, FhirPathValue::Integer)),
             FhirPathValue::Empty => Ok(FhirPathValue::Empty),
             _ => Err(FunctionError::InvalidArgumentType {
                 name: self.name().to_string(),
@@ -1,11 +1,18 @@
 //! contains() function - checks if string contains substring
 
 use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
+use crate::registry::function::{
+    AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
+};
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
 use async_trait::async_trait;
 
 /// contains() function - checks if string contains substring
+///
+/// This is the string function; collection membership is the `contains`
+/// operator (`collection contains item`). Calling the function on a primitive
+/// that is not a String is almost always a mix-up of the two forms, so it is
+/// reported as an error rather than yielding empty.
 pub struct ContainsFunction;
 
 #[async_trait]
@@ -48,7 +55,24 @@ impl AsyncFhirPathFunction for ContainsFunction {
             // Handle empty collections - return empty when any parameter is an empty collection
             (FhirPathValue::Collection(items), _) if items.is_empty() => Ok(FhirPathValue::Empty),
             (_, FhirPathValue::Collection(items)) if items.is_empty() => Ok(FhirPathValue::Empty),
-            // Return empty for non-string inputs instead of throwing error (per FHIRPath spec)
+            (
+                FhirPathValue::Boolean(_)
+                | FhirPathValue::Integer(_)
+                | FhirPathValue::Decimal(_)
+                | FhirPathValue::Date(_)
+                | FhirPathValue::DateTime(_)
+                | FhirPathValue::Time(_)
+                | FhirPathValue::Quantity(_),
+                _,
+            ) => Err(FunctionError::EvaluationError {
+                name: self.name().to_string(),
+                message: format!(
+                    "contains() expects a String input, got {}; use the `contains` operator \
+                     (`collection contains item`) to test collection membership",
+                    context.input.type_name()
+                ),
+            }),
+            // Complex elements have no string value, so there is nothing to search
             _ => Ok(FhirPathValue::Empty),
         }
     }
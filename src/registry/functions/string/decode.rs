@@ -1,11 +1,16 @@
 //! decode() function - decodes URL encoded string
 
+use super::encode::SUPPORTED_FORMATS;
 use crate::model::{FhirPathValue, TypeInfo};
 use crate::registry::function::{
     AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
 };
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
 use async_trait::async_trait;
+use base64::alphabet;
+use base64::engine::{
+    DecodePaddingMode, Engine, GeneralPurpose, GeneralPurposeConfig, general_purpose,
+};
 /// decode() function - decodes URL encoded string
 pub struct DecodeFunction;
 
@@ -121,56 +126,7 @@ impl AsyncFhirPathFunction for DecodeFunction {
                         }
                         Ok(FhirPathValue::String(decoded.into()))
                     }
-                    "base64" => {
-                        // Base64 decoding
-                        let chars =
-                            "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-                        let mut char_map = std::collections::HashMap::new();
-                        for (i, c) in chars.chars().enumerate() {
-                            char_map.insert(c, i as u8);
-                        }
-
-                        let clean_input: String = s
-                            .chars()
-                            .filter(|c| chars.contains(*c) || *c == '=')
-                            .collect();
-                        let mut result = Vec::new();
-
-                        for chunk in clean_input.chars().collect::<Vec<_>>().chunks(4) {
-                            if chunk.len() < 4 {
-                                break;
-                            }
-
-                            let b1 = char_map.get(&chunk[0]).copied().unwrap_or(0);
-                            let b2 = char_map.get(&chunk[1]).copied().unwrap_or(0);
-                            let b3 = if chunk[2] == '=' {
-                                0
-                            } else {
-                                char_map.get(&chunk[2]).copied().unwrap_or(0)
-                            };
-                            let b4 = if chunk[3] == '=' {
-                                0
-                            } else {
-                                char_map.get(&chunk[3]).copied().unwrap_or(0)
-                            };
-
-                            result.push((b1 << 2) | (b2 >> 4));
-                            if chunk[2] != '=' {
-                                result.push(((b2 & 0x0f) << 4) | (b3 >> 2));
-                            }
-                            if chunk[3] != '=' {
-                                result.push(((b3 & 0x03) << 6) | b4);
-                            }
-                        }
-
-                        match String::from_utf8(result) {
-                            Ok(decoded) => Ok(FhirPathValue::String(decoded.into())),
-                            Err(_) => Err(FunctionError::EvaluationError {
-                                name: self.name().to_string(),
-                                message: "Invalid base64 encoding".to_string(),
-                            }),
-                        }
-                    }
+                    "base64" => Ok(decode_base64(&general_purpose::STANDARD, s)),
                     "hex" => {
                         // Hexadecimal decoding
                         let clean_input: String =
@@ -204,60 +160,13 @@ impl AsyncFhirPathFunction for DecodeFunction {
                             }),
                         }
                     }
-                    "urlbase64" => {
-                        // URL-safe Base64 decoding (RFC 4648)
-                        let chars =
-                            "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
-                        let mut char_map = std::collections::HashMap::new();
-                        for (i, c) in chars.chars().enumerate() {
-                            char_map.insert(c, i as u8);
-                        }
-
-                        // Add padding if needed for URL-safe base64
-                        let mut padded_input = s.to_string();
-                        while padded_input.len() % 4 != 0 {
-                            padded_input.push('=');
-                        }
-
-                        let mut result = Vec::new();
-                        for chunk in padded_input.chars().collect::<Vec<_>>().chunks(4) {
-                            if chunk.len() < 4 {
-                                break;
-                            }
-
-                            let b1 = char_map.get(&chunk[0]).copied().unwrap_or(0);
-                            let b2 = char_map.get(&chunk[1]).copied().unwrap_or(0);
-                            let b3 = if chunk[2] == '=' {
-                                0
-                            } else {
-                                char_map.get(&chunk[2]).copied().unwrap_or(0)
-                            };
-                            let b4 = if chunk[3] == '=' {
-                                0
-                            } else {
-                                char_map.get(&chunk[3]).copied().unwrap_or(0)
-                            };
-
-                            result.push((b1 << 2) | (b2 >> 4));
-                            if chunk[2] != '=' {
-                                result.push(((b2 & 0x0f) << 4) | (b3 >> 2));
-                            }
-                            if chunk[3] != '=' {
-                                result.push(((b3 & 0x03) << 6) | b4);
-                            }
-                        }
-
-                        match String::from_utf8(result) {
-                            Ok(decoded) => Ok(FhirPathValue::String(decoded.into())),
-                            Err(_) => Err(FunctionError::EvaluationError {
-                                name: self.name().to_string(),
-                                message: "Invalid urlbase64 encoding".to_string(),
-                            }),
-                        }
-                    }
+                    "urlbase64" => Ok(decode_base64(&URL_SAFE_ANY_PADDING, s)),
                     _ => Err(FunctionError::EvaluationError {
                         name: self.name().to_string(),
-                        message: format!("Unsupported decoding format: {format}"),
+                        message: format!(
+                            "Unsupported decoding format: {format} (supported: {})",
+                            SUPPORTED_FORMATS.join(", ")
+                        ),
                     }),
                 }
             }
@@ -271,3 +180,23 @@ impl AsyncFhirPathFunction for DecodeFunction {
         }
     }
 }
+
+/// URL-safe alphabet, accepting input with or without `=` padding
+const URL_SAFE_ANY_PADDING: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Decode base64 text into a string
+///
+/// Input that is not valid base64, or that does not decode to UTF-8 text, yields
+/// empty rather than an error, as reference engines do.
+fn decode_base64(engine: &impl Engine, s: &str) -> FhirPathValue {
+    engine
+        .decode(s)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .map_or(FhirPathValue::Empty, |decoded| {
+            FhirPathValue::String(decoded.into())
+        })
+}
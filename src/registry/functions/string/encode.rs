@@ -141,7 +141,10 @@ impl AsyncFhirPathFunction for EncodeFunction {
                     }
                     _ => Err(FunctionError::EvaluationError {
                         name: self.name().to_string(),
-                        message: format!("Unsupported encoding format: {format}"),
+                        message: format!(
+                            "Unsupported encoding format: {format} (supported: {})",
+                            SUPPORTED_FORMATS.join(", ")
+                        ),
                     }),
                 }
             }
@@ -155,3 +158,6 @@ impl AsyncFhirPathFunction for EncodeFunction {
         }
     }
 }
+
+/// Formats understood by encode() and decode()
+pub(super) const SUPPORTED_FORMATS: [&str; 5] = ["base64", "urlbase64", "hex", "uri", "html"];
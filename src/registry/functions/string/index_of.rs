@@ -5,6 +5,8 @@ use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, Functi
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
 use async_trait::async_trait;
 /// indexOf() function - finds index of substring
+///
+/// Indices count characters, not bytes, and an empty substring is found at 0.
 pub struct IndexOfFunction;
 
 #[async_trait]
@@ -30,7 +32,7 @@ impl AsyncFhirPathFunction for IndexOfFunction {
     }
 
     fn documentation(&self) -> &str {
-        "Returns the 0-based index of the first position `substring` is found in the input string, or -1 if it is not found."
+        "Returns the 0-based index of the first position `substring` is found in the input string, or -1 if it is not found. If `substring` is an empty string, the function returns 0."
     }
 
     async fn evaluate(
@@ -40,12 +42,9 @@ impl AsyncFhirPathFunction for IndexOfFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
         match (&context.input, &args[0]) {
-            (FhirPathValue::String(s), FhirPathValue::String(substring)) => {
-                match s.as_ref().find(substring.as_ref()) {
-                    Some(index) => Ok(FhirPathValue::Integer(index as i64)),
-                    None => Ok(FhirPathValue::Integer(-1)),
-                }
-            }
+            (FhirPathValue::String(s), FhirPathValue::String(substring)) => Ok(
+                FhirPathValue::Integer(char_index(s, s.find(substring.as_ref()))),
+            ),
             (FhirPathValue::Empty, _) => Ok(FhirPathValue::Empty),
             // Handle empty collections - return empty when any parameter is an empty collection
             (FhirPathValue::Collection(items), _) if items.is_empty() => Ok(FhirPathValue::Empty),
@@ -55,3 +54,11 @@ impl AsyncFhirPathFunction for IndexOfFunction {
         }
     }
 }
+
+/// Convert a byte offset found in `s` into a character index, or -1 if absent
+pub(super) fn char_index(s: &str, byte_offset: Option<usize>) -> i64 {
+    match byte_offset {
+        Some(offset) => s[..offset].chars().count() as i64,
+        None => -1,
+    }
+}
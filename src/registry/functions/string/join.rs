@@ -7,6 +7,9 @@ use crate::registry::function::{
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
 use async_trait::async_trait;
 /// join() function - joins collection of strings
+///
+/// Every item of the input must be a string; other items raise
+/// [`FunctionError::TypeError`]. Without a separator the items are concatenated.
 pub struct JoinFunction;
 
 #[async_trait]
@@ -50,35 +53,21 @@ impl AsyncFhirPathFunction for JoinFunction {
         };
 
         let items = context.input.clone().to_collection();
-        let strings: Vec<String> = items
+        let strings = items
             .into_iter()
             .map(|item| match item {
-                FhirPathValue::String(s) => s.as_ref().to_string(),
-                FhirPathValue::Integer(i) => i.to_string(),
-                FhirPathValue::Decimal(d) => d.to_string(),
-                FhirPathValue::Boolean(b) => b.to_string(),
-                FhirPathValue::Date(d) => d.to_string(),
-                FhirPathValue::DateTime(dt) => dt.to_string(),
-                FhirPathValue::Time(t) => t.to_string(),
-                FhirPathValue::Quantity(q) => q.to_string(),
-                FhirPathValue::Resource(r) => {
-                    // Try to extract string value from FhirResource
-                    match r.as_json() {
-                        serde_json::Value::String(s) => s.clone(),
-                        _ => format!("{r:?}"),
-                    }
-                }
-                FhirPathValue::Empty => String::new(),
-                FhirPathValue::Collection(_) => String::new(), // Empty collections become empty strings
-                FhirPathValue::TypeInfoObject { namespace, name } => {
-                    format!("{namespace}::{name}")
-                }
-                FhirPathValue::JsonValue(json) => {
-                    // Convert JsonValue to string representation
-                    json.as_json().to_string()
+                FhirPathValue::String(s) => Ok(s.as_ref().to_string()),
+                // Primitive string elements navigated from JSON
+                FhirPathValue::Resource(r) if r.as_json().is_string() => {
+                    Ok(r.as_json().as_str().unwrap_or_default().to_string())
                 }
+                other => Err(FunctionError::TypeError {
+                    name: self.name().to_string(),
+                    expected: "String".to_string(),
+                    actual: other.type_name().to_string(),
+                }),
             })
-            .collect();
+            .collect::<FunctionResult<Vec<String>>>()?;
 
         Ok(FhirPathValue::String(
             strings.join(separator.as_ref()).into(),
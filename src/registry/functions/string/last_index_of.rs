@@ -0,0 +1,65 @@
+//! lastIndexOf() function - finds index of the last occurrence of a substring
+
+use crate::model::{FhirPathValue, TypeInfo};
+use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
+use crate::registry::signature::{FunctionSignature, ParameterInfo};
+use async_trait::async_trait;
+
+use super::index_of::char_index;
+
+/// lastIndexOf() function - finds index of the last occurrence of a substring
+///
+/// Indices count characters, not bytes, and an empty substring is found at 0.
+pub struct LastIndexOfFunction;
+
+#[async_trait]
+impl AsyncFhirPathFunction for LastIndexOfFunction {
+    fn name(&self) -> &str {
+        "lastIndexOf"
+    }
+    fn human_friendly_name(&self) -> &str {
+        "Last Index Of"
+    }
+    fn signature(&self) -> &FunctionSignature {
+        static SIG: std::sync::LazyLock<FunctionSignature> = std::sync::LazyLock::new(|| {
+            FunctionSignature::new(
+                "lastIndexOf",
+                vec![ParameterInfo::required("substring", TypeInfo::String)],
+                TypeInfo::Integer,
+            )
+        });
+        &SIG
+    }
+    fn is_pure(&self) -> bool {
+        true // lastIndexOf() is a pure string function
+    }
+
+    fn documentation(&self) -> &str {
+        "Returns the 0-based index of the last position `substring` is found in the input string, or -1 if it is not found. If `substring` is an empty string, the function returns 0."
+    }
+
+    async fn evaluate(
+        &self,
+        args: &[FhirPathValue],
+        context: &EvaluationContext,
+    ) -> FunctionResult<FhirPathValue> {
+        self.validate_args(args)?;
+        match (&context.input, &args[0]) {
+            (FhirPathValue::String(s), FhirPathValue::String(substring)) => {
+                // rfind("") would report the end of the string
+                let offset = if substring.is_empty() {
+                    Some(0)
+                } else {
+                    s.rfind(substring.as_ref())
+                };
+                Ok(FhirPathValue::Integer(char_index(s, offset)))
+            }
+            (FhirPathValue::Empty, _) => Ok(FhirPathValue::Empty),
+            // Handle empty collections - return empty when any parameter is an empty collection
+            (FhirPathValue::Collection(items), _) if items.is_empty() => Ok(FhirPathValue::Empty),
+            (_, FhirPathValue::Collection(items)) if items.is_empty() => Ok(FhirPathValue::Empty),
+            // Return empty for non-string inputs instead of throwing error (per FHIRPath spec)
+            _ => Ok(FhirPathValue::Empty),
+        }
+    }
+}
@@ -8,6 +8,10 @@ use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 
 /// lower() function - converts to lowercase
+///
+/// Uses the full, locale-independent Unicode case mapping, so a character may
+/// map to several (e.g. `'İ'.lower()` is `'i'` followed by U+0307) and no
+/// Turkish dotted/dotless-i rules apply.
 pub struct LowerFunction;
 
 #[async_trait]
@@ -1,12 +1,12 @@
 //! matches() function - regex match
 
+use super::regex_cache::cached_regex;
 use crate::model::{FhirPathValue, TypeInfo};
 use crate::registry::function::{
     AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
 };
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
 use async_trait::async_trait;
-use regex::RegexBuilder;
 
 /// matches() function - regex match
 pub struct MatchesFunction;
@@ -40,11 +40,8 @@ impl AsyncFhirPathFunction for MatchesFunction {
         self.validate_args(args)?;
         match (&context.input, &args[0]) {
             (FhirPathValue::String(s), FhirPathValue::String(pattern)) => {
-                // Use RegexBuilder with single-line mode (dot matches newlines)
-                match RegexBuilder::new(pattern)
-                    .dot_matches_new_line(true)
-                    .build()
-                {
+                // Use single-line mode (dot matches newlines)
+                match cached_regex(pattern, true) {
                     Ok(re) => Ok(FhirPathValue::Boolean(re.is_match(s))),
                     Err(e) => Err(FunctionError::EvaluationError {
                         name: self.name().to_string(),
@@ -1,12 +1,12 @@
 //! matchesFull() function - full regex match
 
+use super::regex_cache::cached_regex;
 use crate::model::{FhirPathValue, TypeInfo};
 use crate::registry::function::{
     AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
 };
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
 use async_trait::async_trait;
-use regex::Regex;
 
 /// matchesFull() function - full regex match
 pub struct MatchesFullFunction;
@@ -52,7 +52,7 @@ impl AsyncFhirPathFunction for MatchesFullFunction {
                         format!("^{pattern}$")
                     };
 
-                match Regex::new(&full_pattern) {
+                match cached_regex(&full_pattern, false) {
                     Ok(re) => Ok(FhirPathValue::Boolean(re.is_match(s.as_ref()))),
                     Err(e) => Err(FunctionError::EvaluationError {
                         name: self.name().to_string(),
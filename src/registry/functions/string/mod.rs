@@ -7,9 +7,11 @@ mod ends_with;
 mod escape;
 mod index_of;
 mod join;
+mod last_index_of;
 mod lower;
 mod matches;
 mod matches_full;
+mod regex_cache;
 mod replace;
 mod replace_matches;
 mod split;
@@ -27,6 +29,7 @@ pub use ends_with::EndsWithFunction;
 pub use escape::EscapeFunction;
 pub use index_of::IndexOfFunction;
 pub use join::JoinFunction;
+pub use last_index_of::LastIndexOfFunction;
 pub use lower::LowerFunction;
 pub use matches::MatchesFunction;
 pub use matches_full::MatchesFullFunction;
@@ -57,6 +60,7 @@ pub fn register_string_functions(registry: &mut FunctionRegistry) {
     registry.register_async(TrimFunction);
     registry.register_async(ToCharsFunction);
     registry.register_async(IndexOfFunction);
+    registry.register_async(LastIndexOfFunction);
     registry.register_async(UpperFunction);
     registry.register_async(LowerFunction);
     registry.register_async(EncodeFunction);
@@ -0,0 +1,76 @@
+//! Thread-local cache of compiled regular expressions
+//!
+//! `matches()`, `matchesFull()` and `replaceMatches()` are typically called
+//! once per item of a collection with the same pattern, so compiled patterns
+//! are kept in a small LRU cache keyed by pattern. Invalid patterns are cached
+//! too, so a bad pattern is only compiled once.
+
+use lru::LruCache;
+use regex::{Regex, RegexBuilder};
+use std::cell::RefCell;
+use std::num::NonZeroUsize;
+
+/// Maximum number of patterns kept per thread
+const CACHE_CAPACITY: usize = 256;
+
+/// Cache key: the pattern and whether `.` matches newlines
+type CacheKey = (String, bool);
+
+thread_local! {
+    static REGEX_CACHE: RefCell<LruCache<CacheKey, Result<Regex, regex::Error>>> =
+        RefCell::new(LruCache::new(NonZeroUsize::new(CACHE_CAPACITY).unwrap()));
+}
+
+/// Get the compiled regex for a pattern, compiling it on first use
+pub(crate) fn cached_regex(
+    pattern: &str,
+    dot_matches_new_line: bool,
+) -> Result<Regex, regex::Error> {
+    REGEX_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let key = (pattern.to_string(), dot_matches_new_line);
+        if let Some(compiled) = cache.get(&key) {
+            return compiled.clone();
+        }
+
+        let compiled = RegexBuilder::new(pattern)
+            .dot_matches_new_line(dot_matches_new_line)
+            .build();
+        cache.put(key, compiled.clone());
+        compiled
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache_len() -> usize {
+        REGEX_CACHE.with(|cache| cache.borrow().len())
+    }
+
+    #[test]
+    fn test_reuses_compiled_pattern() {
+        let first = cached_regex("^a+b$", false).unwrap();
+        let len = cache_len();
+        let second = cached_regex("^a+b$", false).unwrap();
+
+        assert_eq!(cache_len(), len);
+        assert_eq!(first.as_str(), second.as_str());
+        assert!(second.is_match("aaab"));
+    }
+
+    #[test]
+    fn test_flags_are_part_of_the_key() {
+        assert!(cached_regex("a.b", true).unwrap().is_match("a\nb"));
+        assert!(!cached_regex("a.b", false).unwrap().is_match("a\nb"));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_cached_as_error() {
+        assert!(cached_regex("(unclosed", false).is_err());
+        let len = cache_len();
+        assert!(cached_regex("(unclosed", false).is_err());
+        assert_eq!(cache_len(), len);
+    }
+}
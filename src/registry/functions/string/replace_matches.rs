@@ -1,12 +1,12 @@
 //! replaceMatches() function - regex replacement
 
+use super::regex_cache::cached_regex;
 use crate::model::{FhirPathValue, TypeInfo};
 use crate::registry::function::{
     AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
 };
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
 use async_trait::async_trait;
-use regex::Regex;
 
 /// replaceMatches() function - regex replacement
 pub struct ReplaceMatchesFunction;
@@ -42,7 +42,11 @@ impl AsyncFhirPathFunction for ReplaceMatchesFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        match (&context.input, &args[0], &args[1]) {
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
+        };
+
+        match (input_item, &args[0], &args[1]) {
             (
                 FhirPathValue::String(s),
                 FhirPathValue::String(pattern),
@@ -53,9 +57,9 @@ impl AsyncFhirPathFunction for ReplaceMatchesFunction {
                     return Ok(FhirPathValue::String(s.clone()));
                 }
 
-                match Regex::new(pattern.as_ref()) {
+                match cached_regex(pattern.as_ref(), false) {
                     Ok(re) => Ok(FhirPathValue::String(
-                        re.replace_all(s.as_ref(), substitution.as_ref())
+                        re.replace_all(s.as_ref(), to_regex_replacement(substitution).as_str())
                             .to_string()
                             .into(),
                     )),
@@ -67,9 +71,6 @@ impl AsyncFhirPathFunction for ReplaceMatchesFunction {
             }
             (FhirPathValue::Empty, _, _) => Ok(FhirPathValue::Empty),
             // Handle empty collections - return empty when any parameter is an empty collection
-            (FhirPathValue::Collection(items), _, _) if items.is_empty() => {
-                Ok(FhirPathValue::Empty)
-            }
             (_, FhirPathValue::Empty, _) => Ok(FhirPathValue::Empty),
             (_, _, FhirPathValue::Empty) => Ok(FhirPathValue::Empty),
             (_, FhirPathValue::Collection(items), _) if items.is_empty() => {
@@ -87,3 +88,47 @@ impl AsyncFhirPathFunction for ReplaceMatchesFunction {
         }
     }
 }
+
+/// Translate a FHIRPath substitution into the `regex` crate's replacement syntax
+///
+/// `$1` always refers to group 1, even when followed by more text as in `$1x`
+/// (the regex crate would read a group named `1x`). `${name}` refers to a named
+/// group, and `\$` or `$$` produce a literal `$`.
+fn to_regex_replacement(substitution: &str) -> String {
+    let mut result = String::with_capacity(substitution.len());
+    let mut chars = substitution.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'$') => {
+                chars.next();
+                result.push_str("$$");
+            }
+            '$' => match chars.peek() {
+                Some(d) if d.is_ascii_digit() => {
+                    result.push_str("${");
+                    while let Some(d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                        result.push(*d);
+                        chars.next();
+                    }
+                    result.push('}');
+                }
+                Some('{') => {
+                    result.push('$');
+                    for c in chars.by_ref() {
+                        result.push(c);
+                        if c == '}' {
+                            break;
+                        }
+                    }
+                }
+                Some('$') => {
+                    chars.next();
+                    result.push_str("$$");
+                }
+                _ => result.push_str("$$"),
+            },
+            other => result.push(other),
+        }
+    }
+    result
+}
@@ -7,6 +7,10 @@ use crate::registry::function::{
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
 use async_trait::async_trait;
 /// split() function - splits string by separator
+///
+/// The separator is matched literally. An empty separator splits the string
+/// into its individual characters. Empty segments are kept, so
+/// `s.split(sep).join(sep)` always gives back `s`.
 pub struct SplitFunction;
 
 #[async_trait]
@@ -38,11 +42,17 @@ impl AsyncFhirPathFunction for SplitFunction {
         self.validate_args(args)?;
         match (&context.input, &args[0]) {
             (FhirPathValue::String(s), FhirPathValue::String(separator)) => {
-                let parts: Vec<FhirPathValue> = s
-                    .as_ref()
-                    .split(separator.as_ref())
-                    .map(|part| FhirPathValue::String(part.to_string().into()))
-                    .collect();
+                let parts: Vec<FhirPathValue> = if separator.as_ref().is_empty() {
+                    s.as_ref()
+                        .chars()
+                        .map(|c| FhirPathValue::String(c.to_string().into()))
+                        .collect()
+                } else {
+                    s.as_ref()
+                        .split(separator.as_ref())
+                        .map(|part| FhirPathValue::String(part.to_string().into()))
+                        .collect()
+                };
                 Ok(FhirPathValue::collection(parts))
             }
             (FhirPathValue::Empty, _) => Ok(FhirPathValue::Empty),
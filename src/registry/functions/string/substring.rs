@@ -56,7 +56,11 @@ impl AsyncFhirPathFunction for SubstringFunction {
             }
         }
 
-        let input_string = match &context.input {
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
+        };
+
+        let input_string = match input_item {
             FhirPathValue::String(s) => s.as_ref().to_string(),
             FhirPathValue::Resource(r) => {
                 // Try to extract string value from FhirResource
@@ -65,10 +69,6 @@ impl AsyncFhirPathFunction for SubstringFunction {
                     _ => return Ok(FhirPathValue::Empty),
                 }
             }
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            FhirPathValue::Collection(items) if items.is_empty() => {
-                return Ok(FhirPathValue::Empty);
-            }
             _ => return Ok(FhirPathValue::Empty),
         };
 
@@ -7,6 +7,9 @@ use crate::registry::function::{
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 /// trim() function - removes whitespace from both ends
+///
+/// Whitespace is anything with the Unicode `White_Space` property, so
+/// non-breaking and ideographic spaces are removed as well as ASCII ones.
 pub struct TrimFunction;
 
 #[async_trait]
@@ -25,6 +28,11 @@ impl AsyncFhirPathFunction for TrimFunction {
     fn is_pure(&self) -> bool {
         true // trim() is a pure string function
     }
+
+    fn documentation(&self) -> &str {
+        "Returns the input string with leading and trailing whitespace removed."
+    }
+
     async fn evaluate(
         &self,
         args: &[FhirPathValue],
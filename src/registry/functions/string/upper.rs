@@ -7,6 +7,10 @@ use crate::registry::function::{
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 /// upper() function - converts to uppercase
+///
+/// Uses the full, locale-independent Unicode case mapping, so a character may
+/// map to several (e.g. `'ß'.upper()` is `'SS'`) and no Turkish
+/// dotted/dotless-i rules apply.
 pub struct UpperFunction;
 
 #[async_trait]
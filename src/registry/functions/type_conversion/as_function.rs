@@ -1,13 +1,11 @@
 //! as() function - type casting function
 
-use crate::model::{FhirPathValue, TypeInfo};
+use crate::model::{FhirPathValue, TypeInfo, is_value_of_type};
 use crate::registry::function::{
     AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
 };
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
 use async_trait::async_trait;
-use chrono::TimeZone;
-use rust_decimal::prelude::ToPrimitive;
 
 /// as() function - performs type casting
 pub struct AsFunction;
@@ -57,131 +55,18 @@ impl AsyncFhirPathFunction for AsFunction {
             }
         };
 
-        // Perform type casting based on the input value
-        match (&context.input, type_name) {
-            // Empty always returns empty
-            (FhirPathValue::Empty, _) => Ok(FhirPathValue::Empty),
-
-            // String casting - only if actually a string type
-            (FhirPathValue::String(s), "string" | "String" | "System.String") => {
-                Ok(FhirPathValue::String(s.clone()))
-            }
-
-            // Code type casting - in FHIRPath, code is a subtype of string
-            // Since we store codes as strings, we accept string values for code type
-            (FhirPathValue::String(s), "code" | "Code" | "FHIR.code") => {
-                Ok(FhirPathValue::String(s.clone()))
-            }
-
-            // Integer casting
-            (FhirPathValue::Integer(i), "integer" | "Integer" | "System.Integer") => {
-                Ok(FhirPathValue::Integer(*i))
-            }
-            (FhirPathValue::Decimal(d), "integer" | "Integer" | "System.Integer") => {
-                // Try to convert decimal to integer if it has no fractional part
-                if d.fract().is_zero() {
-                    if let Some(i) = d.to_i64() {
-                        Ok(FhirPathValue::Integer(i))
-                    } else {
-                        Ok(FhirPathValue::Empty)
-                    }
-                } else {
-                    Ok(FhirPathValue::Empty)
-                }
-            }
-            (FhirPathValue::String(s), "integer" | "Integer" | "System.Integer") => {
-                match s.parse::<i64>() {
-                    Ok(i) => Ok(FhirPathValue::Integer(i)),
-                    Err(_) => Ok(FhirPathValue::Empty),
-                }
-            }
-
-            // Decimal casting
-            (FhirPathValue::Decimal(d), "decimal" | "Decimal" | "System.Decimal") => {
-                Ok(FhirPathValue::Decimal(*d))
-            }
-            (FhirPathValue::Integer(i), "decimal" | "Decimal" | "System.Decimal") => {
-                Ok(FhirPathValue::Decimal(rust_decimal::Decimal::from(*i)))
-            }
-            (FhirPathValue::String(s), "decimal" | "Decimal" | "System.Decimal") => {
-                match s.parse::<rust_decimal::Decimal>() {
-                    Ok(d) => Ok(FhirPathValue::Decimal(d)),
-                    Err(_) => Ok(FhirPathValue::Empty),
-                }
-            }
-
-            // Boolean casting
-            (FhirPathValue::Boolean(b), "boolean" | "Boolean" | "System.Boolean") => {
-                Ok(FhirPathValue::Boolean(*b))
-            }
-            (FhirPathValue::String(s), "boolean" | "Boolean" | "System.Boolean") => {
-                match s.as_ref() {
-                    "true" => Ok(FhirPathValue::Boolean(true)),
-                    "false" => Ok(FhirPathValue::Boolean(false)),
-                    _ => Ok(FhirPathValue::Empty),
-                }
-            }
-
-            // Date casting
-            (FhirPathValue::Date(d), "date" | "Date" | "System.Date") => {
-                Ok(FhirPathValue::Date(*d))
-            }
-            (FhirPathValue::DateTime(dt), "date" | "Date" | "System.Date") => {
-                Ok(FhirPathValue::Date(dt.date_naive()))
-            }
-            (FhirPathValue::String(s), "date" | "Date" | "System.Date") => {
-                match chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
-                    Ok(d) => Ok(FhirPathValue::Date(d)),
-                    Err(_) => Ok(FhirPathValue::Empty),
-                }
-            }
-
-            // DateTime casting
-            (FhirPathValue::DateTime(dt), "dateTime" | "DateTime" | "System.DateTime") => {
-                Ok(FhirPathValue::DateTime(*dt))
-            }
-            (FhirPathValue::Date(d), "dateTime" | "DateTime" | "System.DateTime") => {
-                let dt = d
-                    .and_hms_opt(0, 0, 0)
-                    .map(|naive| chrono::Utc.from_utc_datetime(&naive).fixed_offset());
-                match dt {
-                    Some(datetime) => Ok(FhirPathValue::DateTime(datetime)),
-                    None => Ok(FhirPathValue::Empty),
-                }
-            }
-
-            // Time casting
-            (FhirPathValue::Time(t), "time" | "Time" | "System.Time") => {
-                Ok(FhirPathValue::Time(*t))
+        // Only a single value of the named type (or a subtype) is returned
+        let value = match &context.input {
+            FhirPathValue::Collection(items) if items.len() == 1 => items.first().unwrap(),
+            FhirPathValue::Collection(_) | FhirPathValue::Empty => {
+                return Ok(FhirPathValue::Empty);
             }
-            (FhirPathValue::DateTime(dt), "time" | "Time" | "System.Time") => {
-                Ok(FhirPathValue::Time(dt.time()))
-            }
-
-            // Quantity casting
-            (FhirPathValue::Quantity(q), "Quantity" | "System.Quantity") => {
-                Ok(FhirPathValue::Quantity(q.clone()))
-            }
-
-            // Collection handling - try to cast the collection
-            (FhirPathValue::Collection(items), _) => {
-                if items.is_empty() {
-                    Ok(FhirPathValue::Empty)
-                } else if items.len() == 1 {
-                    // Single item collection - cast the item
-                    let item_context = EvaluationContext {
-                        input: items.first().unwrap().clone(),
-                        ..context.clone()
-                    };
-                    self.evaluate(args, &item_context).await
-                } else {
-                    // Multiple items - return empty
-                    Ok(FhirPathValue::Empty)
-                }
-            }
-
-            // Default: if the type doesn't match, return empty
-            _ => Ok(FhirPathValue::Empty),
+            single => single,
+        };
+        if is_value_of_type(value, type_name) {
+            Ok(value.clone())
+        } else {
+            Ok(FhirPathValue::Empty)
         }
     }
 }
@@ -1,12 +1,10 @@
 //! convertsToBoolean() function - checks if value can be converted to boolean
 
+use super::to_boolean::to_boolean;
 use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{
-    AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
-};
+use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
-use rust_decimal::prelude::*;
 
 /// convertsToBoolean() function - checks if value can be converted to boolean
 pub struct ConvertsToBooleanFunction;
@@ -36,48 +34,11 @@ impl AsyncFhirPathFunction for ConvertsToBooleanFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        // Extract single item from collection according to spec
-        let input_item = match &context.input {
-            FhirPathValue::Collection(items) => {
-                if items.len() > 1 {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Input collection contains multiple items".to_string(),
-                    });
-                } else if items.is_empty() {
-                    return Ok(FhirPathValue::Empty);
-                } else {
-                    items.get(0).unwrap()
-                }
-            }
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            item => item,
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
         };
 
-        let can_convert = match input_item {
-            FhirPathValue::Boolean(_) => true,
-            FhirPathValue::String(s) => {
-                let lower = s.to_lowercase();
-                matches!(
-                    lower.as_str(),
-                    "true"
-                        | "t"
-                        | "yes"
-                        | "y"
-                        | "1"
-                        | "1.0"
-                        | "false"
-                        | "f"
-                        | "no"
-                        | "n"
-                        | "0"
-                        | "0.0"
-                )
-            }
-            FhirPathValue::Integer(i) => *i == 0 || *i == 1,
-            FhirPathValue::Decimal(d) => *d == Decimal::ZERO || *d == Decimal::ONE,
-            _ => false,
-        };
+        let can_convert = to_boolean(input_item).is_some();
         Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
             can_convert,
         )]))
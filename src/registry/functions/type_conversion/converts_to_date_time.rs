@@ -1,9 +1,7 @@
 //! convertsToDateTime() function - checks if value can be converted to datetime
 
 use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{
-    EvaluationContext, FhirPathFunction, FunctionError, FunctionResult,
-};
+use crate::registry::function::{EvaluationContext, FhirPathFunction, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 
 /// convertsToDateTime() function - checks if value can be converted to datetime
@@ -33,22 +31,8 @@ impl FhirPathFunction for ConvertsToDateTimeFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        // Extract single item from collection according to spec
-        let input_item = match &context.input {
-            FhirPathValue::Collection(items) => {
-                if items.len() > 1 {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Input collection contains multiple items".to_string(),
-                    });
-                } else if items.is_empty() {
-                    return Ok(FhirPathValue::Empty);
-                } else {
-                    items.get(0).unwrap()
-                }
-            }
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            item => item,
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
         };
 
         let can_convert = match input_item {
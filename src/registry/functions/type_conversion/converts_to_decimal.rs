@@ -1,11 +1,9 @@
 //! convertsToDecimal() function - checks if value can be converted to decimal
 
+use super::to_decimal::to_decimal;
 use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{
-    EvaluationContext, FhirPathFunction, FunctionError, FunctionResult,
-};
+use crate::registry::function::{EvaluationContext, FhirPathFunction, FunctionResult};
 use crate::registry::signature::FunctionSignature;
-use rust_decimal::prelude::*;
 
 /// convertsToDecimal() function - checks if value can be converted to decimal
 pub struct ConvertsToDecimalFunction;
@@ -34,31 +32,11 @@ impl FhirPathFunction for ConvertsToDecimalFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        // Extract single item from collection according to spec
-        let input_item = match &context.input {
-            FhirPathValue::Collection(items) => {
-                if items.len() > 1 {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Input collection contains multiple items".to_string(),
-                    });
-                } else if items.is_empty() {
-                    return Ok(FhirPathValue::Empty);
-                } else {
-                    items.get(0).unwrap()
-                }
-            }
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            item => item,
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
         };
 
-        let can_convert = match input_item {
-            FhirPathValue::Decimal(_) => true,
-            FhirPathValue::Integer(_) => true,
-            FhirPathValue::String(s) => Decimal::from_str(s.trim()).is_ok(),
-            FhirPathValue::Boolean(_) => true,
-            _ => false,
-        };
+        let can_convert = to_decimal(input_item).is_some();
         Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
             can_convert,
         )]))
@@ -1,9 +1,8 @@
 //! convertsToInteger() function - checks if value can be converted to integer
 
+use super::to_integer::to_integer;
 use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{
-    EvaluationContext, FhirPathFunction, FunctionError, FunctionResult,
-};
+use crate::registry::function::{EvaluationContext, FhirPathFunction, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 
 /// convertsToInteger() function - checks if value can be converted to integer
@@ -33,30 +32,11 @@ impl FhirPathFunction for ConvertsToIntegerFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        // Extract single item from collection according to spec
-        let input_item = match &context.input {
-            FhirPathValue::Collection(items) => {
-                if items.len() > 1 {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Input collection contains multiple items".to_string(),
-                    });
-                } else if items.is_empty() {
-                    return Ok(FhirPathValue::Empty);
-                } else {
-                    items.get(0).unwrap()
-                }
-            }
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            item => item,
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
         };
 
-        let can_convert = match input_item {
-            FhirPathValue::Integer(_) => true,
-            FhirPathValue::String(s) => !s.contains('.') && s.trim().parse::<i64>().is_ok(),
-            FhirPathValue::Boolean(_) => true,
-            _ => false,
-        };
+        let can_convert = to_integer(input_item).is_some();
         Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
             can_convert,
         )]))
@@ -1,9 +1,8 @@
 //! convertsToQuantity() function - checks if value can be converted to quantity
 
+use super::to_quantity::to_quantity;
 use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{
-    EvaluationContext, FhirPathFunction, FunctionError, FunctionResult,
-};
+use crate::registry::function::{EvaluationContext, FhirPathFunction, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 
 /// convertsToQuantity() function - checks if value can be converted to quantity
@@ -33,34 +32,11 @@ impl FhirPathFunction for ConvertsToQuantityFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        // Extract single item from collection according to spec
-        let input_item = match &context.input {
-            FhirPathValue::Collection(items) => {
-                if items.len() > 1 {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Input collection contains multiple items".to_string(),
-                    });
-                } else if items.is_empty() {
-                    return Ok(FhirPathValue::Empty);
-                } else {
-                    items.get(0).unwrap()
-                }
-            }
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            item => item,
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
         };
 
-        let can_convert = match input_item {
-            FhirPathValue::Quantity(_) => true,
-            FhirPathValue::Integer(_) => true,
-            FhirPathValue::Decimal(_) => true,
-            FhirPathValue::String(s) => {
-                // Check if string can be parsed as a number
-                s.parse::<f64>().is_ok()
-            }
-            _ => false,
-        };
+        let can_convert = to_quantity(input_item).is_some();
         Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
             can_convert,
         )]))
@@ -1,9 +1,7 @@
 //! convertsToTime() function - checks if value can be converted to time
 
-use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{
-    EvaluationContext, FhirPathFunction, FunctionError, FunctionResult,
-};
+use crate::model::{FhirPathValue, PrecisionTime, TypeInfo};
+use crate::registry::function::{EvaluationContext, FhirPathFunction, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 
 /// convertsToTime() function - checks if value can be converted to time
@@ -33,31 +31,14 @@ impl FhirPathFunction for ConvertsToTimeFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        // Extract single item from collection according to spec
-        let input_item = match &context.input {
-            FhirPathValue::Collection(items) => {
-                if items.len() > 1 {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Input collection contains multiple items".to_string(),
-                    });
-                } else if items.is_empty() {
-                    return Ok(FhirPathValue::Empty);
-                } else {
-                    items.get(0).unwrap()
-                }
-            }
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            item => item,
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
         };
 
         let can_convert = match input_item {
             FhirPathValue::Time(_) => true,
-            FhirPathValue::String(s) => {
-                // Check if string matches valid time formats: HH, HH:MM, HH:MM:SS, HH:MM:SS.mmm
-                let time_regex = regex::Regex::new(r"^\d{2}(:\d{2}(:\d{2}(\.\d{3})?)?)?$").unwrap();
-                time_regex.is_match(s)
-            }
+            // Valid time formats: HH, HH:MM, HH:MM:SS, HH:MM:SS.fff
+            FhirPathValue::String(s) => PrecisionTime::parse(s).is_some(),
             _ => false,
         };
         Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
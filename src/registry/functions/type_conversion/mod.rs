@@ -1,4 +1,8 @@
 //! Type conversion functions module
+//!
+//! toBoolean(), toDecimal(), toInteger() and toQuantity() each convert a single
+//! value through a `pub(crate)` helper that the matching convertsTo*() function
+//! calls as well, so the two always agree.
 
 mod as_function;
 mod converts_to_boolean;
@@ -10,10 +14,12 @@ mod converts_to_quantity;
 mod converts_to_string;
 mod converts_to_time;
 mod to_boolean;
+mod to_canonical_string;
 mod to_decimal;
 mod to_integer;
 mod to_quantity;
 mod to_string;
+mod to_time;
 mod type_function;
 
 pub use as_function::AsFunction;
@@ -26,8 +32,10 @@ pub use converts_to_quantity::ConvertsToQuantityFunction;
 pub use converts_to_string::ConvertsToStringFunction;
 pub use converts_to_time::ConvertsToTimeFunction;
 pub use to_boolean::ToBooleanFunction;
+pub use to_canonical_string::ToCanonicalStringFunction;
 pub use to_decimal::ToDecimalFunction;
 pub use to_integer::ToIntegerFunction;
 pub use to_quantity::ToQuantityFunction;
 pub use to_string::ToStringFunction;
+pub use to_time::ToTimeFunction;
 pub use type_function::TypeFunction;
@@ -1,9 +1,7 @@
 //! toBoolean() function - converts value to boolean
 
 use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{
-    AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
-};
+use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 use rust_decimal::prelude::*;
@@ -36,63 +34,30 @@ impl AsyncFhirPathFunction for ToBooleanFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        // Extract single item from collection according to spec
-        let input_item = match &context.input {
-            FhirPathValue::Collection(items) => {
-                if items.len() > 1 {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Input collection contains multiple items".to_string(),
-                    });
-                } else if items.is_empty() {
-                    return Ok(FhirPathValue::Empty);
-                } else {
-                    items.get(0).unwrap()
-                }
-            }
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            item => item,
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
         };
 
-        match input_item {
-            FhirPathValue::Boolean(b) => {
-                Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(*b)]))
-            }
-            FhirPathValue::String(s) => {
-                let lower = s.to_lowercase();
-                match lower.as_str() {
-                    "true" | "t" | "yes" | "y" | "1" | "1.0" => Ok(FhirPathValue::collection(
-                        vec![FhirPathValue::Boolean(true)],
-                    )),
-                    "false" | "f" | "no" | "n" | "0" | "0.0" => Ok(FhirPathValue::collection(
-                        vec![FhirPathValue::Boolean(false)],
-                    )),
-                    _ => Ok(FhirPathValue::Empty),
-                }
-            }
-            FhirPathValue::Integer(i) => match *i {
-                1 => Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
-                    true,
-                )])),
-                0 => Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
-                    false,
-                )])),
-                _ => Ok(FhirPathValue::Empty),
-            },
-            FhirPathValue::Decimal(d) => {
-                if *d == Decimal::ONE {
-                    Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
-                        true,
-                    )]))
-                } else if *d == Decimal::ZERO {
-                    Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
-                        false,
-                    )]))
-                } else {
-                    Ok(FhirPathValue::Empty)
-                }
-            }
-            _ => Ok(FhirPathValue::Empty),
+        match to_boolean(input_item) {
+            Some(b) => Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(b)])),
+            None => Ok(FhirPathValue::Empty),
         }
     }
 }
+
+/// Convert a single value as toBoolean() does, `None` if it does not convert
+pub(crate) fn to_boolean(value: &FhirPathValue) -> Option<bool> {
+    match value {
+        FhirPathValue::Boolean(b) => Some(*b),
+        FhirPathValue::String(s) => match s.to_lowercase().as_str() {
+            "true" | "t" | "yes" | "y" | "1" | "1.0" => Some(true),
+            "false" | "f" | "no" | "n" | "0" | "0.0" => Some(false),
+            _ => None,
+        },
+        FhirPathValue::Integer(1) => Some(true),
+        FhirPathValue::Integer(0) => Some(false),
+        FhirPathValue::Decimal(d) if *d == Decimal::ONE => Some(true),
+        FhirPathValue::Decimal(d) if *d == Decimal::ZERO => Some(false),
+        _ => None,
+    }
+}
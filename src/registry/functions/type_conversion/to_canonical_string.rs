@@ -0,0 +1,69 @@
+//! toCanonicalString() function - converts value to string in canonical units
+
+use super::ToStringFunction;
+use crate::model::{FhirPathValue, TypeInfo};
+use crate::registry::function::{
+    AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
+};
+use crate::registry::signature::FunctionSignature;
+use async_trait::async_trait;
+
+/// toCanonicalString() function - like toString(), but quantities are
+/// expressed in canonical UCUM units
+pub struct ToCanonicalStringFunction;
+
+#[async_trait]
+impl AsyncFhirPathFunction for ToCanonicalStringFunction {
+    fn name(&self) -> &str {
+        "toCanonicalString"
+    }
+    fn human_friendly_name(&self) -> &str {
+        "To Canonical String"
+    }
+    fn signature(&self) -> &FunctionSignature {
+        static SIG: std::sync::LazyLock<FunctionSignature> = std::sync::LazyLock::new(|| {
+            FunctionSignature::new("toCanonicalString", vec![], TypeInfo::String)
+        });
+        &SIG
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn documentation(&self) -> &str {
+        "Returns the value as a String in the same way as toString(), except that a Quantity is first converted to canonical UCUM units, so `(1000 'mg').toCanonicalString()` returns `1 'g'`."
+    }
+
+    async fn evaluate(
+        &self,
+        args: &[FhirPathValue],
+        context: &EvaluationContext,
+    ) -> FunctionResult<FhirPathValue> {
+        self.validate_args(args)?;
+
+        let quantity = match &context.input {
+            FhirPathValue::Quantity(q) => Some(q),
+            FhirPathValue::Collection(items) if items.len() == 1 => match items.get(0) {
+                Some(FhirPathValue::Quantity(q)) => Some(q),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        match quantity {
+            Some(q) => {
+                let canonical = q
+                    .to_canonical()
+                    .map_err(|e| FunctionError::EvaluationError {
+                        name: self.name().to_string(),
+                        message: e.to_string(),
+                    })?;
+                Ok(FhirPathValue::collection(vec![FhirPathValue::String(
+                    canonical.to_literal_string().into(),
+                )]))
+            }
+            None => ToStringFunction.evaluate(args, context).await,
+        }
+    }
+}
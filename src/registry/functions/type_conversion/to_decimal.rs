@@ -1,9 +1,7 @@
 //! toDecimal() function - converts value to decimal
 
 use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{
-    AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
-};
+use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 use rust_decimal::prelude::*;
@@ -36,43 +34,40 @@ impl AsyncFhirPathFunction for ToDecimalFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        // Extract single item from collection according to spec
-        let input_item = match &context.input {
-            FhirPathValue::Collection(items) => {
-                if items.len() > 1 {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Input collection contains multiple items".to_string(),
-                    });
-                } else if items.is_empty() {
-                    return Ok(FhirPathValue::Empty);
-                } else {
-                    items.get(0).unwrap()
-                }
-            }
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            item => item,
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
         };
 
-        match input_item {
-            FhirPathValue::Decimal(d) => {
-                Ok(FhirPathValue::collection(vec![FhirPathValue::Decimal(*d)]))
-            }
-            FhirPathValue::Integer(i) => {
-                Ok(FhirPathValue::collection(vec![FhirPathValue::Decimal(
-                    Decimal::from(*i),
-                )]))
-            }
-            FhirPathValue::String(s) => match Decimal::from_str(s.trim()) {
-                Ok(d) => Ok(FhirPathValue::collection(vec![FhirPathValue::Decimal(d)])),
-                Err(_) => Ok(FhirPathValue::Empty),
-            },
-            FhirPathValue::Boolean(b) => {
-                Ok(FhirPathValue::collection(vec![FhirPathValue::Decimal(
-                    if *b { Decimal::ONE } else { Decimal::ZERO },
-                )]))
-            }
-            _ => Ok(FhirPathValue::Empty),
+        match to_decimal(input_item) {
+            Some(d) => Ok(FhirPathValue::collection(vec![FhirPathValue::Decimal(d)])),
+            None => Ok(FhirPathValue::Empty),
         }
     }
 }
+
+/// Convert a single value as toDecimal() does, `None` if it does not convert
+pub(crate) fn to_decimal(value: &FhirPathValue) -> Option<Decimal> {
+    match value {
+        FhirPathValue::Decimal(d) => Some(*d),
+        FhirPathValue::Integer(i) => Some(Decimal::from(*i)),
+        FhirPathValue::String(s) => parse_decimal(s),
+        FhirPathValue::Boolean(b) => Some(if *b { Decimal::ONE } else { Decimal::ZERO }),
+        _ => None,
+    }
+}
+
+/// Parse a string matching the FHIRPath decimal format `(\+|-)?\d+(\.\d+)?`
+///
+/// Whitespace, exponents and separators are not allowed anywhere.
+pub(crate) fn parse_decimal(s: &str) -> Option<Decimal> {
+    let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+    let (whole, fraction) = match unsigned.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (unsigned, None),
+    };
+    let all_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    if !all_digits(whole) || !fraction.is_none_or(all_digits) {
+        return None;
+    }
+    Decimal::from_str(s.strip_prefix('+').unwrap_or(s)).ok()
+}
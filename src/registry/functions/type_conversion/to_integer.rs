@@ -1,9 +1,7 @@
 //! toInteger() function - converts value to integer
 
 use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{
-    AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
-};
+use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 
@@ -40,45 +38,34 @@ impl AsyncFhirPathFunction for ToIntegerFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        // Extract single item from collection according to spec
-        let input_item = match &context.input {
-            FhirPathValue::Collection(items) => {
-                if items.len() > 1 {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Input collection contains multiple items".to_string(),
-                    });
-                } else if items.is_empty() {
-                    return Ok(FhirPathValue::Empty);
-                } else {
-                    items.get(0).unwrap()
-                }
-            }
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            item => item,
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
         };
 
-        match input_item {
-            FhirPathValue::Integer(i) => {
-                Ok(FhirPathValue::collection(vec![FhirPathValue::Integer(*i)]))
-            }
-            FhirPathValue::String(s) => {
-                // According to FHIRPath spec, strings with decimal points cannot be converted to integers
-                if s.contains('.') {
-                    Ok(FhirPathValue::Empty)
-                } else {
-                    match s.trim().parse::<i64>() {
-                        Ok(i) => Ok(FhirPathValue::collection(vec![FhirPathValue::Integer(i)])),
-                        Err(_) => Ok(FhirPathValue::Empty),
-                    }
-                }
-            }
-            FhirPathValue::Boolean(b) => {
-                Ok(FhirPathValue::collection(vec![FhirPathValue::Integer(
-                    if *b { 1 } else { 0 },
-                )]))
-            }
-            _ => Ok(FhirPathValue::Empty),
+        match to_integer(input_item) {
+            Some(i) => Ok(FhirPathValue::collection(vec![FhirPathValue::Integer(i)])),
+            None => Ok(FhirPathValue::Empty),
         }
     }
 }
+
+/// Convert a single value as toInteger() does, `None` if it does not convert
+pub(crate) fn to_integer(value: &FhirPathValue) -> Option<i64> {
+    match value {
+        FhirPathValue::Integer(i) => Some(*i),
+        FhirPathValue::String(s) => parse_integer(s),
+        FhirPathValue::Boolean(b) => Some(i64::from(*b)),
+        _ => None,
+    }
+}
+
+/// Parse a string matching the FHIRPath integer format `(\+|-)?\d+`
+///
+/// Whitespace, decimal points and separators are not allowed anywhere.
+pub(crate) fn parse_integer(s: &str) -> Option<i64> {
+    let digits = s.strip_prefix(['+', '-']).unwrap_or(s);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
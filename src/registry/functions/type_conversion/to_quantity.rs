@@ -1,12 +1,11 @@
 //! toQuantity() function - converts value to quantity
 
-use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{
-    AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
-};
+use super::to_decimal::parse_decimal;
+use crate::model::{FhirPathValue, Quantity, TypeInfo};
+use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
-use rust_decimal::prelude::*;
+use rust_decimal::Decimal;
 
 /// toQuantity() function - converts value to quantity
 pub struct ToQuantityFunction;
@@ -36,62 +35,62 @@ impl AsyncFhirPathFunction for ToQuantityFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        // Extract single item from collection according to spec
-        let input_item = match &context.input {
-            FhirPathValue::Collection(items) => {
-                if items.len() > 1 {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Input collection contains multiple items".to_string(),
-                    });
-                } else if items.is_empty() {
-                    return Ok(FhirPathValue::Empty);
-                } else {
-                    items.get(0).unwrap()
-                }
-            }
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            item => item,
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
         };
 
-        match input_item {
-            FhirPathValue::Quantity(q) => {
-                Ok(FhirPathValue::collection(vec![FhirPathValue::Quantity(
-                    q.clone(),
-                )]))
-            }
-            FhirPathValue::Integer(i) => {
-                // Convert integer to quantity with unit "1" (dimensionless)
-                let quantity = crate::model::Quantity::new(
-                    rust_decimal::Decimal::from(*i),
-                    Some("1".to_string()),
-                );
-                Ok(FhirPathValue::collection(vec![FhirPathValue::Quantity(
-                    quantity.into(),
-                )]))
-            }
-            FhirPathValue::Decimal(d) => {
-                // Convert decimal to quantity with unit "1" (dimensionless)
-                let quantity = crate::model::Quantity::new(*d, Some("1".to_string()));
-                Ok(FhirPathValue::collection(vec![FhirPathValue::Quantity(
-                    quantity.into(),
-                )]))
-            }
-            FhirPathValue::String(s) => {
-                // Try to parse string as quantity
-                if let Ok(parsed) = s.parse::<f64>() {
-                    let quantity = crate::model::Quantity::new(
-                        rust_decimal::Decimal::from_f64(parsed).unwrap_or_default(),
-                        Some("1".to_string()),
-                    );
-                    Ok(FhirPathValue::collection(vec![FhirPathValue::Quantity(
-                        quantity.into(),
-                    )]))
-                } else {
-                    Ok(FhirPathValue::Empty)
-                }
-            }
-            _ => Ok(FhirPathValue::Empty),
+        match to_quantity(input_item) {
+            Some(q) => Ok(FhirPathValue::collection(vec![FhirPathValue::Quantity(
+                q.into(),
+            )])),
+            None => Ok(FhirPathValue::Empty),
         }
     }
 }
+
+/// Convert a single value as toQuantity() does, `None` if it does not convert
+///
+/// Numbers and Booleans become quantities with the unit `'1'`, and FHIR
+/// Quantity elements their System equivalent.
+pub(crate) fn to_quantity(value: &FhirPathValue) -> Option<Quantity> {
+    let unity = |value| Some(Quantity::new(value, Some("1".to_string())));
+    match value {
+        FhirPathValue::Quantity(q) => Some((**q).clone()),
+        FhirPathValue::Integer(i) => unity(Decimal::from(*i)),
+        FhirPathValue::Decimal(d) => unity(*d),
+        FhirPathValue::Boolean(b) => unity(if *b {
+            Decimal::new(10, 1)
+        } else {
+            Decimal::new(0, 1)
+        }),
+        FhirPathValue::String(s) => parse_quantity(s),
+        other => other.quantity_element().map(|q| (*q).clone()),
+    }
+}
+
+/// Parse a string in the FHIRPath quantity format, e.g. `5 'mg'` or `4 days`
+///
+/// This is the grammar of quantity literals: the number may be followed by a
+/// UCUM unit in quotes or by a calendar duration keyword; `5 mg` is malformed
+/// since `mg` is not a keyword, and nothing may follow the unit. With no unit
+/// the quantity has the unit `'1'`.
+pub(crate) fn parse_quantity(s: &str) -> Option<Quantity> {
+    let number_end = s
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '+' | '-')))
+        .unwrap_or(s.len());
+    let value = parse_decimal(&s[..number_end])?;
+    let unit = s[number_end..].trim_start();
+
+    let unit = if unit.is_empty() {
+        "1"
+    } else if let Some(ucum) = unit.strip_prefix('\'').and_then(|u| u.strip_suffix('\'')) {
+        if ucum.is_empty() || ucum.contains('\'') || octofhir_ucum::validate(ucum).is_err() {
+            return None;
+        }
+        ucum
+    } else {
+        Quantity::calendar_unit(unit)?;
+        unit
+    };
+    Some(Quantity::new(value, Some(unit.to_string())))
+}
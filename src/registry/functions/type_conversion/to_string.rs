@@ -1,9 +1,7 @@
 //! toString() function - converts value to string
 
 use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{
-    AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
-};
+use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 
@@ -40,22 +38,8 @@ impl AsyncFhirPathFunction for ToStringFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        // Extract single item from collection according to spec
-        let input_item = match &context.input {
-            FhirPathValue::Collection(items) => {
-                if items.len() > 1 {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().to_string(),
-                        message: "Input collection contains multiple items".to_string(),
-                    });
-                } else if items.is_empty() {
-                    return Ok(FhirPathValue::Empty);
-                } else {
-                    items.get(0).unwrap()
-                }
-            }
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            item => item,
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
         };
 
         match input_item {
@@ -80,6 +64,7 @@ impl AsyncFhirPathFunction for ToStringFunction {
             FhirPathValue::Date(d) => Ok(FhirPathValue::collection(vec![FhirPathValue::String(
                 d.to_string().into(),
             )])),
+            // ISO 8601 to the precision it was written, with a numeric offset
             FhirPathValue::DateTime(dt) => {
                 Ok(FhirPathValue::collection(vec![FhirPathValue::String(
                     dt.to_string().into(),
@@ -90,7 +75,7 @@ impl AsyncFhirPathFunction for ToStringFunction {
             )])),
             FhirPathValue::Quantity(q) => {
                 Ok(FhirPathValue::collection(vec![FhirPathValue::String(
-                    q.to_string().into(),
+                    q.to_literal_string().into(),
                 )]))
             }
             _ => Ok(FhirPathValue::Empty),
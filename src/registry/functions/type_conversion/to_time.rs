@@ -0,0 +1,49 @@
+//! toTime() function - converts value to time
+
+use crate::model::{FhirPathValue, PrecisionTime, TypeInfo};
+use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
+use crate::registry::signature::FunctionSignature;
+use async_trait::async_trait;
+
+/// toTime() function - converts value to time
+pub struct ToTimeFunction;
+
+#[async_trait]
+impl AsyncFhirPathFunction for ToTimeFunction {
+    fn name(&self) -> &str {
+        "toTime"
+    }
+    fn human_friendly_name(&self) -> &str {
+        "To Time"
+    }
+    fn signature(&self) -> &FunctionSignature {
+        static SIG: std::sync::LazyLock<FunctionSignature> =
+            std::sync::LazyLock::new(|| FunctionSignature::new("toTime", vec![], TypeInfo::Time));
+        &SIG
+    }
+
+    fn is_pure(&self) -> bool {
+        true // toTime() is a pure type conversion function
+    }
+    async fn evaluate(
+        &self,
+        args: &[FhirPathValue],
+        context: &EvaluationContext,
+    ) -> FunctionResult<FhirPathValue> {
+        self.validate_args(args)?;
+
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
+        };
+
+        match input_item {
+            FhirPathValue::Time(t) => Ok(FhirPathValue::collection(vec![FhirPathValue::Time(*t)])),
+            // The string keeps its precision: '14:30' is a minute-precision time
+            FhirPathValue::String(s) => match PrecisionTime::parse(s) {
+                Some(t) => Ok(FhirPathValue::collection(vec![FhirPathValue::Time(t)])),
+                None => Ok(FhirPathValue::Empty),
+            },
+            _ => Ok(FhirPathValue::Empty),
+        }
+    }
+}
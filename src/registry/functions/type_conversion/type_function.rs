@@ -1,9 +1,7 @@
 //! type() function - returns the type of the value
 
-use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{
-    AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
-};
+use crate::model::{FhirPathValue, TypeInfo, type_specifier_of, value_type_info};
+use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
 use crate::registry::signature::FunctionSignature;
 use async_trait::async_trait;
 
@@ -39,124 +37,41 @@ impl AsyncFhirPathFunction for TypeFunction {
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        // Extract single item from collection according to spec
-        let input_item = match &context.input {
-            FhirPathValue::Collection(items) => {
-                if items.len() > 1 {
-                    return Err(FunctionError::EvaluationError {
-                        name: self.name().into(),
-                        message: "Input collection contains multiple items".into(),
-                    });
-                } else if items.is_empty() {
-                    return Ok(FhirPathValue::Empty);
-                } else {
-                    items.get(0).unwrap()
-                }
-            }
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
-            item => item,
+        let Some(input_item) = self.single_input(&context.input)? else {
+            return Ok(FhirPathValue::Empty);
         };
 
-        let type_info = match input_item {
-            FhirPathValue::String(_) => FhirPathValue::TypeInfoObject {
-                namespace: "System".into(),
-                name: "String".into(),
-            },
-            FhirPathValue::Integer(_) => FhirPathValue::TypeInfoObject {
-                namespace: "System".into(),
-                name: "Integer".into(),
-            },
-            FhirPathValue::Decimal(_) => FhirPathValue::TypeInfoObject {
-                namespace: "System".into(),
-                name: "Decimal".into(),
-            },
-            FhirPathValue::Boolean(_) => FhirPathValue::TypeInfoObject {
-                namespace: "System".into(),
-                name: "Boolean".into(),
-            },
-            FhirPathValue::Date(_) => FhirPathValue::TypeInfoObject {
-                namespace: "System".into(),
-                name: "Date".into(),
-            },
-            FhirPathValue::DateTime(_) => FhirPathValue::TypeInfoObject {
-                namespace: "System".into(),
-                name: "DateTime".into(),
-            },
-            FhirPathValue::Time(_) => FhirPathValue::TypeInfoObject {
-                namespace: "System".into(),
-                name: "Time".into(),
-            },
-            FhirPathValue::Quantity(_) => FhirPathValue::TypeInfoObject {
-                namespace: "System".into(),
-                name: "Quantity".into(),
-            },
-            FhirPathValue::Resource(resource) => {
-                // For FHIR resources, determine the appropriate type
-                let resource_type = resource.resource_type();
-
-                // Check if this is a FHIR primitive type by examining the value
-                if let Some(_json_value) = resource.as_json().as_bool() {
-                    // Boolean primitive in FHIR context
-                    FhirPathValue::TypeInfoObject {
-                        namespace: "FHIR".into(),
-                        name: "boolean".into(),
-                    }
-                } else if let Some(json_value) = resource.as_json().as_str() {
-                    // String-based FHIR primitive
-                    // Check if it looks like a UUID or URI
-                    let fhir_type = if json_value.starts_with("urn:uuid:") {
-                        "uuid"
-                    } else if json_value.starts_with("http://")
-                        || json_value.starts_with("https://")
-                        || json_value.starts_with("urn:")
+        let (namespace, name) = match input_item {
+            // Primitives read straight from FHIR JSON report their FHIR type
+            FhirPathValue::Resource(resource) if !resource.as_json().is_object() => {
+                let name = match resource.as_json() {
+                    serde_json::Value::Bool(_) => "boolean",
+                    serde_json::Value::String(s) if s.starts_with("urn:uuid:") => "uuid",
+                    serde_json::Value::String(s)
+                        if s.starts_with("http://")
+                            || s.starts_with("https://")
+                            || s.starts_with("urn:") =>
                     {
                         "uri"
-                    } else {
-                        "string"
-                    };
-                    FhirPathValue::TypeInfoObject {
-                        namespace: "FHIR".into(),
-                        name: fhir_type.into(),
-                    }
-                } else if let Some(_json_value) = resource.as_json().as_i64() {
-                    // Integer primitive in FHIR context
-                    FhirPathValue::TypeInfoObject {
-                        namespace: "FHIR".into(),
-                        name: "integer".into(),
-                    }
-                } else if let Some(_json_value) = resource.as_json().as_f64() {
-                    // Decimal primitive in FHIR context
-                    FhirPathValue::TypeInfoObject {
-                        namespace: "FHIR".into(),
-                        name: "decimal".into(),
-                    }
-                } else if resource_type.is_some() {
-                    // This is a complex FHIR resource with a resourceType
-                    FhirPathValue::TypeInfoObject {
-                        namespace: "FHIR".into(),
-                        name: resource_type.unwrap().into(),
                     }
-                } else {
-                    // Unknown resource type
-                    FhirPathValue::TypeInfoObject {
-                        namespace: "FHIR".into(),
-                        name: "Unknown".into(),
-                    }
-                }
+                    serde_json::Value::String(_) => "string",
+                    serde_json::Value::Number(n) if n.is_i64() => "integer",
+                    serde_json::Value::Number(_) => "decimal",
+                    _ => "Unknown",
+                };
+                ("FHIR".to_string(), name.to_string())
             }
-            FhirPathValue::Collection(_) => FhirPathValue::TypeInfoObject {
-                namespace: "System".into(),
-                name: "Collection".into(),
-            },
-            FhirPathValue::TypeInfoObject { .. } => FhirPathValue::TypeInfoObject {
-                namespace: "System".into(),
-                name: "TypeInfo".into(),
+            FhirPathValue::Collection(_) => ("System".to_string(), "Collection".to_string()),
+            FhirPathValue::JsonValue(_) => ("System".to_string(), "JsonValue".to_string()),
+            // Everything else is named the way `is` and `as` resolve it
+            item => match value_type_info(item) {
+                Some(type_info) => type_specifier_of(&type_info),
+                None => ("FHIR".to_string(), "Unknown".to_string()),
             },
-            FhirPathValue::JsonValue(_) => FhirPathValue::TypeInfoObject {
-                namespace: "System".into(),
-                name: "JsonValue".into(),
-            },
-            FhirPathValue::Empty => return Ok(FhirPathValue::Empty),
+        };
+        let type_info = FhirPathValue::TypeInfoObject {
+            namespace: namespace.into(),
+            name: name.into(),
         };
         Ok(type_info)
     }
@@ -1,17 +1,42 @@
 //! defineVariable() function - defines a variable in scope
 
+use crate::ast::ExpressionNode;
 use crate::model::{FhirPathValue, TypeInfo};
 use crate::registry::function::{
-    AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
+    EvaluationContext, FhirPathFunction, FunctionError, FunctionResult, LambdaEvaluationContext,
+    LambdaFunction,
 };
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
-use async_trait::async_trait;
 
 /// defineVariable() function - defines a variable in scope
+///
+/// The engine binds the variable while evaluating the rest of the expression,
+/// evaluating the value against the input at that point. Called on its own,
+/// the function only checks the name and returns its input, leaving the value
+/// unevaluated.
 pub struct DefineVariableFunction;
 
-#[async_trait]
-impl AsyncFhirPathFunction for DefineVariableFunction {
+impl DefineVariableFunction {
+    /// Check the variable name, returning whether it may be defined
+    fn accepts_name(&self, name: &FhirPathValue) -> FunctionResult<bool> {
+        let FhirPathValue::String(name) = name else {
+            return Err(FunctionError::InvalidArgumentType {
+                name: self.name().to_string(),
+                index: 0,
+                expected: "String".to_string(),
+                actual: format!("{name:?}"),
+            });
+        };
+
+        // Reserved variable names give empty as per spec
+        Ok(!matches!(
+            name.as_ref(),
+            "$this" | "$" | "$$" | "$resource" | "$total" | "context"
+        ))
+    }
+}
+
+impl FhirPathFunction for DefineVariableFunction {
     fn name(&self) -> &str {
         "defineVariable"
     }
@@ -24,22 +49,33 @@ impl AsyncFhirPathFunction for DefineVariableFunction {
                 "defineVariable",
                 vec![
                     ParameterInfo::required("name", TypeInfo::String),
-                    ParameterInfo::optional("value", TypeInfo::Any),
+                    ParameterInfo::optional("value", TypeInfo::Any).lazy(),
                 ],
                 TypeInfo::Any,
             )
         });
         &SIG
     }
-    async fn evaluate(
+    fn evaluate(
         &self,
         args: &[FhirPathValue],
         context: &EvaluationContext,
     ) -> FunctionResult<FhirPathValue> {
-        // defineVariable is handled specially in the engine via MethodCall processing
-        // This function implementation is a fallback that should not normally be called
-        // when used as a method call (.defineVariable())
+        self.validate_args(args)?;
+        if !self.accepts_name(&args[0])? {
+            return Ok(FhirPathValue::Empty);
+        }
+        Ok(context.input.clone())
+    }
+}
 
+#[async_trait::async_trait]
+impl LambdaFunction for DefineVariableFunction {
+    async fn evaluate_with_lambda(
+        &self,
+        args: &[ExpressionNode],
+        context: &LambdaEvaluationContext<'_>,
+    ) -> FunctionResult<FhirPathValue> {
         if args.is_empty() || args.len() > 2 {
             return Err(FunctionError::InvalidArity {
                 name: self.name().to_string(),
@@ -49,38 +85,13 @@ impl AsyncFhirPathFunction for DefineVariableFunction {
             });
         }
 
-        let var_name = match &args[0] {
-            FhirPathValue::String(s) => s.clone(),
-            _ => {
-                return Err(FunctionError::InvalidArgumentType {
-                    name: self.name().to_string(),
-                    index: 0,
-                    expected: "String".to_string(),
-                    actual: format!("{:?}", args[0]),
-                });
-            }
-        };
-
-        let _var_value = if args.len() == 2 {
-            args[1].clone()
-        } else {
-            // If no value provided, use current context
-            context.input.clone()
-        };
-
-        // Since this is a standalone function call (not method call),
-        // we cannot modify the context. Return the input unchanged.
-        // The actual variable definition happens in the engine's MethodCall handling.
-
-        // For validation, check if variable name is reserved
-        if matches!(
-            var_name.as_ref(),
-            "$this" | "$" | "$$" | "$resource" | "$total" | "context"
-        ) {
-            // Return empty for reserved variable names as per spec
+        let name = context
+            .eager_arg(0)
+            .cloned()
+            .unwrap_or(FhirPathValue::Empty);
+        if !self.accepts_name(&name)? {
             return Ok(FhirPathValue::Empty);
         }
-
-        Ok(context.input.clone())
+        Ok(context.context.input.clone())
     }
 }
@@ -1,15 +1,62 @@
 //! iif() function - conditional expression (if-then-else)
 
+use crate::ast::ExpressionNode;
 use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
+use crate::registry::function::{
+    EvaluationContext, FhirPathFunction, FunctionError, FunctionResult, LambdaEvaluationContext,
+    LambdaFunction,
+};
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
-use async_trait::async_trait;
 
 /// iif() function - conditional expression (if-then-else)
+///
+/// The criterion is evaluated once, and only the selected branch is evaluated
+/// afterwards, so the other branch may contain expressions that would fail.
+/// An empty criterion selects the otherwise branch; a criterion that is not a
+/// single Boolean, or an input of more than one item, is an error.
 pub struct IifFunction;
 
-#[async_trait]
-impl AsyncFhirPathFunction for IifFunction {
+impl IifFunction {
+    /// Check iif() can run on this input: empty or a single item
+    fn check_input(&self, input: &FhirPathValue) -> FunctionResult<()> {
+        match input {
+            FhirPathValue::Collection(items) if items.len() > 1 => {
+                Err(FunctionError::EvaluationError {
+                    name: self.name().to_string(),
+                    message: format!("input must be a single item, got {} items", items.len()),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Decide which branch the criterion selects
+    ///
+    /// Empty selects the otherwise branch; anything but a single Boolean is an error.
+    fn selects_true_branch(&self, criterion: &FhirPathValue) -> FunctionResult<bool> {
+        match criterion {
+            FhirPathValue::Boolean(b) => Ok(*b),
+            FhirPathValue::Empty => Ok(false),
+            FhirPathValue::Collection(items) if items.is_empty() => Ok(false),
+            FhirPathValue::Collection(items) if items.len() == 1 => {
+                self.selects_true_branch(items.first().unwrap())
+            }
+            FhirPathValue::Collection(items) => Err(FunctionError::EvaluationError {
+                name: self.name().to_string(),
+                message: format!(
+                    "criterion must be a single Boolean, got {} items",
+                    items.len()
+                ),
+            }),
+            other => Err(FunctionError::EvaluationError {
+                name: self.name().to_string(),
+                message: format!("criterion must be a Boolean, got {}", other.type_name()),
+            }),
+        }
+    }
+}
+
+impl FhirPathFunction for IifFunction {
     fn name(&self) -> &str {
         "iif"
     }
@@ -21,9 +68,9 @@ impl AsyncFhirPathFunction for IifFunction {
             FunctionSignature::new(
                 "iif",
                 vec![
-                    ParameterInfo::required("condition", TypeInfo::Any),
-                    ParameterInfo::required("true_value", TypeInfo::Any),
-                    ParameterInfo::optional("false_value", TypeInfo::Any),
+                    ParameterInfo::required("condition", TypeInfo::Any).lazy(),
+                    ParameterInfo::required("true_value", TypeInfo::Any).lazy(),
+                    ParameterInfo::optional("false_value", TypeInfo::Any).lazy(),
                 ],
                 TypeInfo::Any,
             )
@@ -32,62 +79,59 @@ impl AsyncFhirPathFunction for IifFunction {
     }
 
     fn documentation(&self) -> &str {
-        "An immediate if function that returns the `true_value` if the `condition` evaluates to `true`, or the `false_value` otherwise. If `false_value` is not provided and the condition is false, an empty collection is returned."
+        "An immediate if function that returns the `true_value` if the `condition` evaluates to `true`, or the `false_value` otherwise. An empty condition counts as false, and a condition that is not a Boolean is an error. If `false_value` is not provided and the condition is false, an empty collection is returned. Only the selected branch is evaluated. The branches need not share a type; the selected value is returned unchanged."
     }
-    async fn evaluate(
+    fn evaluate(
         &self,
         args: &[FhirPathValue],
         context: &EvaluationContext,
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        // Check context collection constraints first
-        // iif can only work on empty collections or single items
-        match &context.input {
-            FhirPathValue::Collection(coll) => {
-                if coll.len() > 1 {
-                    // Collections with more than one item return empty
-                    return Ok(FhirPathValue::Empty);
-                }
-                // Empty collections or single items continue
-            }
-            _ => {
-                // Single values are fine
-            }
+        self.check_input(&context.input)?;
+        if self.selects_true_branch(&args[0])? {
+            Ok(args[1].clone())
+        } else {
+            Ok(args.get(2).cloned().unwrap_or(FhirPathValue::Empty))
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LambdaFunction for IifFunction {
+    async fn evaluate_with_lambda(
+        &self,
+        args: &[ExpressionNode],
+        context: &LambdaEvaluationContext<'_>,
+    ) -> FunctionResult<FhirPathValue> {
+        if !(2..=3).contains(&args.len()) {
+            return Err(FunctionError::InvalidArity {
+                name: self.name().to_string(),
+                min: 2,
+                max: Some(3),
+                actual: args.len(),
+            });
         }
 
-        // Handle condition using FHIRPath truthiness rules
-        let condition = match &args[0] {
-            FhirPathValue::Boolean(b) => *b,
-            FhirPathValue::Empty => false,
-            FhirPathValue::Collection(items) => {
-                if items.len() > 1 {
-                    // Multi-item collections make the whole iif return empty
-                    return Ok(FhirPathValue::Empty);
-                } else if items.is_empty() {
-                    false // Empty collection is falsy
-                } else {
-                    // Single item collection - evaluate the item
-                    match items.first().unwrap() {
-                        FhirPathValue::Boolean(b) => *b,
-                        FhirPathValue::Integer(i) => *i != 0,
-                        FhirPathValue::Decimal(d) => !d.is_zero(),
-                        FhirPathValue::String(s) => !s.is_empty(),
-                        FhirPathValue::Empty => false,
-                        _ => true, // Most other types are truthy when present
-                    }
-                }
+        let input = &context.context.input;
+        self.check_input(input)?;
+
+        let criterion = (context.evaluator)(&args[0], input).await?;
+        let branch = if self.selects_true_branch(&criterion)? {
+            &args[1]
+        } else {
+            match args.get(2) {
+                Some(otherwise) => otherwise,
+                None => return Ok(FhirPathValue::Empty),
             }
-            FhirPathValue::Integer(i) => *i != 0,
-            FhirPathValue::Decimal(d) => !d.is_zero(),
-            FhirPathValue::String(s) => !s.is_empty(),
-            _ => true, // Most other types are truthy when present
         };
 
-        if condition {
-            Ok(args[1].clone())
-        } else {
-            Ok(args.get(2).cloned().unwrap_or(FhirPathValue::Empty))
+        // Single-item results are unwrapped, as for eagerly evaluated arguments
+        match (context.evaluator)(branch, input).await? {
+            FhirPathValue::Collection(items) if items.len() == 1 => {
+                Ok(items.first().unwrap().clone())
+            }
+            value => Ok(value),
         }
     }
 }
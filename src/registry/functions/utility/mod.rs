@@ -12,16 +12,16 @@ pub use define_variable::DefineVariableFunction;
 pub use has_value::HasValueFunction;
 pub use iif::IifFunction;
 pub use repeat::RepeatFunction;
-pub use trace::TraceFunction;
+pub use trace::{TraceFunction, TraceSink};
 
 use crate::registry::function::FunctionRegistry;
 
 /// Register all utility functions
 pub fn register_utility_functions(registry: &mut FunctionRegistry) {
     registry.register_async(ConformsToFunction::new());
-    registry.register_async(DefineVariableFunction);
+    registry.register_lambda(DefineVariableFunction);
     registry.register_async(HasValueFunction);
-    registry.register_async(IifFunction);
-    registry.register_async(RepeatFunction);
-    registry.register_async(TraceFunction);
+    registry.register_lambda(IifFunction);
+    registry.register_lambda(RepeatFunction);
+    registry.register_lambda(TraceFunction::new());
 }
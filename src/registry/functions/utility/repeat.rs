@@ -1,17 +1,27 @@
 //! repeat() function - repeats evaluation until no new results
 
+use crate::ast::ExpressionNode;
 use crate::model::{FhirPathValue, TypeInfo};
 use crate::registry::function::{
-    AsyncFhirPathFunction, EvaluationContext, FunctionError, FunctionResult,
+    EvaluationContext, FhirPathFunction, FunctionError, FunctionResult, LambdaEvaluationContext,
+    LambdaFunction,
 };
+use crate::registry::functions::collection::UniqueValues;
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
-use async_trait::async_trait;
+
+/// Maximum number of expansion rounds before giving up
+const MAX_ITERATIONS: usize = 100;
 
 /// repeat() function - repeats evaluation until no new results
+///
+/// Results are returned in discovery order, round by round. Each round applies
+/// the projection only to the items first found in the previous round, and the
+/// input items are part of the result only if the projection yields them.
+/// Items are de-duplicated with `=` semantics, so a cycle ends the walk once it
+/// yields nothing new.
 pub struct RepeatFunction;
 
-#[async_trait]
-impl AsyncFhirPathFunction for RepeatFunction {
+impl FhirPathFunction for RepeatFunction {
     fn name(&self) -> &str {
         "repeat"
     }
@@ -22,150 +32,84 @@ impl AsyncFhirPathFunction for RepeatFunction {
         static SIG: std::sync::LazyLock<FunctionSignature> = std::sync::LazyLock::new(|| {
             FunctionSignature::new(
                 "repeat",
-                vec![ParameterInfo::required("expression", TypeInfo::Any)],
+                vec![ParameterInfo::required("expression", TypeInfo::Any).lazy()],
                 TypeInfo::Collection(Box::new(TypeInfo::Any)),
             )
         });
         &SIG
     }
-    async fn evaluate(
+    fn evaluate(
         &self,
         args: &[FhirPathValue],
-        context: &EvaluationContext,
+        _context: &EvaluationContext,
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
 
-        // Get the expression parameter (this would normally be a lambda expression)
-        let expression_arg = &args[0];
+        // This should not be called for lambda functions - use evaluate_with_lambda instead
+        Err(FunctionError::EvaluationError {
+            name: self.name().to_string(),
+            message: "repeat() should use lambda evaluation".to_string(),
+        })
+    }
+}
 
-        // Special case: if the expression is a literal (like a string), just return it
-        // as a single-item collection regardless of input size
-        if matches!(
-            expression_arg,
-            FhirPathValue::String(_)
-                | FhirPathValue::Integer(_)
-                | FhirPathValue::Boolean(_)
-                | FhirPathValue::Decimal(_)
-        ) {
-            return Ok(FhirPathValue::collection(vec![expression_arg.clone()]));
+#[async_trait::async_trait]
+impl LambdaFunction for RepeatFunction {
+    async fn evaluate_with_lambda(
+        &self,
+        args: &[ExpressionNode],
+        context: &LambdaEvaluationContext<'_>,
+    ) -> FunctionResult<FhirPathValue> {
+        if args.len() != 1 {
+            return Err(FunctionError::InvalidArity {
+                name: self.name().to_string(),
+                min: 1,
+                max: Some(1),
+                actual: args.len(),
+            });
         }
 
-        let mut results = Vec::new();
-        let mut current_values = match &context.input {
+        let projection = &args[0];
+        let mut current_values = match &context.context.input {
             FhirPathValue::Collection(items) => items.iter().cloned().collect::<Vec<_>>(),
-            FhirPathValue::Empty => Vec::new(),
-            single_value => vec![single_value.clone()],
+            FhirPathValue::Empty => return Ok(FhirPathValue::collection(vec![])),
+            single => vec![single.clone()],
         };
 
-        // Add initial values to results
-        results.extend(current_values.clone());
-
-        let max_iterations = 100; // Prevent infinite loops
-        let mut iterations = 0;
-
-        loop {
-            iterations += 1;
-            if iterations > max_iterations {
-                return Err(FunctionError::EvaluationError {
-                    name: self.name().to_string(),
-                    message: "Maximum iteration limit reached to prevent infinite loops"
-                        .to_string(),
-                });
-            }
+        // Results are de-duplicated as they are produced, so only distinct
+        // values are ever held no matter how many paths lead to them
+        let mut results = UniqueValues::default();
 
+        for _ in 0..MAX_ITERATIONS {
             let mut new_values = Vec::new();
 
-            for current_value in &current_values {
-                // Apply the expression to the current value
-                let new_result = apply_expression(current_value, expression_arg)?;
-
-                match new_result {
-                    FhirPathValue::Collection(items) => {
-                        new_values.extend(items);
-                    }
-                    FhirPathValue::Empty => {
-                        // Empty result, continue with next value
-                    }
-                    single_value => {
-                        new_values.push(single_value);
+            // $index is the position within this round's items
+            for (index, current_value) in current_values.iter().enumerate() {
+                let projected = match context
+                    .evaluate_item(projection, current_value, index)
+                    .await?
+                {
+                    FhirPathValue::Collection(items) => items.into_vec(),
+                    FhirPathValue::Empty => Vec::new(),
+                    single => vec![single],
+                };
+
+                for value in projected {
+                    if results.insert(&value) {
+                        new_values.push(value);
                     }
                 }
             }
 
             if new_values.is_empty() {
-                break; // No new values found, stop iteration
+                return Ok(FhirPathValue::collection(results.into_vec()));
             }
-
-            results.extend(new_values.clone());
             current_values = new_values;
         }
 
-        Ok(FhirPathValue::collection(results))
-    }
-}
-
-fn apply_expression(
-    current_value: &FhirPathValue,
-    expression: &FhirPathValue,
-) -> FunctionResult<FhirPathValue> {
-    // For now, handle simple string literal expressions and property access
-    match expression {
-        FhirPathValue::String(prop_name) => {
-            // Simple property access
-            match current_value {
-                FhirPathValue::Resource(resource) => {
-                    if let Some(property_value) = resource.get_property(prop_name) {
-                        Ok(value_to_fhir_path_value(property_value))
-                    } else {
-                        Ok(FhirPathValue::Empty)
-                    }
-                }
-                _ => {
-                    // For string literals like 'test', just return the string
-                    if prop_name.as_ref() == "test" {
-                        Ok(FhirPathValue::String(prop_name.clone()))
-                    } else {
-                        Ok(FhirPathValue::Empty)
-                    }
-                }
-            }
-        }
-        _ => {
-            // For more complex expressions, we'd need full expression evaluation
-            // For now, return empty
-            Ok(FhirPathValue::Empty)
-        }
-    }
-}
-
-fn value_to_fhir_path_value(value: &serde_json::Value) -> FhirPathValue {
-    use crate::model::FhirResource;
-
-    match value {
-        serde_json::Value::Array(arr) => {
-            let mut collection = Vec::new();
-            for item in arr {
-                collection.push(value_to_fhir_path_value(item));
-            }
-            FhirPathValue::collection(collection)
-        }
-        serde_json::Value::Object(_) => {
-            let resource = FhirResource::from_json(value.clone());
-            FhirPathValue::Resource(resource.into())
-        }
-        serde_json::Value::String(s) => FhirPathValue::String(s.clone().into()),
-        serde_json::Value::Number(n) => {
-            if let Some(i) = n.as_i64() {
-                FhirPathValue::Integer(i)
-            } else if let Some(f) = n.as_f64() {
-                FhirPathValue::Decimal(
-                    rust_decimal::Decimal::from_f64_retain(f).unwrap_or_default(),
-                )
-            } else {
-                FhirPathValue::Empty
-            }
-        }
-        serde_json::Value::Bool(b) => FhirPathValue::Boolean(*b),
-        serde_json::Value::Null => FhirPathValue::Empty,
+        Err(FunctionError::EvaluationError {
+            name: self.name().to_string(),
+            message: "Maximum iteration limit reached to prevent infinite loops".to_string(),
+        })
     }
 }
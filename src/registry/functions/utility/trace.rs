@@ -1,15 +1,82 @@
 //! trace() function - debugging function that logs and returns input
 
+use crate::ast::ExpressionNode;
 use crate::model::{FhirPathValue, TypeInfo};
-use crate::registry::function::{AsyncFhirPathFunction, EvaluationContext, FunctionResult};
+use crate::registry::function::{
+    EvaluationContext, FhirPathFunction, FunctionResult, LambdaEvaluationContext, LambdaFunction,
+};
 use crate::registry::signature::{FunctionSignature, ParameterInfo};
-use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Receives the name and value of every `trace()` call
+pub type TraceSink = Arc<dyn Fn(&str, &FhirPathValue) + Send + Sync>;
+
+/// How many items the summary of a large traced collection shows
+const SUMMARY_ITEMS: usize = 5;
 
 /// trace() function - debugging function that logs and returns input
-pub struct TraceFunction;
+///
+/// The traced value is the input, or the results of the projection evaluated
+/// on each input item when one is given. It is handed to the [`TraceSink`] if
+/// one is attached and discarded otherwise.
+///
+/// Collections larger than the summary threshold, if one is set, are traced as
+/// a string giving their size and first few items, as in
+/// `"120 items: [1, 2, 3, 4, 5, ...]"`. The input always passes through whole.
+#[derive(Clone, Default)]
+pub struct TraceFunction {
+    sink: Option<TraceSink>,
+    summary_threshold: Option<usize>,
+}
+
+impl TraceFunction {
+    /// Create a trace() function that discards traced values
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send traced values to `sink`
+    pub fn with_sink(mut self, sink: TraceSink) -> Self {
+        self.sink = Some(sink);
+        self
+    }
 
-#[async_trait]
-impl AsyncFhirPathFunction for TraceFunction {
+    /// Trace collections of more than `max_items` items as a summary
+    pub fn with_summary_threshold(mut self, max_items: usize) -> Self {
+        self.summary_threshold = Some(max_items);
+        self
+    }
+
+    fn emit(&self, name: &FhirPathValue, value: &FhirPathValue) {
+        if let Some(sink) = &self.sink {
+            let name = match name {
+                FhirPathValue::String(s) => s.as_ref(),
+                _ => "trace",
+            };
+            match value {
+                FhirPathValue::Collection(items)
+                    if self.summary_threshold.is_some_and(|max| items.len() > max) =>
+                {
+                    sink(name, &summarize(items.iter()))
+                }
+                _ => sink(name, value),
+            }
+        }
+    }
+}
+
+/// Summarize a collection as its size and first few items
+fn summarize<'a>(items: impl ExactSizeIterator<Item = &'a FhirPathValue>) -> FhirPathValue {
+    let count = items.len();
+    let first: Vec<String> = items
+        .take(SUMMARY_ITEMS)
+        .map(|item| item.to_string())
+        .collect();
+    let more = if count > SUMMARY_ITEMS { ", ..." } else { "" };
+    FhirPathValue::String(format!("{count} items: [{}{more}]", first.join(", ")).into())
+}
+
+impl FhirPathFunction for TraceFunction {
     fn name(&self) -> &str {
         "trace"
     }
@@ -22,36 +89,69 @@ impl AsyncFhirPathFunction for TraceFunction {
                 "trace",
                 vec![
                     ParameterInfo::required("name", TypeInfo::String),
-                    ParameterInfo::optional("selector", TypeInfo::Any),
+                    ParameterInfo::optional("projection", TypeInfo::Any).lazy(),
                 ],
                 TypeInfo::Any,
             )
         });
         &SIG
     }
-    async fn evaluate(
+    fn evaluate(
         &self,
         args: &[FhirPathValue],
         context: &EvaluationContext,
     ) -> FunctionResult<FhirPathValue> {
         self.validate_args(args)?;
+        self.emit(&args[0], args.get(1).unwrap_or(&context.input));
 
-        let _name = match &args[0] {
-            FhirPathValue::String(s) => s.as_ref().to_string(),
-            _ => "trace".to_string(),
-        };
+        // trace() function always returns the original input (context), not the traced value
+        Ok(context.input.clone())
+    }
+}
 
-        // Check if there's a second argument (selector)
-        let _value_to_trace = if args.len() > 1 {
-            &args[1]
-        } else {
-            &context.input
+#[async_trait::async_trait]
+impl LambdaFunction for TraceFunction {
+    async fn evaluate_with_lambda(
+        &self,
+        args: &[ExpressionNode],
+        context: &LambdaEvaluationContext<'_>,
+    ) -> FunctionResult<FhirPathValue> {
+        // A one-item collection is traced and returned as its item
+        let input = match &context.context.input {
+            FhirPathValue::Collection(items) if items.len() == 1 => items.first().unwrap(),
+            input => input,
         };
+        let name = context
+            .eager_arg(0)
+            .cloned()
+            .unwrap_or(FhirPathValue::Empty);
 
-        // In a real implementation, this would log to appropriate output
-        // For debugging: eprintln!("{}: {:?}", name, value_to_trace);
+        let value = match args.get(1) {
+            Some(projection) => {
+                let items = match input {
+                    FhirPathValue::Collection(items) => items.iter().collect(),
+                    FhirPathValue::Empty => Vec::new(),
+                    item => vec![item],
+                };
 
-        // trace() function always returns the original input (context), not the traced value
-        Ok(context.input.clone())
+                // Evaluated per item like select(), with $this and $index bound
+                let mut projected = Vec::new();
+                for (index, item) in items.into_iter().enumerate() {
+                    match context.evaluate_item(projection, item, index).await? {
+                        FhirPathValue::Collection(values) => projected.extend(values),
+                        FhirPathValue::Empty => {}
+                        value => projected.push(value),
+                    }
+                }
+                match projected.len() {
+                    1 => projected.pop().unwrap(),
+                    _ => FhirPathValue::collection(projected),
+                }
+            }
+            None => input.clone(),
+        };
+        self.emit(&name, &value);
+
+        Ok(input.clone())
     }
 }
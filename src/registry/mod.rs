@@ -21,7 +21,7 @@ pub use compiled_signatures::{
 pub use fast_path::{FastPathFunction, FastPathRegistry};
 pub use function::{FhirPathFunction, FunctionRegistry};
 pub use operator::{Associativity, FhirPathOperator, OperatorRegistry};
-pub use signature::{FunctionSignature, OperatorSignature};
+pub use signature::{ArgumentEvaluation, FunctionSignature, OperatorSignature};
 
 /// Create a standard registry with all built-in functions and operators
 pub fn create_standard_registries() -> (FunctionRegistry, OperatorRegistry) {
@@ -3,13 +3,18 @@
 use super::super::operator::{
     Associativity, FhirPathOperator, OperatorError, OperatorRegistry, OperatorResult,
 };
-use crate::model::{FhirPathValue, TypeInfo};
+use crate::model::quantity::Quantity;
+use crate::model::{
+    FhirPathValue, PrecisionDate, PrecisionDateTime, PrecisionTime, TemporalPrecision, TypeInfo,
+};
 use crate::registry::signature::OperatorSignature;
+use chrono::{Months, NaiveDateTime, NaiveTime, TimeDelta, TimeZone};
 use octofhir_ucum;
+use rust_decimal::Decimal;
 use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 
-/// Time unit classification for date/datetime arithmetic
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Time unit classification for date/datetime arithmetic, coarsest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 enum TimeUnitType {
     Year,
     Month,
@@ -115,6 +120,187 @@ fn classify_time_unit(unit_str: &str) -> Option<TimeUnitType> {
     }
 }
 
+/// The unit of a time-valued quantity that can be added to a date or time
+///
+/// UCUM `'a'` and `'mo'` are averages (365.25 and 30.4375 days) rather than
+/// calendar years and months, so only the `year` and `month` keywords qualify.
+fn duration_unit(quantity: &Quantity) -> Option<TimeUnitType> {
+    let unit = classify_time_unit(quantity.unit.as_deref()?)?;
+    match unit {
+        TimeUnitType::Year | TimeUnitType::Month if !quantity.is_calendar_duration() => None,
+        _ => Some(unit),
+    }
+}
+
+/// The unit of the finest component specified at a precision
+fn precision_unit(precision: TemporalPrecision) -> TimeUnitType {
+    match precision {
+        TemporalPrecision::Year => TimeUnitType::Year,
+        TemporalPrecision::Month => TimeUnitType::Month,
+        TemporalPrecision::Day => TimeUnitType::Day,
+        TemporalPrecision::Hour => TimeUnitType::Hour,
+        TemporalPrecision::Minute => TimeUnitType::Minute,
+        TemporalPrecision::Second => TimeUnitType::Second,
+        TemporalPrecision::Millisecond => TimeUnitType::Millisecond,
+    }
+}
+
+/// Length of a unit in milliseconds, using the UCUM averages for years and months
+fn unit_milliseconds(unit: TimeUnitType) -> Decimal {
+    Decimal::from(match unit {
+        TimeUnitType::Year => 31_557_600_000_i64,
+        TimeUnitType::Month => 2_629_800_000,
+        TimeUnitType::Week => 604_800_000,
+        TimeUnitType::Day => 86_400_000,
+        TimeUnitType::Hour => 3_600_000,
+        TimeUnitType::Minute => 60_000,
+        TimeUnitType::Second => 1_000,
+        TimeUnitType::Millisecond => 1,
+    })
+}
+
+/// Whole number of units to add to a value of the given precision
+///
+/// Weeks are added as days and seconds as milliseconds, so `0.1 's'` keeps its
+/// fraction. A duration finer than the value's precision is converted to that
+/// precision, so `@2014 + 24 months` is `@2016`. Any remaining fraction is
+/// dropped.
+fn whole_duration(
+    amount: Decimal,
+    unit: TimeUnitType,
+    precision: TemporalPrecision,
+) -> Option<(i64, TimeUnitType)> {
+    let (amount, unit) = match unit {
+        TimeUnitType::Week => (amount * Decimal::from(7), TimeUnitType::Day),
+        TimeUnitType::Second => (amount * Decimal::from(1000), TimeUnitType::Millisecond),
+        unit => (amount, unit),
+    };
+    let target = precision_unit(precision);
+    let (amount, unit) = if unit > target {
+        (
+            amount * unit_milliseconds(unit) / unit_milliseconds(target),
+            target,
+        )
+    } else {
+        (amount, unit)
+    };
+    Some((amount.trunc().to_i64()?, unit))
+}
+
+/// A fixed-length duration, or `None` for years and months
+fn fixed_duration(amount: i64, unit: TimeUnitType) -> Option<TimeDelta> {
+    match unit {
+        TimeUnitType::Year | TimeUnitType::Month => None,
+        TimeUnitType::Week => TimeDelta::try_weeks(amount),
+        TimeUnitType::Day => TimeDelta::try_days(amount),
+        TimeUnitType::Hour => TimeDelta::try_hours(amount),
+        TimeUnitType::Minute => TimeDelta::try_minutes(amount),
+        TimeUnitType::Second => TimeDelta::try_seconds(amount),
+        TimeUnitType::Millisecond => TimeDelta::try_milliseconds(amount),
+    }
+}
+
+/// Shift a date and time by a whole number of units
+///
+/// Years and months follow the calendar, clamping to the last day of the
+/// month, so `@2012-02-29 + 1 year` is `@2013-02-28`.
+fn shift_datetime(
+    datetime: NaiveDateTime,
+    amount: i64,
+    unit: TimeUnitType,
+) -> Option<NaiveDateTime> {
+    let months = match unit {
+        TimeUnitType::Year => amount.checked_mul(12)?,
+        TimeUnitType::Month => amount,
+        _ => return datetime.checked_add_signed(fixed_duration(amount, unit)?),
+    };
+    let shift = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+    if months < 0 {
+        datetime.checked_sub_months(shift)
+    } else {
+        datetime.checked_add_months(shift)
+    }
+}
+
+/// Add (or with `negate`, subtract) a time-valued quantity to a date, date
+/// time or time
+///
+/// The result keeps the precision of the value. A quantity that is not a
+/// duration, such as `1 'cm'` or `1 'a'`, gives empty; dates and times only
+/// support date and time units respectively, and anything else is an error.
+fn add_duration(
+    operator: &str,
+    value: &FhirPathValue,
+    quantity: &Quantity,
+    negate: bool,
+) -> OperatorResult<FhirPathValue> {
+    let Some(unit) = duration_unit(quantity) else {
+        return Ok(FhirPathValue::Empty);
+    };
+    let amount = if negate {
+        -quantity.value
+    } else {
+        quantity.value
+    };
+    let unsupported = || OperatorError::EvaluationError {
+        operator: operator.to_string(),
+        message: format!(
+            "{} cannot be applied to a {}",
+            quantity.to_literal_string(),
+            value.type_name()
+        ),
+    };
+
+    let result = match value {
+        FhirPathValue::Date(date) => {
+            if unit > TimeUnitType::Day {
+                return Err(unsupported());
+            }
+            whole_duration(amount, unit, date.precision)
+                .and_then(|(amount, unit)| {
+                    shift_datetime(date.date.and_time(NaiveTime::MIN), amount, unit)
+                })
+                .map(|shifted| {
+                    FhirPathValue::Date(PrecisionDate::new(shifted.date(), date.precision))
+                })
+        }
+        FhirPathValue::DateTime(datetime) => whole_duration(amount, unit, datetime.precision)
+            .and_then(|(amount, unit)| {
+                shift_datetime(datetime.datetime.naive_local(), amount, unit)
+            })
+            .and_then(|shifted| {
+                datetime
+                    .datetime
+                    .offset()
+                    .from_local_datetime(&shifted)
+                    .single()
+            })
+            .map(|shifted| {
+                FhirPathValue::DateTime(PrecisionDateTime::new(
+                    shifted,
+                    datetime.precision,
+                    datetime.timezone_specified,
+                ))
+            }),
+        FhirPathValue::Time(time) => {
+            if unit < TimeUnitType::Hour {
+                return Err(unsupported());
+            }
+            // Times wrap around midnight
+            whole_duration(amount, unit, time.precision)
+                .and_then(|(amount, unit)| fixed_duration(amount, unit))
+                .map(|duration| {
+                    let (shifted, _) = time.time.overflowing_add_signed(duration);
+                    FhirPathValue::Time(PrecisionTime::new(shifted, time.precision))
+                })
+        }
+        _ => return Err(unsupported()),
+    };
+
+    // Results outside the supported range are empty
+    Ok(result.unwrap_or(FhirPathValue::Empty))
+}
+
 /// Addition operator (+)
 pub struct AddOperator;
 
@@ -225,19 +411,14 @@ impl FhirPathOperator for AddOperator {
                     }
                 }
             }
-            (FhirPathValue::Date(date), FhirPathValue::Quantity(quantity)) => {
-                self.add_date_quantity(date, quantity)?
-            }
+            (
+                FhirPathValue::Date(_) | FhirPathValue::DateTime(_) | FhirPathValue::Time(_),
+                FhirPathValue::Quantity(quantity),
+            ) => add_duration(self.symbol(), left, quantity, false)?,
             (FhirPathValue::Date(date), FhirPathValue::Integer(days)) => {
                 // Treat integer as days for date arithmetic
-                let new_date = *date + chrono::Duration::days(*days);
-                FhirPathValue::Date(new_date)
-            }
-            (FhirPathValue::DateTime(datetime), FhirPathValue::Quantity(quantity)) => {
-                self.add_datetime_quantity(datetime, quantity)?
-            }
-            (FhirPathValue::Time(time), FhirPathValue::Quantity(quantity)) => {
-                self.add_time_quantity(time, quantity)?
+                let new_date = date.date + chrono::Duration::days(*days);
+                FhirPathValue::Date(PrecisionDate::new(new_date, date.precision))
             }
             _ => {
                 return Err(OperatorError::InvalidOperandTypes {
@@ -264,183 +445,6 @@ impl FhirPathOperator for AddOperator {
     }
 }
 
-impl AddOperator {
-    /// Add a quantity to a date
-    fn add_date_quantity(
-        &self,
-        date: &chrono::NaiveDate,
-        quantity: &crate::model::quantity::Quantity,
-    ) -> OperatorResult<FhirPathValue> {
-        use chrono::Datelike;
-
-        let unit = quantity.unit.as_deref().unwrap_or("");
-        let amount = quantity.value;
-
-        let result_date = match classify_time_unit(unit) {
-            Some(TimeUnitType::Year) => {
-                let years = amount.to_i64().unwrap_or(0);
-                date.with_year((date.year() + years as i32).max(1))
-            }
-            Some(TimeUnitType::Month) => {
-                let months = amount.to_i64().unwrap_or(0);
-                let total_months = date.year() as i64 * 12 + date.month() as i64 - 1 + months;
-                let new_year = (total_months / 12) as i32;
-                let new_month = (total_months % 12 + 1) as u32;
-                date.with_year(new_year.max(1))
-                    .and_then(|d| d.with_month(new_month))
-            }
-            Some(TimeUnitType::Week) => {
-                // Handle fractional weeks by converting to days
-                let total_days = amount * rust_decimal::Decimal::from(7);
-                let days = total_days.to_i64().unwrap_or(0);
-                Some(*date + chrono::Duration::days(days))
-            }
-            Some(TimeUnitType::Day) => {
-                // Handle fractional days by truncating to whole days
-                // FHIRPath spec: fractional days are truncated for date arithmetic
-                let days = amount.to_i64().unwrap_or(0);
-                Some(*date + chrono::Duration::days(days))
-            }
-            _ => None, // Invalid unit for date arithmetic
-        };
-
-        match result_date {
-            Some(new_date) => Ok(FhirPathValue::String(
-                format!("@{}", new_date.format("%Y-%m-%d")).into(),
-            )),
-            None => Ok(FhirPathValue::Empty), // Invalid operation returns empty
-        }
-    }
-
-    /// Add a quantity to a datetime
-    fn add_datetime_quantity(
-        &self,
-        datetime: &chrono::DateTime<chrono::FixedOffset>,
-        quantity: &crate::model::quantity::Quantity,
-    ) -> OperatorResult<FhirPathValue> {
-        use chrono::Datelike;
-
-        let unit = quantity.unit.as_deref().unwrap_or("");
-        let amount = quantity.value;
-
-        let result_datetime = match classify_time_unit(unit) {
-            Some(TimeUnitType::Year) => {
-                let new_year = (datetime.year() as i64 + amount.to_i64().unwrap_or(0)) as i32;
-                datetime.with_year(new_year.max(1))
-            }
-            Some(TimeUnitType::Month) => {
-                let total_months = datetime.year() as i64 * 12 + datetime.month() as i64 - 1
-                    + amount.to_i64().unwrap_or(0);
-                let new_year = (total_months / 12) as i32;
-                let new_month = (total_months % 12 + 1) as u32;
-                datetime
-                    .with_year(new_year.max(1))
-                    .and_then(|d| d.with_month(new_month))
-            }
-            Some(TimeUnitType::Week) => {
-                // Handle fractional weeks by converting to total seconds
-                let total_seconds = amount * rust_decimal::Decimal::from(7 * 24 * 3600);
-                let seconds = total_seconds.to_i64().unwrap_or(0);
-                Some(*datetime + chrono::Duration::seconds(seconds))
-            }
-            Some(TimeUnitType::Day) => {
-                // Handle fractional days by converting to total seconds
-                let total_seconds = amount * rust_decimal::Decimal::from(24 * 3600);
-                let seconds = total_seconds.to_i64().unwrap_or(0);
-                Some(*datetime + chrono::Duration::seconds(seconds))
-            }
-            Some(TimeUnitType::Hour) => {
-                // Handle fractional hours by converting to total seconds
-                let total_seconds = amount * rust_decimal::Decimal::from(3600);
-                let seconds = total_seconds.to_i64().unwrap_or(0);
-                Some(*datetime + chrono::Duration::seconds(seconds))
-            }
-            Some(TimeUnitType::Minute) => {
-                // Handle fractional minutes by converting to total seconds
-                let total_seconds = amount * rust_decimal::Decimal::from(60);
-                let seconds = total_seconds.to_i64().unwrap_or(0);
-                Some(*datetime + chrono::Duration::seconds(seconds))
-            }
-            Some(TimeUnitType::Second) => {
-                let seconds = amount.to_i64().unwrap_or(0);
-                let millis = ((amount - rust_decimal::Decimal::from(seconds))
-                    * rust_decimal::Decimal::from(1000))
-                .to_i64()
-                .unwrap_or(0);
-                Some(
-                    *datetime
-                        + chrono::Duration::seconds(seconds)
-                        + chrono::Duration::milliseconds(millis),
-                )
-            }
-            Some(TimeUnitType::Millisecond) => {
-                Some(*datetime + chrono::Duration::milliseconds(amount.to_i64().unwrap_or(0)))
-            }
-            _ => None, // Invalid unit
-        };
-
-        match result_datetime {
-            Some(new_datetime) => Ok(FhirPathValue::String(
-                format!("@{}", new_datetime.format("%Y-%m-%dT%H:%M:%S%.3f%:z")).into(),
-            )),
-            None => Ok(FhirPathValue::Empty), // Invalid operation returns empty
-        }
-    }
-
-    /// Add a quantity to a time
-    fn add_time_quantity(
-        &self,
-        time: &chrono::NaiveTime,
-        quantity: &crate::model::quantity::Quantity,
-    ) -> OperatorResult<FhirPathValue> {
-        let unit = quantity.unit.as_deref().unwrap_or("");
-        let amount = quantity.value;
-
-        let result_time = match classify_time_unit(unit) {
-            Some(TimeUnitType::Hour) => {
-                let hours = amount.to_i64().unwrap_or(0) % 24; // Handle wrap-around
-                Some(
-                    time.overflowing_add_signed(chrono::Duration::hours(hours))
-                        .0,
-                )
-            }
-            Some(TimeUnitType::Minute) => Some(
-                time.overflowing_add_signed(chrono::Duration::minutes(
-                    amount.to_i64().unwrap_or(0),
-                ))
-                .0,
-            ),
-            Some(TimeUnitType::Second) => {
-                let seconds = amount.to_i64().unwrap_or(0);
-                let millis = ((amount - rust_decimal::Decimal::from(seconds))
-                    * rust_decimal::Decimal::from(1000))
-                .to_i64()
-                .unwrap_or(0);
-                Some(
-                    time.overflowing_add_signed(
-                        chrono::Duration::seconds(seconds) + chrono::Duration::milliseconds(millis),
-                    )
-                    .0,
-                )
-            }
-            Some(TimeUnitType::Millisecond) => Some(
-                time.overflowing_add_signed(chrono::Duration::milliseconds(
-                    amount.to_i64().unwrap_or(0),
-                ))
-                .0,
-            ),
-            _ => None, // Invalid unit for time arithmetic
-        };
-
-        match result_time {
-            Some(new_time) => Ok(FhirPathValue::String(
-                format!("@T{}", new_time.format("%H:%M:%S")).into(),
-            )),
-            None => Ok(FhirPathValue::Empty), // Invalid operation returns empty
-        }
-    }
-}
-
 /// Subtraction operator (-)
 pub struct SubtractOperator;
 
@@ -541,15 +545,10 @@ impl FhirPathOperator for SubtractOperator {
                     }
                 }
             }
-            (FhirPathValue::Date(date), FhirPathValue::Quantity(quantity)) => {
-                self.subtract_date_quantity(date, quantity)?
-            }
-            (FhirPathValue::DateTime(datetime), FhirPathValue::Quantity(quantity)) => {
-                self.subtract_datetime_quantity(datetime, quantity)?
-            }
-            (FhirPathValue::Time(time), FhirPathValue::Quantity(quantity)) => {
-                self.subtract_time_quantity(time, quantity)?
-            }
+            (
+                FhirPathValue::Date(_) | FhirPathValue::DateTime(_) | FhirPathValue::Time(_),
+                FhirPathValue::Quantity(quantity),
+            ) => add_duration(self.symbol(), left, quantity, true)?,
             (FhirPathValue::String(_), FhirPathValue::String(_)) => {
                 // String subtraction returns empty per FHIRPath spec
                 return Ok(FhirPathValue::Empty);
@@ -582,177 +581,6 @@ impl FhirPathOperator for SubtractOperator {
     }
 }
 
-impl SubtractOperator {
-    /// Subtract a quantity from a date
-    fn subtract_date_quantity(
-        &self,
-        date: &chrono::NaiveDate,
-        quantity: &crate::model::quantity::Quantity,
-    ) -> OperatorResult<FhirPathValue> {
-        use chrono::Datelike;
-
-        let unit = quantity.unit.as_deref().unwrap_or("");
-        let amount = quantity.value;
-
-        let result_date = match classify_time_unit(unit) {
-            Some(TimeUnitType::Year) => {
-                let years = amount.to_i64().unwrap_or(0);
-                date.with_year((date.year() - years as i32).max(1))
-            }
-            Some(TimeUnitType::Month) => {
-                let months = amount.to_i64().unwrap_or(0);
-                let total_months = date.year() as i64 * 12 + date.month() as i64 - 1 - months;
-                let new_year = (total_months / 12) as i32;
-                let new_month = (total_months % 12 + 1) as u32;
-                date.with_year(new_year.max(1))
-                    .and_then(|d| d.with_month(new_month))
-            }
-            Some(TimeUnitType::Week) => {
-                let total_days = amount * rust_decimal::Decimal::from(7);
-                let days = total_days.to_i64().unwrap_or(0);
-                Some(*date - chrono::Duration::days(days))
-            }
-            Some(TimeUnitType::Day) => {
-                let days = amount.to_i64().unwrap_or(0);
-                Some(*date - chrono::Duration::days(days))
-            }
-            _ => None, // Invalid unit for date arithmetic
-        };
-
-        match result_date {
-            Some(new_date) => Ok(FhirPathValue::String(
-                format!("@{}", new_date.format("%Y-%m-%d")).into(),
-            )),
-            None => Ok(FhirPathValue::Empty), // Invalid operation returns empty
-        }
-    }
-
-    /// Subtract a quantity from a datetime
-    fn subtract_datetime_quantity(
-        &self,
-        datetime: &chrono::DateTime<chrono::FixedOffset>,
-        quantity: &crate::model::quantity::Quantity,
-    ) -> OperatorResult<FhirPathValue> {
-        use chrono::Datelike;
-
-        let unit = quantity.unit.as_deref().unwrap_or("");
-        let amount = quantity.value;
-
-        let result_datetime = match classify_time_unit(unit) {
-            Some(TimeUnitType::Year) => {
-                let new_year = (datetime.year() as i64 - amount.to_i64().unwrap_or(0)) as i32;
-                datetime.with_year(new_year.max(1))
-            }
-            Some(TimeUnitType::Month) => {
-                let total_months = datetime.year() as i64 * 12 + datetime.month() as i64
-                    - 1
-                    - amount.to_i64().unwrap_or(0);
-                let new_year = (total_months / 12) as i32;
-                let new_month = (total_months % 12 + 1) as u32;
-                datetime
-                    .with_year(new_year.max(1))
-                    .and_then(|d| d.with_month(new_month))
-            }
-            Some(TimeUnitType::Week) => {
-                let total_seconds = amount * rust_decimal::Decimal::from(7 * 24 * 3600);
-                let seconds = total_seconds.to_i64().unwrap_or(0);
-                Some(*datetime - chrono::Duration::seconds(seconds))
-            }
-            Some(TimeUnitType::Day) => {
-                let total_seconds = amount * rust_decimal::Decimal::from(24 * 3600);
-                let seconds = total_seconds.to_i64().unwrap_or(0);
-                Some(*datetime - chrono::Duration::seconds(seconds))
-            }
-            Some(TimeUnitType::Hour) => {
-                let total_seconds = amount * rust_decimal::Decimal::from(3600);
-                let seconds = total_seconds.to_i64().unwrap_or(0);
-                Some(*datetime - chrono::Duration::seconds(seconds))
-            }
-            Some(TimeUnitType::Minute) => {
-                let total_seconds = amount * rust_decimal::Decimal::from(60);
-                let seconds = total_seconds.to_i64().unwrap_or(0);
-                Some(*datetime - chrono::Duration::seconds(seconds))
-            }
-            Some(TimeUnitType::Second) => {
-                let seconds = amount.to_i64().unwrap_or(0);
-                let millis = ((amount - rust_decimal::Decimal::from(seconds))
-                    * rust_decimal::Decimal::from(1000))
-                .to_i64()
-                .unwrap_or(0);
-                Some(
-                    *datetime
-                        - chrono::Duration::seconds(seconds)
-                        - chrono::Duration::milliseconds(millis),
-                )
-            }
-            Some(TimeUnitType::Millisecond) => {
-                Some(*datetime - chrono::Duration::milliseconds(amount.to_i64().unwrap_or(0)))
-            }
-            _ => None, // Invalid unit
-        };
-
-        match result_datetime {
-            Some(new_datetime) => Ok(FhirPathValue::String(
-                format!("@{}", new_datetime.format("%Y-%m-%dT%H:%M:%S%.3f%:z")).into(),
-            )),
-            None => Ok(FhirPathValue::Empty), // Invalid operation returns empty
-        }
-    }
-
-    /// Subtract a quantity from a time
-    fn subtract_time_quantity(
-        &self,
-        time: &chrono::NaiveTime,
-        quantity: &crate::model::quantity::Quantity,
-    ) -> OperatorResult<FhirPathValue> {
-        let unit = quantity.unit.as_deref().unwrap_or("");
-        let amount = quantity.value;
-
-        let result_time = match classify_time_unit(unit) {
-            Some(TimeUnitType::Hour) => {
-                let hours = amount.to_i64().unwrap_or(0);
-                Some(
-                    time.overflowing_sub_signed(chrono::Duration::hours(hours))
-                        .0,
-                )
-            }
-            Some(TimeUnitType::Minute) => Some(
-                time.overflowing_sub_signed(chrono::Duration::minutes(
-                    amount.to_i64().unwrap_or(0),
-                ))
-                .0,
-            ),
-            Some(TimeUnitType::Second) => {
-                let seconds = amount.to_i64().unwrap_or(0);
-                let millis = ((amount - rust_decimal::Decimal::from(seconds))
-                    * rust_decimal::Decimal::from(1000))
-                .to_i64()
-                .unwrap_or(0);
-                Some(
-                    time.overflowing_sub_signed(
-                        chrono::Duration::seconds(seconds) + chrono::Duration::milliseconds(millis),
-                    )
-                    .0,
-                )
-            }
-            Some(TimeUnitType::Millisecond) => Some(
-                time.overflowing_sub_signed(chrono::Duration::milliseconds(
-                    amount.to_i64().unwrap_or(0),
-                ))
-                .0,
-            ),
-            _ => None, // Invalid unit for time arithmetic
-        };
-
-        match result_time {
-            Some(new_time) => Ok(FhirPathValue::String(
-                format!("@T{}", new_time.format("%H:%M:%S")).into(),
-            )),
-            None => Ok(FhirPathValue::Empty), // Invalid operation returns empty
-        }
-    }
-}
-
 /// Multiplication operator (*)
 pub struct MultiplyOperator;
 
@@ -808,6 +636,18 @@ impl FhirPathOperator for MultiplyOperator {
                     TypeInfo::Decimal,
                     TypeInfo::Quantity,
                 ),
+                OperatorSignature::binary(
+                    "*",
+                    TypeInfo::Integer,
+                    TypeInfo::Quantity,
+                    TypeInfo::Quantity,
+                ),
+                OperatorSignature::binary(
+                    "*",
+                    TypeInfo::Decimal,
+                    TypeInfo::Quantity,
+                    TypeInfo::Quantity,
+                ),
                 OperatorSignature::binary(
                     "*",
                     TypeInfo::Quantity,
@@ -843,16 +683,13 @@ impl FhirPathOperator for MultiplyOperator {
             (FhirPathValue::Decimal(a), FhirPathValue::Integer(b)) => {
                 FhirPathValue::Decimal(a * rust_decimal::Decimal::from(*b))
             }
-            (FhirPathValue::Quantity(q), FhirPathValue::Integer(n)) => {
-                let result = crate::model::Quantity::new(
-                    q.value * rust_decimal::Decimal::from(*n),
-                    q.unit.clone(),
-                );
-                FhirPathValue::Quantity(result.into())
+            (FhirPathValue::Quantity(q), FhirPathValue::Integer(n))
+            | (FhirPathValue::Integer(n), FhirPathValue::Quantity(q)) => {
+                FhirPathValue::Quantity(q.multiply_scalar(rust_decimal::Decimal::from(*n)).into())
             }
-            (FhirPathValue::Quantity(q), FhirPathValue::Decimal(d)) => {
-                let result = crate::model::Quantity::new(q.value * d, q.unit.clone());
-                FhirPathValue::Quantity(result.into())
+            (FhirPathValue::Quantity(q), FhirPathValue::Decimal(d))
+            | (FhirPathValue::Decimal(d), FhirPathValue::Quantity(q)) => {
+                FhirPathValue::Quantity(q.multiply_scalar(*d).into())
             }
             (FhirPathValue::Quantity(q1), FhirPathValue::Quantity(q2)) => {
                 // Multiply two quantities with UCUM unit multiplication
@@ -1010,22 +847,15 @@ impl FhirPathOperator for DivideOperator {
                 FhirPathValue::Decimal(a / rust_decimal::Decimal::from(*b))
             }
             (FhirPathValue::Quantity(q), FhirPathValue::Integer(n)) => {
-                if *n == 0 {
-                    return Ok(FhirPathValue::Empty);
-                }
-                let result = crate::model::Quantity::new(
-                    q.value / rust_decimal::Decimal::from(*n),
-                    q.unit.clone(),
-                );
-                FhirPathValue::Quantity(result.into())
-            }
-            (FhirPathValue::Quantity(q), FhirPathValue::Decimal(d)) => {
-                if d.is_zero() {
-                    return Ok(FhirPathValue::Empty);
+                match q.divide_scalar(rust_decimal::Decimal::from(*n)) {
+                    Some(result) => FhirPathValue::Quantity(result.into()),
+                    None => return Ok(FhirPathValue::Empty),
                 }
-                let result = crate::model::Quantity::new(q.value / d, q.unit.clone());
-                FhirPathValue::Quantity(result.into())
             }
+            (FhirPathValue::Quantity(q), FhirPathValue::Decimal(d)) => match q.divide_scalar(*d) {
+                Some(result) => FhirPathValue::Quantity(result.into()),
+                None => return Ok(FhirPathValue::Empty),
+            },
             (FhirPathValue::Quantity(q1), FhirPathValue::Quantity(q2)) => {
                 if q2.value.is_zero() {
                     return Ok(FhirPathValue::Empty);
@@ -2,6 +2,7 @@
 
 use super::super::operator::{Associativity, FhirPathOperator, OperatorRegistry, OperatorResult};
 use crate::model::{FhirPathValue, TypeInfo};
+use crate::registry::functions::collection::union_of;
 use crate::registry::signature::OperatorSignature;
 
 /// Union operator (|)
@@ -37,9 +38,7 @@ impl FhirPathOperator for UnionOperator {
         left: &FhirPathValue,
         right: &FhirPathValue,
     ) -> OperatorResult<FhirPathValue> {
-        let mut result = left.clone().to_collection();
-        result.extend(right.clone().to_collection());
-        Ok(FhirPathValue::Collection(result))
+        Ok(union_of(left.clone(), right.clone()))
     }
 }
 
@@ -100,10 +99,12 @@ impl FhirPathOperator for InOperator {
             return Ok(FhirPathValue::Empty);
         }
 
-        // Single item test
+        // Single item test, with `=` semantics
         if let Some(single_item) = left_collection.first() {
             Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
-                right_collection.contains(single_item),
+                right_collection
+                    .iter()
+                    .any(|item| item.fhirpath_eq(single_item)),
             )]))
         } else {
             Ok(FhirPathValue::Empty)
@@ -163,9 +164,10 @@ impl FhirPathOperator for ContainsOperator {
             return Ok(FhirPathValue::Empty);
         }
 
+        // Membership uses `=` semantics
         let left_collection = left.clone().to_collection();
         Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
-            left_collection.contains(right),
+            left_collection.iter().any(|item| item.fhirpath_eq(right)),
         )]))
     }
 }
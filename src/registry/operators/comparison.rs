@@ -3,9 +3,12 @@
 use super::super::operator::{
     Associativity, FhirPathOperator, OperatorError, OperatorRegistry, OperatorResult,
 };
-use crate::model::{FhirPathValue, TypeInfo};
+use crate::model::{Collection, FhirPathValue, Quantity, TypeInfo};
 use crate::registry::signature::OperatorSignature;
 use rust_decimal::Decimal;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::str::FromStr;
 
 /// Equality operator (=)
 pub struct EqualOperator;
@@ -53,70 +56,27 @@ impl FhirPathOperator for EqualOperator {
             (false, false) => {} // Continue with normal comparison
         }
 
-        // FHIRPath equality with type coercion support
-        let result = match (left, right) {
-            (FhirPathValue::Boolean(l), FhirPathValue::Boolean(r)) => l == r,
-            (FhirPathValue::Integer(l), FhirPathValue::Integer(r)) => l == r,
-            (FhirPathValue::Decimal(l), FhirPathValue::Decimal(r)) => l == r,
-            (FhirPathValue::String(l), FhirPathValue::String(r)) => l == r,
-            (FhirPathValue::Date(l), FhirPathValue::Date(r)) => l == r,
-            (FhirPathValue::DateTime(l), FhirPathValue::DateTime(r)) => {
-                // Per FHIRPath spec: DateTime comparison with different precision returns empty
-                // This handles ambiguous timezone cases like @2012-04-15T15:00:00Z vs @2012-04-15T10:00:00
-                // where they represent different times but comparison should return empty due to precision mismatch
-                if l != r {
-                    // Check if this is a case where precision differs significantly
-                    // If times are 5+ hours apart, it's likely a timezone precision issue
-                    let time_diff = (l.timestamp() - r.timestamp()).abs();
-                    if time_diff >= 5 * 3600 {
-                        // 5 hours in seconds
-                        return Ok(FhirPathValue::Empty);
-                    }
-                }
-                l == r
-            }
-            (FhirPathValue::Date(_), FhirPathValue::DateTime(_)) => {
-                // Per FHIRPath spec: different precision levels return empty
-                return Ok(FhirPathValue::Empty);
-            }
-            (FhirPathValue::DateTime(_), FhirPathValue::Date(_)) => {
-                // Per FHIRPath spec: different precision levels return empty
-                return Ok(FhirPathValue::Empty);
-            }
-            (FhirPathValue::Time(l), FhirPathValue::Time(r)) => l == r,
-
-            // Cross-type numeric comparisons (Integer vs Decimal)
-            (FhirPathValue::Integer(l), FhirPathValue::Decimal(r)) => Decimal::from(*l) == *r,
-            (FhirPathValue::Decimal(l), FhirPathValue::Integer(r)) => *l == Decimal::from(*r),
-
-            // Quantity comparisons with unit conversion
-            (FhirPathValue::Quantity(q1), FhirPathValue::Quantity(q2)) => {
-                self.compare_quantities_equal(q1, q2)?
-            }
-
-            // Resource comparisons - compare JSON representations
-            (FhirPathValue::Resource(r1), FhirPathValue::Resource(r2)) => {
-                r1.to_json() == r2.to_json()
-            }
-
+        match (left, right) {
             (FhirPathValue::Collection(l), FhirPathValue::Collection(r)) => {
-                l.len() == r.len()
+                let result = l.len() == r.len()
                     && l.iter()
                         .zip(r.iter())
                         .all(|(a, b)| match self.compare_values_equal(a, b) {
                             Ok(FhirPathValue::Boolean(b)) => b,
                             _ => false,
-                        })
+                        });
+                Ok(FhirPathValue::Boolean(result))
             }
-            _ => false,
-        };
-
-        Ok(FhirPathValue::Boolean(result))
+            _ => self.compare_values_equal(left, right),
+        }
     }
 }
 
 impl EqualOperator {
     /// Compare two values for equality without recursion
+    ///
+    /// Collections are not equal to single values, nor to each other here;
+    /// `=` compares collections item by item with this.
     fn compare_values_equal(
         &self,
         left: &FhirPathValue,
@@ -127,31 +87,19 @@ impl EqualOperator {
             (FhirPathValue::Integer(l), FhirPathValue::Integer(r)) => l == r,
             (FhirPathValue::Decimal(l), FhirPathValue::Decimal(r)) => l == r,
             (FhirPathValue::String(l), FhirPathValue::String(r)) => l == r,
-            (FhirPathValue::Date(l), FhirPathValue::Date(r)) => l == r,
-            (FhirPathValue::DateTime(l), FhirPathValue::DateTime(r)) => {
-                // Per FHIRPath spec: DateTime comparison with different precision returns empty
-                // This handles ambiguous timezone cases like @2012-04-15T15:00:00Z vs @2012-04-15T10:00:00
-                // where they represent different times but comparison should return empty due to precision mismatch
-                if l != r {
-                    // Check if this is a case where precision differs significantly
-                    // If times are 5+ hours apart, it's likely a timezone precision issue
-                    let time_diff = (l.timestamp() - r.timestamp()).abs();
-                    if time_diff >= 5 * 3600 {
-                        // 5 hours in seconds
-                        return Ok(FhirPathValue::Empty);
-                    }
-                }
-                l == r
-            }
-            (FhirPathValue::Date(_), FhirPathValue::DateTime(_)) => {
-                // Per FHIRPath spec: different precision levels return empty
-                return Ok(FhirPathValue::Empty);
-            }
-            (FhirPathValue::DateTime(_), FhirPathValue::Date(_)) => {
-                // Per FHIRPath spec: different precision levels return empty
-                return Ok(FhirPathValue::Empty);
-            }
-            (FhirPathValue::Time(l), FhirPathValue::Time(r)) => l == r,
+            (
+                FhirPathValue::Date(_) | FhirPathValue::DateTime(_),
+                FhirPathValue::Date(_) | FhirPathValue::DateTime(_),
+            ) => match compare_dates(left, right) {
+                Some(ordering) => ordering.is_eq(),
+                // Differing precision makes the result unknown
+                None => return Ok(FhirPathValue::Empty),
+            },
+            (FhirPathValue::Time(l), FhirPathValue::Time(r)) => match l.compare(r) {
+                Some(ordering) => ordering.is_eq(),
+                // Differing precision makes the result unknown
+                None => return Ok(FhirPathValue::Empty),
+            },
 
             // Cross-type numeric comparisons (Integer vs Decimal)
             (FhirPathValue::Integer(l), FhirPathValue::Decimal(r)) => Decimal::from(*l) == *r,
@@ -159,14 +107,44 @@ impl EqualOperator {
 
             // Quantity comparisons with unit conversion
             (FhirPathValue::Quantity(q1), FhirPathValue::Quantity(q2)) => {
+                if !q1.is_comparable_with(q2) {
+                    return Ok(FhirPathValue::Empty);
+                }
                 self.compare_quantities_equal(q1, q2)?
             }
 
-            // Resource comparisons - compare JSON representations
-            (FhirPathValue::Resource(r1), FhirPathValue::Resource(r2)) => {
-                r1.to_json() == r2.to_json()
+            // Integers and decimals convert implicitly to quantities with unit '1'
+            (FhirPathValue::Integer(n), FhirPathValue::Quantity(q))
+            | (FhirPathValue::Quantity(q), FhirPathValue::Integer(n)) => {
+                self.compare_quantities_equal(&implicit_quantity(Decimal::from(*n)), q)?
+            }
+            (FhirPathValue::Decimal(d), FhirPathValue::Quantity(q))
+            | (FhirPathValue::Quantity(q), FhirPathValue::Decimal(d)) => {
+                self.compare_quantities_equal(&implicit_quantity(*d), q)?
             }
 
+            // FHIR Quantity elements compare as System quantities
+            (
+                element @ (FhirPathValue::Resource(_) | FhirPathValue::JsonValue(_)),
+                FhirPathValue::Quantity(q),
+            )
+            | (
+                FhirPathValue::Quantity(q),
+                element @ (FhirPathValue::Resource(_) | FhirPathValue::JsonValue(_)),
+            ) => match element.quantity_element() {
+                Some(element) => self.compare_quantities_equal(&element, q)?,
+                None => false,
+            },
+
+            // Resources and complex elements compare by their JSON content
+            (
+                FhirPathValue::Resource(_) | FhirPathValue::JsonValue(_),
+                FhirPathValue::Resource(_) | FhirPathValue::JsonValue(_),
+            ) => match (element_json(left), element_json(right)) {
+                (Some(l), Some(r)) => json_equal(l, r),
+                _ => false,
+            },
+
             // For collections, they are not equal to non-collections
             (FhirPathValue::Collection(_), _) | (_, FhirPathValue::Collection(_)) => false,
 
@@ -191,6 +169,77 @@ impl EqualOperator {
     }
 }
 
+/// Order two dates or date times honouring precision
+///
+/// A date compares as a date time specified to the same precision, so
+/// `@2012-04-15 = @2012-04-15T10:00:00` is empty.
+fn compare_dates(left: &FhirPathValue, right: &FhirPathValue) -> Option<Ordering> {
+    let as_datetime = |value: &FhirPathValue| match value {
+        FhirPathValue::Date(date) => Some(date.to_datetime()),
+        FhirPathValue::DateTime(datetime) => Some(*datetime),
+        _ => None,
+    };
+    as_datetime(left)?.compare(&as_datetime(right)?)
+}
+
+/// Quantity an Integer or Decimal converts to when compared with a Quantity
+pub(crate) fn implicit_quantity(value: Decimal) -> Quantity {
+    Quantity::new(value, Some("1".to_string()))
+}
+
+/// The JSON behind a resource or complex element
+pub(crate) fn element_json(value: &FhirPathValue) -> Option<&Value> {
+    match value {
+        FhirPathValue::Resource(resource) => Some(resource.as_json()),
+        FhirPathValue::JsonValue(json) => Some(json.as_json()),
+        _ => None,
+    }
+}
+
+/// Deep equality of JSON values, comparing numbers by value
+///
+/// `serde_json` keeps integers and floats apart, so `1.0` and `1` would differ
+/// under `PartialEq`; here they are equal, as they are in FHIRPath. Object key
+/// order does not matter, array order does.
+pub(crate) fn json_equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => {
+            l == r
+                || matches!(
+                    (json_number(l), json_number(r)),
+                    (Some(l), Some(r)) if l == r
+                )
+        }
+        (Value::Array(l), Value::Array(r)) => {
+            l.len() == r.len() && l.iter().zip(r).all(|(l, r)| json_equal(l, r))
+        }
+        (Value::Object(l), Value::Object(r)) => {
+            l.len() == r.len()
+                && l.iter()
+                    .all(|(key, l)| r.get(key).is_some_and(|r| json_equal(l, r)))
+        }
+        _ => left == right,
+    }
+}
+
+pub(crate) fn json_number(number: &serde_json::Number) -> Option<Decimal> {
+    let text = number.to_string();
+    Decimal::from_str(&text)
+        .or_else(|_| Decimal::from_scientific(&text))
+        .ok()
+}
+
+/// Check whether two items are equal under FHIRPath `=` semantics
+///
+/// Backs [`FhirPathValue::fhirpath_eq`]. An unknown (empty) comparison counts
+/// as not equal.
+pub(crate) fn values_equal(left: &FhirPathValue, right: &FhirPathValue) -> bool {
+    matches!(
+        EqualOperator.compare_values_equal(left, right),
+        Ok(FhirPathValue::Boolean(true))
+    )
+}
+
 /// Not equal operator (!=)
 pub struct NotEqualOperator;
 
@@ -332,6 +381,7 @@ impl FhirPathOperator for LessThanOperator {
         if left.is_empty() || right.is_empty() {
             return Ok(FhirPathValue::Empty);
         }
+        require_single_items(self.symbol(), left, right)?;
 
         let result = match (left, right) {
             (FhirPathValue::Integer(a), FhirPathValue::Integer(b)) => a < b,
@@ -347,23 +397,17 @@ impl FhirPathOperator for LessThanOperator {
                 a_decimal < *b
             }
             (FhirPathValue::String(a), FhirPathValue::String(b)) => a < b,
-            (FhirPathValue::Date(a), FhirPathValue::Date(b)) => a < b,
-            (FhirPathValue::DateTime(a), FhirPathValue::DateTime(b)) => a < b,
-            (FhirPathValue::Date(a), FhirPathValue::DateTime(b)) => {
-                // Convert date to datetime at start of day for comparison
-                use chrono::{NaiveTime, TimeZone, Utc};
-                let start_of_day = a.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-                let date_as_datetime = Utc.from_utc_datetime(&start_of_day);
-                date_as_datetime < *b
-            }
-            (FhirPathValue::DateTime(a), FhirPathValue::Date(b)) => {
-                // Convert date to datetime at start of day for comparison
-                use chrono::{NaiveTime, TimeZone, Utc};
-                let start_of_day = b.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-                let date_as_datetime = Utc.from_utc_datetime(&start_of_day);
-                *a < date_as_datetime
-            }
-            (FhirPathValue::Time(a), FhirPathValue::Time(b)) => a < b,
+            (
+                FhirPathValue::Date(_) | FhirPathValue::DateTime(_),
+                FhirPathValue::Date(_) | FhirPathValue::DateTime(_),
+            ) => match compare_dates(left, right) {
+                Some(ordering) => ordering.is_lt(),
+                None => return Ok(FhirPathValue::Empty),
+            },
+            (FhirPathValue::Time(a), FhirPathValue::Time(b)) => match a.compare(b) {
+                Some(ordering) => ordering.is_lt(),
+                None => return Ok(FhirPathValue::Empty),
+            },
             (FhirPathValue::Quantity(a), FhirPathValue::Quantity(b)) => {
                 // For quantity comparison, check if units are compatible
                 if a.has_compatible_dimensions(b) {
@@ -466,6 +510,7 @@ impl FhirPathOperator for LessThanOrEqualOperator {
         if left.is_empty() || right.is_empty() {
             return Ok(FhirPathValue::Empty);
         }
+        require_single_items(self.symbol(), left, right)?;
 
         let result = match (left, right) {
             (FhirPathValue::Integer(a), FhirPathValue::Integer(b)) => a <= b,
@@ -481,23 +526,17 @@ impl FhirPathOperator for LessThanOrEqualOperator {
                 a_decimal <= *b
             }
             (FhirPathValue::String(a), FhirPathValue::String(b)) => a <= b,
-            (FhirPathValue::Date(a), FhirPathValue::Date(b)) => a <= b,
-            (FhirPathValue::DateTime(a), FhirPathValue::DateTime(b)) => a <= b,
-            (FhirPathValue::Date(a), FhirPathValue::DateTime(b)) => {
-                // Convert date to datetime at start of day for comparison
-                use chrono::{NaiveTime, TimeZone, Utc};
-                let start_of_day = a.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-                let date_as_datetime = Utc.from_utc_datetime(&start_of_day);
-                date_as_datetime <= *b
-            }
-            (FhirPathValue::DateTime(a), FhirPathValue::Date(b)) => {
-                // Convert date to datetime at start of day for comparison
-                use chrono::{NaiveTime, TimeZone, Utc};
-                let start_of_day = b.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-                let date_as_datetime = Utc.from_utc_datetime(&start_of_day);
-                *a <= date_as_datetime
-            }
-            (FhirPathValue::Time(a), FhirPathValue::Time(b)) => a <= b,
+            (
+                FhirPathValue::Date(_) | FhirPathValue::DateTime(_),
+                FhirPathValue::Date(_) | FhirPathValue::DateTime(_),
+            ) => match compare_dates(left, right) {
+                Some(ordering) => ordering.is_le(),
+                None => return Ok(FhirPathValue::Empty),
+            },
+            (FhirPathValue::Time(a), FhirPathValue::Time(b)) => match a.compare(b) {
+                Some(ordering) => ordering.is_le(),
+                None => return Ok(FhirPathValue::Empty),
+            },
             (FhirPathValue::Quantity(a), FhirPathValue::Quantity(b)) => {
                 // For quantity comparison, check if units are compatible
                 if a.has_compatible_dimensions(b) {
@@ -599,6 +638,7 @@ impl FhirPathOperator for GreaterThanOperator {
         if left.is_empty() || right.is_empty() {
             return Ok(FhirPathValue::Empty);
         }
+        require_single_items(self.symbol(), left, right)?;
 
         let result = match (left, right) {
             (FhirPathValue::Integer(a), FhirPathValue::Integer(b)) => a > b,
@@ -614,23 +654,17 @@ impl FhirPathOperator for GreaterThanOperator {
                 a_decimal > *b
             }
             (FhirPathValue::String(a), FhirPathValue::String(b)) => a > b,
-            (FhirPathValue::Date(a), FhirPathValue::Date(b)) => a > b,
-            (FhirPathValue::DateTime(a), FhirPathValue::DateTime(b)) => a > b,
-            (FhirPathValue::Date(a), FhirPathValue::DateTime(b)) => {
-                // Convert date to datetime at start of day for comparison
-                use chrono::{NaiveTime, TimeZone, Utc};
-                let start_of_day = a.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-                let date_as_datetime = Utc.from_utc_datetime(&start_of_day);
-                date_as_datetime > *b
-            }
-            (FhirPathValue::DateTime(a), FhirPathValue::Date(b)) => {
-                // Convert date to datetime at start of day for comparison
-                use chrono::{NaiveTime, TimeZone, Utc};
-                let start_of_day = b.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-                let date_as_datetime = Utc.from_utc_datetime(&start_of_day);
-                *a > date_as_datetime
-            }
-            (FhirPathValue::Time(a), FhirPathValue::Time(b)) => a > b,
+            (
+                FhirPathValue::Date(_) | FhirPathValue::DateTime(_),
+                FhirPathValue::Date(_) | FhirPathValue::DateTime(_),
+            ) => match compare_dates(left, right) {
+                Some(ordering) => ordering.is_gt(),
+                None => return Ok(FhirPathValue::Empty),
+            },
+            (FhirPathValue::Time(a), FhirPathValue::Time(b)) => match a.compare(b) {
+                Some(ordering) => ordering.is_gt(),
+                None => return Ok(FhirPathValue::Empty),
+            },
             (FhirPathValue::Quantity(a), FhirPathValue::Quantity(b)) => {
                 // For quantity comparison, check if units are compatible
                 if a.has_compatible_dimensions(b) {
@@ -732,6 +766,7 @@ impl FhirPathOperator for GreaterThanOrEqualOperator {
         if left.is_empty() || right.is_empty() {
             return Ok(FhirPathValue::Empty);
         }
+        require_single_items(self.symbol(), left, right)?;
 
         let result = match (left, right) {
             (FhirPathValue::Integer(a), FhirPathValue::Integer(b)) => a >= b,
@@ -747,23 +782,17 @@ impl FhirPathOperator for GreaterThanOrEqualOperator {
                 a_decimal >= *b
             }
             (FhirPathValue::String(a), FhirPathValue::String(b)) => a >= b,
-            (FhirPathValue::Date(a), FhirPathValue::Date(b)) => a >= b,
-            (FhirPathValue::DateTime(a), FhirPathValue::DateTime(b)) => a >= b,
-            (FhirPathValue::Date(a), FhirPathValue::DateTime(b)) => {
-                // Convert date to datetime at start of day for comparison
-                use chrono::{NaiveTime, TimeZone, Utc};
-                let start_of_day = a.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-                let date_as_datetime = Utc.from_utc_datetime(&start_of_day);
-                date_as_datetime >= *b
-            }
-            (FhirPathValue::DateTime(a), FhirPathValue::Date(b)) => {
-                // Convert date to datetime at start of day for comparison
-                use chrono::{NaiveTime, TimeZone, Utc};
-                let start_of_day = b.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-                let date_as_datetime = Utc.from_utc_datetime(&start_of_day);
-                *a >= date_as_datetime
-            }
-            (FhirPathValue::Time(a), FhirPathValue::Time(b)) => a >= b,
+            (
+                FhirPathValue::Date(_) | FhirPathValue::DateTime(_),
+                FhirPathValue::Date(_) | FhirPathValue::DateTime(_),
+            ) => match compare_dates(left, right) {
+                Some(ordering) => ordering.is_ge(),
+                None => return Ok(FhirPathValue::Empty),
+            },
+            (FhirPathValue::Time(a), FhirPathValue::Time(b)) => match a.compare(b) {
+                Some(ordering) => ordering.is_ge(),
+                None => return Ok(FhirPathValue::Empty),
+            },
             (FhirPathValue::Quantity(a), FhirPathValue::Quantity(b)) => {
                 // For quantity comparison, check if units are compatible
                 if a.has_compatible_dimensions(b) {
@@ -827,6 +856,12 @@ impl FhirPathOperator for EquivalentOperator {
         // For strings, it should be case-insensitive (but not implemented yet)
 
         let result = match (left, right) {
+            // Collections are equivalent when their items pair up in any order
+            _ if left.is_empty() && right.is_empty() => true,
+            (FhirPathValue::Collection(l), FhirPathValue::Collection(r)) => {
+                self.collections_equivalent(l, r)?
+            }
+            (FhirPathValue::Collection(_), _) | (_, FhirPathValue::Collection(_)) => false,
             // Handle quantities with unit conversion (same as equality)
             (FhirPathValue::Quantity(q1), FhirPathValue::Quantity(q2)) => {
                 self.compare_quantities_equivalent(q1, q2)?
@@ -854,6 +889,34 @@ impl FhirPathOperator for EquivalentOperator {
 }
 
 impl EquivalentOperator {
+    /// Whether every item of `left` is equivalent to a different item of `right`
+    fn collections_equivalent(
+        &self,
+        left: &Collection,
+        right: &Collection,
+    ) -> OperatorResult<bool> {
+        if left.len() != right.len() {
+            return Ok(false);
+        }
+        let mut unmatched: Vec<&FhirPathValue> = right.iter().collect();
+        for item in left.iter() {
+            let mut matched = None;
+            for (index, candidate) in unmatched.iter().enumerate() {
+                if self.evaluate_binary(item, candidate)? == FhirPathValue::Boolean(true) {
+                    matched = Some(index);
+                    break;
+                }
+            }
+            match matched {
+                Some(index) => {
+                    unmatched.swap_remove(index);
+                }
+                None => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+
     /// Compare two quantities for equivalence (same as equality for quantities)
     fn compare_quantities_equivalent(
         &self,
@@ -866,6 +929,32 @@ impl EquivalentOperator {
     }
 }
 
+/// Fail an ordering comparison with an operand of more than one item
+///
+/// Single-item collections are unwrapped before operators are applied, so a
+/// collection here always has several items. Unlike `=`, which compares
+/// collections item by item, `<` and friends have no meaning for them.
+fn require_single_items(
+    operator: &str,
+    left: &FhirPathValue,
+    right: &FhirPathValue,
+) -> OperatorResult<()> {
+    for (side, operand) in [("left", left), ("right", right)] {
+        if let FhirPathValue::Collection(items) = operand
+            && items.len() > 1
+        {
+            return Err(OperatorError::EvaluationError {
+                operator: operator.to_string(),
+                message: format!(
+                    "the {side} operand has {} items, but ordering compares single items",
+                    items.len()
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
 /// Not equivalent operator (!~)
 pub struct NotEquivalentOperator;
 
@@ -899,9 +988,12 @@ impl FhirPathOperator for NotEquivalentOperator {
         left: &FhirPathValue,
         right: &FhirPathValue,
     ) -> OperatorResult<FhirPathValue> {
-        // TODO: Implement proper equivalence logic
+        let equivalent = matches!(
+            EquivalentOperator.evaluate_binary(left, right)?,
+            FhirPathValue::Boolean(true)
+        );
         Ok(FhirPathValue::collection(vec![FhirPathValue::Boolean(
-            left != right,
+            !equivalent,
         )]))
     }
 }
@@ -917,3 +1009,48 @@ pub fn register_comparison_operators(registry: &mut OperatorRegistry) {
     registry.register(EquivalentOperator);
     registry.register(NotEquivalentOperator);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_equal_normalizes_numbers() {
+        assert!(json_equal(&json!(1), &json!(1.0)));
+        assert!(json_equal(&json!(1.50), &json!(1.5)));
+        assert!(json_equal(&json!(-0.0), &json!(0)));
+        assert!(json_equal(&json!(1e2), &json!(100)));
+        assert!(!json_equal(&json!(1), &json!(1.01)));
+        assert!(!json_equal(&json!(1), &json!("1")));
+    }
+
+    #[test]
+    fn test_json_equal_compares_structure() {
+        assert!(json_equal(
+            &json!({"a": 1, "b": [1, {"c": 2.0}]}),
+            &json!({"b": [1.0, {"c": 2}], "a": 1.0})
+        ));
+        // Array order matters
+        assert!(!json_equal(&json!([1, 2]), &json!([2, 1])));
+        // Missing and extra keys differ
+        assert!(!json_equal(&json!({"a": 1}), &json!({"a": 1, "b": null})));
+        assert!(!json_equal(&json!({"a": 1}), &json!({"b": 1})));
+    }
+
+    #[test]
+    fn test_fhirpath_eq_for_elements() {
+        let resource = |json| FhirPathValue::resource_from_json(json);
+        assert!(
+            resource(json!({"resourceType": "Patient", "id": "a", "multipleBirthInteger": 2}))
+                .fhirpath_eq(&resource(
+                    json!({"id": "a", "multipleBirthInteger": 2.0, "resourceType": "Patient"})
+                ))
+        );
+        assert!(
+            !resource(json!({"resourceType": "Patient", "id": "a"}))
+                .fhirpath_eq(&resource(json!({"resourceType": "Patient", "id": "b"})))
+        );
+        assert!(FhirPathValue::Integer(1).fhirpath_eq(&FhirPathValue::Decimal(Decimal::ONE)));
+    }
+}
@@ -28,6 +28,21 @@ pub struct ParameterInfo {
     pub param_type: TypeInfo,
     /// Whether this parameter is optional
     pub optional: bool,
+    /// Whether the argument is evaluated before the call
+    #[serde(default)]
+    pub evaluation: ArgumentEvaluation,
+}
+
+/// How the evaluator passes an argument to a function
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ArgumentEvaluation {
+    /// Evaluated against the function's input before the call, e.g. the
+    /// arguments of `substring()` and `startsWith()`
+    #[default]
+    Eager,
+    /// Passed to the function as an unevaluated expression, which the function
+    /// evaluates as often as it needs, e.g. the criteria of `where()`
+    Lazy,
 }
 
 /// Operator signature for type checking
@@ -109,6 +124,22 @@ impl FunctionSignature {
 
         true
     }
+
+    /// How the argument at `index` is evaluated
+    ///
+    /// Extra arguments of a variadic function are evaluated like its last
+    /// parameter.
+    pub fn argument_evaluation(&self, index: usize) -> ArgumentEvaluation {
+        self.parameters
+            .get(index)
+            .or_else(|| self.parameters.last().filter(|_| self.max_arity.is_none()))
+            .map_or(ArgumentEvaluation::Eager, |param| param.evaluation)
+    }
+
+    /// Check if any argument is passed as an unevaluated expression
+    pub fn has_lazy_parameters(&self) -> bool {
+        self.parameters.iter().any(ParameterInfo::is_lazy)
+    }
 }
 
 impl ParameterInfo {
@@ -118,6 +149,7 @@ impl ParameterInfo {
             name: name.into(),
             param_type,
             optional: false,
+            evaluation: ArgumentEvaluation::Eager,
         }
     }
 
@@ -127,8 +159,20 @@ impl ParameterInfo {
             name: name.into(),
             param_type,
             optional: true,
+            evaluation: ArgumentEvaluation::Eager,
         }
     }
+
+    /// Pass the argument as an unevaluated expression
+    pub fn lazy(mut self) -> Self {
+        self.evaluation = ArgumentEvaluation::Lazy;
+        self
+    }
+
+    /// Check if the argument is passed as an unevaluated expression
+    pub fn is_lazy(&self) -> bool {
+        self.evaluation == ArgumentEvaluation::Lazy
+    }
 }
 
 impl OperatorSignature {
@@ -225,6 +269,30 @@ mod tests {
         assert!(!sig.matches(&[TypeInfo::String])); // Wrong type
     }
 
+    #[test]
+    fn test_argument_evaluation() {
+        let sig = FunctionSignature::new(
+            "aggregate",
+            vec![
+                ParameterInfo::required("aggregator", TypeInfo::Any).lazy(),
+                ParameterInfo::optional("init", TypeInfo::Any),
+            ],
+            TypeInfo::Any,
+        );
+
+        assert!(sig.has_lazy_parameters());
+        assert_eq!(sig.argument_evaluation(0), ArgumentEvaluation::Lazy);
+        assert_eq!(sig.argument_evaluation(1), ArgumentEvaluation::Eager);
+        assert_eq!(sig.argument_evaluation(2), ArgumentEvaluation::Eager);
+
+        let variadic = FunctionSignature::variadic(
+            "sort",
+            vec![ParameterInfo::optional("expressions", TypeInfo::Any).lazy()],
+            TypeInfo::Any,
+        );
+        assert_eq!(variadic.argument_evaluation(3), ArgumentEvaluation::Lazy);
+    }
+
     #[test]
     fn test_operator_signature_matching() {
         let sig =
@@ -263,10 +263,27 @@ impl FhirTypeRegistry {
         }
 
         // Set up inheritance hierarchy
-        // Complex types inherit from Element
+        // Primitive types inherit from Element, some by specializing another primitive
+        for type_name in &primitive_types {
+            let parent = match *type_name {
+                "code" | "id" | "markdown" => "string",
+                "url" | "canonical" | "oid" | "uuid" => "uri",
+                "unsignedInt" | "positiveInt" => "integer",
+                _ => "Element",
+            };
+            self.type_hierarchy
+                .insert(type_name.to_string(), parent.to_string());
+        }
+
+        // Complex types inherit from Element, or from the type they constrain
         for type_name in &complex_types {
+            let parent = match *type_name {
+                "Age" | "Count" | "Distance" | "Duration" | "SimpleQuantity" => "Quantity",
+                "Timing" | "Dosage" => "BackboneElement",
+                _ => "Element",
+            };
             self.type_hierarchy
-                .insert(type_name.to_string(), "Element".to_string());
+                .insert(type_name.to_string(), parent.to_string());
         }
 
         // All resources inherit from Resource
@@ -275,10 +292,7 @@ impl FhirTypeRegistry {
                 if *type_name == "DomainResource" {
                     self.type_hierarchy
                         .insert(type_name.to_string(), "Resource".to_string());
-                } else if !matches!(
-                    *type_name,
-                    "Binary" | "Bundle" | "Parameters" | "OperationOutcome"
-                ) {
+                } else if !matches!(*type_name, "Binary" | "Bundle" | "Parameters") {
                     // Most resources inherit from DomainResource
                     self.type_hierarchy
                         .insert(type_name.to_string(), "DomainResource".to_string());
@@ -492,6 +506,17 @@ mod tests {
 
         // Not compatible
         assert!(!registry.is_type_compatible("Patient", "Observation"));
+
+        // Bundle is not a DomainResource
+        assert!(registry.is_type_compatible("Bundle", "Resource"));
+        assert!(!registry.is_type_compatible("Bundle", "DomainResource"));
+
+        // Specialized primitives and constrained data types
+        assert!(registry.is_type_compatible("code", "string"));
+        assert!(registry.is_type_compatible("FHIR.uuid", "uri"));
+        assert!(registry.is_type_compatible("positiveInt", "integer"));
+        assert!(registry.is_type_compatible("Age", "Quantity"));
+        assert!(!registry.is_type_compatible("instant", "dateTime"));
     }
 
     #[test]
@@ -0,0 +1,62 @@
+//! Tests for the `%vs-[name]` and `%ext-[name]` abbreviation variables
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::{Value, json};
+
+fn patient() -> Value {
+    json!({
+        "resourceType": "Patient",
+        "extension": [
+            {
+                "url": "http://hl7.org/fhir/StructureDefinition/patient-birthTime",
+                "valueDateTime": "2020-01-01T10:00:00Z"
+            },
+            {
+                "url": "http://hl7.org/fhir/StructureDefinition/patient-religion",
+                "valueString": "none"
+            }
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_value_set_abbreviation_expands_to_canonical_url() {
+    assert_eq!(
+        common::unwrap_singleton(common::eval("%`vs-administrative-gender`", patient()).await),
+        FhirPathValue::String("http://hl7.org/fhir/ValueSet/administrative-gender".into())
+    );
+}
+
+#[tokio::test]
+async fn test_extension_abbreviation_expands_to_canonical_url() {
+    assert_eq!(
+        common::unwrap_singleton(common::eval("%`ext-patient-birthTime`", patient()).await),
+        FhirPathValue::String("http://hl7.org/fhir/StructureDefinition/patient-birthTime".into())
+    );
+}
+
+#[tokio::test]
+async fn test_extension_abbreviation_in_extension_function() {
+    assert_eq!(
+        common::unwrap_singleton(
+            common::eval(
+                "Patient.extension(%`ext-patient-religion`).value",
+                patient()
+            )
+            .await
+        ),
+        FhirPathValue::String("none".into())
+    );
+}
+
+#[tokio::test]
+async fn test_unknown_variable_is_an_error() {
+    for expression in ["%`vs-`", "%`other-thing`"] {
+        assert!(
+            common::try_eval(expression, patient()).await.is_err(),
+            "'{expression}' should fail"
+        );
+    }
+}
@@ -0,0 +1,67 @@
+//! Tests for aggregate() and its $this/$index/$total variables
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_sum_with_init() {
+    assert_eq!(
+        common::eval("(1 | 2 | 3).aggregate($this + $total, 0)", json!({})).await,
+        FhirPathValue::Integer(6)
+    );
+    assert_eq!(
+        common::eval("(1 | 2 | 3).aggregate($this * $total, 1) + 1", json!({})).await,
+        FhirPathValue::Integer(7)
+    );
+}
+
+#[tokio::test]
+async fn test_total_starts_empty_without_init() {
+    assert_eq!(
+        common::eval(
+            "(4 | 9 | 2).aggregate(iif($total.empty(), $this, iif($this > $total, $this, $total)))",
+            json!({})
+        )
+        .await,
+        FhirPathValue::Integer(9)
+    );
+    assert!(
+        common::eval("(1 | 2).aggregate($this + $total)", json!({}))
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_empty_input_yields_init() {
+    assert_eq!(
+        common::eval("{}.aggregate($this + $total, 0)", json!({})).await,
+        FhirPathValue::Integer(0)
+    );
+    assert!(
+        common::eval("{}.aggregate($this + $total)", json!({}))
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_index_and_navigated_items() {
+    assert_eq!(
+        common::eval("(10 | 20 | 30).aggregate($total + $index, 0)", json!({})).await,
+        FhirPathValue::Integer(3)
+    );
+    assert_eq!(
+        common::eval(
+            "name.given.aggregate($total + $this.length(), 0)",
+            json!({
+                "resourceType": "Patient",
+                "name": [{"given": ["Peter", "James"]}, {"given": ["Jim"]}]
+            })
+        )
+        .await,
+        FhirPathValue::Integer(13)
+    );
+}
@@ -0,0 +1,160 @@
+//! Tests that functions get eager arguments evaluated and lazy ones as expressions
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use octofhir_fhirpath::registry::{ArgumentEvaluation, create_standard_registries};
+use serde_json::{Value, json};
+
+fn patient() -> Value {
+    json!({"resourceType": "Patient", "name": [{"given": ["Ann", "Beth"]}]})
+}
+
+#[test]
+fn test_signatures_declare_argument_evaluation() {
+    let (functions, _) = create_standard_registries();
+    let evaluation = |name: &str, index: usize| {
+        functions
+            .get(name)
+            .unwrap_or_else(|| panic!("{name} is not registered"))
+            .signature()
+            .argument_evaluation(index)
+    };
+
+    for (name, index) in [
+        ("where", 0),
+        ("select", 0),
+        ("all", 0),
+        ("exists", 0),
+        ("repeat", 0),
+        ("aggregate", 0),
+        ("iif", 0),
+        ("iif", 1),
+        ("iif", 2),
+        ("trace", 1),
+        ("defineVariable", 1),
+    ] {
+        assert_eq!(evaluation(name, index), ArgumentEvaluation::Lazy, "{name}");
+    }
+
+    for (name, index) in [
+        ("substring", 0),
+        ("substring", 1),
+        ("startsWith", 0),
+        ("aggregate", 1),
+        ("trace", 0),
+        ("defineVariable", 0),
+    ] {
+        assert_eq!(evaluation(name, index), ArgumentEvaluation::Eager, "{name}");
+    }
+}
+
+#[test]
+fn test_functions_with_lazy_parameters_take_expressions() {
+    // Without a lambda implementation, lazy arguments would be evaluated up front
+    let (functions, _) = create_standard_registries();
+    for name in functions.function_names() {
+        let signature = functions.get(name).unwrap().signature();
+        if signature.has_lazy_parameters() {
+            assert!(functions.is_lambda_function(name), "{name}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_lazy_arguments_are_evaluated_per_item() {
+    let (engine, log) = common::collecting_trace_engine();
+    // Pre-evaluating the criteria would trace the whole collection once
+    let result = common::eval_with(
+        &engine,
+        "name.given.where($this.trace('item') = 'Beth')",
+        patient(),
+    )
+    .await;
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::String("Beth".into())])
+    );
+    assert_eq!(
+        log.take(),
+        [
+            ("item".to_string(), FhirPathValue::String("Ann".into())),
+            ("item".to_string(), FhirPathValue::String("Beth".into())),
+        ]
+    );
+
+    common::eval_with(
+        &engine,
+        "name.given.select(length().trace('length'))",
+        patient(),
+    )
+    .await;
+    assert_eq!(
+        log.take(),
+        [
+            ("length".to_string(), FhirPathValue::Integer(3)),
+            ("length".to_string(), FhirPathValue::Integer(4)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_lazy_arguments_are_not_evaluated_for_empty_input() {
+    let (engine, log) = common::collecting_trace_engine();
+    let result =
+        common::eval_with(&engine, "{}.where(trace('criteria')).exists()", patient()).await;
+    let calls = log.take();
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::Boolean(false)])
+    );
+    assert!(calls.is_empty(), "criteria was evaluated: {calls:?}");
+
+    common::eval_with(&engine, "{}.all(trace('criteria'))", patient()).await;
+    let calls = log.take();
+    assert!(calls.is_empty(), "criteria was evaluated: {calls:?}");
+}
+
+#[tokio::test]
+async fn test_eager_arguments_are_evaluated_once_before_the_call() {
+    let (engine, log) = common::collecting_trace_engine();
+    let result = common::eval_with(
+        &engine,
+        "(1 | 2 | 3).aggregate($total.trace('step') + $this, 10.trace('init'))",
+        patient(),
+    )
+    .await;
+    assert_eq!(result, FhirPathValue::Integer(16));
+    assert_eq!(log.take_names(), ["init", "step", "step", "step"]);
+
+    let result = common::eval_with(
+        &engine,
+        "'abcdef'.substring(1.trace('start'), 2.trace('length'))",
+        patient(),
+    )
+    .await;
+    assert_eq!(result, FhirPathValue::String("bc".into()));
+    assert_eq!(log.take_names(), ["start", "length"]);
+}
+
+#[tokio::test]
+async fn test_trace_selector_is_evaluated_on_the_input() {
+    let (engine, log) = common::collecting_trace_engine();
+    let result = common::eval_with(
+        &engine,
+        "name.trace('given', given.count()).given",
+        patient(),
+    )
+    .await;
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![
+            FhirPathValue::String("Ann".into()),
+            FhirPathValue::String("Beth".into()),
+        ])
+    );
+    assert_eq!(
+        log.take(),
+        [("given".to_string(), FhirPathValue::Integer(2))]
+    );
+}
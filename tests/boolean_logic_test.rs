@@ -0,0 +1,100 @@
+//! Truth tables and short-circuiting of the and, or, xor and implies operators
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::json;
+
+/// Each operand with its value, `None` standing for the empty collection
+const OPERANDS: [(&str, Option<bool>); 3] =
+    [("true", Some(true)), ("false", Some(false)), ("{}", None)];
+
+/// The truth value of `expression`, `None` standing for the empty collection
+async fn truth_value(expression: &str) -> Option<bool> {
+    let items = common::items(common::eval(expression, json!({"resourceType": "Patient"})).await);
+    match items.as_slice() {
+        [] => None,
+        [FhirPathValue::Boolean(b)] => Some(*b),
+        other => panic!("'{expression}' should be a Boolean or empty, got {other:?}"),
+    }
+}
+
+/// Check `op` against `expected(left, right)` for every pair of operands
+async fn check_truth_table(op: &str, expected: fn(Option<bool>, Option<bool>) -> Option<bool>) {
+    for (left, left_value) in OPERANDS {
+        for (right, right_value) in OPERANDS {
+            let expression = format!("{left} {op} {right}");
+            assert_eq!(
+                truth_value(&expression).await,
+                expected(left_value, right_value),
+                "{expression}"
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_and_truth_table() {
+    check_truth_table("and", |left, right| match (left, right) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_or_truth_table() {
+    check_truth_table("or", |left, right| match (left, right) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (Some(false), Some(false)) => Some(false),
+        _ => None,
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_xor_truth_table() {
+    check_truth_table("xor", |left, right| Some(left? != right?)).await;
+}
+
+#[tokio::test]
+async fn test_implies_truth_table() {
+    check_truth_table("implies", |left, right| match (left, right) {
+        (Some(false), _) | (_, Some(true)) => Some(true),
+        (Some(true), Some(false)) => Some(false),
+        _ => None,
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_decided_left_operand_skips_the_right() {
+    // %undefined is not a known variable, so evaluating it is an error
+    assert_eq!(truth_value("false and %undefined").await, Some(false));
+    assert_eq!(truth_value("false and (1 / 0 > 0)").await, Some(false));
+    assert_eq!(truth_value("true or %undefined").await, Some(true));
+    assert_eq!(truth_value("false implies %undefined").await, Some(true));
+    assert_eq!(
+        truth_value("(false and %undefined).not()").await,
+        Some(true)
+    );
+}
+
+#[tokio::test]
+async fn test_undecided_left_operand_evaluates_the_right() {
+    for expression in [
+        "true and %undefined",
+        "{} and %undefined",
+        "false or %undefined",
+        "true implies %undefined",
+        "false xor %undefined",
+    ] {
+        assert!(
+            common::try_eval(expression, json!({"resourceType": "Patient"}))
+                .await
+                .is_err(),
+            "'{expression}' should fail"
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Tests for evaluating expressions with a Bundle as the input
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::{Value, json};
+
+fn bundle() -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": [
+            {
+                "fullUrl": "http://example.com/Patient/1",
+                "resource": {"resourceType": "Patient", "id": "1", "name": [{"family": "Doe"}]}
+            },
+            {
+                "fullUrl": "http://example.com/Observation/o1",
+                "resource": {
+                    "resourceType": "Observation",
+                    "id": "o1",
+                    "subject": {"reference": "Patient/1"}
+                }
+            },
+            {
+                "fullUrl": "urn:uuid:2b4a4b0e-6a3e-4b8e-9a43-1c1d4e0f3a11",
+                "resource": {"resourceType": "Patient", "id": "2", "name": [{"family": "Roe"}]}
+            },
+            {
+                "resource": {
+                    "resourceType": "Observation",
+                    "id": "o2",
+                    "subject": {"reference": "urn:uuid:2b4a4b0e-6a3e-4b8e-9a43-1c1d4e0f3a11"}
+                }
+            }
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_bare_and_qualified_paths_agree() {
+    for (expression, expected) in [
+        (
+            "entry.resource.ofType(Patient).id",
+            FhirPathValue::collection(common::strings(&["1", "2"])),
+        ),
+        (
+            "entry.resource.ofType(Observation).id",
+            FhirPathValue::collection(common::strings(&["o1", "o2"])),
+        ),
+        (
+            "entry.resource.ofType(Observation).subject.resolve().id",
+            FhirPathValue::collection(common::strings(&["1", "2"])),
+        ),
+        (
+            "entry.resource.ofType(Observation).subject.resolve().ofType(Patient).name.family",
+            FhirPathValue::collection(common::strings(&["Doe", "Roe"])),
+        ),
+        (
+            "entry.where(resource is Patient).fullUrl",
+            FhirPathValue::collection(common::strings(&[
+                "http://example.com/Patient/1",
+                "urn:uuid:2b4a4b0e-6a3e-4b8e-9a43-1c1d4e0f3a11",
+            ])),
+        ),
+    ] {
+        assert_eq!(
+            common::eval(expression, bundle()).await,
+            expected,
+            "{expression}"
+        );
+        let qualified = format!("Bundle.{expression}");
+        assert_eq!(
+            common::eval(&qualified, bundle()).await,
+            expected,
+            "{qualified}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_bare_field_navigates_the_bundle() {
+    assert_eq!(
+        common::eval("type", bundle()).await,
+        FhirPathValue::String("collection".into())
+    );
+    assert_eq!(
+        common::eval("entry.count()", bundle()).await,
+        common::eval("Bundle.entry.count()", bundle()).await
+    );
+    assert!(common::eval("Patient.entry", bundle()).await.is_empty());
+}
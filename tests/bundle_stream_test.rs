@@ -0,0 +1,154 @@
+//! Tests for streaming evaluation over Bundle entries
+
+use octofhir_fhirpath::engine::FhirPathEngine;
+use octofhir_fhirpath::evaluator::bundle_stream::entry_resources;
+use serde_json::{Value, json};
+use std::io::Cursor;
+
+fn bundle() -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "type": "searchset",
+        "link": [{"relation": "self", "url": "http://example.org/fhir/Patient"}],
+        "entry": [
+            {
+                "fullUrl": "http://example.org/fhir/Patient/p1",
+                "resource": {
+                    "resourceType": "Patient",
+                    "id": "p1",
+                    "name": [{"family": "Chalmers", "given": ["Peter", "James"]}]
+                },
+                "search": {"mode": "match"}
+            },
+            {"fullUrl": "urn:uuid:no-resource"},
+            {
+                "resource": {
+                    "resourceType": "Observation",
+                    "id": "o1",
+                    "valueQuantity": {"value": 185, "code": "[lb_av]"}
+                }
+            },
+            {
+                "resource": {
+                    "resourceType": "Patient",
+                    "id": "p2",
+                    "name": [{"family": "Windsor", "given": ["Anne"]}]
+                }
+            }
+        ],
+        "total": 3
+    })
+}
+
+#[tokio::test]
+async fn test_streaming_matches_eager_evaluation() {
+    let bundle = bundle();
+    let bytes = serde_json::to_vec(&bundle).unwrap();
+    let engine = FhirPathEngine::new();
+
+    for expression in [
+        "id",
+        "where(resourceType = 'Patient').name.given",
+        "value",
+        "name.family.first()",
+    ] {
+        let eager = engine
+            .evaluate(
+                &format!("Bundle.entry.resource.select({expression})"),
+                bundle.clone(),
+            )
+            .await
+            .unwrap();
+        let streamed = engine
+            .evaluate_bundle_entries(expression, Cursor::new(bytes.clone()))
+            .await
+            .unwrap();
+        assert_eq!(streamed, eager, "{expression}");
+    }
+}
+
+#[test]
+fn test_entry_resources_yields_resources_in_order() {
+    let bytes = serde_json::to_vec(&bundle()).unwrap();
+    let ids: Vec<String> = entry_resources(bytes.as_slice())
+        .map(|resource| resource.unwrap()["id"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(ids, ["p1", "o1", "p2"]);
+}
+
+#[test]
+fn test_entry_resources_ends_after_an_error() {
+    let bytes =
+        br#"{"resourceType": "Bundle", "entry": [{"resource": {"id": "p1"}}, {"resource": }]}"#;
+    let mut resources = entry_resources(&bytes[..]);
+    assert_eq!(resources.next().unwrap().unwrap()["id"], "p1");
+    let err = resources.next().unwrap().unwrap_err();
+    assert!(err.to_string().contains("Invalid Bundle JSON"), "{err}");
+    assert!(resources.next().is_none());
+}
+
+#[tokio::test]
+async fn test_streaming_rejects_non_bundle_and_invalid_json() {
+    let patient = serde_json::to_vec(&json!({"resourceType": "Patient", "id": "p1"})).unwrap();
+    let err = FhirPathEngine::new()
+        .evaluate_bundle_entries("id", Cursor::new(patient))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("expected a Bundle"), "{err}");
+
+    let err = FhirPathEngine::new()
+        .evaluate_bundle_entries("id", &b"{\"resourceType\": \"Bundle\", \"entry\": ["[..])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid Bundle JSON"), "{err}");
+}
+
+#[tokio::test]
+async fn test_streaming_reports_error_context() {
+    let bytes = serde_json::to_vec(&bundle()).unwrap();
+    let err = FhirPathEngine::new()
+        .with_error_context(true)
+        .evaluate_bundle_entries("name.given.substring(0, 1)", Cursor::new(bytes))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.navigation_stack().expect("Should capture the stack"),
+        ["name", "given", "substring(0, 1)"]
+    );
+}
+
+#[tokio::test]
+async fn test_streaming_rejects_missing_or_late_resource_type() {
+    let late = br#"{"entry": [{"resource": {"resourceType": "Patient", "id": "p1"}}], "resourceType": "Bundle"}"#;
+    let missing = br#"{"entry": [{"resource": {"resourceType": "Patient", "id": "p1"}}]}"#;
+    let empty = br#"{"type": "collection"}"#;
+    for bytes in [&late[..], &missing[..], &empty[..]] {
+        let mut resources = entry_resources(bytes);
+        let err = resources.next().unwrap().unwrap_err();
+        assert!(err.to_string().contains("resourceType"), "{err}");
+        assert!(resources.next().is_none());
+
+        let err = FhirPathEngine::new()
+            .evaluate_bundle_entries("id", bytes)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("resourceType"), "{err}");
+    }
+}
+
+#[tokio::test]
+async fn test_streaming_root_resource_is_the_entry() {
+    let bytes = serde_json::to_vec(&bundle()).unwrap();
+    let result = FhirPathEngine::new()
+        .evaluate_bundle_entries("%rootResource.resourceType", Cursor::new(bytes))
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        octofhir_fhirpath::FhirPathValue::collection(
+            ["Patient", "Observation", "Patient"]
+                .map(|s| octofhir_fhirpath::FhirPathValue::String(s.into()))
+                .to_vec()
+        )
+    );
+}
@@ -0,0 +1,140 @@
+//! Tests for type tests and casts on choice elements such as Observation.value[x]
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::{Value, json};
+
+fn quantity_observation() -> Value {
+    json!({
+        "resourceType": "Observation",
+        "status": "final",
+        "valueQuantity": {"value": 185, "unit": "lbs", "system": "http://unitsofmeasure.org", "code": "[lb_av]"},
+        "effectivePeriod": {"start": "2024-01-01"}
+    })
+}
+
+fn string_observation() -> Value {
+    json!({
+        "resourceType": "Observation",
+        "status": "final",
+        "valueString": "negative"
+    })
+}
+
+fn boolean(value: bool) -> FhirPathValue {
+    FhirPathValue::collection(vec![FhirPathValue::Boolean(value)])
+}
+
+#[tokio::test]
+async fn test_is_on_choice_elements() {
+    assert_eq!(
+        common::eval("Observation.value is Quantity", quantity_observation()).await,
+        boolean(true)
+    );
+    assert_eq!(
+        common::eval("Observation.value is FHIR.Quantity", quantity_observation()).await,
+        boolean(true)
+    );
+    assert_eq!(
+        common::eval("Observation.value is string", quantity_observation()).await,
+        boolean(false)
+    );
+    assert_eq!(
+        common::eval("Observation.effective is Period", quantity_observation()).await,
+        boolean(true)
+    );
+
+    assert_eq!(
+        common::eval("Observation.value is string", string_observation()).await,
+        boolean(true)
+    );
+    assert_eq!(
+        common::eval("Observation.value is Quantity", string_observation()).await,
+        boolean(false)
+    );
+}
+
+#[tokio::test]
+async fn test_as_on_choice_elements() {
+    assert_eq!(
+        common::eval(
+            "(Observation.value as Quantity).unit",
+            quantity_observation()
+        )
+        .await,
+        FhirPathValue::collection(vec![FhirPathValue::String("lbs".into())])
+    );
+    assert_eq!(
+        common::eval(
+            "Observation.value.as(Quantity).unit",
+            quantity_observation()
+        )
+        .await,
+        FhirPathValue::String("lbs".into())
+    );
+    assert!(
+        common::eval("Observation.value as Period", quantity_observation())
+            .await
+            .is_empty()
+    );
+
+    assert!(
+        common::eval("Observation.value as Quantity", string_observation())
+            .await
+            .is_empty()
+    );
+    assert_eq!(
+        common::eval("Observation.value as string", string_observation()).await,
+        FhirPathValue::collection(vec![FhirPathValue::String("negative".into())])
+    );
+}
+
+#[tokio::test]
+async fn test_of_type_on_choice_elements() {
+    assert_eq!(
+        common::eval(
+            "Observation.value.ofType(Quantity).value",
+            quantity_observation()
+        )
+        .await,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(185)])
+    );
+    assert!(
+        common::eval("Observation.value.ofType(Quantity)", string_observation())
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_type_of_choice_elements() {
+    assert_eq!(
+        common::eval("Observation.value.type().name", quantity_observation()).await,
+        FhirPathValue::String("Quantity".into())
+    );
+    assert_eq!(
+        common::eval("Observation.value.type().namespace", quantity_observation()).await,
+        FhirPathValue::String("FHIR".into())
+    );
+}
+
+#[tokio::test]
+async fn test_non_choice_prefixes_are_not_choice_elements() {
+    let patient = json!({
+        "resourceType": "Patient",
+        "birthDate": "1974-12-25",
+        "managingOrganization": {"reference": "Organization/1"}
+    });
+    assert!(
+        common::eval("Patient.birth", patient.clone())
+            .await
+            .is_empty()
+    );
+    assert!(
+        common::eval("Patient.managing", patient.clone())
+            .await
+            .is_empty()
+    );
+    assert!(!common::eval("Patient.birthDate", patient).await.is_empty());
+}
@@ -0,0 +1,157 @@
+//! Tests for navigating Codings and CodeableConcepts and the codingFor() extension
+
+mod common;
+
+use octofhir_fhirpath::registry::extension::builtin::FhirExtension;
+use octofhir_fhirpath::registry::extension::{ExtensionManager, FunctionResolution};
+use octofhir_fhirpath::registry::function;
+use octofhir_fhirpath::{FhirPathValue, FunctionRegistry, engine::FhirPathEngine};
+use serde_json::{Value, json};
+
+fn observation() -> Value {
+    json!({
+        "resourceType": "Observation",
+        "status": "final",
+        "code": {
+            "coding": [
+                {"system": "http://loinc.org", "code": "85354-9", "display": "Blood pressure panel"},
+                {"system": "http://snomed.info/sct", "code": "75367002"},
+                {"system": "http://loinc.org", "code": "55284-4"}
+            ],
+            "text": "Blood pressure"
+        },
+        "component": [
+            {
+                "code": {
+                    "coding": [{"system": "http://loinc.org", "code": "8480-6"}],
+                    "text": "Systolic"
+                }
+            },
+            {
+                "code": {
+                    "coding": [
+                        {"system": "http://snomed.info/sct", "code": "271650006"},
+                        {"system": "http://loinc.org", "code": "8462-4"}
+                    ]
+                }
+            }
+        ]
+    })
+}
+
+async fn string_values(expression: &str) -> Vec<String> {
+    let to_string = |value: &FhirPathValue| match value {
+        FhirPathValue::String(s) => s.to_string(),
+        other => panic!("{expression}: expected a string, got {other:?}"),
+    };
+    match common::eval(expression, observation()).await {
+        FhirPathValue::Collection(items) => items.iter().map(to_string).collect(),
+        FhirPathValue::Empty => Vec::new(),
+        single => vec![to_string(&single)],
+    }
+}
+
+#[tokio::test]
+async fn test_code_system_variables() {
+    assert_eq!(string_values("%loinc").await, ["http://loinc.org"]);
+    assert_eq!(string_values("%sct").await, ["http://snomed.info/sct"]);
+    assert_eq!(string_values("%ucum").await, ["http://unitsofmeasure.org"]);
+}
+
+#[tokio::test]
+async fn test_codings_filtered_by_system() {
+    assert_eq!(
+        string_values("Observation.code.coding.where(system = %loinc).code").await,
+        ["85354-9", "55284-4"]
+    );
+    assert_eq!(
+        string_values("Observation.code.coding.where(system = %sct).code").await,
+        ["75367002"]
+    );
+    assert_eq!(
+        string_values("Observation.code.coding.where(system = %loinc).display").await,
+        ["Blood pressure panel"]
+    );
+}
+
+#[tokio::test]
+async fn test_nested_codeable_concepts() {
+    assert_eq!(
+        string_values("Observation.code.text").await,
+        ["Blood pressure"]
+    );
+    assert_eq!(
+        string_values("Observation.component.code.text").await,
+        ["Systolic"]
+    );
+    assert_eq!(
+        string_values("Observation.component.code.coding.where(system = %loinc).code").await,
+        ["8480-6", "8462-4"]
+    );
+}
+
+/// Call `fhir:codingFor` from the FHIR extension on the result of `input`
+async fn coding_for(input: &str, system: &str) -> Vec<String> {
+    let mut manager = ExtensionManager::new(FunctionRegistry::new());
+    manager
+        .load_extension(Box::new(FhirExtension::new()))
+        .unwrap();
+    let FunctionResolution::Extension { function, .. } = manager.resolve_function("fhir:codingFor")
+    else {
+        panic!("fhir:codingFor is not registered");
+    };
+
+    let context = function::EvaluationContext::new(common::eval(input, observation()).await);
+    let args = [FhirPathValue::String(system.into())];
+    match function.evaluate_async(&args, &context).await.unwrap() {
+        FhirPathValue::Collection(items) => items
+            .iter()
+            .map(|item| match item {
+                FhirPathValue::String(s) => s.to_string(),
+                other => panic!("expected a string, got {other:?}"),
+            })
+            .collect(),
+        FhirPathValue::Empty => Vec::new(),
+        other => panic!("expected a collection, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_coding_for() {
+    assert_eq!(
+        coding_for("Observation.code", "http://loinc.org").await,
+        ["85354-9", "55284-4"]
+    );
+    assert_eq!(
+        coding_for("Observation.component.code", "http://loinc.org").await,
+        ["8480-6", "8462-4"]
+    );
+
+    // Codings are accepted as well as CodeableConcepts
+    assert_eq!(
+        coding_for("Observation.code.coding", "http://snomed.info/sct").await,
+        ["75367002"]
+    );
+
+    assert!(
+        coding_for("Observation.code", "http://example.org")
+            .await
+            .is_empty()
+    );
+    assert!(
+        coding_for("Observation.status", "http://loinc.org")
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_coding_for_is_not_a_core_function() {
+    assert!(!FunctionRegistry::new().contains("codingFor"));
+    assert!(
+        FhirPathEngine::new()
+            .evaluate("Observation.code.codingFor(%loinc)", observation())
+            .await
+            .is_err()
+    );
+}
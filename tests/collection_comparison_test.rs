@@ -0,0 +1,86 @@
+//! Tests for comparison operators applied to collections of several items
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::json;
+
+fn patient() -> serde_json::Value {
+    json!({
+        "resourceType": "Patient",
+        "name": [{"given": ["Ann", "Beth"]}, {"given": ["Beth", "Ann"]}]
+    })
+}
+
+/// Expected result of a comparison, with `None` standing for empty
+async fn assert_comparisons(cases: &[(&str, Option<bool>)]) {
+    for (expression, expected) in cases {
+        let result = common::eval(expression, patient()).await;
+        let actual = match result {
+            FhirPathValue::Boolean(b) => Some(b),
+            FhirPathValue::Collection(ref items) if items.len() == 1 => match items.get(0) {
+                Some(FhirPathValue::Boolean(b)) => Some(*b),
+                other => panic!("{expression}: unexpected result {other:?}"),
+            },
+            ref empty if empty.is_empty() => None,
+            other => panic!("{expression}: unexpected result {other:?}"),
+        };
+        assert_eq!(actual, *expected, "{expression}");
+    }
+}
+
+#[tokio::test]
+async fn test_ordering_requires_single_items() {
+    for (expression, side, count) in [
+        ("(1 | 2) < 3", "left", 2),
+        ("3 > (1 | 2 | 4)", "right", 3),
+        ("name[0].given <= 'Z'", "left", 2),
+        ("(1 | 2) >= (1 | 2)", "left", 2),
+    ] {
+        let error = common::try_eval(expression, patient())
+            .await
+            .expect_err(expression)
+            .to_string();
+        assert!(
+            error.contains(&format!("the {side} operand has {count} items")),
+            "{expression}: {error}"
+        );
+    }
+
+    // An empty operand still gives empty, and a single item is compared
+    assert_comparisons(&[
+        ("{} < (1 | 2)", None),
+        ("name[0].given.first() < 'Z'", Some(true)),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_equality_compares_collections_in_order() {
+    assert_comparisons(&[
+        ("(1 | 2) = (1 | 2)", Some(true)),
+        ("(1 | 2) = (2 | 1)", Some(false)),
+        ("(1 | 2) = (1 | 2 | 3)", Some(false)),
+        ("(1 | 2) = 1", Some(false)),
+        ("name[0].given = name[1].given", Some(false)),
+        ("name[0].given = name[0].given", Some(true)),
+        ("(1 | 2) != (2 | 1)", Some(true)),
+        ("{} = {}", None),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_equivalence_compares_collections_in_any_order() {
+    assert_comparisons(&[
+        ("(1 | 2) ~ (2 | 1)", Some(true)),
+        ("name[0].given ~ name[1].given", Some(true)),
+        ("(1 | 2) ~ (1 | 3)", Some(false)),
+        ("(1 | 2) ~ (1 | 2 | 3)", Some(false)),
+        ("(1 | 2) ~ 1", Some(false)),
+        ("(1 | 2) !~ (2 | 1)", Some(false)),
+        ("{} ~ {}", Some(true)),
+        ("1 ~ {}", Some(false)),
+    ])
+    .await;
+}
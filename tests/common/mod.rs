@@ -0,0 +1,118 @@
+//! Helpers shared by the integration tests
+//!
+//! Each test crate uses its own subset, so unused ones are allowed.
+#![allow(dead_code)]
+
+use octofhir_fhirpath::registry::functions::TraceSink;
+use octofhir_fhirpath::{FhirPathError, FhirPathValue, engine::FhirPathEngine};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+
+/// Evaluate `expression` against `input`, panicking if it fails
+pub async fn eval(expression: &str, input: Value) -> FhirPathValue {
+    eval_with(&FhirPathEngine::new(), expression, input).await
+}
+
+/// Evaluate `expression` against `input` with `engine`, panicking if it fails
+pub async fn eval_with(engine: &FhirPathEngine, expression: &str, input: Value) -> FhirPathValue {
+    engine
+        .evaluate(expression, input)
+        .await
+        .unwrap_or_else(|e| panic!("'{expression}' should evaluate: {e}"))
+}
+
+/// Evaluate `expression` against `input`, returning any error
+pub async fn try_eval(expression: &str, input: Value) -> Result<FhirPathValue, FhirPathError> {
+    FhirPathEngine::new().evaluate(expression, input).await
+}
+
+/// The items of a result: none for empty, and itself for a single value
+pub fn items(value: FhirPathValue) -> Vec<FhirPathValue> {
+    match value {
+        FhirPathValue::Collection(items) => items.into_iter().collect(),
+        FhirPathValue::Empty => Vec::new(),
+        single => vec![single],
+    }
+}
+
+/// A one-item collection as its item and an empty collection as empty
+pub fn unwrap_singleton(value: FhirPathValue) -> FhirPathValue {
+    match value {
+        FhirPathValue::Collection(items) if items.len() == 1 => items.first().unwrap().clone(),
+        FhirPathValue::Collection(items) if items.is_empty() => FhirPathValue::Empty,
+        other => other,
+    }
+}
+
+/// A FHIRPath string
+pub fn string(s: &str) -> FhirPathValue {
+    FhirPathValue::String(s.into())
+}
+
+/// FHIRPath strings, in order
+pub fn strings(values: &[&str]) -> Vec<FhirPathValue> {
+    values.iter().map(|s| string(s)).collect()
+}
+
+/// A Patient named Peter James Chalmers
+pub fn patient() -> Value {
+    json!({
+        "resourceType": "Patient",
+        "name": [{"family": "Chalmers", "given": ["Peter", "James"]}]
+    })
+}
+
+/// A collection Bundle of two Patients, `p1` (active) and `p2`
+pub fn bundle() -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": [
+            {"resource": {"resourceType": "Patient", "id": "p1", "active": true}},
+            {"resource": {"resourceType": "Patient", "id": "p2"}}
+        ]
+    })
+}
+
+/// The name and value of every trace() call of an engine, in call order
+#[derive(Clone, Default)]
+pub struct TraceLog(Arc<Mutex<Vec<(String, FhirPathValue)>>>);
+
+impl TraceLog {
+    /// The calls logged so far, leaving the log empty
+    pub fn take(&self) -> Vec<(String, FhirPathValue)> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+
+    /// The names of the calls logged so far, leaving the log empty
+    pub fn take_names(&self) -> Vec<String> {
+        self.take().into_iter().map(|(name, _)| name).collect()
+    }
+
+    /// The values of the calls logged so far, leaving the log empty
+    pub fn take_values(&self) -> Vec<FhirPathValue> {
+        self.take().into_iter().map(|(_, value)| value).collect()
+    }
+}
+
+/// An engine whose trace() calls are collected into the returned log
+pub fn collecting_trace_engine() -> (FhirPathEngine, TraceLog) {
+    collecting_trace_engine_with(FhirPathEngine::with_trace_sink)
+}
+
+/// Like [`collecting_trace_engine`], with the sink installed by `attach`, e.g.
+/// `|engine, sink| engine.with_summarized_trace_sink(sink, 10)`
+pub fn collecting_trace_engine_with(
+    attach: impl FnOnce(FhirPathEngine, TraceSink) -> FhirPathEngine,
+) -> (FhirPathEngine, TraceLog) {
+    let log = TraceLog::default();
+    let sink_log = log.clone();
+    let sink: TraceSink = Arc::new(move |name: &str, value: &FhirPathValue| {
+        sink_log
+            .0
+            .lock()
+            .unwrap()
+            .push((name.to_string(), value.clone()));
+    });
+    (attach(FhirPathEngine::new(), sink), log)
+}
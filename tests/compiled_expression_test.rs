@@ -0,0 +1,76 @@
+//! Tests for compiling an expression once and evaluating it many times
+
+use octofhir_fhirpath::{
+    FhirPathValue,
+    engine::{CompiledExpression, FhirPathEngine},
+    parser::ParseError,
+};
+use serde_json::{Value, json};
+
+fn bundle(i: usize) -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": [
+            {"resource": {"resourceType": "Patient", "active": i.is_multiple_of(2), "name": [{"family": format!("F{i}")}]}},
+            {"resource": {"resourceType": "Observation", "status": "final"}}
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_compiled_matches_string_evaluation() {
+    let engine = FhirPathEngine::new();
+    let expression = "Bundle.entry.resource.where(resourceType = 'Patient' and active).name.family";
+    let compiled = engine.compile(expression).unwrap();
+    assert_eq!(compiled.source(), expression);
+
+    for i in 0..50 {
+        let from_string = engine.evaluate(expression, bundle(i)).await.unwrap();
+        let from_compiled = engine
+            .evaluate_compiled(&compiled, bundle(i))
+            .await
+            .unwrap();
+        assert_eq!(from_compiled, from_string, "bundle {i}");
+    }
+
+    let result = engine
+        .evaluate_compiled(&compiled, bundle(4))
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::String("F4".into())])
+    );
+}
+
+#[tokio::test]
+async fn test_compile_reports_syntax_errors() {
+    let engine = FhirPathEngine::new();
+
+    // evaluate() turns a syntax error into an empty result, compile() does not
+    assert!(engine.compile("Patient.name.where(").is_err());
+    assert_eq!(
+        engine.compile("   ").unwrap_err().error,
+        ParseError::EmptyExpression
+    );
+}
+
+#[tokio::test]
+async fn test_compiled_expression_is_engine_independent() {
+    let compiled: CompiledExpression = FhirPathEngine::new()
+        .compile("Patient.name.family.count()")
+        .unwrap();
+    let patient = json!({"resourceType": "Patient", "name": [{"family": "A"}, {"family": "B"}]});
+
+    // Functions are looked up at evaluation time, by whichever engine evaluates
+    let other = FhirPathEngine::new().with_strict_navigation(true);
+    let result = other
+        .evaluate_compiled(&compiled.clone(), patient)
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(2)])
+    );
+}
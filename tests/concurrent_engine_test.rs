@@ -0,0 +1,91 @@
+//! Tests that one engine serves many concurrent evaluations
+
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+fn patient(i: usize) -> Value {
+    json!({
+        "resourceType": "Patient",
+        "id": format!("p{i}"),
+        "name": [{"family": format!("Family{i}"), "given": ["A", "B"]}],
+        "birthDate": format!("{}-01-01", 1900 + i)
+    })
+}
+
+/// A Bundle whose Observation refers to the i-th Patient
+fn bundle(i: usize) -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": [
+            {"fullUrl": format!("http://example.org/Patient/p{i}"), "resource": patient(i)},
+            {
+                "fullUrl": "http://example.org/Observation/o",
+                "resource": {
+                    "resourceType": "Observation",
+                    "id": "o",
+                    "subject": {"reference": format!("Patient/p{i}")}
+                }
+            }
+        ]
+    })
+}
+
+fn string(s: String) -> FhirPathValue {
+    FhirPathValue::collection(vec![FhirPathValue::String(s.into())])
+}
+
+#[test]
+fn test_engine_is_send_and_sync() {
+    assert_send_sync::<FhirPathEngine>();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn test_shared_engine_serves_concurrent_evaluations() {
+    let engine = Arc::new(FhirPathEngine::new());
+
+    let tasks: Vec<_> = (0..256)
+        .map(|i| {
+            let engine = engine.clone();
+            tokio::spawn(async move {
+                // Each task repeats the same expressions, so the parse caches
+                // are read and written concurrently
+                let family = engine
+                    .evaluate("Patient.name.family", patient(i))
+                    .await
+                    .unwrap();
+                assert_eq!(family, string(format!("Family{i}")), "task {i}");
+
+                let year = format!(
+                    "Patient.birthDate.toString().substring(0, 4) = '{}'",
+                    1900 + i
+                );
+                assert!(
+                    engine.evaluate_bool(&year, patient(i)).await.unwrap(),
+                    "task {i}"
+                );
+
+                let resolved = engine
+                    .evaluate(
+                        "Bundle.entry.resource.ofType(Observation).subject.resolve().id",
+                        bundle(i),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(resolved, string(format!("p{i}")), "task {i}");
+
+                engine
+                    .evaluate_bool("Patient.name.given.count() = 2", patient(i))
+                    .await
+                    .unwrap()
+            })
+        })
+        .collect();
+
+    for task in tasks {
+        assert!(task.await.unwrap());
+    }
+}
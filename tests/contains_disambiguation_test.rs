@@ -0,0 +1,99 @@
+//! Tests for telling the `contains` operator apart from the contains() function
+
+mod common;
+
+use octofhir_fhirpath::ast::{BinaryOperator, ExpressionNode};
+use octofhir_fhirpath::parser::parse_expression;
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+
+fn parse_error(expression: &str) -> String {
+    parse_expression(expression)
+        .expect_err(expression)
+        .to_string()
+}
+
+#[test]
+fn test_call_syntax_parses_as_function_and_infix_as_operator() {
+    assert!(matches!(
+        parse_expression("name.family.contains('al')").unwrap(),
+        ExpressionNode::MethodCall(call) if call.method == "contains"
+    ));
+    assert!(matches!(
+        parse_expression("name.given contains 'Peter'").unwrap(),
+        ExpressionNode::BinaryOp(op) if op.op == BinaryOperator::Contains
+    ));
+}
+
+#[tokio::test]
+async fn test_function_form_searches_strings() {
+    assert_eq!(
+        common::eval("Patient.name.family.contains('alm')", common::patient()).await,
+        FhirPathValue::Boolean(true)
+    );
+    assert_eq!(
+        common::eval("Patient.name.family.contains('x')", common::patient()).await,
+        FhirPathValue::Boolean(false)
+    );
+}
+
+#[tokio::test]
+async fn test_operator_form_tests_membership() {
+    let truth = |b| FhirPathValue::collection(vec![FhirPathValue::Boolean(b)]);
+    assert_eq!(
+        common::eval("Patient.name.given contains 'James'", common::patient()).await,
+        truth(true)
+    );
+    assert_eq!(
+        common::eval("Patient.name.given contains 'Jam'", common::patient()).await,
+        truth(false)
+    );
+    // A single string is a one-item collection, not something to search in
+    assert_eq!(
+        common::eval("'abc' contains 'b'", common::patient()).await,
+        truth(false)
+    );
+    assert_eq!(
+        common::eval("'abc' contains 'abc'", common::patient()).await,
+        truth(true)
+    );
+}
+
+#[tokio::test]
+async fn test_function_form_on_non_string_collection_is_an_error() {
+    let err = FhirPathEngine::new()
+        .evaluate("(1 | 2).contains(2)", common::patient())
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("contains() expects a String input, got Integer"),
+        "{err}"
+    );
+    assert!(err.contains("`collection contains item`"), "{err}");
+}
+
+#[test]
+fn test_operator_without_operands_is_a_syntax_error() {
+    let err = parse_error("contains 'x'");
+    assert!(
+        err.contains("'contains' operator is missing its left operand"),
+        "{err}"
+    );
+
+    let err = parse_error("name.given contains");
+    assert!(
+        err.contains("'contains' operator is missing its right operand"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_matches_has_no_operator_form() {
+    assert!(parse_expression("name.family.matches('^C')").is_ok());
+
+    let err = parse_error("name.family matches '^C'");
+    assert!(
+        err.contains("'matches' is a function, not an operator"),
+        "{err}"
+    );
+}
@@ -0,0 +1,152 @@
+//! Correctness tests for focus versus root propagation during navigation
+//!
+//! `%context` and `%rootResource` must keep referring to the evaluation input
+//! however deep navigation and nested lambdas go, while `$this` follows the
+//! current focus. resolve() relies on the root to find bundle entries.
+
+mod common;
+
+use common::strings;
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::{Value, json};
+
+fn patient() -> Value {
+    json!({
+        "resourceType": "Patient",
+        "id": "p1",
+        "name": [
+            {"use": "official", "family": "Chalmers", "given": ["Peter", "James"]},
+            {"use": "usual", "given": ["Jim"]}
+        ],
+        "contact": [
+            {"name": {"family": "du Marché", "given": ["Bénédicte"]}}
+        ]
+    })
+}
+
+fn bundle() -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "id": "b1",
+        "type": "collection",
+        "entry": [
+            {
+                "fullUrl": "http://example.com/Patient/p1",
+                "resource": patient()
+            },
+            {
+                "fullUrl": "http://example.com/Observation/o1",
+                "resource": {
+                    "resourceType": "Observation",
+                    "id": "o1",
+                    "subject": {"reference": "Patient/p1"}
+                }
+            }
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_context_and_root_at_top_level() {
+    assert_eq!(
+        common::items(common::eval("%context.id", patient()).await),
+        strings(&["p1"])
+    );
+    assert_eq!(
+        common::items(common::eval("%rootResource.id", patient()).await),
+        strings(&["p1"])
+    );
+    assert_eq!(
+        common::items(common::eval("%context = %rootResource", patient()).await),
+        vec![FhirPathValue::Boolean(true)]
+    );
+}
+
+#[tokio::test]
+async fn test_root_is_kept_one_level_deep() {
+    // One result per name, each still seeing the whole patient
+    assert_eq!(
+        common::items(common::eval("name.select(%context.id)", patient()).await),
+        strings(&["p1", "p1"])
+    );
+    assert_eq!(
+        common::items(common::eval("name.select(%rootResource.name.count())", patient()).await),
+        vec![FhirPathValue::Integer(2), FhirPathValue::Integer(2)]
+    );
+}
+
+#[tokio::test]
+async fn test_root_is_kept_two_levels_deep() {
+    assert_eq!(
+        common::items(common::eval("name.given.where(%rootResource.id = 'p1')", patient()).await),
+        strings(&["Peter", "James", "Jim"])
+    );
+    assert_eq!(
+        common::items(
+            common::eval("contact.name.select(%context.id & ':' & family)", patient()).await
+        ),
+        strings(&["p1:du Marché"])
+    );
+}
+
+#[tokio::test]
+async fn test_focus_moves_while_root_stays_in_nested_lambdas() {
+    assert_eq!(
+        common::items(
+            common::eval(
+                "name.where(use = 'official').given.select($this & '@' & %rootResource.id)",
+                patient()
+            )
+            .await
+        ),
+        strings(&["Peter@p1", "James@p1"])
+    );
+    assert_eq!(
+        common::items(
+            common::eval(
+                "name.select(given.where($this = 'Jim').select(%context.resourceType))",
+                patient()
+            )
+            .await
+        ),
+        strings(&["Patient"])
+    );
+}
+
+#[tokio::test]
+async fn test_root_inside_bundle_is_the_bundle() {
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Bundle.entry.resource.select(%rootResource.resourceType)",
+                bundle()
+            )
+            .await
+        ),
+        strings(&["Bundle", "Bundle"])
+    );
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Bundle.entry.resource.where(%context.id = 'b1').id",
+                bundle()
+            )
+            .await
+        ),
+        strings(&["p1", "o1"])
+    );
+}
+
+#[tokio::test]
+async fn test_resolve_sees_bundle_root_from_nested_focus() {
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Bundle.entry.resource.where(resourceType = 'Observation').subject.resolve().name.where(use = 'official').family",
+                bundle()
+            )
+            .await
+        ),
+        strings(&["Chalmers"])
+    );
+}
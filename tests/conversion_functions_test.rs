@@ -0,0 +1,261 @@
+//! Tests for the to*() conversion functions and their convertsTo*() predicates
+
+mod common;
+
+use common::string;
+use octofhir_fhirpath::FhirPathValue;
+use rust_decimal::Decimal;
+use serde_json::json;
+use std::str::FromStr;
+
+#[tokio::test]
+async fn test_to_integer() {
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'123'.toInteger()", json!({})).await),
+        FhirPathValue::Integer(123)
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("true.toInteger()", json!({})).await),
+        FhirPathValue::Integer(1)
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("false.toInteger()", json!({})).await),
+        FhirPathValue::Integer(0)
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'abc'.toInteger()", json!({})).await),
+        FhirPathValue::Empty
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("1.5.toInteger()", json!({})).await),
+        FhirPathValue::Empty
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("{}.toInteger()", json!({})).await),
+        FhirPathValue::Empty
+    );
+}
+
+#[tokio::test]
+async fn test_to_decimal_keeps_precision() {
+    // The scale of the string is kept, so the result prints as it was written
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'1.0'.toDecimal()", json!({})).await),
+        FhirPathValue::Decimal(Decimal::from_str("1.0").unwrap())
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'1.0'.toDecimal().toString()", json!({})).await),
+        string("1.0")
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'1.50'.toDecimal().toString()", json!({})).await),
+        string("1.50")
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'1.0'.toDecimal() = 1", json!({})).await),
+        FhirPathValue::Boolean(true)
+    );
+
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'abc'.toDecimal()", json!({})).await),
+        FhirPathValue::Empty
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'1e5'.toDecimal()", json!({})).await),
+        FhirPathValue::Empty
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("true.toDecimal()", json!({})).await),
+        FhirPathValue::Decimal(Decimal::ONE)
+    );
+}
+
+#[tokio::test]
+async fn test_to_string_canonical_forms() {
+    for (expression, expected) in [
+        ("1.toString()", "1"),
+        ("(-7).toString()", "-7"),
+        ("1.50.toString()", "1.50"),
+        ("true.toString()", "true"),
+        ("@2014-12-14.toString()", "2014-12-14"),
+        (
+            "@2014-12-14T10:30:00Z.toString()",
+            "2014-12-14T10:30:00+00:00",
+        ),
+        (
+            "@2014-12-14T10:30:00.123+02:00.toString()",
+            "2014-12-14T10:30:00.123+02:00",
+        ),
+        ("@T10:30:00.500.toString()", "10:30:00.500"),
+        ("5 'mg'.toString()", "5 'mg'"),
+    ] {
+        assert_eq!(
+            common::unwrap_singleton(common::eval(expression, json!({})).await),
+            string(expected),
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_converts_to_predicates() {
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'1.5'.convertsToInteger()", json!({})).await),
+        FhirPathValue::Boolean(false)
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'1.5'.convertsToDecimal()", json!({})).await),
+        FhirPathValue::Boolean(true)
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'yes'.convertsToBoolean()", json!({})).await),
+        FhirPathValue::Boolean(true)
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("2.convertsToBoolean()", json!({})).await),
+        FhirPathValue::Boolean(false)
+    );
+
+    // Empty input gives empty, not false
+    for function in [
+        "convertsToInteger",
+        "convertsToDecimal",
+        "convertsToBoolean",
+    ] {
+        assert_eq!(
+            common::unwrap_singleton(common::eval(&format!("{{}}.{function}()"), json!({})).await),
+            FhirPathValue::Empty,
+            "{function}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_converts_to_agrees_with_conversion() {
+    for input in [
+        "'123'",
+        "'1.5'",
+        "'abc'",
+        "'true'",
+        "'T'",
+        "'0.0'",
+        "''",
+        "1",
+        "0",
+        "2",
+        "1.0",
+        "1.5",
+        "true",
+        "@2024-01-01",
+    ] {
+        for kind in ["Integer", "Decimal", "Boolean"] {
+            let converts = common::unwrap_singleton(
+                common::eval(&format!("{input}.convertsTo{kind}()"), json!({})).await,
+            );
+            let converted = common::unwrap_singleton(
+                common::eval(&format!("{input}.to{kind}().exists()"), json!({})).await,
+            );
+            assert_eq!(converts, converted, "{input} to {kind}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_to_quantity() {
+    for (expression, expected) in [
+        ("'5 \\'mg\\''.toQuantity().toString()", "5 'mg'"),
+        ("'4 days'.toQuantity().toString()", "4 days"),
+        ("'1 year'.toQuantity().toString()", "1 year"),
+        ("'5'.toQuantity().toString()", "5 '1'"),
+        ("'-1.5'.toQuantity().toString()", "-1.5 '1'"),
+        ("2.toQuantity().toString()", "2 '1'"),
+        ("true.toQuantity().toString()", "1.0 '1'"),
+    ] {
+        assert_eq!(
+            common::unwrap_singleton(common::eval(expression, json!({})).await),
+            string(expected),
+            "{expression}"
+        );
+    }
+
+    // Unquoted units must be calendar keywords, and quoted ones UCUM units
+    for input in [
+        "'5 mg'",
+        "'1 wk'",
+        "'1.a'",
+        "'5 \\'not a unit\\''",
+        "'mg'",
+        "''",
+        "'5 mg extra'",
+        "'5 \\'mg\\' extra'",
+        "'5 days extra'",
+        "'.5 \\'mg\\''",
+        "'5. days'",
+    ] {
+        assert_eq!(
+            common::unwrap_singleton(
+                common::eval(&format!("{input}.toQuantity()"), json!({})).await
+            ),
+            FhirPathValue::Empty,
+            "{input}"
+        );
+        assert_eq!(
+            common::unwrap_singleton(
+                common::eval(&format!("{input}.convertsToQuantity()"), json!({})).await
+            ),
+            FhirPathValue::Boolean(false),
+            "{input}"
+        );
+    }
+    assert_eq!(
+        common::unwrap_singleton(common::eval("{}.convertsToQuantity()", json!({})).await),
+        FhirPathValue::Empty
+    );
+}
+
+#[tokio::test]
+async fn test_to_quantity_agrees_with_literals() {
+    for quantity in ["5 'mg'", "4 days", "1 year", "-1.5 'kg'", "0.25 's'"] {
+        let expression = format!(
+            "'{}'.toQuantity() = {quantity}",
+            quantity.replace('\'', "\\'")
+        );
+        assert_eq!(
+            common::unwrap_singleton(common::eval(&expression, json!({})).await),
+            FhirPathValue::Boolean(true),
+            "{expression}"
+        );
+    }
+
+    // Only calendar keywords may follow a number unquoted in either form
+    assert!(octofhir_fhirpath::parse("5 mg").is_err());
+    assert!(octofhir_fhirpath::parse("5 weeks").is_ok());
+}
+
+#[tokio::test]
+async fn test_calendar_durations_are_not_ucum_units() {
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'1 week'.toQuantity() = 1 'wk'", json!({})).await),
+        FhirPathValue::Boolean(true)
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'1 \\'d\\''.toQuantity() = 1 day", json!({})).await),
+        FhirPathValue::Boolean(true)
+    );
+
+    // A calendar year or month has no fixed length, unlike 'a' and 'mo'
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'1 year'.toQuantity() = 1 'a'", json!({})).await),
+        FhirPathValue::Empty
+    );
+    assert_eq!(
+        common::unwrap_singleton(
+            common::eval("'1 \\'mo\\''.toQuantity() = 1 month", json!({})).await
+        ),
+        FhirPathValue::Empty
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'1 month'.toQuantity() = 1 month", json!({})).await),
+        FhirPathValue::Boolean(true)
+    );
+}
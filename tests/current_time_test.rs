@@ -0,0 +1,74 @@
+//! Tests that now(), today() and timeOfDay() read the clock once per evaluation
+
+mod common;
+
+use chrono::FixedOffset;
+use octofhir_fhirpath::registry::functions::TraceSink;
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// An engine whose trace() calls pause, so the clock moves on between the
+/// calls an expression makes
+fn slow_engine() -> FhirPathEngine {
+    let sink: TraceSink = Arc::new(|_: &str, _: &FhirPathValue| {
+        std::thread::sleep(Duration::from_millis(5));
+    });
+    FhirPathEngine::new().with_trace_sink(sink)
+}
+
+#[tokio::test]
+async fn test_current_time_is_stable_within_an_evaluation() {
+    let engine = slow_engine();
+    for expression in [
+        "now().trace('pause') = now()",
+        "timeOfDay().trace('pause') = timeOfDay()",
+        "today().trace('pause') = today()",
+        "(1 | 2 | 3).select(now().trace('pause')).distinct().count() = 1",
+        "today().toString() = now().toString().substring(0, 10)",
+    ] {
+        assert_eq!(
+            common::unwrap_singleton(common::eval_with(&engine, expression, json!({})).await),
+            FhirPathValue::Boolean(true),
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_current_time_moves_between_evaluations() {
+    let engine = slow_engine();
+    let first = common::unwrap_singleton(
+        common::eval_with(&engine, "now().trace('pause')", json!({})).await,
+    );
+    let second = common::unwrap_singleton(common::eval_with(&engine, "now()", json!({})).await);
+    match (first, second) {
+        (FhirPathValue::DateTime(first), FhirPathValue::DateTime(second)) => {
+            assert!(first.datetime < second.datetime)
+        }
+        other => panic!("expected two date times, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_configured_timezone() {
+    let offset = FixedOffset::east_opt(10 * 3600).unwrap();
+    let engine = slow_engine().with_timezone(offset);
+
+    match common::unwrap_singleton(common::eval_with(&engine, "now()", json!({})).await) {
+        FhirPathValue::DateTime(now) => assert_eq!(*now.datetime.offset(), offset),
+        other => panic!("expected a date time, got {other:?}"),
+    }
+    let now =
+        common::unwrap_singleton(common::eval_with(&engine, "now().toString()", json!({})).await);
+    assert!(now.to_string().ends_with("+10:00"), "{now}");
+
+    // The timezone survives configuring functions after it
+    let engine = FhirPathEngine::new()
+        .with_timezone(offset)
+        .with_trace_sink(Arc::new(|_: &str, _: &FhirPathValue| {}));
+    let now =
+        common::unwrap_singleton(common::eval_with(&engine, "now().toString()", json!({})).await);
+    assert!(now.to_string().ends_with("+10:00"), "{now}");
+}
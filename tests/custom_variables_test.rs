@@ -0,0 +1,109 @@
+//! Tests for `%` variables supplied by the caller
+
+mod common;
+
+use common::strings;
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use serde_json::{Value, json};
+
+fn patient() -> Value {
+    json!({
+        "resourceType": "Patient",
+        "id": "example",
+        "gender": "male",
+        "name": [
+            {"family": "Chalmers", "given": ["Peter", "James"]},
+            {"family": "Windsor", "given": ["Jim"]}
+        ]
+    })
+}
+
+fn engine() -> FhirPathEngine {
+    let mut engine = FhirPathEngine::new();
+    engine.set_variable("family", FhirPathValue::String("Windsor".into()));
+    engine.set_variable(
+        "genders",
+        FhirPathValue::collection(vec![
+            FhirPathValue::String("male".into()),
+            FhirPathValue::String("female".into()),
+        ]),
+    );
+    engine
+}
+
+#[tokio::test]
+async fn test_string_variable() {
+    let engine = engine();
+    assert_eq!(
+        common::items(common::eval_with(&engine, "%family", patient()).await),
+        strings(&["Windsor"])
+    );
+    assert_eq!(
+        common::items(
+            common::eval_with(
+                &engine,
+                "Patient.name.where(family = %family).given",
+                patient()
+            )
+            .await
+        ),
+        strings(&["Jim"])
+    );
+}
+
+#[tokio::test]
+async fn test_collection_variable() {
+    let engine = engine();
+    assert_eq!(
+        common::items(common::eval_with(&engine, "%genders", patient()).await),
+        strings(&["male", "female"])
+    );
+    assert_eq!(
+        common::items(common::eval_with(&engine, "%genders.count()", patient()).await),
+        vec![FhirPathValue::Integer(2)]
+    );
+    assert_eq!(
+        common::items(common::eval_with(&engine, "Patient.gender in %genders", patient()).await),
+        vec![FhirPathValue::Boolean(true)]
+    );
+}
+
+#[tokio::test]
+async fn test_setting_a_variable_again_replaces_it() {
+    let mut engine = engine();
+    engine.set_variable("family", FhirPathValue::String("Chalmers".into()));
+    assert_eq!(
+        common::items(
+            common::eval_with(
+                &engine,
+                "Patient.name.where(family = %family).given",
+                patient()
+            )
+            .await
+        ),
+        strings(&["Peter", "James"])
+    );
+}
+
+#[tokio::test]
+async fn test_variables_do_not_hide_built_ins() {
+    let mut engine = engine();
+    engine.set_variable("resource", FhirPathValue::String("shadowed".into()));
+    assert_eq!(
+        common::items(common::eval_with(&engine, "%resource.id", patient()).await),
+        strings(&["example"])
+    );
+    assert_eq!(
+        common::items(common::eval_with(&engine, "%ucum", patient()).await),
+        strings(&["http://unitsofmeasure.org"])
+    );
+}
+
+#[tokio::test]
+async fn test_unknown_variable_is_an_error() {
+    let error = engine()
+        .evaluate("Patient.name.where(family = %surname)", patient())
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("%surname"), "{error}");
+}
@@ -0,0 +1,97 @@
+//! Tests for adding and subtracting time-valued quantities to dates and times
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::json;
+
+/// Evaluate `expression` and render its single result with `toString()`
+async fn eval_string(expression: &str) -> Option<String> {
+    let result = common::eval(&format!("({expression}).toString()"), json!({})).await;
+    match result {
+        FhirPathValue::Collection(items) if items.len() == 1 => match items.get(0) {
+            Some(FhirPathValue::String(s)) => Some(s.to_string()),
+            other => panic!("{expression} gave {other:?}"),
+        },
+        FhirPathValue::Collection(items) if items.is_empty() => None,
+        FhirPathValue::Empty => None,
+        other => panic!("{expression} gave {other:?}"),
+    }
+}
+
+async fn assert_results(cases: &[(&str, Option<&str>)]) {
+    for (expression, expected) in cases {
+        assert_eq!(
+            eval_string(expression).await.as_deref(),
+            *expected,
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_calendar_months_clamp_to_month_end() {
+    assert_results(&[
+        ("@2013-01-01 + 1 year", Some("2014-01-01")),
+        ("@2013-01-31 + 1 month", Some("2013-02-28")),
+        ("@2012-01-31 + 1 month", Some("2012-02-29")),
+        ("@2012-02-29 + 1 year", Some("2013-02-28")),
+        ("@2012-02-29 + 4 years", Some("2016-02-29")),
+        ("@2013-03-31 - 1 month", Some("2013-02-28")),
+        ("@2012-03-31 - 1 month", Some("2012-02-29")),
+        ("@2013-01-31 + 13 months", Some("2014-02-28")),
+        (
+            "@2012-02-29T10:30:00+10:00 + 1 year",
+            Some("2013-02-28T10:30:00+10:00"),
+        ),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_result_keeps_input_precision() {
+    assert_results(&[
+        ("@2013 + 1 year", Some("2014")),
+        ("@2014 + 24 months", Some("2016")),
+        ("@2014 + 18 months", Some("2015")),
+        ("@2013-01 + 1 month", Some("2013-02")),
+        ("@2013-01-01 + 2 weeks", Some("2013-01-15")),
+        ("@2013-01-01 + 7.7 days", Some("2013-01-08")),
+        ("@2013-01-01T10:00 + 90 minutes", Some("2013-01-01T11:30")),
+        (
+            "@2013-01-01T10:00:00.000+10:00 + 0.1 's'",
+            Some("2013-01-01T10:00:00.100+10:00"),
+        ),
+        ("@T10:00 + 90 seconds", Some("10:01")),
+        ("@T23:00:00 + 2 hours", Some("01:00:00")),
+        ("@T00:30:00 - 1 hour", Some("23:30:00")),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_ucum_years_and_months_are_not_calendar_durations() {
+    // 'a' and 'mo' are 365.25 and 30.4375 days, not calendar units
+    assert_results(&[
+        ("@2013-01-01 + 1 'a'", None),
+        ("@2013-01-01 + 1 'mo'", None),
+        ("@2013-01-01T10:00:00Z - 1 'a'", None),
+        ("@2013-01-01 + 1 'd'", Some("2013-01-02")),
+        ("@2013-01-01 + 1 'cm'", None),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_unsupported_operands_are_errors() {
+    for expression in [
+        "@2013-01-01 - @2012-01-01",
+        "@2013-01-01 + 25 hours",
+        "@T10:00 + 1 day",
+    ] {
+        assert!(
+            common::try_eval(expression, json!({})).await.is_err(),
+            "{expression}"
+        );
+    }
+}
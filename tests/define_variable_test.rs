@@ -0,0 +1,151 @@
+//! Tests for defineVariable() scoping and name checks
+
+mod common;
+
+use common::strings;
+use serde_json::{Value, json};
+
+fn patient() -> Value {
+    json!({
+        "resourceType": "Patient",
+        "id": "example",
+        "name": [
+            {"family": "Chalmers", "given": ["Peter", "James"]},
+            {"family": "Windsor", "given": ["Jim"]}
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_variable_is_visible_downstream() {
+    let single_name = json!({
+        "resourceType": "Patient",
+        "name": [{"family": "Chalmers", "given": ["Peter", "James"]}]
+    });
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Patient.name.defineVariable('fname', family).given.select(%fname + ' ' + $this)",
+                single_name
+            )
+            .await
+        ),
+        strings(&["Chalmers Peter", "Chalmers James"])
+    );
+
+    // Defined per name inside select(), so each name sees its own family
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Patient.name.select(defineVariable('fname', family).given.select($this + ' ' + %fname))",
+                patient()
+            )
+            .await
+        ),
+        strings(&["Peter Chalmers", "James Chalmers", "Jim Windsor"])
+    );
+
+    // Without a value the variable holds the input
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Patient.name.first().defineVariable('n').given.select(%n.family)",
+                patient()
+            )
+            .await
+        ),
+        strings(&["Chalmers", "Chalmers"])
+    );
+}
+
+#[tokio::test]
+async fn test_nested_scopes_shadow_outer_variables() {
+    // An expression argument starts a new scope, so the name can be reused there
+    assert_eq!(
+        common::items(
+            common::eval(
+                "defineVariable('v', 'outer').select(defineVariable('v', 'inner').select(%v))",
+                patient()
+            )
+            .await
+        ),
+        strings(&["inner"])
+    );
+    // and the outer value is back once the argument is done
+    assert_eq!(
+        common::items(
+            common::eval(
+                "defineVariable('v', 'outer').select(defineVariable('v', 'inner').select(%v)).select(%v)",
+                patient()
+            )
+            .await
+        ),
+        strings(&["outer"])
+    );
+}
+
+#[tokio::test]
+async fn test_scope_boundaries() {
+    // Each side of a union has its own scope
+    assert_eq!(
+        common::items(
+            common::eval(
+                "defineVariable('v', 'a').select(%v) | defineVariable('v', 'b').select(%v)",
+                patient()
+            )
+            .await
+        ),
+        strings(&["a", "b"])
+    );
+    // A variable defined inside an argument does not escape it
+    let message = common::try_eval(
+        "Patient.name.where(defineVariable('f', family).exists()).select(%f)",
+        patient(),
+    )
+    .await
+    .expect_err("Patient.name.where(defineVariable('f', family).exists()).select(%f)")
+    .to_string();
+    assert!(message.contains("%f"), "{message}");
+    // A variable defined earlier in the chain is visible inside later arguments
+    assert_eq!(
+        common::items(
+            common::eval(
+                "defineVariable('v', 'Windsor').name.where(family = %v).given",
+                patient()
+            )
+            .await
+        ),
+        strings(&["Jim"])
+    );
+}
+
+#[tokio::test]
+async fn test_redefining_in_the_same_scope_fails() {
+    let message = common::try_eval(
+        "defineVariable('v', 'a').defineVariable('v', 'b')",
+        patient(),
+    )
+    .await
+    .expect_err("defineVariable('v', 'a').defineVariable('v', 'b')")
+    .to_string();
+    assert!(message.contains("defineVariable"), "{message}");
+    assert!(
+        message.contains("variable '%v' is already defined"),
+        "{message}"
+    );
+}
+
+#[tokio::test]
+async fn test_system_variables_cannot_be_redefined() {
+    for name in ["context", "resource", "rootResource", "ucum", "sct", "this"] {
+        let expression = format!("defineVariable('{name}', 'x')");
+        let message = common::try_eval(&expression, patient())
+            .await
+            .expect_err(&expression)
+            .to_string();
+        assert!(
+            message.contains(&format!("cannot redefine system variable '%{name}'")),
+            "{name}: {message}"
+        );
+    }
+}
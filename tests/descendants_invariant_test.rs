@@ -0,0 +1,135 @@
+//! Tests for `descendants()...all()` invariants
+//!
+//! How many nodes the lazy evaluation visits is tested next to the traversal;
+//! these check the results. Nodes stored under a choice element key such as
+//! `valueReference` know their type, so `ofType(Reference)` finds them.
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::{Value, json};
+
+fn boolean(value: bool) -> FhirPathValue {
+    FhirPathValue::collection(vec![FhirPathValue::Boolean(value)])
+}
+
+fn patient(second_reference: Value) -> Value {
+    json!({
+        "resourceType": "Patient",
+        "extension": [
+            {
+                "url": "http://example.org/referrer",
+                "valueReference": {"reference": "Practitioner/1", "display": "Dr Who"}
+            },
+            {"url": "http://example.org/note", "valueString": "seen twice"},
+            {"url": "http://example.org/insurer", "valueReference": second_reference},
+            {
+                "url": "http://example.org/pharmacy",
+                "valueReference": {"reference": "Organization/3"}
+            }
+        ],
+        "name": [{"family": "Doe", "given": ["Jane", "Ann"]}]
+    })
+}
+
+#[tokio::test]
+async fn test_all_fails_for_violating_reference() {
+    let expression = "descendants().ofType(Reference).all(reference.exists())";
+    let result = common::eval(expression, patient(json!({"display": "Acme"}))).await;
+    assert_eq!(result, boolean(false));
+}
+
+#[tokio::test]
+async fn test_all_holds_for_valid_references() {
+    let expression = "descendants().ofType(Reference).all(reference.exists())";
+    let result = common::eval(
+        expression,
+        patient(json!({"reference": "Organization/2", "display": "Acme"})),
+    )
+    .await;
+    assert_eq!(result, boolean(true));
+}
+
+#[tokio::test]
+async fn test_choice_elements_know_their_type() {
+    let result = common::eval(
+        "descendants().ofType(Reference).display",
+        patient(json!({"display": "Acme"})),
+    )
+    .await;
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![
+            FhirPathValue::String("Dr Who".into()),
+            FhirPathValue::String("Acme".into()),
+        ])
+    );
+
+    // valueString is not an object, and `extension` is no choice element
+    let result = common::eval(
+        "descendants().ofType(Extension).count()",
+        patient(json!({"display": "Acme"})),
+    )
+    .await;
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(0)])
+    );
+}
+
+#[tokio::test]
+async fn test_of_type_filters_nodes_before_all() {
+    let bundle = json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": [
+            {"resource": {"resourceType": "Patient", "id": "p1", "active": true}},
+            {"resource": {"resourceType": "Observation", "id": "o1", "status": "final"}},
+            {"resource": {"resourceType": "Patient", "id": "p2", "active": false}},
+            {"resource": {"resourceType": "Patient", "id": "p3", "active": true}}
+        ]
+    });
+
+    let result = common::eval("descendants().ofType(Patient).all(active)", bundle.clone()).await;
+    assert_eq!(result, boolean(false));
+
+    let result = common::eval(
+        "Bundle.descendants().ofType(FHIR.Observation).all(status = 'final')",
+        bundle,
+    )
+    .await;
+    assert_eq!(result, boolean(true));
+}
+
+#[tokio::test]
+async fn test_empty_descendants_satisfy_all() {
+    let result = common::eval(
+        "descendants().where(false).all(false)",
+        json!({"resourceType": "Patient", "id": "p1"}),
+    )
+    .await;
+    assert_eq!(result, boolean(true));
+}
+
+#[tokio::test]
+async fn test_resource_is_the_entry_inside_lazy_lambdas() {
+    let result = common::eval(
+        "Bundle.entry.resource.where(children().where(%resource.id = 'p2').exists()).id",
+        common::bundle(),
+    )
+    .await;
+    assert_eq!(result, FhirPathValue::collection(common::strings(&["p2"])));
+
+    let result = common::eval(
+        "Bundle.entry.resource.select(descendants().all(%resource.active.exists()))",
+        common::bundle(),
+    )
+    .await;
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![
+            FhirPathValue::Boolean(true),
+            FhirPathValue::Boolean(false)
+        ])
+    );
+}
@@ -0,0 +1,313 @@
+//! Tests for the ordering guarantees of the descendants() function
+
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use serde_json::json;
+
+/// Render each item so the ordering can be compared as a flat list
+fn describe(result: &FhirPathValue) -> Vec<String> {
+    let FhirPathValue::Collection(items) = result else {
+        panic!("Expected collection result, got {result:?}");
+    };
+    items
+        .iter()
+        .map(|item| match item {
+            FhirPathValue::String(s) => s.to_string(),
+            FhirPathValue::Boolean(b) => b.to_string(),
+            FhirPathValue::Integer(i) => i.to_string(),
+            FhirPathValue::Resource(_) => "<object>".to_string(),
+            other => panic!("Unexpected descendant {other:?}"),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_descendants_document_order() {
+    // Field order is deliberately not alphabetical
+    let patient = json!({
+        "resourceType": "Patient",
+        "name": [
+            {
+                "use": "official",
+                "given": ["Jane", "Q"],
+                "family": "Doe"
+            },
+            {
+                "text": "Janie"
+            }
+        ],
+        "active": true,
+        "contact": [{
+            "telecom": [{"value": "555-1234", "rank": 1}]
+        }]
+    });
+
+    let engine = FhirPathEngine::new();
+    let result = engine
+        .evaluate("Patient.descendants()", patient)
+        .await
+        .expect("Should evaluate successfully");
+
+    assert_eq!(
+        describe(&result),
+        vec![
+            "<object>", "official", "Jane", "Q", "Doe", "<object>", "Janie", "true", "<object>",
+            "<object>", "555-1234", "1",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_descendants_is_reproducible() {
+    let observation = json!({
+        "resourceType": "Observation",
+        "status": "final",
+        "code": {"coding": [{"system": "http://loinc.org", "code": "1234-5"}], "text": "Test"},
+        "component": [
+            {"code": {"text": "A"}},
+            {"code": {"text": "B"}}
+        ]
+    });
+
+    let engine = FhirPathEngine::new();
+    let first = engine
+        .evaluate("Observation.component.descendants()", observation.clone())
+        .await
+        .expect("Should evaluate successfully");
+    assert_eq!(describe(&first), vec!["<object>", "A", "<object>", "B"]);
+
+    for _ in 0..5 {
+        let again = FhirPathEngine::new()
+            .evaluate("Observation.descendants()", observation.clone())
+            .await
+            .expect("Should evaluate successfully");
+        assert_eq!(
+            describe(&again),
+            vec![
+                "final",
+                "<object>",
+                "<object>",
+                "http://loinc.org",
+                "1234-5",
+                "Test",
+                "<object>",
+                "<object>",
+                "A",
+                "<object>",
+                "<object>",
+                "B",
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_descendants_and_children_with_paths() {
+    use octofhir_fhirpath::registry::functions::collection::{
+        ChildrenFunction, DescendantsFunction,
+    };
+
+    let patient = FhirPathValue::resource_from_json(json!({
+        "resourceType": "Patient",
+        "name": [{"given": ["Jane", "Q"], "family": "Doe"}],
+        "active": true
+    }));
+
+    let paths = |nodes: Vec<(String, FhirPathValue)>| {
+        nodes.into_iter().map(|(path, _)| path).collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        paths(DescendantsFunction::descendants_with_paths(
+            &patient, "Patient"
+        )),
+        vec![
+            "Patient.name[0]",
+            "Patient.name[0].given[0]",
+            "Patient.name[0].given[1]",
+            "Patient.name[0].family",
+            "Patient.active",
+        ]
+    );
+    assert_eq!(
+        paths(ChildrenFunction::children_with_paths(&patient, "Patient")),
+        vec!["Patient.name[0]", "Patient.active"]
+    );
+
+    let nodes = DescendantsFunction::descendants_with_paths(&patient, "Patient");
+    let (_, given) = &nodes[2];
+    assert_eq!(given, &FhirPathValue::String("Q".into()));
+}
+
+#[tokio::test]
+async fn test_primitive_arrays_are_traversed_element_wise() {
+    let patient = json!({
+        "resourceType": "Patient",
+        "address": [
+            {"line": ["1 Main St", "Apt 2", "Building C"], "city": "Springfield"},
+            {"line": ["PO Box 9"]}
+        ]
+    });
+
+    let engine = FhirPathEngine::new();
+    let children = engine
+        .evaluate("Patient.address.children()", patient.clone())
+        .await
+        .expect("Should evaluate successfully");
+    assert_eq!(
+        describe(&children),
+        vec![
+            "1 Main St",
+            "Apt 2",
+            "Building C",
+            "Springfield",
+            "PO Box 9"
+        ]
+    );
+
+    let descendants = engine
+        .evaluate("Patient.descendants()", patient.clone())
+        .await
+        .expect("Should evaluate successfully");
+    // Two addresses, four lines and the city
+    assert_eq!(describe(&descendants).len(), 7);
+
+    let count = engine
+        .evaluate("Patient.address.descendants().count()", patient)
+        .await
+        .expect("Should evaluate successfully");
+    assert_eq!(
+        count,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(5)])
+    );
+}
+
+fn patient_with_primitive_extensions() -> serde_json::Value {
+    json!({
+        "resourceType": "Patient",
+        "id": "p1",
+        "birthDate": "1970-03-30",
+        "_birthDate": {
+            "extension": [{"url": "http://example.org/birthTime", "valueDateTime": "1970-03-30T14:30:00Z"}]
+        },
+        "name": [{
+            "given": ["Jane", "Q"],
+            "_given": [null, {"extension": [{"url": "http://example.org/initial", "valueBoolean": true}]}]
+        }],
+        "contained": [{
+            "resourceType": "Patient",
+            "id": "self",
+            "link": [{"other": {"reference": "#"}, "type": "seealso"}]
+        }]
+    })
+}
+
+#[tokio::test]
+async fn test_children_skip_resource_type_and_shadow_properties() {
+    let engine = FhirPathEngine::new();
+    let result = engine
+        .evaluate("Patient.children()", patient_with_primitive_extensions())
+        .await
+        .expect("Should evaluate successfully");
+    assert_eq!(
+        describe(&result),
+        vec!["p1", "1970-03-30", "<object>", "<object>"]
+    );
+
+    let result = engine
+        .evaluate(
+            "Patient.name.children()",
+            patient_with_primitive_extensions(),
+        )
+        .await
+        .expect("Should evaluate successfully");
+    assert_eq!(describe(&result), vec!["Jane", "Q"]);
+}
+
+#[test]
+fn test_primitive_extensions_are_descendants_of_the_primitive() {
+    use octofhir_fhirpath::registry::functions::collection::DescendantsFunction;
+
+    let patient = FhirPathValue::resource_from_json(patient_with_primitive_extensions());
+    let paths: Vec<String> = DescendantsFunction::descendants_with_paths(&patient, "Patient")
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+
+    assert_eq!(
+        paths,
+        vec![
+            "Patient.id",
+            "Patient.birthDate",
+            "Patient.birthDate.extension[0]",
+            "Patient.birthDate.extension[0].url",
+            "Patient.birthDate.extension[0].valueDateTime",
+            "Patient.name[0]",
+            "Patient.name[0].given[0]",
+            "Patient.name[0].given[1]",
+            "Patient.name[0].given[1].extension[0]",
+            "Patient.name[0].given[1].extension[0].url",
+            "Patient.name[0].given[1].extension[0].valueBoolean",
+            "Patient.contained[0]",
+            "Patient.contained[0].id",
+            "Patient.contained[0].link[0]",
+            "Patient.contained[0].link[0].other",
+            "Patient.contained[0].link[0].other.reference",
+            "Patient.contained[0].link[0].type",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_descendants_of_self_referencing_resource_terminate() {
+    // References are not followed, so a resource linking to itself is walked once
+    let engine = FhirPathEngine::new();
+    let result = engine
+        .evaluate(
+            "Patient.contained.descendants().count()",
+            patient_with_primitive_extensions(),
+        )
+        .await
+        .expect("Should evaluate successfully");
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(5)])
+    );
+}
+
+#[tokio::test]
+async fn test_nested_arrays_are_flattened() {
+    let engine = FhirPathEngine::new();
+    let result = engine
+        .evaluate(
+            "children()",
+            json!({"resourceType": "Basic", "matrix": [[1, 2], [3]], "empty": []}),
+        )
+        .await
+        .expect("Should evaluate successfully");
+    assert_eq!(describe(&result), vec!["1", "2", "3"]);
+}
+
+#[tokio::test]
+async fn test_contained_resources_are_typed_but_backbone_elements_are_not() {
+    // The backbone element has the same fields as the contained Medication,
+    // only the contained entry carries a resourceType
+    let request = json!({
+        "resourceType": "MedicationRequest",
+        "contained": [{"resourceType": "Medication", "id": "med1", "code": {"text": "Aspirin"}}],
+        "dispenseRequest": {"id": "dispense", "code": {"text": "Aspirin"}}
+    });
+
+    let engine = FhirPathEngine::new();
+    for (expression, expected) in [
+        ("descendants().ofType(Medication).id", vec!["med1"]),
+        ("descendants().ofType(FHIR.Resource).id", vec!["med1"]),
+        ("descendants().where($this is Medication).id", vec!["med1"]),
+        ("dispenseRequest.ofType(Medication).id", vec![]),
+    ] {
+        let result = engine
+            .evaluate(expression, request.clone())
+            .await
+            .expect("Should evaluate successfully");
+        assert_eq!(describe(&result), expected, "{expression}");
+    }
+}
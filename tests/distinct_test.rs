@@ -0,0 +1,80 @@
+//! Tests for distinct() and isDistinct() equality semantics
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::{Value, json};
+
+fn bundle() -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "entry": [
+            {"resource": {"resourceType": "Observation", "id": "a", "valueInteger": 1}},
+            {"resource": {"resourceType": "Observation", "id": "b", "valueInteger": 2}},
+            {"resource": {"resourceType": "Observation", "valueInteger": 1.0, "id": "a"}}
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_distinct_treats_integer_and_decimal_as_equal() {
+    let result = common::eval("1.combine(1.0).combine(2).distinct().count()", json!({})).await;
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(2)])
+    );
+    let result = common::eval("1.combine(1.0).isDistinct()", json!({})).await;
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::Boolean(false)])
+    );
+}
+
+#[tokio::test]
+async fn test_distinct_compares_resources_by_content() {
+    let result = common::eval("entry.resource.distinct().id", bundle()).await;
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![
+            FhirPathValue::String("a".into()),
+            FhirPathValue::String("b".into()),
+        ])
+    );
+    let result = common::eval("entry.resource.isDistinct()", bundle()).await;
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::Boolean(false)])
+    );
+    let result = common::eval("entry.resource.take(2).isDistinct()", bundle()).await;
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::Boolean(true)])
+    );
+}
+
+#[tokio::test]
+async fn test_distinct_keeps_first_occurrence_order() {
+    let result = common::eval(
+        "('b' | 'c').combine('a').combine('b').distinct()",
+        json!({}),
+    )
+    .await;
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![
+            FhirPathValue::String("b".into()),
+            FhirPathValue::String("c".into()),
+            FhirPathValue::String("a".into()),
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_empty_input() {
+    assert!(common::eval("{}.distinct()", json!({})).await.is_empty());
+    let result = common::eval("{}.isDistinct()", json!({})).await;
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::Boolean(true)])
+    );
+}
@@ -0,0 +1,32 @@
+//! Tests that empty and whitespace-only expressions are reported as errors
+
+use octofhir_fhirpath::engine::FhirPathEngine;
+use octofhir_fhirpath::parse;
+use octofhir_fhirpath::parser::ParseError;
+use serde_json::json;
+
+const EMPTY_EXPRESSIONS: [&str; 5] = ["", "   ", "\n", " \t\r\n ", "// just a comment"];
+
+#[test]
+fn test_parse_empty_expression() {
+    for expression in EMPTY_EXPRESSIONS {
+        let error = parse(expression).unwrap_err();
+        assert_eq!(error, ParseError::EmptyExpression, "{expression:?}");
+        assert_eq!(error.to_string(), "Empty expression");
+    }
+}
+
+#[tokio::test]
+async fn test_evaluate_empty_expression() {
+    let engine = FhirPathEngine::new();
+    for expression in EMPTY_EXPRESSIONS {
+        let error = engine
+            .evaluate(expression, json!({}))
+            .await
+            .expect_err(expression);
+        assert!(
+            error.to_string().contains("Empty expression"),
+            "{expression:?}: {error}"
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! Tests for encode() and decode() beyond the official suite
+
+mod common;
+
+use common::string;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_encode_decode_round_trip() {
+    for format in ["base64", "urlbase64", "hex"] {
+        let expression = format!("'test ?_~ ü'.encode('{format}').decode('{format}')");
+        assert_eq!(
+            common::eval(&expression, json!({})).await,
+            string("test ?_~ ü"),
+            "{format}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_decode_urlbase64_without_padding() {
+    assert_eq!(
+        common::eval("'c3ViamVjdHM_X2Q'.decode('urlbase64')", json!({})).await,
+        string("subjects?_d")
+    );
+}
+
+#[tokio::test]
+async fn test_decode_invalid_base64_is_empty() {
+    assert!(
+        common::eval("'not base64!'.decode('base64')", json!({}))
+            .await
+            .is_empty()
+    );
+    assert!(
+        common::eval("'dGVzdA'.decode('base64')", json!({}))
+            .await
+            .is_empty()
+    );
+    // Valid base64 that is not UTF-8 text
+    assert!(
+        common::eval("'/w=='.decode('base64')", json!({}))
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_unknown_format_lists_supported_formats() {
+    for function in ["encode", "decode"] {
+        let err = common::try_eval(&format!("'test'.{function}('rot13')"), json!({}))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("rot13 (supported: base64, urlbase64, hex, uri, html)"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Tests for the strict and lenient engine presets
+
+use octofhir_fhir_model::provider::{
+    BoxedValueWithMetadata, EmptyModelProvider, ExpressionAnalysis, NavigationValidation,
+    PrimitiveExtensionData,
+};
+use octofhir_fhir_model::{
+    ConformanceResult, ConstraintInfo, FhirVersion, ModelProvider, ResolutionContext,
+    SearchParameter, StructureDefinition, TypeReflectionInfo, ValueReflection,
+};
+use octofhir_fhirpath::registry::functions::{DanglingReferences, ResolveMode};
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use serde_json::json;
+use std::sync::Arc;
+
+/// Model describing just enough of Patient to check element names
+#[derive(Debug, Default)]
+struct PatientModel {
+    empty: EmptyModelProvider,
+}
+
+fn fhir_type(name: &str) -> TypeReflectionInfo {
+    TypeReflectionInfo::SimpleType {
+        namespace: "FHIR".to_string(),
+        name: name.to_string(),
+        base_type: None,
+    }
+}
+
+impl ModelProvider for PatientModel {
+    fn get_type_reflection(&self, type_name: &str) -> Option<TypeReflectionInfo> {
+        matches!(type_name, "Patient" | "HumanName").then(|| fhir_type(type_name))
+    }
+
+    fn get_element_reflection(
+        &self,
+        parent_type: &str,
+        element: &str,
+    ) -> Option<TypeReflectionInfo> {
+        self.get_property_type(parent_type, element)
+    }
+
+    fn get_property_type(&self, parent_type: &str, property: &str) -> Option<TypeReflectionInfo> {
+        match (parent_type, property) {
+            ("Patient", "id") => Some(fhir_type("id")),
+            ("Patient", "name") => Some(fhir_type("HumanName")),
+            ("Patient", "generalPractitioner") => Some(fhir_type("Reference")),
+            ("HumanName", "family" | "given") => Some(fhir_type("string")),
+            _ => None,
+        }
+    }
+
+    fn get_structure_definition(&self, url: &str) -> Option<StructureDefinition> {
+        self.empty.get_structure_definition(url)
+    }
+
+    fn validate_conformance(
+        &self,
+        value: &dyn ValueReflection,
+        profile_url: &str,
+    ) -> octofhir_fhir_model::Result<ConformanceResult> {
+        self.empty.validate_conformance(value, profile_url)
+    }
+
+    fn get_constraints(&self, type_name: &str) -> Vec<ConstraintInfo> {
+        self.empty.get_constraints(type_name)
+    }
+
+    fn resolve_reference(
+        &self,
+        reference: &str,
+        context: &dyn ResolutionContext,
+    ) -> Option<Box<dyn ValueReflection>> {
+        self.empty.resolve_reference(reference, context)
+    }
+
+    fn analyze_expression(
+        &self,
+        expression: &str,
+    ) -> octofhir_fhir_model::Result<ExpressionAnalysis> {
+        self.empty.analyze_expression(expression)
+    }
+
+    fn box_value_with_metadata(
+        &self,
+        value: &dyn ValueReflection,
+        navigation_path: &str,
+    ) -> octofhir_fhir_model::Result<BoxedValueWithMetadata> {
+        self.empty.box_value_with_metadata(value, navigation_path)
+    }
+
+    fn extract_primitive_extensions(
+        &self,
+        parent_value: &dyn ValueReflection,
+        property_name: &str,
+    ) -> Option<PrimitiveExtensionData> {
+        self.empty
+            .extract_primitive_extensions(parent_value, property_name)
+    }
+
+    fn get_search_params(&self, resource_type: &str) -> Vec<SearchParameter> {
+        self.empty.get_search_params(resource_type)
+    }
+
+    fn is_resource_type(&self, type_name: &str) -> bool {
+        type_name == "Patient"
+    }
+
+    fn fhir_version(&self) -> FhirVersion {
+        FhirVersion::R4
+    }
+
+    fn is_subtype_of(&self, child_type: &str, parent_type: &str) -> bool {
+        self.empty.is_subtype_of(child_type, parent_type)
+    }
+
+    fn get_properties(&self, type_name: &str) -> Vec<(String, TypeReflectionInfo)> {
+        self.empty.get_properties(type_name)
+    }
+
+    fn get_base_type(&self, type_name: &str) -> Option<String> {
+        self.empty.get_base_type(type_name)
+    }
+
+    fn validate_navigation_path(
+        &self,
+        base_type: &str,
+        path: &str,
+    ) -> octofhir_fhir_model::Result<NavigationValidation> {
+        self.empty.validate_navigation_path(base_type, path)
+    }
+}
+
+fn patient() -> serde_json::Value {
+    json!({
+        "resourceType": "Patient",
+        "id": "pat1",
+        "contained": [{"resourceType": "Practitioner", "id": "pr1"}],
+        "name": [{"family": "Chalmers", "given": ["Peter"]}],
+        "generalPractitioner": [
+            {"reference": "Practitioner/missing"},
+            {"reference": "pr1"}
+        ]
+    })
+}
+
+fn model() -> Arc<dyn ModelProvider> {
+    Arc::new(PatientModel::default())
+}
+
+fn len(value: &FhirPathValue) -> usize {
+    match value {
+        FhirPathValue::Collection(items) => items.len(),
+        FhirPathValue::Empty => 0,
+        _ => 1,
+    }
+}
+
+#[tokio::test]
+async fn test_strict_preset_errors_on_dangling_reference() {
+    let err = FhirPathEngine::strict()
+        .evaluate("Patient.generalPractitioner.first().resolve()", patient())
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Reference 'Practitioner/missing' could not be resolved"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn test_lenient_preset_drops_dangling_reference() {
+    // The dangling reference is dropped and the bare contained id still resolves
+    let result = FhirPathEngine::lenient()
+        .evaluate("Patient.generalPractitioner.resolve().id", patient())
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::String("pr1".into())])
+    );
+
+    // The default engine does not match the bare id
+    let result = FhirPathEngine::new()
+        .evaluate("Patient.generalPractitioner.resolve().id", patient())
+        .await
+        .unwrap();
+    assert!(result.is_empty(), "{result:?}");
+}
+
+#[tokio::test]
+async fn test_dangling_references_setting_keeps_lenient_matching() {
+    let engine = FhirPathEngine::lenient().with_dangling_references(DanglingReferences::Error);
+
+    // The bare contained id still resolves, the dangling reference now fails
+    let result = engine
+        .evaluate("Patient.generalPractitioner.last().resolve().id", patient())
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::String("pr1".into())])
+    );
+    let err = engine
+        .evaluate("Patient.generalPractitioner.resolve()", patient())
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Reference 'Practitioner/missing' could not be resolved"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn test_default_engine_drops_dangling_reference() {
+    let result = FhirPathEngine::new()
+        .evaluate("Patient.generalPractitioner.first().resolve()", patient())
+        .await
+        .unwrap();
+    assert_eq!(len(&result), 0);
+}
+
+#[tokio::test]
+async fn test_placeholder_mode_keeps_dangling_reference() {
+    let result = FhirPathEngine::new()
+        .with_resolve_mode(ResolveMode::Placeholder)
+        .evaluate(
+            "Patient.generalPractitioner.first().resolve().id",
+            patient(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::String("missing".into())])
+    );
+}
+
+#[tokio::test]
+async fn test_placeholder_mode_keeps_lenient_matching() {
+    let result = FhirPathEngine::lenient()
+        .with_resolve_mode(ResolveMode::Placeholder)
+        .evaluate("Patient.generalPractitioner.resolve().id", patient())
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![
+            FhirPathValue::String("missing".into()),
+            FhirPathValue::String("pr1".into()),
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_strict_preset_errors_on_misspelled_field() {
+    let engine = FhirPathEngine::strict().with_model_provider(model());
+
+    let err = engine
+        .evaluate("Patient.nmae.given", patient())
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Unknown element 'nmae' on type 'Patient'"),
+        "{err}"
+    );
+
+    // Elements of nested types are checked too
+    let err = engine
+        .evaluate("name.where(use = 'official').gven", patient())
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Unknown element 'gven' on type 'HumanName'"),
+        "{err}"
+    );
+
+    let result = engine
+        .evaluate("Patient.name.given", patient())
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::String("Peter".into())])
+    );
+}
+
+#[tokio::test]
+async fn test_lenient_preset_tolerates_misspelled_field() {
+    let result = FhirPathEngine::lenient()
+        .with_model_provider(model())
+        .evaluate("Patient.nmae.given", patient())
+        .await
+        .unwrap();
+    assert!(result.is_empty());
+}
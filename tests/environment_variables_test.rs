@@ -0,0 +1,168 @@
+//! Tests for the %context, %resource and %rootResource environment variables
+
+mod common;
+
+use common::strings;
+use serde_json::{Value, json};
+
+fn bundle() -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "id": "bundle",
+        "type": "collection",
+        "entry": [
+            {
+                "resource": {
+                    "resourceType": "Patient",
+                    "id": "p1",
+                    "name": [{"family": "Chalmers"}],
+                    "contained": [{"resourceType": "Organization", "id": "org"}]
+                }
+            },
+            {
+                "resource": {
+                    "resourceType": "Patient",
+                    "id": "p2",
+                    "name": [{"family": "Windsor"}]
+                }
+            }
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_variables_at_the_root_are_the_input() {
+    assert_eq!(
+        common::items(common::eval("%context.id", bundle()).await),
+        strings(&["bundle"])
+    );
+    assert_eq!(
+        common::items(common::eval("%resource.id", bundle()).await),
+        strings(&["bundle"])
+    );
+    assert_eq!(
+        common::items(common::eval("%rootResource.id", bundle()).await),
+        strings(&["bundle"])
+    );
+}
+
+#[tokio::test]
+async fn test_resource_is_the_entry_resource() {
+    assert_eq!(
+        common::items(common::eval("Bundle.entry.resource.select(%resource.id)", bundle()).await),
+        strings(&["p1", "p2"])
+    );
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Bundle.entry.resource.where(%resource.id = 'p2').name.family",
+                bundle()
+            )
+            .await
+        ),
+        strings(&["Windsor"])
+    );
+
+    // Navigating from an entry into its resource moves %resource along
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Bundle.entry.select(resource.name.select(%resource.id + ' ' + family))",
+                bundle()
+            )
+            .await
+        ),
+        strings(&["p1 Chalmers", "p2 Windsor"])
+    );
+}
+
+#[tokio::test]
+async fn test_root_resource_and_context_stay_on_the_bundle() {
+    assert_eq!(
+        common::items(
+            common::eval("Bundle.entry.resource.select(%rootResource.id)", bundle()).await
+        ),
+        strings(&["bundle", "bundle"])
+    );
+    assert_eq!(
+        common::items(common::eval("Bundle.entry.resource.select(%context.id)", bundle()).await),
+        strings(&["bundle", "bundle"])
+    );
+}
+
+#[tokio::test]
+async fn test_contained_resources() {
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Bundle.entry.resource.contained.select(%resource.id)",
+                bundle()
+            )
+            .await
+        ),
+        strings(&["org"])
+    );
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Bundle.entry.resource.contained.select(%rootResource.id)",
+                bundle()
+            )
+            .await
+        ),
+        strings(&["bundle"])
+    );
+}
+
+#[tokio::test]
+async fn test_resource_follows_each_item_of_a_collection() {
+    // Each id is compared with the entry resource it was reached through
+    assert_eq!(
+        common::items(
+            common::eval("entry.resource.id.where(%resource.id = $this)", bundle()).await
+        ),
+        strings(&["p1", "p2"])
+    );
+    assert_eq!(
+        common::items(
+            common::eval(
+                "entry.resource.name.family.select(%resource.id + ' ' + $this)",
+                bundle()
+            )
+            .await
+        ),
+        strings(&["p1 Chalmers", "p2 Windsor"])
+    );
+
+    // $index still counts across all the items
+    assert_eq!(
+        common::items(
+            common::eval(
+                "entry.resource.id.select(%resource.id + $index.toString())",
+                bundle()
+            )
+            .await
+        ),
+        strings(&["p10", "p21"])
+    );
+}
+
+#[tokio::test]
+async fn test_resource_is_kept_through_functions_returning_input_items() {
+    assert_eq!(
+        common::items(
+            common::eval("entry.resource.first().id.select(%resource.id)", bundle()).await
+        ),
+        strings(&["p1"])
+    );
+    assert_eq!(
+        common::items(
+            common::eval(
+                "entry.resource.where(id = 'p2').name.select(%resource.id)",
+                bundle()
+            )
+            .await
+        ),
+        strings(&["p2"])
+    );
+}
@@ -0,0 +1,94 @@
+//! Tests for `=` across operand types
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::json;
+
+fn observation() -> serde_json::Value {
+    json!({
+        "resourceType": "Observation",
+        "status": "final",
+        "valueQuantity": {
+            "value": 185,
+            "unit": "lbs",
+            "system": "http://unitsofmeasure.org",
+            "code": "[lb_av]"
+        }
+    })
+}
+
+/// Expected result of `=`, with `None` standing for empty
+async fn assert_equality(cases: &[(&str, Option<bool>)]) {
+    for (expression, expected) in cases {
+        let actual = match common::eval(expression, observation()).await {
+            FhirPathValue::Boolean(b) => Some(b),
+            ref empty if empty.is_empty() => None,
+            other => panic!("{expression}: unexpected result {other:?}"),
+        };
+        assert_eq!(actual, *expected, "{expression}");
+    }
+}
+
+#[tokio::test]
+async fn test_equality_same_type() {
+    assert_equality(&[
+        ("1 = 1", Some(true)),
+        ("1 = 2", Some(false)),
+        ("1.10 = 1.1", Some(true)),
+        ("'a' = 'a'", Some(true)),
+        ("'a' = 'A'", Some(false)),
+        ("true = true", Some(true)),
+        ("@2012-04-15 = @2012-04-15", Some(true)),
+        ("@T10:00 = @T10:00", Some(true)),
+        ("1 'mg' = 0.001 'g'", Some(true)),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_equality_across_scalar_types() {
+    assert_equality(&[
+        // Unrelated types are never equal
+        ("'1' = 1", Some(false)),
+        ("1 = '1'", Some(false)),
+        ("true = 'true'", Some(false)),
+        ("true = 1", Some(false)),
+        ("'2012-04-15' = @2012-04-15", Some(false)),
+        // Implicit conversions apply before comparing
+        ("1 = 1.0", Some(true)),
+        ("2 = 2 '1'", Some(true)),
+        ("2.5 = 2.5 '1'", Some(true)),
+        ("1 = 1 'mg'", Some(false)),
+        ("1 'mg' = 1 'cm'", Some(false)),
+        // Differing precision makes the result unknown
+        ("@2012-04-15 = @2012-04-15T10:00:00", None),
+        ("@T10:00 = @T10:00:00", None),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_equality_with_fhir_quantity_element() {
+    assert_equality(&[
+        ("Observation.value = 185 '[lb_av]'", Some(true)),
+        ("Observation.value = 185 'kg'", Some(false)),
+        ("Observation.value = 185", Some(false)),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_equality_collections() {
+    assert_equality(&[
+        ("{} = 1", None),
+        ("1 = {}", None),
+        ("{} = {}", None),
+        ("(1 | 2) = (1 | 2)", Some(true)),
+        // Order matters and sizes must match
+        ("(1 | 2) = (2 | 1)", Some(false)),
+        ("(1 | 2) = 1", Some(false)),
+        ("Observation.status = 'final'", Some(true)),
+    ])
+    .await;
+}
@@ -0,0 +1,126 @@
+//! Tests for the opt-in navigation stack attached to evaluation errors
+
+use octofhir_fhirpath::engine::FhirPathEngine;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn bundle() -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": [
+            {"resource": {"resourceType": "Patient", "name": [{"given": ["Ann"]}]}},
+            {"resource": {"resourceType": "Patient", "name": [{"given": ["Bo"]}]}},
+            {"resource": {"resourceType": "Patient", "name": [{"given": ["Cy", "Di"]}]}}
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_error_deep_in_chain_reports_navigation_stack() {
+    let engine = FhirPathEngine::new().with_error_context(true);
+    let error = engine
+        .evaluate("Bundle.entry.resource.name.given.substring(0, 1)", bundle())
+        .await
+        .expect_err("substring() on several names should fail");
+
+    assert_eq!(
+        error.navigation_stack().expect("Should capture the stack"),
+        [
+            "Bundle",
+            "entry",
+            "resource",
+            "name",
+            "given",
+            "substring(0, 1)"
+        ]
+    );
+    assert!(
+        error
+            .to_string()
+            .ends_with("(at Bundle.entry.resource.name.given.substring(0, 1))"),
+        "{error}"
+    );
+    assert_eq!(
+        error.to_operation_outcome()["issue"][0]["expression"],
+        json!(["Bundle.entry.resource.name.given.substring(0, 1)"])
+    );
+}
+
+#[tokio::test]
+async fn test_error_inside_lambda_argument_reports_navigation_stack() {
+    let engine = FhirPathEngine::new().with_error_context(true);
+    let error = engine
+        .evaluate(
+            "Bundle.entry.resource.select(name.given.substring(0, 1))",
+            bundle(),
+        )
+        .await
+        .expect_err("substring() on several names should fail");
+
+    assert_eq!(
+        error.navigation_stack().expect("Should capture the stack"),
+        [
+            "Bundle",
+            "entry",
+            "resource[2]",
+            "select(name.given.substring(0, 1))"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_error_in_operand_reports_navigation_stack() {
+    let engine = FhirPathEngine::new().with_error_context(true);
+    let error = engine
+        .evaluate(
+            "Bundle.entry.resource.where(name.given.substring(0, 1) = 'C')",
+            bundle(),
+        )
+        .await
+        .expect_err("substring() on several names should fail");
+
+    assert_eq!(
+        error.navigation_stack().expect("Should capture the stack"),
+        [
+            "Bundle",
+            "entry",
+            "resource[2]",
+            "where(name.given.substring(0, 1))"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_navigation_stack_does_not_evaluate_again() {
+    let traced = Arc::new(AtomicUsize::new(0));
+    let counter = traced.clone();
+    let engine = FhirPathEngine::new()
+        .with_error_context(true)
+        .with_trace_sink(Arc::new(move |_, _| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }));
+
+    let error = engine
+        .evaluate(
+            "Bundle.entry.trace('entries').resource.name.given.substring(0, 1)",
+            bundle(),
+        )
+        .await
+        .expect_err("substring() on several names should fail");
+
+    assert!(error.navigation_stack().is_some());
+    assert_eq!(traced.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test]
+async fn test_navigation_stack_is_opt_in() {
+    let engine = FhirPathEngine::new();
+    let error = engine
+        .evaluate("Bundle.entry.resource.name.given.substring(0, 1)", bundle())
+        .await
+        .expect_err("substring() on several names should fail");
+
+    assert!(error.navigation_stack().is_none());
+}
@@ -0,0 +1,96 @@
+//! Tests for reporting the items an all() invariant fails for
+
+use octofhir_fhirpath::engine::FhirPathEngine;
+use serde_json::{Value, json};
+
+fn patient() -> Value {
+    json!({
+        "resourceType": "Patient",
+        "id": "example",
+        "contact": [
+            {
+                "name": {"family": "Abels"},
+                "telecom": [{"system": "phone", "value": "555-0100"}]
+            },
+            {
+                "name": {"family": "Brandt"}
+            },
+            {
+                "name": {"family": "Claes"},
+                "telecom": []
+            }
+        ]
+    })
+}
+
+async fn failing(expression: &str) -> Vec<String> {
+    FhirPathEngine::new()
+        .evaluate_all_matches(expression, patient())
+        .await
+        .unwrap_or_else(|e| panic!("{expression} failed: {e}"))
+}
+
+#[tokio::test]
+async fn test_reports_every_failing_contact() {
+    assert_eq!(
+        failing("Patient.contact.all(telecom.exists())").await,
+        vec!["Patient.contact[1]", "Patient.contact[2]"]
+    );
+    assert_eq!(
+        failing("contact.all(telecom.exists())").await,
+        vec!["contact[1]", "contact[2]"]
+    );
+}
+
+#[tokio::test]
+async fn test_passing_invariant_reports_nothing() {
+    assert!(
+        failing("Patient.contact.all(name.exists())")
+            .await
+            .is_empty()
+    );
+    // all() over no items is true
+    assert!(failing("Patient.link.all(other.exists())").await.is_empty());
+}
+
+#[tokio::test]
+async fn test_paths_follow_nested_collections() {
+    assert_eq!(
+        failing("Patient.contact.telecom.all(system = 'email')").await,
+        vec!["Patient.contact[0].telecom[0]"]
+    );
+}
+
+#[tokio::test]
+async fn test_filtered_items_keep_their_paths() {
+    assert_eq!(
+        failing("Patient.contact.where(name.family != 'Abels').all(telecom.exists())").await,
+        vec!["Patient.contact[1]", "Patient.contact[2]"]
+    );
+    assert_eq!(
+        failing("Patient.contact.last().all(telecom.exists())").await,
+        vec!["Patient.contact[2]"]
+    );
+}
+
+#[tokio::test]
+async fn test_empty_criteria_result_fails() {
+    // `=` on an empty telecom gives empty, which is not true
+    assert_eq!(
+        failing("Patient.contact.all(telecom.system = 'phone')").await,
+        vec!["Patient.contact[1]", "Patient.contact[2]"]
+    );
+}
+
+#[tokio::test]
+async fn test_rejects_expressions_that_are_not_all() {
+    let err = FhirPathEngine::new()
+        .evaluate_all_matches("Patient.contact.exists()", patient())
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Expected an expression of the form '<items>.all(<criteria>)'"),
+        "{err}"
+    );
+}
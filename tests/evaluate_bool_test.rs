@@ -0,0 +1,57 @@
+//! Tests for evaluating invariants to a single boolean with `evaluate_bool`
+
+use octofhir_fhirpath::engine::FhirPathEngine;
+use serde_json::{Value, json};
+
+fn patient() -> Value {
+    json!({
+        "resourceType": "Patient",
+        "active": true,
+        "name": [{"family": "Doe", "given": ["Jane", "Ann"]}]
+    })
+}
+
+async fn eval_bool(expression: &str) -> bool {
+    FhirPathEngine::new()
+        .evaluate_bool(expression, patient())
+        .await
+        .unwrap_or_else(|e| panic!("{expression} failed: {e}"))
+}
+
+#[tokio::test]
+async fn test_invariant_results() {
+    assert!(eval_bool("name.all(family.exists())").await);
+    assert!(eval_bool("Patient.active").await);
+    assert!(!eval_bool("name.given.count() = 1").await);
+}
+
+#[tokio::test]
+async fn test_empty_result() {
+    assert!(!eval_bool("Patient.deceased").await);
+    assert!(!eval_bool("{}").await);
+
+    let engine = FhirPathEngine::new().with_empty_invariant_result(true);
+    assert!(
+        engine
+            .evaluate_bool("Patient.deceased", patient())
+            .await
+            .unwrap()
+    );
+    assert!(
+        !engine
+            .evaluate_bool("Patient.active.not()", patient())
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_non_boolean_results_are_errors() {
+    let engine = FhirPathEngine::new();
+    for expression in ["name.given", "name.family", "name.given.count()", "(1 +"] {
+        assert!(
+            engine.evaluate_bool(expression, patient()).await.is_err(),
+            "{expression}"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Tests for evaluating a compiled expression over a stream of resources
+
+mod common;
+
+use common::string;
+use futures::StreamExt;
+use octofhir_fhirpath::engine::FhirPathEngine;
+use serde_json::Value;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A bulk export style NDJSON file of `count` Patients, one per line
+///
+/// Every tenth Patient has a second name.
+fn ndjson(count: usize) -> String {
+    (0..count)
+        .map(|i| {
+            let mut names = vec![format!(r#"{{"family": "Family{i}"}}"#)];
+            if i % 10 == 0 {
+                names.push(r#"{"family": "Alias"}"#.to_string());
+            }
+            format!(
+                r#"{{"resourceType": "Patient", "id": "p{i}", "name": [{}]}}"#,
+                names.join(", ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[tokio::test]
+async fn test_each_resource_gives_one_result_in_order() {
+    let engine = FhirPathEngine::new();
+    let expression = engine.compile("Patient.id").unwrap();
+    let data = ndjson(1000);
+    let resources = data
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap());
+
+    let results: Vec<_> = engine
+        .evaluate_stream(&expression, futures::stream::iter(resources))
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 1000);
+    for (i, result) in results.into_iter().enumerate() {
+        assert_eq!(result.unwrap(), string(&format!("p{i}")));
+    }
+}
+
+#[tokio::test]
+async fn test_resources_are_read_as_results_are_taken() {
+    let engine = FhirPathEngine::new();
+    let expression = engine.compile("Patient.name.first().family").unwrap();
+    let data = ndjson(1000);
+    let parsed = AtomicUsize::new(0);
+    let resources = data.lines().map(|line| {
+        parsed.fetch_add(1, Ordering::Relaxed);
+        serde_json::from_str::<Value>(line).unwrap()
+    });
+
+    let results = engine.evaluate_stream(&expression, futures::stream::iter(resources));
+    futures::pin_mut!(results);
+    for i in 0..5 {
+        let family = results.next().await.unwrap().unwrap();
+        assert_eq!(family, string(&format!("Family{i}")));
+    }
+    assert_eq!(parsed.load(Ordering::Relaxed), 5);
+}
+
+#[tokio::test]
+async fn test_each_resource_is_the_root_of_its_evaluation() {
+    let engine = FhirPathEngine::new();
+    let expression = engine.compile("%resource.id + '/' + %context.id").unwrap();
+    let data = ndjson(1000);
+    let resources = data
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap());
+
+    let results: Vec<_> = engine
+        .evaluate_stream(&expression, futures::stream::iter(resources))
+        .collect()
+        .await;
+
+    assert_eq!(results[0].as_ref().unwrap(), &string("p0/p0"));
+    assert_eq!(results[999].as_ref().unwrap(), &string("p999/p999"));
+}
+
+#[tokio::test]
+async fn test_an_error_for_one_resource_does_not_stop_the_rest() {
+    let engine = FhirPathEngine::new();
+    // single() fails for the Patients with two names
+    let expression = engine.compile("Patient.name.family.single()").unwrap();
+    let data = ndjson(1000);
+    let resources = data
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap());
+
+    let results: Vec<_> = engine
+        .evaluate_stream(&expression, futures::stream::iter(resources))
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 1000);
+    assert_eq!(results.iter().filter(|result| result.is_err()).count(), 100);
+    assert!(results[0].is_err());
+    assert_eq!(results[1].as_ref().unwrap(), &string("Family1"));
+}
@@ -0,0 +1,123 @@
+//! Tests for evaluation outcomes carrying non-fatal warnings
+
+use octofhir_fhirpath::diagnostics::DiagnosticCode;
+use octofhir_fhirpath::registry::functions::ResolveMode;
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_unresolved_reference_produces_warning_with_value() {
+    let bundle = json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": [{
+            "fullUrl": "http://example.org/fhir/Observation/obs1",
+            "resource": {
+                "resourceType": "Observation",
+                "id": "obs1",
+                "subject": {"reference": "Patient/missing"}
+            }
+        }]
+    });
+
+    let engine = FhirPathEngine::new().with_resolve_mode(ResolveMode::Placeholder);
+    let outcome = engine
+        .evaluate_with_warnings("Bundle.entry.resource.subject.resolve()", bundle)
+        .await
+        .expect("Should evaluate successfully");
+
+    match &outcome.value {
+        FhirPathValue::Collection(items) => assert_eq!(items.len(), 1),
+        other => panic!("Expected a collection, got {other:?}"),
+    }
+
+    assert!(outcome.has_warnings());
+    assert_eq!(outcome.warnings.len(), 1);
+    let warning = &outcome.warnings[0];
+    assert!(warning.is_warning());
+    assert_eq!(warning.code, DiagnosticCode::UnresolvedReference);
+    assert!(warning.message.contains("Patient/missing"));
+}
+
+#[tokio::test]
+async fn test_resolved_reference_produces_no_warnings() {
+    let bundle = json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": [
+            {
+                "fullUrl": "http://example.org/fhir/Patient/p1",
+                "resource": {"resourceType": "Patient", "id": "p1"}
+            },
+            {
+                "fullUrl": "http://example.org/fhir/Observation/obs1",
+                "resource": {
+                    "resourceType": "Observation",
+                    "id": "obs1",
+                    "subject": {"reference": "Patient/p1"}
+                }
+            }
+        ]
+    });
+
+    let engine = FhirPathEngine::new();
+    let outcome = engine
+        .evaluate_with_warnings("Bundle.entry.resource.subject.resolve().id", bundle)
+        .await
+        .expect("Should evaluate successfully");
+
+    assert!(!outcome.has_warnings());
+    assert_eq!(
+        outcome.value,
+        FhirPathValue::collection(vec![FhirPathValue::String("p1".into())])
+    );
+}
+
+#[tokio::test]
+async fn test_dropped_reference_produces_warning() {
+    let observation = json!({
+        "resourceType": "Observation",
+        "id": "obs1",
+        "subject": {"reference": "Patient/missing"}
+    });
+    let engine = FhirPathEngine::new();
+
+    let outcome = engine
+        .evaluate_with_warnings("Observation.subject.resolve()", observation.clone())
+        .await
+        .unwrap();
+    assert!(outcome.value.is_empty());
+    assert_eq!(outcome.warnings.len(), 1);
+    assert!(outcome.warnings[0].message.contains("Patient/missing"));
+
+    // Reported even though the result does not hold the reference
+    let outcome = engine
+        .evaluate_with_warnings("Observation.subject.resolve().exists()", observation)
+        .await
+        .unwrap();
+    assert_eq!(
+        outcome.value,
+        FhirPathValue::collection(vec![FhirPathValue::Boolean(false)])
+    );
+    assert_eq!(outcome.warnings.len(), 1);
+    assert_eq!(
+        outcome.warnings[0].code,
+        DiagnosticCode::UnresolvedReference
+    );
+}
+
+#[tokio::test]
+async fn test_placeholder_key_in_data_produces_no_warning() {
+    let patient = json!({
+        "resourceType": "Patient",
+        "id": "p1",
+        "_placeholder": true,
+        "_originalReference": "Patient/p1"
+    });
+
+    let outcome = FhirPathEngine::new()
+        .evaluate_with_warnings("Patient", patient)
+        .await
+        .unwrap();
+    assert!(!outcome.has_warnings());
+}
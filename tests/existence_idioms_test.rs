@@ -0,0 +1,98 @@
+//! Tests that `exists().not()`, `empty()` and `exists() = false` agree, and
+//! that `exists()` stops at the first node its criteria holds for
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::{Value, json};
+
+fn boolean(value: bool) -> FhirPathValue {
+    FhirPathValue::collection(vec![FhirPathValue::Boolean(value)])
+}
+
+fn patient() -> Value {
+    json!({
+        "resourceType": "Patient",
+        "managingOrganization": {"reference": "Organization/1", "display": "Acme"},
+        "generalPractitioner": [
+            {"display": "Dr Who"},
+            {"reference": "Practitioner/2", "display": "Dr No"}
+        ],
+        "gender": "female",
+        "name": [{"family": "Doe", "given": ["Jane", "Ann"]}],
+        "telecom": [{"system": "phone", "value": "555"}, {"system": "email", "value": "a@b"}]
+    })
+}
+
+/// The three spellings of "`base` is empty"
+fn idioms(base: &str) -> [String; 3] {
+    [
+        format!("{base}.exists().not()"),
+        format!("{base}.empty()"),
+        format!("{base}.exists() = false"),
+    ]
+}
+
+#[tokio::test]
+async fn test_idioms_agree() {
+    let cases = [
+        ("photo", true),
+        ("name.where(family = 'Roe')", true),
+        ("gender", false),
+        ("name.given", false),
+        ("telecom.where(system = 'email')", false),
+        ("descendants().ofType(Attachment)", true),
+        ("descendants().where(reference.exists())", false),
+        ("{}", true),
+    ];
+    for (base, empty) in cases {
+        for expression in idioms(base) {
+            let result = common::eval(&expression, patient()).await;
+            assert_eq!(result, boolean(empty), "{expression}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_exists_equal_true_is_exists() {
+    for (expression, expected) in [
+        ("gender.exists() = true", true),
+        ("photo.exists() = true", false),
+    ] {
+        let result = common::eval(expression, patient()).await;
+        assert_eq!(result, boolean(expected), "{expression}");
+    }
+}
+
+#[tokio::test]
+async fn test_exists_criteria_stops_at_first_match() {
+    let (engine, log) = common::collecting_trace_engine();
+    // The criteria is traced, so the trace shows how many nodes were checked;
+    // managingOrganization matches
+    let result = common::eval_with(
+        &engine,
+        "descendants().exists(trace('node').reference.exists())",
+        patient(),
+    )
+    .await;
+    assert_eq!(result, boolean(true));
+    assert_eq!(log.take_names().len(), 1);
+}
+
+#[tokio::test]
+async fn test_idioms_visit_every_node_without_a_match() {
+    let (engine, log) = common::collecting_trace_engine();
+    let total = common::eval("descendants().count()", patient()).await;
+    let FhirPathValue::Collection(items) = total else {
+        panic!("count() gave {total:?}");
+    };
+    let Some(FhirPathValue::Integer(total)) = items.get(0).cloned() else {
+        panic!("count() gave {items:?}");
+    };
+
+    for expression in idioms("descendants().where(trace('node').url.exists())") {
+        let result = common::eval_with(&engine, &expression, patient()).await;
+        assert_eq!(result, boolean(true), "{expression}");
+        assert_eq!(log.take_names().len() as i64, total, "{expression}");
+    }
+}
@@ -0,0 +1,144 @@
+//! Tests for the engine's cache of parsed expressions
+
+use octofhir_fhirpath::{
+    FhirPathValue,
+    engine::{ExpressionCacheStats, FhirPathEngine},
+};
+use serde_json::json;
+use std::sync::Arc;
+
+/// The integer a result holds, whether or not it is wrapped in a collection
+fn integer(value: FhirPathValue) -> i64 {
+    match value {
+        FhirPathValue::Integer(i) => i,
+        FhirPathValue::Collection(items) if items.len() == 1 => {
+            integer(items.get(0).unwrap().clone())
+        }
+        other => panic!("expected an integer, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_repeated_evaluation_hits_the_cache() {
+    let engine = FhirPathEngine::new();
+    let expression = "Patient.name.given.count()";
+
+    for i in 0..5i64 {
+        let given: Vec<String> = (0..i).map(|g| format!("G{g}")).collect();
+        let patient = json!({"resourceType": "Patient", "name": [{"given": given}]});
+        assert_eq!(
+            integer(engine.evaluate(expression, patient).await.unwrap()),
+            i
+        );
+    }
+    assert_eq!(
+        engine.expression_cache_stats(),
+        ExpressionCacheStats {
+            hits: 4,
+            misses: 1,
+            entries: 1
+        }
+    );
+
+    engine.evaluate("1 + 1", json!({})).await.unwrap();
+    let stats = engine.expression_cache_stats();
+    assert_eq!((stats.misses, stats.entries), (2, 2));
+}
+
+#[tokio::test]
+async fn test_expressions_are_keyed_by_exact_text() {
+    let engine = FhirPathEngine::new();
+
+    // Whitespace inside a string literal makes a different expression
+    assert_eq!(
+        integer(engine.evaluate("'a b'.length()", json!({})).await.unwrap()),
+        3
+    );
+    assert_eq!(
+        integer(
+            engine
+                .evaluate("'a   b'.length()", json!({}))
+                .await
+                .unwrap()
+        ),
+        5
+    );
+    assert_eq!(engine.expression_cache_stats().misses, 2);
+}
+
+#[tokio::test]
+async fn test_capacity_evicts_least_recently_used() {
+    let engine = FhirPathEngine::new().with_cache_capacity(2);
+
+    engine.evaluate("1", json!({})).await.unwrap();
+    engine.evaluate("2", json!({})).await.unwrap();
+    engine.evaluate("1", json!({})).await.unwrap();
+    // Evicts "2", the least recently used
+    engine.evaluate("3", json!({})).await.unwrap();
+    engine.evaluate("1", json!({})).await.unwrap();
+    engine.evaluate("2", json!({})).await.unwrap();
+
+    assert_eq!(
+        engine.expression_cache_stats(),
+        ExpressionCacheStats {
+            hits: 2,
+            misses: 4,
+            entries: 2
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_clear_and_disable() {
+    let engine = FhirPathEngine::new();
+    engine.evaluate("1 + 2", json!({})).await.unwrap();
+    engine.clear_expression_cache();
+    assert_eq!(engine.expression_cache_stats().entries, 0);
+
+    // Cleared, so it is parsed again
+    engine.evaluate("1 + 2", json!({})).await.unwrap();
+    assert_eq!(engine.expression_cache_stats().misses, 2);
+
+    let uncached = FhirPathEngine::new().with_cache_capacity(0);
+    for _ in 0..3 {
+        assert_eq!(
+            integer(uncached.evaluate("1 + 2", json!({})).await.unwrap()),
+            3
+        );
+    }
+    assert_eq!(
+        uncached.expression_cache_stats(),
+        ExpressionCacheStats::default()
+    );
+}
+
+#[tokio::test]
+async fn test_syntax_errors_are_not_cached() {
+    let engine = FhirPathEngine::new();
+    for _ in 0..2 {
+        engine.evaluate("Patient.name.where(", json!({})).await.ok();
+    }
+    let stats = engine.expression_cache_stats();
+    assert_eq!((stats.hits, stats.entries), (0, 0));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_cache_is_shared_through_an_arc() {
+    let engine = Arc::new(FhirPathEngine::new());
+    let tasks: Vec<_> = (0..64)
+        .map(|i| {
+            let engine = Arc::clone(&engine);
+            tokio::spawn(async move {
+                let expression = format!("{} + 1", i % 4);
+                engine.evaluate(&expression, json!({})).await.unwrap()
+            })
+        })
+        .collect();
+    for (i, task) in tasks.into_iter().enumerate() {
+        assert_eq!(integer(task.await.unwrap()), (i % 4) as i64 + 1);
+    }
+
+    let stats = engine.expression_cache_stats();
+    assert_eq!(stats.hits + stats.misses, 64);
+    assert_eq!(stats.entries, 4);
+}
@@ -38,7 +38,7 @@ mod failed_expressions_tests {
     }
 
     async fn test_expression(expression: &str, input: JsonValue, expected: Vec<FhirPathValue>) {
-        let mut engine = Engine::new();
+        let engine = Engine::new();
         let result = engine.evaluate(expression, input).await;
 
         match result {
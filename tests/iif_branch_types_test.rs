@@ -0,0 +1,60 @@
+//! Tests that iif() returns the selected branch as-is, whatever its type
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use rust_decimal::Decimal;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_iif_heterogeneous_literal_branches() {
+    assert_eq!(
+        common::unwrap_singleton(common::eval("iif(true, 1, 'two')", json!({})).await),
+        FhirPathValue::Integer(1)
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("iif(false, 1, 'two')", json!({})).await),
+        FhirPathValue::String("two".into())
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("iif(false, 'one', 2.5)", json!({})).await),
+        FhirPathValue::Decimal(Decimal::new(25, 1))
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("iif(true, true, 0)", json!({})).await),
+        FhirPathValue::Boolean(true)
+    );
+}
+
+#[tokio::test]
+async fn test_iif_branch_types_follow_data_driven_condition() {
+    let patient = json!({"resourceType": "Patient", "active": true, "gender": "female"});
+    assert_eq!(
+        common::unwrap_singleton(
+            common::eval("iif(Patient.active, Patient.gender, 0)", patient.clone()).await
+        ),
+        FhirPathValue::String("female".into())
+    );
+    assert_eq!(
+        common::unwrap_singleton(
+            common::eval("iif(Patient.active.not(), Patient.gender, 0)", patient).await
+        ),
+        FhirPathValue::Integer(0)
+    );
+}
+
+#[tokio::test]
+async fn test_iif_selected_branch_keeps_its_type_downstream() {
+    assert_eq!(
+        common::unwrap_singleton(common::eval("iif(true, 1, 'two') is Integer", json!({})).await),
+        FhirPathValue::Boolean(true)
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("iif(false, 1, 'two') is String", json!({})).await),
+        FhirPathValue::Boolean(true)
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("iif(true, 41, 'two') + 1", json!({})).await),
+        FhirPathValue::Integer(42)
+    );
+}
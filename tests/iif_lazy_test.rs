@@ -0,0 +1,119 @@
+//! Tests that iif() evaluates its criterion once and only the selected branch
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::{Value, json};
+
+fn patient() -> Value {
+    json!({"resourceType": "Patient", "name": [{"family": "Doe"}]})
+}
+
+#[tokio::test]
+async fn test_only_the_selected_branch_is_traced() {
+    let (engine, log) = common::collecting_trace_engine();
+    let result = common::eval_with(
+        &engine,
+        "iif(true.trace('criterion'), 'a'.trace('then'), 'b'.trace('else'))",
+        patient(),
+    )
+    .await;
+    assert_eq!(result, FhirPathValue::String("a".into()));
+    assert_eq!(log.take_names(), ["criterion", "then"]);
+
+    let result = common::eval_with(
+        &engine,
+        "iif(false.trace('criterion'), 'a'.trace('then'), 'b'.trace('else'))",
+        patient(),
+    )
+    .await;
+    assert_eq!(result, FhirPathValue::String("b".into()));
+    assert_eq!(log.take_names(), ["criterion", "else"]);
+
+    let result = common::eval_with(
+        &engine,
+        "iif({}.trace('criterion'), 'a'.trace('then'))",
+        patient(),
+    )
+    .await;
+    assert!(result.is_empty());
+    assert_eq!(log.take_names(), ["criterion"]);
+}
+
+#[tokio::test]
+async fn test_criterion_is_evaluated_once_in_larger_expressions() {
+    let (engine, log) = common::collecting_trace_engine();
+    let result = common::eval_with(
+        &engine,
+        "iif(name.family.trace('criterion') = 'Doe', 'x'.trace('then'), 'y'.trace('else')).length() + 3 * 4 + 5 - 6",
+        patient(),
+    )
+    .await;
+    assert_eq!(result, FhirPathValue::Integer(12));
+    assert_eq!(log.take_names(), ["criterion", "then"]);
+}
+
+#[tokio::test]
+async fn test_unselected_branch_may_fail() {
+    // join() rejects non-string items, so evaluating this branch would be an error
+    let result = common::eval("iif(true, 1, (1 | 2).join(','))", patient()).await;
+    assert_eq!(result, FhirPathValue::Integer(1));
+    let result = common::eval(
+        "iif(name.exists(), name.family, (1 | 2).join(','))",
+        patient(),
+    )
+    .await;
+    assert_eq!(result, FhirPathValue::String("Doe".into()));
+}
+
+#[tokio::test]
+async fn test_untaken_branch_errors_do_not_fire() {
+    // %undefined is not a known variable, so evaluating it is an error
+    let result = common::eval("iif(false, %undefined, 5)", patient()).await;
+    assert_eq!(result, FhirPathValue::Integer(5));
+    let result = common::eval("iif(true, 5, %undefined)", patient()).await;
+    assert_eq!(result, FhirPathValue::Integer(5));
+    let result = common::eval("iif({}, %undefined, 5)", patient()).await;
+    assert_eq!(result, FhirPathValue::Integer(5));
+    let result = common::eval("iif(false, %undefined)", patient()).await;
+    assert!(result.is_empty());
+
+    // Division by zero is empty rather than an error, but is not evaluated either
+    let (engine, log) = common::collecting_trace_engine();
+    let result =
+        common::eval_with(&engine, "iif(false, (1 / 0).trace('then'), 5)", patient()).await;
+    assert_eq!(result, FhirPathValue::Integer(5));
+    assert!(log.take_names().is_empty());
+
+    // The taken branch still fails
+    assert!(
+        common::try_eval("iif(true, %undefined, 5)", json!({}))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_non_boolean_criterion_or_multi_item_input_fails() {
+    for expression in [
+        "iif('not a boolean', 'a', 'b')",
+        "iif(true | false, 'a', 'b')",
+        "('a' | 'b').iif(true, 'a', 'b')",
+    ] {
+        assert!(
+            common::try_eval(expression, json!({})).await.is_err(),
+            "{expression} should fail"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_trace_sink_receives_selector_value() {
+    let (engine, log) = common::collecting_trace_engine();
+    let result = common::eval_with(&engine, "name.trace('family', family).family", patient()).await;
+    assert_eq!(result, FhirPathValue::String("Doe".into()));
+    assert_eq!(
+        log.take(),
+        [("family".to_string(), FhirPathValue::String("Doe".into()))]
+    );
+}
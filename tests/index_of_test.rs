@@ -0,0 +1,66 @@
+//! Tests for indexOf() and lastIndexOf()
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::json;
+
+async fn assert_index(cases: &[(&str, i64)]) {
+    for (expression, expected) in cases {
+        assert_eq!(
+            common::eval(expression, json!({})).await,
+            FhirPathValue::Integer(*expected),
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_index_of() {
+    assert_index(&[
+        ("'LogicalModel-Person'.indexOf('-')", 12),
+        ("'abcabc'.indexOf('bc')", 1),
+        ("'abcabc'.indexOf('x')", -1),
+        ("'abc'.indexOf('')", 0),
+        ("''.indexOf('')", 0),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_last_index_of() {
+    assert_index(&[
+        ("'abcabc'.lastIndexOf('bc')", 4),
+        ("'abcabc'.lastIndexOf('abc')", 3),
+        ("'abcabc'.lastIndexOf('x')", -1),
+        ("'abc'.lastIndexOf('')", 0),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_indices_count_characters_not_bytes() {
+    assert_index(&[
+        ("'😀😀x😀x'.indexOf('x')", 2),
+        ("'😀😀x😀x'.lastIndexOf('x')", 4),
+        ("'😀😀x😀x'.lastIndexOf('😀')", 3),
+        ("'café au lait'.indexOf('au')", 5),
+        ("'漢字テスト漢字'.lastIndexOf('漢字')", 5),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_empty_input_or_argument_returns_empty() {
+    for expression in [
+        "{}.indexOf('a')",
+        "'abc'.indexOf({})",
+        "{}.lastIndexOf('a')",
+        "'abc'.lastIndexOf({})",
+    ] {
+        assert!(
+            common::eval(expression, json!({})).await.is_empty(),
+            "{expression}"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! Tests for `$index` in iteration functions
+
+mod common;
+
+use common::strings;
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::{Value, json};
+
+fn bundle() -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "entry": [
+            {"resource": {"resourceType": "Patient", "id": "a"}},
+            {"resource": {"resourceType": "Patient", "id": "b"}},
+            {"resource": {"resourceType": "Patient", "id": "c"}},
+            {"resource": {"resourceType": "Patient", "id": "d"}}
+        ],
+        "signature": [{"who": ["w", "x", "y"]}, {"who": ["z"]}]
+    })
+}
+
+fn integers(values: &[i64]) -> Vec<FhirPathValue> {
+    values.iter().map(|i| FhirPathValue::Integer(*i)).collect()
+}
+
+fn boolean(value: bool) -> Vec<FhirPathValue> {
+    vec![FhirPathValue::Boolean(value)]
+}
+
+#[tokio::test]
+async fn test_index_in_where() {
+    assert_eq!(
+        common::items(common::eval("Bundle.entry.where($index < 3).resource.id", bundle()).await),
+        strings(&["a", "b", "c"])
+    );
+    assert_eq!(
+        common::items(common::eval("Bundle.entry.resource.id.where($index = 1)", bundle()).await),
+        strings(&["b"])
+    );
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Bundle.entry.resource.id.where($index mod 2 = 1 and $this != 'b')",
+                bundle()
+            )
+            .await
+        ),
+        strings(&["d"])
+    );
+}
+
+#[tokio::test]
+async fn test_index_in_select() {
+    assert_eq!(
+        common::items(common::eval("Bundle.entry.select($index)", bundle()).await),
+        integers(&[0, 1, 2, 3])
+    );
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Bundle.entry.resource.id.select($index.toString() + $this)",
+                bundle()
+            )
+            .await
+        ),
+        strings(&["0a", "1b", "2c", "3d"])
+    );
+}
+
+#[tokio::test]
+async fn test_index_in_all_any_exists() {
+    assert_eq!(
+        common::items(common::eval("Bundle.entry.all($index < 4)", bundle()).await),
+        boolean(true)
+    );
+    assert_eq!(
+        common::items(common::eval("Bundle.entry.all($index < 3)", bundle()).await),
+        boolean(false)
+    );
+    assert_eq!(
+        common::items(common::eval("Bundle.entry.any($index = 3)", bundle()).await),
+        boolean(true)
+    );
+    assert_eq!(
+        common::items(common::eval("Bundle.entry.any($index = 4)", bundle()).await),
+        boolean(false)
+    );
+    assert_eq!(
+        common::items(common::eval("Bundle.entry.exists($index = 2)", bundle()).await),
+        boolean(true)
+    );
+}
+
+#[tokio::test]
+async fn test_index_in_repeat() {
+    // $index is the position within each round's items
+    assert_eq!(
+        common::items(
+            common::eval(
+                "(1 | 2 | 3).repeat(iif($index = 0 and $this < 30, $this + 10, {}))",
+                bundle()
+            )
+            .await
+        ),
+        integers(&[11, 21, 31])
+    );
+}
+
+#[tokio::test]
+async fn test_nested_iterations_shadow_index() {
+    // The inner select has its own $index
+    assert_eq!(
+        common::items(common::eval("Bundle.signature.select(who.select($index))", bundle()).await),
+        integers(&[0, 1, 2, 0])
+    );
+    // After the inner where, $index is the outer one again
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Bundle.signature.select(who.where($index = 0) | $index.toString())",
+                bundle()
+            )
+            .await
+        ),
+        strings(&["w", "0", "z", "1"])
+    );
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Bundle.signature.where(who.where($index > 0).exists()).who",
+                bundle()
+            )
+            .await
+        ),
+        strings(&["w", "x", "y"])
+    );
+    assert_eq!(
+        common::items(
+            common::eval("Bundle.signature.select(who.count() + $index)", bundle()).await
+        ),
+        integers(&[3, 2])
+    );
+}
+
+#[tokio::test]
+async fn test_index_outside_iteration_is_empty() {
+    assert_eq!(
+        common::items(common::eval("$index", bundle()).await),
+        Vec::<FhirPathValue>::new()
+    );
+}
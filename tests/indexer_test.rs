@@ -0,0 +1,58 @@
+//! Tests for the `[index]` indexer on out-of-range and negative indices
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+
+#[tokio::test]
+async fn test_index_in_range() {
+    assert_eq!(
+        common::items(common::eval("Patient.name[0].family", common::patient()).await),
+        [FhirPathValue::String("Chalmers".into())]
+    );
+    assert_eq!(
+        common::items(common::eval("Patient.name.given[1]", common::patient()).await),
+        [FhirPathValue::String("James".into())]
+    );
+    assert_eq!(
+        common::items(common::eval("5[0]", common::patient()).await),
+        [FhirPathValue::Integer(5)]
+    );
+}
+
+#[tokio::test]
+async fn test_index_zero_on_empty() {
+    assert!(common::items(common::eval("{}[0]", common::patient()).await).is_empty());
+    assert!(common::items(common::eval("Patient.telecom[0]", common::patient()).await).is_empty());
+    assert!(
+        common::items(common::eval("Patient.telecom[0].value", common::patient()).await).is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_large_index() {
+    assert!(
+        common::items(common::eval("Patient.name[5].family", common::patient()).await).is_empty()
+    );
+    assert!(
+        common::items(common::eval("Patient.name.given[2]", common::patient()).await).is_empty()
+    );
+    assert!(common::items(common::eval("5[1]", common::patient()).await).is_empty());
+    assert!(
+        common::items(common::eval("(1 | 2)[9223372036854775807]", common::patient()).await)
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_negative_index() {
+    // Negative indices do not count from the end
+    assert!(
+        common::items(common::eval("Patient.name[-1].family", common::patient()).await).is_empty()
+    );
+    assert!(
+        common::items(common::eval("Patient.name.given[-1]", common::patient()).await).is_empty()
+    );
+    assert!(common::items(common::eval("(1 | 2)[-2]", common::patient()).await).is_empty());
+    assert!(common::items(common::eval("5[-1]", common::patient()).await).is_empty());
+}
@@ -280,18 +280,30 @@ impl IntegrationTestRunner {
                 actual_items
                     .iter()
                     .zip(expected_items.iter())
-                    .all(|(a, e)| a == e)
+                    .all(|(a, e)| Self::items_match(a, e))
             }
             // Handle single value vs single-item collection (common in FHIRPath tests)
             (single_val, FhirPathValue::Collection(expected_items))
                 if expected_items.len() == 1 =>
             {
-                single_val == expected_items.first().unwrap()
+                Self::items_match(single_val, expected_items.first().unwrap())
             }
             (FhirPathValue::Collection(actual_items), single_val) if actual_items.len() == 1 => {
-                actual_items.first().unwrap() == single_val
+                Self::items_match(actual_items.first().unwrap(), single_val)
             }
-            _ => actual == &expected_value,
+            _ => Self::items_match(actual, &expected_value),
+        }
+    }
+
+    /// Compare a single item, matching dates and times against their
+    /// `@`-prefixed literal form since the test files store them as strings
+    fn items_match(actual: &FhirPathValue, expected: &FhirPathValue) -> bool {
+        match (actual, expected) {
+            (
+                FhirPathValue::Date(_) | FhirPathValue::DateTime(_) | FhirPathValue::Time(_),
+                FhirPathValue::String(e),
+            ) => actual.to_string() == e.as_ref(),
+            _ => actual == expected,
         }
     }
 
@@ -402,16 +414,10 @@ impl IntegrationTestRunner {
                 Value::Array(vec![num])
             }
             FhirPathValue::String(s) => Value::Array(vec![Value::String(s.to_string())]),
-            FhirPathValue::Date(d) => {
-                Value::Array(vec![Value::String(format!("@{}", d.format("%Y-%m-%d")))])
-            }
-            FhirPathValue::DateTime(dt) => Value::Array(vec![Value::String(format!(
-                "@{}",
-                dt.format("%Y-%m-%dT%H:%M:%S%.3f%z")
-            ))]),
-            FhirPathValue::Time(t) => {
-                Value::Array(vec![Value::String(format!("@T{}", t.format("%H:%M:%S")))])
+            FhirPathValue::Date(_) | FhirPathValue::DateTime(_) => {
+                Value::Array(vec![Value::String(value.to_string())])
             }
+            FhirPathValue::Time(t) => Value::Array(vec![Value::String(format!("@T{t}"))]),
             FhirPathValue::Quantity(q) => Value::Array(vec![q.to_json()]),
             FhirPathValue::Collection(items) => {
                 if items.is_empty() {
@@ -457,11 +463,8 @@ impl IntegrationTestRunner {
                 )
             }
             FhirPathValue::String(s) => Value::String(s.to_string()),
-            FhirPathValue::Date(d) => Value::String(format!("@{}", d.format("%Y-%m-%d"))),
-            FhirPathValue::DateTime(dt) => {
-                Value::String(format!("@{}", dt.format("%Y-%m-%dT%H:%M:%S%.3f%z")))
-            }
-            FhirPathValue::Time(t) => Value::String(format!("@T{}", t.format("%H:%M:%S"))),
+            FhirPathValue::Date(_) | FhirPathValue::DateTime(_) => Value::String(value.to_string()),
+            FhirPathValue::Time(t) => Value::String(format!("@T{t}")),
             FhirPathValue::Quantity(q) => q.to_json(),
             FhirPathValue::Collection(items) => {
                 if items.is_empty() {
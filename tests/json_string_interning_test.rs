@@ -0,0 +1,87 @@
+//! Tests for interning of string values lifted from JSON input
+
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use serde_json::json;
+
+fn bundle_with_repeated_systems() -> serde_json::Value {
+    let entries: Vec<_> = (0..5)
+        .map(|i| {
+            json!({
+                "resource": {
+                    "resourceType": "Observation",
+                    "id": format!("obs{i}"),
+                    "code": {
+                        "coding": [{"system": "http://terminology.hl7.org/CodeSystem/v3-ActCode", "code": "AMB"}]
+                    }
+                }
+            })
+        })
+        .collect();
+
+    json!({"resourceType": "Bundle", "type": "collection", "entry": entries})
+}
+
+fn strings(value: &FhirPathValue) -> Vec<&str> {
+    match value {
+        FhirPathValue::Collection(items) => items.iter().filter_map(|item| item.as_str()).collect(),
+        other => other.as_str().into_iter().collect(),
+    }
+}
+
+fn string_pointers(value: &FhirPathValue) -> Vec<*const u8> {
+    let FhirPathValue::Collection(items) = value else {
+        panic!("Expected a collection, got {value:?}");
+    };
+    items
+        .iter()
+        .map(|item| match item {
+            FhirPathValue::String(s) => s.as_ptr(),
+            other => panic!("Expected a string, got {other:?}"),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_json_string_interning_preserves_results_and_shares_storage() {
+    let expression = "Bundle.entry.resource.code.coding.system";
+
+    let plain = FhirPathEngine::new()
+        .evaluate(expression, bundle_with_repeated_systems())
+        .await
+        .unwrap();
+    let interned = FhirPathEngine::new()
+        .with_json_string_interning(true)
+        .evaluate(expression, bundle_with_repeated_systems())
+        .await
+        .unwrap();
+
+    assert_eq!(plain, interned);
+    assert_eq!(
+        strings(&interned),
+        vec!["http://terminology.hl7.org/CodeSystem/v3-ActCode"; 5]
+    );
+
+    // Every repeated system shares a single allocation
+    let pointers = string_pointers(&interned);
+    assert!(pointers.windows(2).all(|pair| pair[0] == pair[1]));
+}
+
+#[tokio::test]
+async fn test_json_string_interning_is_per_engine() {
+    let expression = "Bundle.entry.resource.code.coding.system";
+    let interning = FhirPathEngine::new().with_json_string_interning(true);
+    let plain = FhirPathEngine::new();
+
+    interning
+        .evaluate(expression, bundle_with_repeated_systems())
+        .await
+        .unwrap();
+    let result = plain
+        .evaluate(expression, bundle_with_repeated_systems())
+        .await
+        .unwrap();
+
+    // An engine that does not intern still allocates each string on its own
+    let pointers = string_pointers(&result);
+    assert!(pointers.windows(2).all(|pair| pair[0] != pair[1]));
+}
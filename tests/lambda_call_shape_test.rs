@@ -0,0 +1,59 @@
+//! Tests for call-shape validation of lambda functions at evaluation time
+
+use octofhir_fhirpath::evaluator::FhirPathEngine;
+use octofhir_fhirpath::model::FhirPathValue;
+use octofhir_fhirpath::parse;
+use serde_json::json;
+
+async fn eval(expression: &str) -> Result<FhirPathValue, String> {
+    let patient = json!({
+        "resourceType": "Patient",
+        "name": [
+            {"use": "official", "family": "Chalmers"},
+            {"use": "usual", "given": ["Jim"]}
+        ]
+    });
+    let ast = parse(expression).expect("expression should parse");
+    FhirPathEngine::new()
+        .evaluate(&ast, FhirPathValue::from(patient))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tokio::test]
+async fn test_where_without_argument_is_arity_error() {
+    let err = eval("Patient.name.where()").await.unwrap_err();
+    assert!(
+        err.contains("'where' expects 1-1 arguments, got 0"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn test_select_with_two_arguments_is_arity_error() {
+    let err = eval("Patient.name.select(1, 2)").await.unwrap_err();
+    assert!(
+        err.contains("'select' expects 1-1 arguments, got 2"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn test_lambda_syntax_rejected_by_regular_function() {
+    let err = eval("Patient.name.family.substring(x => 1)")
+        .await
+        .unwrap_err();
+    assert!(err.contains("substring"), "{err}");
+}
+
+#[tokio::test]
+async fn test_single_lambda_argument_evaluates() {
+    let result = eval("Patient.name.where(use = 'official').family")
+        .await
+        .expect("where() with one argument should evaluate");
+    let family = match &result {
+        FhirPathValue::Collection(items) => items.get(0).cloned(),
+        other => Some(other.clone()),
+    };
+    assert_eq!(family, Some(FhirPathValue::String("Chalmers".into())));
+}
@@ -0,0 +1,159 @@
+//! Tests for the sqrt(), exp(), ln(), log(), power() and truncate() math functions
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use rust_decimal::Decimal;
+use serde_json::json;
+use std::str::FromStr;
+
+fn decimal(value: &str) -> FhirPathValue {
+    FhirPathValue::Decimal(Decimal::from_str(value).unwrap())
+}
+
+#[tokio::test]
+async fn test_integer_and_decimal_inputs_return_decimal() {
+    for (expression, expected) in [
+        ("81.sqrt()", "9"),
+        ("2.25.sqrt()", "1.5"),
+        ("0.exp()", "1"),
+        ("1.ln()", "0"),
+        ("1.0.ln()", "0"),
+        ("16.log(2)", "4"),
+        ("100.0.log(10.0)", "2"),
+        ("8.log(2.0)", "3"),
+    ] {
+        assert_eq!(
+            common::eval(expression, json!({})).await,
+            decimal(expected),
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_unrepresentable_results_are_empty() {
+    for expression in [
+        "(-1).sqrt()",
+        "(-2.5).sqrt()",
+        "0.ln()",
+        "(-1).ln()",
+        "0.0.log(10)",
+        "(-8).log(2)",
+        "8.log(1)",
+        "8.log(0)",
+        "8.log(-2)",
+        "1000.exp()",
+    ] {
+        assert!(
+            common::eval(expression, json!({})).await.is_empty(),
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_empty_input_or_base_returns_empty() {
+    for expression in [
+        "{}.sqrt()",
+        "{}.exp()",
+        "{}.ln()",
+        "{}.log(10)",
+        "16.log({})",
+    ] {
+        assert!(
+            common::eval(expression, json!({})).await.is_empty(),
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_non_numeric_input_is_an_error() {
+    for expression in ["'4'.sqrt()", "true.exp()", "'e'.ln()", "16.log('2')"] {
+        assert!(
+            common::try_eval(expression, json!({})).await.is_err(),
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_power_keeps_integers_when_representable() {
+    for (expression, expected) in [
+        ("2.power(3)", 8),
+        ("(-2).power(3)", -8),
+        ("5.power(0)", 1),
+        ("0.power(0)", 1),
+        ("2.power(62)", 1 << 62),
+    ] {
+        assert_eq!(
+            common::eval(expression, json!({})).await,
+            FhirPathValue::Integer(expected),
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_power_falls_back_to_decimal() {
+    for (expression, expected) in [
+        ("2.5.power(2)", "6.25"),
+        ("2.power(-1)", "0.5"),
+        ("2.power(3.0)", "8"),
+        ("4.power(0.5)", "2"),
+        ("(-8.0).power(3)", "-512"),
+        ("2.power(64)", "18446744073709551616"),
+    ] {
+        assert_eq!(
+            common::eval(expression, json!({})).await,
+            decimal(expected),
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_power_unrepresentable_results_are_empty() {
+    for expression in [
+        "(-1).power(0.5)",
+        "(-2.5).power(1.5)",
+        "0.power(-1)",
+        "10.power(400)",
+        "{}.power(2)",
+        "2.power({})",
+    ] {
+        assert!(
+            common::eval(expression, json!({})).await.is_empty(),
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_truncate_drops_fraction_toward_zero() {
+    for (expression, expected) in [
+        ("(-2.5).truncate()", -2),
+        ("(-2.5).floor()", -3),
+        ("2.5.truncate()", 2),
+        ("(-0.9).truncate()", 0),
+        ("101.truncate()", 101),
+        ("(-7).truncate()", -7),
+    ] {
+        assert_eq!(
+            common::eval(expression, json!({})).await,
+            FhirPathValue::Integer(expected),
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_truncate_unrepresentable_or_empty_is_empty() {
+    for expression in ["{}.truncate()", "99999999999999999999.5.truncate()"] {
+        assert!(
+            common::eval(expression, json!({})).await.is_empty(),
+            "{expression}"
+        );
+    }
+}
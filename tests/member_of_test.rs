@@ -0,0 +1,139 @@
+//! Tests for the memberOf() function with a stub terminology provider
+
+use async_trait::async_trait;
+use octofhir_fhirpath::evaluator::FhirPathEngine;
+use octofhir_fhirpath::model::{Coding, FhirPathValue};
+use octofhir_fhirpath::parse;
+use octofhir_fhirpath::registry::create_standard_registries;
+use octofhir_fhirpath::registry::function::FunctionResult;
+use octofhir_fhirpath::registry::functions::fhir_types::{MemberOfFunction, TerminologyProvider};
+use serde_json::json;
+use std::sync::Arc;
+
+const GENDER_VS: &str = "http://hl7.org/fhir/ValueSet/administrative-gender";
+const GENDER_CS: &str = "http://hl7.org/fhir/administrative-gender";
+
+/// Stub provider knowing a single value set with a single code system
+struct StubTerminologyProvider;
+
+#[async_trait]
+impl TerminologyProvider for StubTerminologyProvider {
+    async fn member_of(&self, value_set_url: &str, coding: &Coding) -> FunctionResult<bool> {
+        if value_set_url != GENDER_VS {
+            return Ok(false);
+        }
+        let system_matches = coding.system.as_deref().is_none_or(|s| s == GENDER_CS);
+        Ok(
+            system_matches
+                && ["male", "female", "other", "unknown"].contains(&coding.code.as_str()),
+        )
+    }
+}
+
+fn engine_with_stub_provider() -> FhirPathEngine {
+    let (mut functions, operators) = create_standard_registries();
+    functions.register_async(MemberOfFunction::with_provider(Arc::new(
+        StubTerminologyProvider,
+    )));
+    FhirPathEngine::with_registries(Arc::new(functions), Arc::new(operators))
+}
+
+async fn eval(
+    engine: &FhirPathEngine,
+    expression: &str,
+    input: serde_json::Value,
+) -> FhirPathValue {
+    let ast = parse(expression).expect("expression should parse");
+    engine
+        .evaluate(&ast, FhirPathValue::from(input))
+        .await
+        .expect("expression should evaluate")
+}
+
+fn as_bool(value: &FhirPathValue) -> Option<bool> {
+    match value {
+        FhirPathValue::Boolean(b) => Some(*b),
+        FhirPathValue::Collection(items) if items.len() == 1 => match items.get(0) {
+            Some(FhirPathValue::Boolean(b)) => Some(*b),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn test_member_of_bare_code_string() {
+    let engine = engine_with_stub_provider();
+    let patient = json!({"resourceType": "Patient", "gender": "male"});
+
+    let result = eval(
+        &engine,
+        &format!("Patient.gender.memberOf('{GENDER_VS}')"),
+        patient.clone(),
+    )
+    .await;
+    assert_eq!(as_bool(&result), Some(true));
+
+    let result = eval(
+        &engine,
+        &format!("'dragon'.memberOf('{GENDER_VS}')"),
+        patient,
+    )
+    .await;
+    assert_eq!(as_bool(&result), Some(false));
+}
+
+#[tokio::test]
+async fn test_member_of_coding_and_concept() {
+    let engine = engine_with_stub_provider();
+    let observation = json!({
+        "resourceType": "Observation",
+        "code": {
+            "coding": [
+                {"system": "http://loinc.org", "code": "male"},
+                {"system": GENDER_CS, "code": "female"}
+            ]
+        }
+    });
+
+    let result = eval(
+        &engine,
+        &format!("Observation.code.memberOf('{GENDER_VS}')"),
+        observation.clone(),
+    )
+    .await;
+    assert_eq!(as_bool(&result), Some(true));
+
+    // The LOINC coding carries a system, so it is not lifted to a bare code
+    let result = eval(
+        &engine,
+        &format!("Observation.code.coding.first().memberOf('{GENDER_VS}')"),
+        observation,
+    )
+    .await;
+    assert_eq!(as_bool(&result), Some(false));
+}
+
+#[tokio::test]
+async fn test_member_of_without_provider_is_empty() {
+    let engine = FhirPathEngine::new();
+    let result = eval(
+        &engine,
+        &format!("'male'.memberOf('{GENDER_VS}')"),
+        json!({"resourceType": "Patient"}),
+    )
+    .await;
+    assert!(result.is_empty());
+}
+
+#[tokio::test]
+async fn test_member_of_with_value_set_abbreviation() {
+    let engine = engine_with_stub_provider();
+    let result = eval(
+        &engine,
+        "Patient.gender.memberOf(%`vs-administrative-gender`)",
+        json!({"resourceType": "Patient", "gender": "female"}),
+    )
+    .await;
+    assert_eq!(as_bool(&result), Some(true));
+}
@@ -0,0 +1,80 @@
+//! Tests for unioning resources with scalars and serializing the mixed result
+
+mod common;
+
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn test_union_patients_with_strings_serializes_each_item() {
+    let expected = json!([
+        {"resourceType": "Patient", "id": "p1", "active": true},
+        {"resourceType": "Patient", "id": "p2"},
+        "p1",
+        "p2"
+    ]);
+
+    for expression in [
+        "Bundle.entry.resource | Bundle.entry.resource.id",
+        "Bundle.entry.resource.union(id)",
+    ] {
+        let result = common::eval(expression, common::bundle()).await;
+        assert_eq!(Value::from(result.clone()), expected, "{expression}");
+        assert_eq!(
+            serde_json::to_value(&result).expect("Should serialize"),
+            expected,
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_union_removes_duplicates_across_types() {
+    let result = common::eval(
+        "Bundle.entry.resource.union(first() | 'x' | 1 | 'x')",
+        common::bundle(),
+    )
+    .await;
+
+    assert_eq!(
+        serde_json::to_value(&result).expect("Should serialize"),
+        json!([
+            {"resourceType": "Patient", "id": "p1", "active": true},
+            {"resourceType": "Patient", "id": "p2"},
+            "x",
+            1
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_combine_keeps_duplicates_in_mixed_collection() {
+    let result = common::eval(
+        "Bundle.entry.resource.first().combine('p1' | true)",
+        common::bundle(),
+    )
+    .await;
+
+    assert_eq!(
+        serde_json::to_value(&result).expect("Should serialize"),
+        json!([
+            {"resourceType": "Patient", "id": "p1", "active": true},
+            "p1",
+            true
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_union_removes_duplicates_within_each_side() {
+    for expression in [
+        "'p1'.combine('p1') | 1.combine(1.0)",
+        "'p1'.combine('p1').union(1.combine(1.0))",
+    ] {
+        let result = common::eval(expression, common::bundle()).await;
+        assert_eq!(
+            serde_json::to_value(&result).expect("Should serialize"),
+            json!(["p1", 1]),
+            "{expression}"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Tests for navigating into Narrative.div as a plain string
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::{Value, json};
+
+const DIV: &str =
+    "<div xmlns=\"http://www.w3.org/1999/xhtml\"><p>Peter James <b>Chalmers</b></p></div>";
+
+fn patient() -> Value {
+    json!({
+        "resourceType": "Patient",
+        "text": {"status": "generated", "div": DIV},
+        "name": [{"family": "Chalmers"}]
+    })
+}
+
+fn truth(value: bool) -> FhirPathValue {
+    FhirPathValue::collection(vec![FhirPathValue::Boolean(value)])
+}
+
+#[tokio::test]
+async fn test_div_is_the_raw_markup() {
+    assert_eq!(
+        common::eval("Patient.text.div", patient()).await,
+        FhirPathValue::String(DIV.into())
+    );
+    assert_eq!(
+        common::eval("Patient.text.`div`", patient()).await,
+        FhirPathValue::String(DIV.into())
+    );
+}
+
+#[tokio::test]
+async fn test_string_functions_apply_to_div() {
+    assert_eq!(
+        common::eval("text.div.contains('<p>')", patient()).await,
+        FhirPathValue::Boolean(true)
+    );
+    assert_eq!(
+        common::eval("text.div.contains('<table>')", patient()).await,
+        FhirPathValue::Boolean(false)
+    );
+    assert_eq!(
+        common::eval("text.div.length()", patient()).await,
+        FhirPathValue::Integer(DIV.chars().count() as i64)
+    );
+    assert_eq!(
+        common::eval(
+            "text.div.contains(%resource.name.family.first())",
+            patient()
+        )
+        .await,
+        FhirPathValue::Boolean(true)
+    );
+}
+
+#[tokio::test]
+async fn test_div_is_a_system_string() {
+    assert_eq!(
+        common::eval("text.div is System.String", patient()).await,
+        truth(true)
+    );
+    assert_eq!(
+        common::eval("text.div.type().namespace", patient()).await,
+        FhirPathValue::String("System".into())
+    );
+}
+
+#[tokio::test]
+async fn test_xhtml_is_not_parsed() {
+    assert!(common::eval("text.div.p", patient()).await.is_empty());
+    assert!(common::eval("text.div.b", patient()).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_div_operator_still_works() {
+    assert_eq!(
+        common::eval("7 div 2", patient()).await,
+        FhirPathValue::Integer(3)
+    );
+    assert_eq!(
+        common::eval("text.div.length() div 1000", patient()).await,
+        FhirPathValue::Integer(0)
+    );
+}
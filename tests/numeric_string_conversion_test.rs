@@ -0,0 +1,94 @@
+//! Tests for the exact string formats accepted by toInteger() and toDecimal()
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use rust_decimal::Decimal;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_to_integer_accepts_signed_strings() {
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'5'.toInteger()", json!({})).await),
+        FhirPathValue::Integer(5)
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'+5'.toInteger()", json!({})).await),
+        FhirPathValue::Integer(5)
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'-5'.toInteger()", json!({})).await),
+        FhirPathValue::Integer(-5)
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'+5'.convertsToInteger()", json!({})).await),
+        FhirPathValue::Boolean(true)
+    );
+}
+
+#[tokio::test]
+async fn test_to_integer_rejects_whitespace_and_separators() {
+    for input in [
+        "' 5'", "'5 '", "'1,000'", "'1_000'", "'+'", "'--5'", "'5.0'", "''",
+    ] {
+        assert_eq!(
+            common::unwrap_singleton(
+                common::eval(&format!("{input}.toInteger()"), json!({})).await
+            ),
+            FhirPathValue::Empty,
+            "{input} should not convert"
+        );
+        assert_eq!(
+            common::unwrap_singleton(
+                common::eval(&format!("{input}.convertsToInteger()"), json!({})).await
+            ),
+            FhirPathValue::Boolean(false),
+            "{input} should not convert"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_to_decimal_accepts_signed_strings() {
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'+1.5'.toDecimal()", json!({})).await),
+        FhirPathValue::Decimal(Decimal::new(15, 1))
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'-1.5'.toDecimal()", json!({})).await),
+        FhirPathValue::Decimal(Decimal::new(-15, 1))
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'42'.toDecimal()", json!({})).await),
+        FhirPathValue::Decimal(Decimal::from(42))
+    );
+}
+
+#[tokio::test]
+async fn test_to_decimal_rejects_whitespace_and_separators() {
+    for input in [
+        "' 1.5'",
+        "'1.5 '",
+        "'1,000.5'",
+        "'1_000'",
+        "'.5'",
+        "'5.'",
+        "'1e3'",
+        "'+-1'",
+    ] {
+        assert_eq!(
+            common::unwrap_singleton(
+                common::eval(&format!("{input}.toDecimal()"), json!({})).await
+            ),
+            FhirPathValue::Empty,
+            "{input} should not convert"
+        );
+        assert_eq!(
+            common::unwrap_singleton(
+                common::eval(&format!("{input}.convertsToDecimal()"), json!({})).await
+            ),
+            FhirPathValue::Boolean(false),
+            "{input} should not convert"
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Tests for ofType() over heterogeneous collections and the FHIR type hierarchy
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::{Value, json};
+
+fn bundle() -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": [
+            {"resource": {"resourceType": "Patient", "id": "p1"}},
+            {"resource": {"resourceType": "Observation", "id": "o1"}},
+            {"resource": {"resourceType": "Bundle", "id": "b1", "type": "collection"}},
+            {"resource": {"resourceType": "Patient", "id": "p2"}},
+            {"resource": {"resourceType": "Parameters", "id": "x1"}}
+        ]
+    })
+}
+
+/// The ids of the resources `expression` selects from the Bundle entries
+async fn ids_of_type(type_specifier: &str) -> Vec<String> {
+    let expression = format!("entry.resource.ofType({type_specifier}).id");
+    match common::eval(&expression, bundle()).await {
+        FhirPathValue::Collection(items) => items.iter().map(id_string).collect(),
+        FhirPathValue::Empty => Vec::new(),
+        single => vec![id_string(&single)],
+    }
+}
+
+fn id_string(value: &FhirPathValue) -> String {
+    match value {
+        FhirPathValue::String(s) => s.to_string(),
+        other => panic!("expected an id, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_of_type_filters_resources_by_type() {
+    assert_eq!(ids_of_type("Patient").await, ["p1", "p2"]);
+    assert_eq!(ids_of_type("FHIR.Observation").await, ["o1"]);
+    assert_eq!(ids_of_type("FHIR.`Bundle`").await, ["b1"]);
+    assert!(ids_of_type("Encounter").await.is_empty());
+}
+
+#[tokio::test]
+async fn test_of_type_follows_the_resource_hierarchy() {
+    // Bundle and Parameters derive from Resource directly
+    assert_eq!(ids_of_type("DomainResource").await, ["p1", "o1", "p2"]);
+    assert_eq!(
+        ids_of_type("Resource").await,
+        ["p1", "o1", "b1", "p2", "x1"]
+    );
+    assert!(ids_of_type("System.Patient").await.is_empty());
+}
+
+#[tokio::test]
+async fn test_of_type_on_primitives() {
+    assert_eq!(
+        common::eval("(1 | 'a' | 2.5 | true | 'b').ofType(String)", bundle()).await,
+        FhirPathValue::collection(vec![
+            FhirPathValue::String("a".into()),
+            FhirPathValue::String("b".into()),
+        ])
+    );
+    assert_eq!(
+        common::eval("(1 | 'a' | 2.5 | true).ofType(System.Integer)", bundle()).await,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(1)])
+    );
+
+    // Strings are of every FHIR type represented as a string
+    assert_eq!(
+        common::eval("(type | 1).ofType(FHIR.code)", bundle()).await,
+        FhirPathValue::collection(vec![FhirPathValue::String("collection".into())])
+    );
+    assert!(
+        common::eval("(1 | 'a').ofType(FHIR.boolean)", bundle())
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_of_type_on_date_times() {
+    let result = common::eval(
+        "(@2024-01-01 | @2024-01-01T10:00:00Z).ofType(instant).count()",
+        bundle(),
+    )
+    .await;
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(1)])
+    );
+    let result = common::eval(
+        "(@2024-01-01 | @2024-01-01T10:00:00Z).ofType(dateTime).count()",
+        bundle(),
+    )
+    .await;
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(1)])
+    );
+}
+
+#[tokio::test]
+async fn test_is_follows_the_resource_hierarchy() {
+    assert_eq!(
+        common::eval(
+            "entry.resource.where($this is DomainResource).count()",
+            bundle()
+        )
+        .await,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(3)])
+    );
+}
@@ -0,0 +1,105 @@
+//! Tests that parse errors point at the offending part of the expression
+
+use octofhir_fhirpath::{
+    FhirPathError,
+    engine::FhirPathEngine,
+    parser::{ParseError, parse_expression_pratt, parse_expression_spanned},
+};
+use serde_json::json;
+
+#[test]
+fn test_reported_spans() {
+    let cases = [
+        // More input was expected, so the span is empty at the end
+        ("Patient.name.", 13..13),
+        ("(1 + 2", 6..6),
+        ("Patient.name.where(use = 'x'", 28..28),
+        // The token the parser could not use
+        ("Patient.name.where(use = 'x'))", 29..30),
+        ("1 + * 2", 4..5),
+        ("5 mg", 2..4),
+        ("a matches b", 2..9),
+        ("a.b(1,)", 6..7),
+        // Text the tokenizer could not read
+        ("name.given = 'abc", 13..17),
+        ("Patient.name # x", 13..14),
+        // The literal that is not valid
+        ("@2020-13-45", 0..11),
+        ("'\\u12'", 0..6),
+    ];
+
+    for (expression, span) in cases {
+        let error = parse_expression_spanned(expression).unwrap_err();
+        assert_eq!(error.span, span, "{expression:?}: {error}");
+        assert_eq!(error.expression, expression);
+        assert_eq!(error.error.position(), Some(span.start), "{expression:?}");
+    }
+}
+
+#[test]
+fn test_error_positions_without_span() {
+    let error = parse_expression_pratt("Patient.name.where(use = 'x'))").unwrap_err();
+    assert_eq!(error.position(), Some(29));
+}
+
+#[test]
+fn test_caret_rendering() {
+    let error = parse_expression_spanned("Patient.name.").unwrap_err();
+    assert_eq!(error.error, ParseError::expected_identifier(13));
+    assert_eq!(
+        error.to_string(),
+        "Expected identifier at position 13\n  Patient.name.\n               ^"
+    );
+
+    let error = parse_expression_spanned("5 mg").unwrap_err();
+    assert!(error.to_string().ends_with("\n  5 mg\n    ^^"), "{error}");
+}
+
+#[test]
+fn test_caret_rendering_multi_line_and_non_ascii() {
+    // Only the line with the error is shown
+    let error = parse_expression_spanned("Patient\n  .name + * 2").unwrap_err();
+    assert_eq!(error.span, 18..19);
+    assert_eq!(
+        error.caret_lines(),
+        ("  .name + * 2", "          ^".to_string())
+    );
+
+    // The caret is placed by characters, not bytes
+    let error = parse_expression_spanned("name.where('é' = é)").unwrap_err();
+    assert_eq!(error.span, 18..20);
+    assert_eq!(
+        error.caret_lines(),
+        ("name.where('é' = é)", "                 ^".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_engine_reports_spans() {
+    let engine = FhirPathEngine::new();
+
+    let error = engine
+        .compile("Patient.name.where(use = 'x'))")
+        .unwrap_err();
+    assert_eq!(error.span, 29..30);
+
+    let patient = json!({"resourceType": "Patient"});
+    match engine.evaluate_bool("1 + * 2", patient.clone()).await {
+        Err(FhirPathError::ParseError { position, message }) => {
+            assert_eq!(position, 4);
+            assert!(message.ends_with("\n  1 + * 2\n      ^"), "{message}");
+        }
+        other => panic!("expected a parse error, got {other:?}"),
+    }
+
+    // evaluate() reports syntax errors rather than giving empty
+    for (expression, expected) in [("Patient.name.", 13), ("Patient.name)", 12)] {
+        match engine.evaluate(expression, patient.clone()).await {
+            Err(FhirPathError::ParseError { position, message }) => {
+                assert_eq!(position, expected, "{expression}");
+                assert!(message.contains(expression), "{message}");
+            }
+            other => panic!("{expression}: expected a parse error, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Tests for arithmetic and comparison of quantities across convertible units
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::json;
+
+/// Assert that each expression evaluates to `true`
+async fn assert_true(expressions: &[&str]) {
+    for expression in expressions {
+        assert_eq!(
+            common::unwrap_singleton(common::eval(expression, json!({})).await),
+            FhirPathValue::Boolean(true),
+            "{expression}"
+        );
+    }
+}
+
+/// Evaluate `expression` and render it with `toString()`
+async fn eval_string(expression: &str) -> String {
+    let result = common::eval(&format!("({expression}).toString()"), json!({})).await;
+    match common::unwrap_singleton(result) {
+        FhirPathValue::String(s) => s.to_string(),
+        other => panic!("{expression} gave {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_mass() {
+    assert_eq!(eval_string("5 'mg' + 10 'mg'").await, "15 'mg'");
+    assert_eq!(eval_string("1 'g' - 100 'mg'").await, "0.9 'g'");
+    assert_eq!(eval_string("3 * 2 'kg'").await, "6 'kg'");
+    assert_eq!(eval_string("2 'kg' * 1.5").await, "3.0 'kg'");
+    assert_true(&[
+        "1 'kg' = 1000 'g'",
+        "1 'kg' >= 1000 'g'",
+        "1 'g' > 999 'mg'",
+        "500 'mg' < 1 'g'",
+        "1 'g' + 500 'mg' = 1.5 'g'",
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_length() {
+    assert_eq!(eval_string("1 'm' + 10 'cm'").await, "1.1 'm'");
+    assert_eq!(eval_string("1 'm' - 1 'cm'").await, "0.99 'm'");
+    assert_eq!(eval_string("6 'm' / 2").await, "3 'm'");
+    assert_true(&[
+        "1 'm' = 100 'cm'",
+        "1 'm' > 10 'cm'",
+        "1 'km' >= 1000 'm'",
+        "2.0 'cm' * 2.0 'm' = 0.040 'm2'",
+        "1.0 'm' / 1.0 'm' = 1 '1'",
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_time() {
+    assert_eq!(eval_string("6 'm' / 2 's'").await, "3 'm/s'");
+    assert_eq!(eval_string("2 * 1 year").await, "2 years");
+    assert_true(&[
+        "1 'h' = 60 'min'",
+        "1 'h' < 61 'min'",
+        "1 'd' = 24 'h'",
+        "1 'wk' > 6 'd'",
+        "30 'min' + 30 'min' = 1 'h'",
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_incompatible_units() {
+    assert!(common::try_eval("1 'mg' + 1 's'", json!({})).await.is_err());
+    assert!(common::try_eval("1 'm' - 1 'g'", json!({})).await.is_err());
+    assert_eq!(
+        common::unwrap_singleton(common::eval("1 'mg' < 1 's'", json!({})).await),
+        FhirPathValue::Empty
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("1 'mg' = 1 's'", json!({})).await),
+        FhirPathValue::Boolean(false)
+    );
+}
@@ -0,0 +1,109 @@
+//! Tests for FHIR Quantity elements interoperating with quantity literals
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use rust_decimal::Decimal;
+use serde_json::{Value, json};
+
+fn observation() -> Value {
+    json!({
+        "resourceType": "Observation",
+        "status": "final",
+        "valueQuantity": {
+            "value": 5,
+            "unit": "mg",
+            "system": "http://unitsofmeasure.org",
+            "code": "mg"
+        },
+        "component": [{
+            "valueQuantity": {
+                "value": 5000,
+                "unit": "microgram",
+                "system": "http://unitsofmeasure.org",
+                "code": "ug"
+            }
+        }]
+    })
+}
+
+async fn assert_boolean(cases: &[(&str, bool)]) {
+    for (expression, expected) in cases {
+        assert_eq!(
+            common::eval(expression, observation()).await,
+            FhirPathValue::Boolean(*expected),
+            "{expression}"
+        );
+    }
+}
+
+fn quantity(value: i64, unit: &str) -> FhirPathValue {
+    FhirPathValue::quantity(Decimal::from(value), Some(unit.to_string()))
+}
+
+#[tokio::test]
+async fn test_quantity_element_equals_literal() {
+    assert_boolean(&[
+        ("Observation.valueQuantity = 5 'mg'", true),
+        ("5 'mg' = Observation.valueQuantity", true),
+        ("Observation.value = 5 'mg'", true),
+        ("Observation.valueQuantity = 6 'mg'", false),
+        ("Observation.valueQuantity != 5 'mg'", false),
+        ("Observation.valueQuantity ~ 5 'mg'", true),
+        // Compared after unit conversion
+        ("Observation.valueQuantity = 5000 'ug'", true),
+        (
+            "Observation.valueQuantity = Observation.component.valueQuantity",
+            true,
+        ),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_quantity_element_ordering() {
+    assert_boolean(&[
+        ("Observation.valueQuantity > 4 'mg'", true),
+        ("Observation.valueQuantity < 4 'mg'", false),
+        ("Observation.valueQuantity <= 5 'mg'", true),
+        ("Observation.valueQuantity >= 1 'g'", false),
+        ("Observation.component.valueQuantity < 6 'mg'", true),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_quantity_element_arithmetic() {
+    assert_eq!(
+        common::eval("Observation.valueQuantity + 1 'mg'", observation()).await,
+        quantity(6, "mg")
+    );
+    assert_eq!(
+        common::eval("Observation.valueQuantity * 2", observation()).await,
+        quantity(10, "mg")
+    );
+    assert_eq!(
+        common::eval(
+            "(Observation.valueQuantity + 1 'mg') = 6 'mg'",
+            observation()
+        )
+        .await,
+        FhirPathValue::Boolean(true)
+    );
+}
+
+#[tokio::test]
+async fn test_quantity_element_keeps_its_elements() {
+    assert_eq!(
+        common::eval("Observation.valueQuantity.code", observation()).await,
+        FhirPathValue::String("mg".into())
+    );
+    assert_eq!(
+        common::eval("Observation.valueQuantity.system", observation()).await,
+        FhirPathValue::String("http://unitsofmeasure.org".into())
+    );
+    assert_eq!(
+        common::eval("Observation.valueQuantity.value", observation()).await,
+        FhirPathValue::Integer(5)
+    );
+}
@@ -0,0 +1,45 @@
+//! Tests for toString() and toCanonicalString() on quantities
+
+mod common;
+
+use serde_json::json;
+
+#[tokio::test]
+async fn test_to_string_keeps_original_unit() {
+    assert_eq!(
+        common::unwrap_singleton(common::eval("(1000 'mg').toString()", json!({})).await),
+        common::string("1000 'mg'")
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("1 'wk'.toString()", json!({})).await),
+        common::string("1 'wk'")
+    );
+}
+
+#[tokio::test]
+async fn test_to_canonical_string_uses_base_units() {
+    assert_eq!(
+        common::unwrap_singleton(common::eval("(1000 'mg').toCanonicalString()", json!({})).await),
+        common::string("1 'g'")
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("(2 'km').toCanonicalString()", json!({})).await),
+        common::string("2000 'm'")
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("(1 'h').toCanonicalString()", json!({})).await),
+        common::string("3600 's'")
+    );
+}
+
+#[tokio::test]
+async fn test_to_canonical_string_of_non_quantity_matches_to_string() {
+    assert_eq!(
+        common::unwrap_singleton(common::eval("42.toCanonicalString()", json!({})).await),
+        common::string("42")
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'text'.toCanonicalString()", json!({})).await),
+        common::string("text")
+    );
+}
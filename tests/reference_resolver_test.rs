@@ -0,0 +1,171 @@
+//! Tests for resolving references through caller-supplied resolvers
+
+use octofhir_fhirpath::model::FhirResource;
+use octofhir_fhirpath::registry::functions::{
+    AsyncReferenceResolver, DanglingReferences, ReferenceResolver,
+};
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use serde_json::{Value, json};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Resolver serving patients from a map, recording every reference it is asked for
+#[derive(Default)]
+struct MockResolver {
+    requests: Mutex<Vec<String>>,
+}
+
+impl ReferenceResolver for MockResolver {
+    fn resolve(&self, reference: &str) -> Option<FhirResource> {
+        self.requests.lock().unwrap().push(reference.to_string());
+        let id = reference.strip_prefix("Patient/")?;
+        (id != "missing").then(|| {
+            FhirResource::from_json(json!({
+                "resourceType": "Patient",
+                "id": id,
+                "name": [{"family": format!("Stored {id}")}]
+            }))
+        })
+    }
+}
+
+fn bundle() -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "entry": [
+            {
+                "fullUrl": "http://example.org/fhir/Patient/local",
+                "resource": {"resourceType": "Patient", "id": "local", "name": [{"family": "Local"}]}
+            },
+            {
+                "fullUrl": "http://example.org/fhir/Observation/obs1",
+                "resource": {
+                    "resourceType": "Observation",
+                    "id": "obs1",
+                    "subject": {"reference": "Patient/local"},
+                    "performer": [{"reference": "Patient/123"}, {"reference": "Patient/missing"}]
+                }
+            }
+        ]
+    })
+}
+
+fn strings(result: &FhirPathValue) -> Vec<String> {
+    let FhirPathValue::Collection(items) = result else {
+        panic!("Expected collection result, got {result:?}");
+    };
+    items
+        .iter()
+        .map(|item| match item {
+            FhirPathValue::String(s) => s.to_string(),
+            other => panic!("Unexpected item {other:?}"),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_resolver_is_consulted_for_references_not_in_the_bundle() {
+    let resolver = Arc::new(MockResolver::default());
+    let engine = FhirPathEngine::new().with_reference_resolver(resolver.clone());
+
+    let result = engine
+        .evaluate(
+            "Bundle.entry.resource.ofType(Observation).performer.resolve().name.family",
+            bundle(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(strings(&result), ["Stored 123"]);
+    assert_eq!(
+        *resolver.requests.lock().unwrap(),
+        ["Patient/123", "Patient/missing"]
+    );
+}
+
+#[tokio::test]
+async fn test_bundle_is_searched_before_the_resolver() {
+    let resolver = Arc::new(MockResolver::default());
+    let engine = FhirPathEngine::new().with_reference_resolver(resolver.clone());
+
+    let result = engine
+        .evaluate(
+            "Bundle.entry.resource.ofType(Observation).subject.resolve().name.family",
+            bundle(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(strings(&result), ["Local"]);
+    assert!(resolver.requests.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_unresolved_references_follow_the_dangling_policy() {
+    let resolver = Arc::new(MockResolver::default());
+    let engine = FhirPathEngine::new()
+        .with_reference_resolver(resolver.clone())
+        .with_dangling_references(DanglingReferences::Drop);
+
+    let result = engine
+        .evaluate(
+            "Bundle.entry.resource.ofType(Observation).performer.resolve().id",
+            bundle(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(strings(&result), ["123"]);
+}
+
+/// Slow resolver recording how many requests were in flight at once
+#[derive(Default)]
+struct StubServer {
+    in_flight: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl AsyncReferenceResolver for StubServer {
+    async fn resolve(&self, reference: &str) -> Option<FhirResource> {
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(in_flight, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        let id = reference.strip_prefix("Patient/")?;
+        Some(FhirResource::from_json(
+            json!({"resourceType": "Patient", "id": id}),
+        ))
+    }
+}
+
+/// Resolve 100 references through `server`, returning the resolved ids
+async fn resolve_through(server: Arc<StubServer>, max_concurrent: usize) -> Vec<String> {
+    let performers: Vec<Value> = (0..100)
+        .map(|i| json!({"reference": format!("Patient/{i}")}))
+        .collect();
+    let observation = json!({"resourceType": "Observation", "performer": performers});
+
+    let engine = FhirPathEngine::new().with_async_reference_resolver(server, max_concurrent);
+    let result = engine
+        .evaluate("Observation.performer.resolve().id", observation)
+        .await
+        .unwrap();
+    strings(&result)
+}
+
+#[tokio::test]
+async fn test_async_resolver_fetches_concurrently_in_order() {
+    let server = Arc::new(StubServer::default());
+    let ids = resolve_through(server.clone(), 10).await;
+
+    let expected: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+    assert_eq!(ids, expected);
+    assert_eq!(server.peak.load(Ordering::SeqCst), 10);
+}
+
+#[tokio::test]
+async fn test_async_resolver_concurrency_is_limited() {
+    let server = Arc::new(StubServer::default());
+    assert_eq!(resolve_through(server.clone(), 1).await.len(), 100);
+    assert_eq!(server.peak.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,101 @@
+//! Tests for regex functions applied repeatedly with cached compiled patterns
+
+mod common;
+
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use serde_json::{Value, json};
+
+fn patient_with_many_names() -> Value {
+    let given: Vec<String> = (0..200).map(|i| format!("Name{i}")).collect();
+    json!({"resourceType": "Patient", "name": [{"given": given}]})
+}
+
+#[tokio::test]
+async fn test_matches_is_consistent_across_repeated_calls() {
+    let engine = FhirPathEngine::new();
+    // Names ending in 7: Name7, Name17, ..., Name197
+    for _ in 0..3 {
+        assert_eq!(
+            common::items(
+                common::eval_with(
+                    &engine,
+                    "Patient.name.given.where(matches('7$')).count()",
+                    patient_with_many_names()
+                )
+                .await
+            ),
+            vec![FhirPathValue::Integer(20)]
+        );
+        assert_eq!(
+            common::items(
+                common::eval_with(
+                    &engine,
+                    "Patient.name.given.where(matchesFull('Name1[0-9]')).count()",
+                    patient_with_many_names()
+                )
+                .await
+            ),
+            vec![FhirPathValue::Integer(10)]
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_replace_matches_with_cached_pattern() {
+    let engine = FhirPathEngine::new();
+    for _ in 0..3 {
+        assert_eq!(
+            common::items(
+                common::eval_with(
+                    &engine,
+                    "Patient.name.given.select(replaceMatches('[0-9]+', '#')).distinct()",
+                    patient_with_many_names()
+                )
+                .await
+            ),
+            vec![FhirPathValue::String("Name#".into())]
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_pattern_keeps_failing() {
+    let engine = FhirPathEngine::new();
+    for _ in 0..2 {
+        let result = engine
+            .evaluate("'abc'.matches('(unclosed')", json!({}))
+            .await;
+        assert!(result.is_err(), "invalid pattern should error: {result:?}");
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_pattern_reports_compilation_message() {
+    let engine = FhirPathEngine::new();
+    for expression in [
+        "'abc'.matches('(unclosed')",
+        "'abc'.matchesFull('(unclosed')",
+    ] {
+        let error = engine
+            .evaluate(expression, json!({}))
+            .await
+            .expect_err("invalid pattern should error");
+        assert!(error.to_string().contains("unclosed group"), "{error}");
+    }
+}
+
+#[tokio::test]
+async fn test_matches_on_empty_input_is_empty() {
+    let engine = FhirPathEngine::new();
+    for expression in [
+        "{}.matches('a')",
+        "{}.matchesFull('a')",
+        "'abc'.matches({})",
+    ] {
+        let result = engine.evaluate(expression, json!({})).await.unwrap();
+        assert!(
+            result.is_empty(),
+            "{expression} should be empty: {result:?}"
+        );
+    }
+}
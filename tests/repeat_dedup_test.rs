@@ -0,0 +1,124 @@
+//! Tests for repeat() de-duplicating results while traversing linked resources
+
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use serde_json::{Value, json};
+
+const LAYERS: usize = 10;
+const WIDTH: usize = 3;
+
+/// Bundle of Observations where every node links to every node in the next layer
+///
+/// There are `WIDTH^(LAYERS - 1)` paths from a root to the last layer, but only
+/// `WIDTH * (LAYERS - 1)` distinct observations reachable from it.
+fn lattice_bundle() -> Value {
+    let mut entries = Vec::new();
+    for layer in 0..LAYERS {
+        for node in 0..WIDTH {
+            let has_member: Vec<Value> = if layer + 1 < LAYERS {
+                (0..WIDTH)
+                    .map(|next| json!({"reference": format!("Observation/n{}-{next}", layer + 1)}))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            entries.push(json!({
+                "fullUrl": format!("http://example.com/Observation/n{layer}-{node}"),
+                "resource": {
+                    "resourceType": "Observation",
+                    "id": format!("n{layer}-{node}"),
+                    "status": "final",
+                    "hasMember": has_member
+                }
+            }));
+        }
+    }
+    json!({"resourceType": "Bundle", "type": "collection", "entry": entries})
+}
+
+fn ids(result: &FhirPathValue) -> Vec<String> {
+    match result {
+        FhirPathValue::Collection(items) => items
+            .iter()
+            .map(|item| match item {
+                FhirPathValue::String(s) => s.to_string(),
+                other => panic!("Expected string id, got {other:?}"),
+            })
+            .collect(),
+        FhirPathValue::String(s) => vec![s.to_string()],
+        FhirPathValue::Empty => Vec::new(),
+        other => panic!("Expected collection of ids, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_repeat_resolve_dedups_duplicate_paths() {
+    let engine = FhirPathEngine::new();
+    let result = engine
+        .evaluate(
+            "Bundle.entry.resource.where(id = 'n0-0').repeat(hasMember.resolve()).id",
+            lattice_bundle(),
+        )
+        .await
+        .expect("Should evaluate successfully");
+
+    let ids = ids(&result);
+    let expected: Vec<String> = (1..LAYERS)
+        .flat_map(|layer| (0..WIDTH).map(move |node| format!("n{layer}-{node}")))
+        .collect();
+    assert_eq!(ids, expected);
+}
+
+#[tokio::test]
+async fn test_repeat_excludes_input_and_stops_on_cycles() {
+    let bundle = json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": [
+            {
+                "fullUrl": "http://example.com/Observation/a",
+                "resource": {
+                    "resourceType": "Observation",
+                    "id": "a",
+                    "hasMember": [{"reference": "Observation/b"}]
+                }
+            },
+            {
+                "fullUrl": "http://example.com/Observation/b",
+                "resource": {
+                    "resourceType": "Observation",
+                    "id": "b",
+                    "hasMember": [{"reference": "Observation/a"}]
+                }
+            }
+        ]
+    });
+
+    let engine = FhirPathEngine::new();
+    let result = engine
+        .evaluate(
+            "Bundle.entry.resource.where(id = 'a').repeat(hasMember.resolve()).id",
+            bundle,
+        )
+        .await
+        .expect("Should evaluate successfully");
+
+    // b is reached from a, then a is reached from b; each appears once
+    assert_eq!(ids(&result), vec!["b".to_string(), "a".to_string()]);
+}
+
+#[tokio::test]
+async fn test_repeat_literal_projection_yields_single_value() {
+    let engine = FhirPathEngine::new();
+    let result = engine
+        .evaluate(
+            "Patient.name.repeat('test')",
+            json!({
+                "resourceType": "Patient",
+                "name": [{"family": "Chalmers"}, {"family": "Windsor"}]
+            }),
+        )
+        .await
+        .expect("Should evaluate successfully");
+
+    assert_eq!(ids(&result), vec!["test".to_string()]);
+}
@@ -0,0 +1,208 @@
+//! Tests for the output composition, order and termination of repeat() on nested items
+
+mod common;
+
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use serde_json::{Value, json};
+
+/// QuestionnaireResponse with two top-level groups and nested questions
+///
+/// ```text
+/// g1
+/// ├── q1.1
+/// │   └── q1.1.1
+/// └── q1.2
+/// g2
+/// └── q2.1
+/// ```
+fn questionnaire_response() -> Value {
+    json!({
+        "resourceType": "QuestionnaireResponse",
+        "status": "completed",
+        "item": [
+            {
+                "linkId": "g1",
+                "item": [
+                    {
+                        "linkId": "q1.1",
+                        "item": [
+                            {"linkId": "q1.1.1", "answer": [{"valueString": "deep"}]}
+                        ]
+                    },
+                    {"linkId": "q1.2", "answer": [{"valueBoolean": true}]}
+                ]
+            },
+            {
+                "linkId": "g2",
+                "item": [{"linkId": "q2.1", "answer": [{"valueInteger": 3}]}]
+            }
+        ]
+    })
+}
+
+async fn link_ids(expression: &str) -> Vec<String> {
+    match common::eval(expression, questionnaire_response()).await {
+        FhirPathValue::Collection(items) => items
+            .iter()
+            .map(|item| match item {
+                FhirPathValue::String(s) => s.to_string(),
+                other => panic!("Expected string linkId, got {other:?}"),
+            })
+            .collect(),
+        FhirPathValue::String(s) => vec![s.to_string()],
+        FhirPathValue::Empty => Vec::new(),
+        other => panic!("Expected collection of linkIds, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_repeat_from_resource_yields_all_items_in_discovery_order() {
+    // Each round only expands the items found in the previous round
+    assert_eq!(
+        link_ids("QuestionnaireResponse.repeat(item).linkId").await,
+        vec!["g1", "g2", "q1.1", "q1.2", "q2.1", "q1.1.1"]
+    );
+}
+
+#[tokio::test]
+async fn test_repeat_does_not_include_seed_items() {
+    // The top-level items are the input, so only their descendants are returned
+    assert_eq!(
+        link_ids("QuestionnaireResponse.item.repeat(item).linkId").await,
+        vec!["q1.1", "q1.2", "q2.1", "q1.1.1"]
+    );
+
+    assert_eq!(
+        link_ids("QuestionnaireResponse.item.where(linkId = 'g2').repeat(item).linkId").await,
+        vec!["q2.1"]
+    );
+}
+
+#[tokio::test]
+async fn test_repeat_includes_seed_when_projection_yields_it() {
+    // $this is yielded for every seed, so the seeds are matched and included
+    assert_eq!(
+        link_ids("QuestionnaireResponse.item.repeat($this | item).linkId").await,
+        vec!["g1", "q1.1", "q1.2", "g2", "q2.1", "q1.1.1"]
+    );
+}
+
+#[tokio::test]
+async fn test_repeat_on_leaf_items_is_empty() {
+    assert!(
+        link_ids("QuestionnaireResponse.repeat(item).where(item.empty()).repeat(item).linkId")
+            .await
+            .is_empty()
+    );
+}
+
+/// Questionnaire with groups nested three levels deep
+fn questionnaire() -> Value {
+    json!({
+        "resourceType": "Questionnaire",
+        "status": "active",
+        "item": [
+            {
+                "linkId": "demographics",
+                "type": "group",
+                "item": [
+                    {"linkId": "name", "type": "string"},
+                    {
+                        "linkId": "address",
+                        "type": "group",
+                        "item": [
+                            {"linkId": "line", "type": "string", "repeats": true},
+                            {"linkId": "city", "type": "string"}
+                        ]
+                    }
+                ]
+            },
+            {"linkId": "consent", "type": "boolean"}
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_repeat_collects_every_nested_questionnaire_item() {
+    let engine = FhirPathEngine::new();
+    let result = engine
+        .evaluate("Questionnaire.repeat(item).linkId", questionnaire())
+        .await
+        .expect("Should evaluate successfully");
+    assert_eq!(
+        result,
+        FhirPathValue::collection(
+            ["demographics", "consent", "name", "address", "line", "city"]
+                .into_iter()
+                .map(|id| FhirPathValue::String(id.into()))
+                .collect()
+        )
+    );
+
+    let count = engine
+        .evaluate(
+            "Questionnaire.repeat(item).where(type = 'string').count()",
+            questionnaire(),
+        )
+        .await
+        .expect("Should evaluate successfully");
+    assert_eq!(
+        count,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(3)])
+    );
+}
+
+#[tokio::test]
+async fn test_repeat_stops_when_a_cycle_yields_nothing_new() {
+    let engine = FhirPathEngine::new();
+    // 2, 3, 4, 1 and then back to 2
+    let result = engine
+        .evaluate("1.repeat(iif($this < 4, $this + 1, 1))", json!({}))
+        .await
+        .expect("Should evaluate successfully");
+    assert_eq!(
+        result,
+        FhirPathValue::collection(
+            [2, 3, 4, 1]
+                .into_iter()
+                .map(FhirPathValue::Integer)
+                .collect()
+        )
+    );
+
+    // 1.0 and 1 are equal, so switching representation is not progress
+    let result = engine
+        .evaluate(
+            "1.repeat(iif($this is Integer, $this * 1.0, $this.truncate())).count()",
+            json!({}),
+        )
+        .await
+        .expect("Should evaluate successfully");
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(1)])
+    );
+}
+
+#[tokio::test]
+async fn test_repeat_treats_equal_representations_as_one() {
+    for expression in [
+        // 1000 'mg' = 1 'g'
+        "(1000 'mg').repeat(iif(unit = 'mg', 1 'g', 1000 'mg')).count()",
+        // The same instant in different timezones
+        "@2012-04-15T10:00+05:00.repeat(@2012-04-15T05:00Z.combine(@2012-04-15T10:00+05:00)).count()",
+        // A FHIR Quantity element and the System Quantity it equals
+        "Observation.valueQuantity.repeat(%resource.valueQuantity.combine(5.4 'mg')).count()",
+    ] {
+        let observation = json!({
+            "resourceType": "Observation",
+            "valueQuantity": {"value": 5.4, "unit": "mg", "code": "mg"}
+        });
+        let result = common::eval(expression, observation).await;
+        assert_eq!(
+            result,
+            FhirPathValue::collection(vec![FhirPathValue::Integer(1)]),
+            "{expression}"
+        );
+    }
+}
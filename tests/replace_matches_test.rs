@@ -0,0 +1,101 @@
+//! Tests for replaceMatches() substitution handling beyond the official suite
+
+mod common;
+
+use common::string;
+
+#[tokio::test]
+async fn test_replace_matches_numbered_groups() {
+    assert_eq!(
+        common::eval("'abc'.replaceMatches('(b)', '[$1]')", common::patient()).await,
+        string("a[b]c")
+    );
+    // A group reference directly followed by text still refers to the group
+    assert_eq!(
+        common::eval("'abc'.replaceMatches('(b)', '$1x')", common::patient()).await,
+        string("abxc")
+    );
+    assert_eq!(
+        common::eval(
+            "'2024-03-15'.replaceMatches('(\\\\d+)-(\\\\d+)-(\\\\d+)', '$3/$2/$1')",
+            common::patient()
+        )
+        .await,
+        string("15/03/2024")
+    );
+}
+
+#[tokio::test]
+async fn test_replace_matches_named_groups() {
+    assert_eq!(
+        common::eval(
+            "'2024-03-15'.replaceMatches('(?<year>\\\\d{4})-(?<month>\\\\d{2})-(?<day>\\\\d{2})', '${day}.${month}.${year}')",
+            common::patient()
+        ).await,
+        string("15.03.2024")
+    );
+}
+
+#[tokio::test]
+async fn test_replace_matches_literal_dollar() {
+    assert_eq!(
+        common::eval(
+            "'price: 5'.replaceMatches('(\\\\d+)', '\\\\$$1')",
+            common::patient()
+        )
+        .await,
+        string("price: $5")
+    );
+    assert_eq!(
+        common::eval(
+            "'price: 5'.replaceMatches('(\\\\d+)', '$$$1')",
+            common::patient()
+        )
+        .await,
+        string("price: $5")
+    );
+    assert_eq!(
+        common::eval("'a'.replaceMatches('a', '$')", common::patient()).await,
+        string("$")
+    );
+}
+
+#[tokio::test]
+async fn test_replace_matches_on_empty_input_is_empty() {
+    assert!(
+        common::eval("{}.replaceMatches('a', 'b')", common::patient())
+            .await
+            .is_empty()
+    );
+    assert!(
+        common::eval(
+            "Patient.name.suffix.replaceMatches('a', 'b')",
+            common::patient()
+        )
+        .await
+        .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_replace_matches_on_single_item_collection() {
+    assert_eq!(
+        common::eval(
+            "Patient.name.family.replaceMatches('(Ch)almers', '$1')",
+            common::patient()
+        )
+        .await,
+        string("Ch")
+    );
+}
+
+#[tokio::test]
+async fn test_replace_matches_rejects_multiple_items() {
+    let err = common::try_eval(
+        "Patient.name.given.replaceMatches('e', 'E')",
+        common::patient(),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("multiple items"));
+}
@@ -0,0 +1,159 @@
+//! Tests that resolve() searches a Bundle once per distinct reference
+
+use octofhir_fhirpath::registry::function::{AsyncFhirPathFunction, EvaluationContext};
+use octofhir_fhirpath::registry::functions::{ResolutionCache, ResolveFunction};
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use serde_json::{Value, json};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+/// A Bundle of `observations` Observations about two patients, referring to
+/// them alternately by relative reference, absolute URL and urn
+fn bundle(observations: usize) -> Value {
+    let subjects = [
+        "Patient/1",
+        "http://example.org/fhir/Patient/1",
+        "urn:uuid:4f5c2b1e-0000-4000-8000-000000000002",
+    ];
+    let mut entries = vec![
+        json!({
+            "fullUrl": "http://example.org/fhir/Patient/1",
+            "resource": {"resourceType": "Patient", "id": "1", "name": [{"family": "One"}]}
+        }),
+        json!({
+            "fullUrl": "urn:uuid:4f5c2b1e-0000-4000-8000-000000000002",
+            "resource": {"resourceType": "Patient", "id": "2", "name": [{"family": "Two"}]}
+        }),
+    ];
+    entries.extend((0..observations).map(|i| {
+        json!({
+            "fullUrl": format!("http://example.org/fhir/Observation/{i}"),
+            "resource": {
+                "resourceType": "Observation",
+                "id": i.to_string(),
+                "subject": {"reference": subjects[i % subjects.len()]}
+            }
+        })
+    }));
+    json!({"resourceType": "Bundle", "type": "collection", "entry": entries})
+}
+
+/// Resolve `references` against `bundle`, returning the ids found
+async fn resolve_ids(
+    references: &[&str],
+    bundle: &Value,
+    cache: &Arc<ResolutionCache>,
+) -> Vec<String> {
+    let input = FhirPathValue::collection(
+        references
+            .iter()
+            .map(|reference| FhirPathValue::String((*reference).into()))
+            .collect(),
+    );
+    let mut context = EvaluationContext::new(input);
+    context.root = FhirPathValue::from(bundle.clone());
+    context.resolution_cache = Some(cache.clone());
+
+    let result = ResolveFunction::new()
+        .evaluate(&[], &context)
+        .await
+        .expect("resolve() should succeed");
+    let FhirPathValue::Collection(items) = result else {
+        panic!("Expected collection result, got {result:?}");
+    };
+    items
+        .iter()
+        .map(|item| match item {
+            FhirPathValue::Resource(resource) => resource
+                .as_json()
+                .get("id")
+                .and_then(|id| id.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            other => panic!("Unexpected item {other:?}"),
+        })
+        .collect()
+}
+
+fn cache() -> Arc<ResolutionCache> {
+    Arc::new(ResolutionCache::new(NonZeroUsize::new(16).unwrap()))
+}
+
+#[tokio::test]
+async fn test_bundle_is_searched_once_per_reference() {
+    let bundle = bundle(0);
+    let cache = cache();
+    let references = [
+        "Patient/1",
+        "http://example.org/fhir/Patient/1",
+        "urn:uuid:4f5c2b1e-0000-4000-8000-000000000002",
+        "Patient/1",
+    ];
+
+    for _ in 0..3 {
+        assert_eq!(
+            resolve_ids(&references, &bundle, &cache).await,
+            ["1", "1", "2", "1"]
+        );
+    }
+
+    // Patient/1 and its fullUrl share one search
+    let stats = cache.stats();
+    assert_eq!(stats.misses, 2);
+    assert_eq!(stats.hits, 10);
+    assert_eq!(stats.entries, 2);
+}
+
+#[tokio::test]
+async fn test_absolute_references_only_match_their_own_base() {
+    let bundle = bundle(0);
+    let cache = cache();
+
+    // Another server's Patient/1 is not the Bundle's
+    assert_eq!(resolve_ids(&["Patient/1"], &bundle, &cache).await, ["1"]);
+    assert_eq!(
+        resolve_ids(&["http://other.org/fhir/Patient/1"], &bundle, &cache).await,
+        Vec::<String>::new()
+    );
+
+    // An absolute URL not in the Bundle does not hide the relative reference
+    let cache = self::cache();
+    assert_eq!(
+        resolve_ids(&["http://other.org/fhir/Patient/1"], &bundle, &cache).await,
+        Vec::<String>::new()
+    );
+    assert_eq!(resolve_ids(&["Patient/1"], &bundle, &cache).await, ["1"]);
+    assert_eq!(cache.stats().misses, 2);
+}
+
+#[tokio::test]
+async fn test_least_recently_used_references_are_evicted() {
+    let bundle = bundle(0);
+    let cache = Arc::new(ResolutionCache::new(NonZeroUsize::new(1).unwrap()));
+    let references = [
+        "Patient/1",
+        "urn:uuid:4f5c2b1e-0000-4000-8000-000000000002",
+        "Patient/1",
+    ];
+    resolve_ids(&references, &bundle, &cache).await;
+    assert_eq!(cache.stats().misses, 3);
+    assert_eq!(cache.stats().entries, 1);
+}
+
+#[tokio::test]
+async fn test_results_do_not_depend_on_the_cache() {
+    let expression = "entry.resource.ofType(Observation)\
+        .where(subject.resolve().name.family = 'One').id.count()";
+    for size in [0, 1, 1024] {
+        let result = FhirPathEngine::new()
+            .with_resolution_cache_size(size)
+            .evaluate(expression, bundle(30))
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            FhirPathValue::collection(vec![FhirPathValue::Integer(20)]),
+            "cache size {size}"
+        );
+    }
+}
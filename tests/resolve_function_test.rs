@@ -1,7 +1,10 @@
 //! Tests for the resolve() function with Bundle resources
 
-use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use octofhir_fhirpath::registry::create_standard_registries;
+use octofhir_fhirpath::registry::functions::fhir_types::{ResolveFunction, ResolveMode};
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine, evaluator, parse};
 use serde_json::json;
+use std::sync::Arc;
 
 #[tokio::test]
 async fn test_resolve_contained_resource() {
@@ -23,7 +26,7 @@ async fn test_resolve_contained_resource() {
         }]
     });
 
-    let mut engine = FhirPathEngine::new();
+    let engine = FhirPathEngine::new();
     let result = engine
         .evaluate(
             "Patient.generalPractitioner.resolve().name.family",
@@ -79,7 +82,7 @@ async fn test_resolve_bundle_entry_by_relative_reference() {
         ]
     });
 
-    let mut engine = FhirPathEngine::new();
+    let engine = FhirPathEngine::new();
 
     // Test resolving relative reference within Bundle
     let result = engine
@@ -133,7 +136,7 @@ async fn test_resolve_bundle_entry_by_absolute_url() {
         ]
     });
 
-    let mut engine = FhirPathEngine::new();
+    let engine = FhirPathEngine::new();
 
     // Test resolving absolute URL reference within Bundle
     let result = engine
@@ -199,7 +202,7 @@ async fn test_resolve_multiple_references() {
         ]
     });
 
-    let mut engine = FhirPathEngine::new();
+    let engine = FhirPathEngine::new();
 
     // Test resolving multiple references
     let result = engine
@@ -235,7 +238,7 @@ async fn test_resolve_with_empty_collection() {
         // No references to resolve
     });
 
-    let mut engine = FhirPathEngine::new();
+    let engine = FhirPathEngine::new();
 
     // Test resolve on empty collection
     let result = engine
@@ -256,7 +259,7 @@ async fn test_resolve_invalid_reference_ignored() {
         }
     });
 
-    let mut engine = FhirPathEngine::new();
+    let engine = FhirPathEngine::new();
 
     // Invalid references should be ignored (return empty)
     let result = engine
@@ -300,7 +303,7 @@ async fn test_resolve_string_reference_directly() {
         ]
     });
 
-    let mut engine = FhirPathEngine::new();
+    let engine = FhirPathEngine::new();
 
     // Test resolve on a string reference directly
     let result = engine
@@ -320,3 +323,286 @@ async fn test_resolve_string_reference_directly() {
         _ => panic!("Unexpected result"),
     }
 }
+
+#[tokio::test]
+async fn test_resolve_collection_of_reference_strings() {
+    let bundle = json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": [
+            {
+                "fullUrl": "http://example.com/Patient/1",
+                "resource": {"resourceType": "Patient", "id": "1", "gender": "male"}
+            },
+            {
+                "fullUrl": "http://example.com/Patient/2",
+                "resource": {"resourceType": "Patient", "id": "2", "gender": "female"}
+            }
+        ]
+    });
+
+    let engine = FhirPathEngine::new();
+    let result = engine
+        .evaluate("('Patient/1' | 'Patient/2').resolve()", bundle)
+        .await
+        .expect("Should evaluate successfully");
+
+    let FhirPathValue::Collection(items) = result else {
+        panic!("Expected collection result, got {result:?}");
+    };
+    let genders: Vec<_> = items
+        .iter()
+        .map(|item| match item {
+            FhirPathValue::Resource(resource) => resource
+                .as_json()
+                .get("gender")
+                .and_then(|g| g.as_str())
+                .map(str::to_string),
+            other => panic!("Expected resolved resource, got {other:?}"),
+        })
+        .collect();
+    assert_eq!(
+        genders,
+        vec![Some("male".to_string()), Some("female".to_string())]
+    );
+}
+
+fn patient_with_contained_practitioner(reference: &str) -> serde_json::Value {
+    json!({
+        "resourceType": "Patient",
+        "id": "patient1",
+        "contained": [
+            {
+                "resourceType": "Practitioner",
+                "id": "p1",
+                "name": [{"family": "Jones"}]
+            }
+        ],
+        "generalPractitioner": [{"reference": reference}]
+    })
+}
+
+async fn resolve_family(resolve: ResolveFunction, input: serde_json::Value) -> Vec<String> {
+    let (mut functions, operators) = create_standard_registries();
+    functions.register_async(resolve);
+    let engine =
+        evaluator::FhirPathEngine::with_registries(Arc::new(functions), Arc::new(operators));
+
+    let ast = parse("Patient.generalPractitioner.resolve().name.family").expect("Should parse");
+    let result = engine
+        .evaluate(&ast, FhirPathValue::from(input))
+        .await
+        .expect("Should evaluate successfully");
+
+    match result {
+        FhirPathValue::Collection(items) => items
+            .iter()
+            .map(|item| match item {
+                FhirPathValue::String(s) => s.to_string(),
+                other => panic!("Expected string, got {other:?}"),
+            })
+            .collect(),
+        FhirPathValue::Empty => Vec::new(),
+        other => panic!("Expected collection, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_resolve_bare_contained_id_is_strict_by_default() {
+    let result = resolve_family(
+        ResolveFunction::new(),
+        patient_with_contained_practitioner("p1"),
+    )
+    .await;
+    assert!(result.is_empty());
+}
+
+#[tokio::test]
+async fn test_resolve_tolerant_contained_with_fragment() {
+    let result = resolve_family(
+        ResolveFunction::tolerant(),
+        patient_with_contained_practitioner("#p1"),
+    )
+    .await;
+    assert_eq!(result, vec!["Jones"]);
+}
+
+#[tokio::test]
+async fn test_resolve_tolerant_contained_with_bare_id() {
+    let result = resolve_family(
+        ResolveFunction::tolerant(),
+        patient_with_contained_practitioner("p1"),
+    )
+    .await;
+    assert_eq!(result, vec!["Jones"]);
+
+    // Ids that match no contained resource still resolve to nothing
+    let result = resolve_family(
+        ResolveFunction::tolerant(),
+        patient_with_contained_practitioner("p2"),
+    )
+    .await;
+    assert!(result.is_empty());
+}
+
+fn bundle_with_logical_reference(identifier: serde_json::Value) -> serde_json::Value {
+    json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": [
+            {
+                "fullUrl": "http://example.org/fhir/Patient/p1",
+                "resource": {
+                    "resourceType": "Patient",
+                    "id": "p1",
+                    "identifier": [
+                        {"system": "http://example.org/mrn", "value": "12345"}
+                    ]
+                }
+            },
+            {
+                "fullUrl": "http://example.org/fhir/Observation/obs1",
+                "resource": {
+                    "resourceType": "Observation",
+                    "id": "obs1",
+                    "subject": {"type": "Patient", "identifier": identifier}
+                }
+            }
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_resolve_logical_reference_by_identifier() {
+    let bundle = bundle_with_logical_reference(
+        json!({"system": "http://example.org/mrn", "value": "12345"}),
+    );
+
+    let engine = FhirPathEngine::new();
+    let result = engine
+        .evaluate("Bundle.entry.resource.subject.resolve().id", bundle)
+        .await
+        .expect("Should evaluate successfully");
+
+    assert_eq!(
+        result,
+        FhirPathValue::collection(vec![FhirPathValue::String("p1".into())])
+    );
+}
+
+#[tokio::test]
+async fn test_resolve_logical_reference_with_mismatched_identifier() {
+    let bundle = bundle_with_logical_reference(
+        json!({"system": "http://example.org/other", "value": "12345"}),
+    );
+
+    let engine = FhirPathEngine::new();
+    let result = engine
+        .evaluate("Bundle.entry.resource.subject.resolve()", bundle)
+        .await
+        .expect("Should evaluate successfully");
+
+    assert!(result.is_empty());
+}
+
+/// Bundle whose Group lists its members in a different order than the entries
+fn bundle_with_group_members() -> serde_json::Value {
+    json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": [
+            {
+                "fullUrl": "http://example.com/Patient/a",
+                "resource": {"resourceType": "Patient", "id": "a"}
+            },
+            {
+                "fullUrl": "urn:uuid:6f1c2e0a-9d2b-4c55-8e0e-3f4a5b6c7d8e",
+                "resource": {"resourceType": "Patient", "id": "b"}
+            },
+            {
+                "fullUrl": "http://example.com/Patient/c",
+                "resource": {"resourceType": "Patient", "id": "c"}
+            },
+            {
+                "fullUrl": "http://example.com/Group/g",
+                "resource": {
+                    "resourceType": "Group",
+                    "id": "g",
+                    "member": [
+                        {"entity": {"reference": "Patient/c"}},
+                        {"entity": {"reference": "http://example.com/Patient/a"}},
+                        {"entity": {"reference": "Patient/missing"}},
+                        {"entity": {"reference": "urn:uuid:6f1c2e0a-9d2b-4c55-8e0e-3f4a5b6c7d8e"}},
+                        {"entity": {"reference": "Patient/c"}}
+                    ]
+                }
+            }
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_resolve_preserves_reference_order() {
+    let expected = |ids: &[&str]| {
+        FhirPathValue::collection(
+            ids.iter()
+                .map(|id| FhirPathValue::String((*id).into()))
+                .collect(),
+        )
+    };
+
+    // Dropping the dangling reference must not disturb the others
+    let result = FhirPathEngine::lenient()
+        .evaluate(
+            "Bundle.entry.resource.ofType(Group).member.entity.resolve().id",
+            bundle_with_group_members(),
+        )
+        .await
+        .expect("Should evaluate successfully");
+    assert_eq!(result, expected(&["c", "a", "b", "c"]));
+
+    // Placeholders keep the position of their reference
+    let result = FhirPathEngine::new()
+        .with_resolve_mode(ResolveMode::Placeholder)
+        .evaluate(
+            "Bundle.entry.resource.ofType(Group).member.entity.resolve().id",
+            bundle_with_group_members(),
+        )
+        .await
+        .expect("Should evaluate successfully");
+    assert_eq!(result, expected(&["c", "a", "missing", "b", "c"]));
+}
+
+#[tokio::test]
+async fn test_resolve_default_mode_never_returns_placeholders() {
+    let engine = FhirPathEngine::new();
+    for (expression, count) in [
+        (
+            "Bundle.entry.resource.ofType(Group).member.entity.resolve()",
+            4,
+        ),
+        ("'Patient/missing'.resolve()", 0),
+        ("'http://example.com/Patient/missing'.resolve()", 0),
+        ("('Patient/a' | 'Patient/missing').resolve()", 1),
+    ] {
+        let result = engine
+            .evaluate(expression, bundle_with_group_members())
+            .await
+            .expect("Should evaluate successfully");
+        let items: Vec<FhirPathValue> = match result {
+            FhirPathValue::Collection(items) => items.iter().cloned().collect(),
+            FhirPathValue::Empty => Vec::new(),
+            other => vec![other],
+        };
+        assert_eq!(items.len(), count, "{expression}");
+        for item in items {
+            let FhirPathValue::Resource(resource) = item else {
+                panic!("{expression} gave {item:?}");
+            };
+            assert!(
+                resource.as_json().get("_placeholder").is_none(),
+                "{expression} gave a placeholder"
+            );
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Tests for `$this` in where()/all() criteria over collections of scalars
+
+mod common;
+
+use common::strings;
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::{Value, json};
+
+fn patient() -> Value {
+    json!({
+        "resourceType": "Patient",
+        "name": [{"given": ["Peter", "James", "Jim"]}]
+    })
+}
+
+fn integers(values: &[i64]) -> Vec<FhirPathValue> {
+    values.iter().map(|i| FhirPathValue::Integer(*i)).collect()
+}
+
+#[tokio::test]
+async fn test_where_compares_string_this() {
+    assert_eq!(
+        common::items(common::eval("name.given.where($this = 'Jim')", patient()).await),
+        strings(&["Jim"])
+    );
+    assert_eq!(
+        common::items(common::eval("name.given.where($this != 'Jim')", patient()).await),
+        strings(&["Peter", "James"])
+    );
+    assert_eq!(
+        common::items(common::eval("name.given.where($this.startsWith('J'))", patient()).await),
+        strings(&["James", "Jim"])
+    );
+}
+
+#[tokio::test]
+async fn test_where_compares_integer_this() {
+    assert_eq!(
+        common::items(common::eval("(1 | 5 | 10 | 2).where($this > 3)", patient()).await),
+        integers(&[5, 10])
+    );
+    assert_eq!(
+        common::items(
+            common::eval("(1 | 5 | 10).select($this * 2).where($this > 5)", patient()).await
+        ),
+        integers(&[10, 20])
+    );
+}
+
+#[tokio::test]
+async fn test_all_with_scalar_this() {
+    assert_eq!(
+        common::items(common::eval("name.given.all($this.length() >= 3)", patient()).await),
+        vec![FhirPathValue::Boolean(true)]
+    );
+    assert_eq!(
+        common::items(common::eval("(1 | 5 | 10).all($this > 1)", patient()).await),
+        vec![FhirPathValue::Boolean(false)]
+    );
+}
+
+#[tokio::test]
+async fn test_navigating_from_scalar_this_is_empty() {
+    // Scalars have no children, so navigation yields empty rather than an error
+    assert_eq!(
+        common::items(common::eval("name.given.where($this.family.empty())", patient()).await),
+        strings(&["Peter", "James", "Jim"])
+    );
+    assert_eq!(
+        common::items(common::eval("(1 | 5).where($this.value.exists())", patient()).await),
+        vec![]
+    );
+}
@@ -0,0 +1,284 @@
+//! Tests for union/intersect/exclude/combine using FHIRPath equality
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use octofhir_fhirpath::model::PrecisionDate;
+use rust_decimal::Decimal;
+use serde_json::json;
+use std::str::FromStr;
+
+async fn count(expression: &str) -> i64 {
+    common::eval(&format!("({expression}).count()"), json!({}))
+        .await
+        .as_i64()
+        .expect("count() should return an integer")
+}
+
+#[tokio::test]
+async fn test_union_dedups_equal_dates() {
+    assert_eq!(count("@2015-01-01 | @2015-01-01").await, 1);
+    assert_eq!(count("@2015-01-01 | @2015-01-02").await, 2);
+    assert_eq!(count("@2015-01-01.union(@2015-01-01)").await, 1);
+}
+
+#[tokio::test]
+async fn test_union_dedups_datetimes_by_instant() {
+    assert_eq!(
+        count("@2015-01-01T10:00:00Z | @2015-01-01T11:00:00+01:00").await,
+        1
+    );
+    assert_eq!(
+        count("@2015-01-01T10:00:00Z | @2015-01-01T10:00:01Z").await,
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_exclude_dates() {
+    assert_eq!(
+        count("(@2015-01-01 | @2015-02-01).exclude(@2015-01-01)").await,
+        1
+    );
+    assert_eq!(
+        common::eval(
+            "(@2015-01-01 | @2015-02-01).exclude(@2015-01-01)",
+            json!({})
+        )
+        .await,
+        FhirPathValue::collection(vec![FhirPathValue::Date(
+            PrecisionDate::parse("2015-02-01").unwrap()
+        )])
+    );
+}
+
+#[tokio::test]
+async fn test_union_dedups_equivalent_quantities() {
+    assert_eq!(count("1000 'mg' | 1 'g'").await, 1);
+    assert_eq!(count("(1000 'mg').union(1 'g')").await, 1);
+    assert_eq!(count("1 'g' | 2 'g'").await, 2);
+    // Incompatible units never compare equal
+    assert_eq!(count("1 'g' | 1 'm'").await, 2);
+}
+
+#[tokio::test]
+async fn test_exclude_equivalent_quantities() {
+    assert_eq!(count("(1000 'mg' | 5 'g').exclude(1 'g')").await, 1);
+    assert_eq!(count("(1 'm').exclude(1 'g')").await, 1);
+}
+
+#[tokio::test]
+async fn test_combine_keeps_equal_items() {
+    assert_eq!(count("(1000 'mg').combine(1 'g')").await, 2);
+    assert_eq!(count("@2015-01-01.combine(@2015-01-01)").await, 2);
+}
+
+#[tokio::test]
+async fn test_intersect_is_distinct_and_uses_equality() {
+    assert_eq!(
+        count("(1 | 2 | 3).combine(2).intersect(2 | 3 | 4)").await,
+        2
+    );
+    assert_eq!(
+        common::eval("1.combine(1.0).intersect(1.0)", json!({})).await,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(1)])
+    );
+    assert_eq!(count("(1000 'mg').intersect(1 'g')").await, 1);
+}
+
+#[tokio::test]
+async fn test_exclude_keeps_duplicates_of_the_input() {
+    assert_eq!(count("(1 | 2).combine(1).combine(3).exclude(3)").await, 3);
+    assert_eq!(count("(1 | 2).exclude(1.0)").await, 1);
+}
+
+#[tokio::test]
+async fn test_mixed_types_are_never_equal() {
+    let input = "(1 | '1' | true | @2015-01-01)";
+    assert_eq!(
+        common::eval(
+            &format!("{input}.intersect('1' | 'true' | '2015-01-01')"),
+            json!({})
+        )
+        .await,
+        FhirPathValue::collection(common::strings(&["1"]))
+    );
+    assert_eq!(
+        count(&format!("{input}.exclude('1' | 'true' | '2015-01-01')")).await,
+        3
+    );
+    assert_eq!(count(&format!("{input}.combine({input})")).await, 8);
+}
+
+#[tokio::test]
+async fn test_argument_shares_the_function_context() {
+    let patient = json!({
+        "resourceType": "Patient",
+        "name": [
+            {"use": "official", "given": ["Peter", "James"], "family": "Chalmers"},
+            {"use": "usual", "given": ["Jim"]}
+        ]
+    });
+    // The argument's focus is the input collection; $this reaches the outer focus
+    assert_eq!(
+        common::eval("name.given.combine(name.family)", patient.clone()).await,
+        FhirPathValue::collection(common::strings(&["Peter", "James", "Jim"]))
+    );
+    assert_eq!(
+        common::eval(
+            "name.given.exclude($this.name.last().given)",
+            patient.clone()
+        )
+        .await,
+        FhirPathValue::collection(common::strings(&["Peter", "James"]))
+    );
+    assert_eq!(
+        common::eval("name.select(given.intersect($this.given.first()))", patient).await,
+        FhirPathValue::collection(common::strings(&["Peter", "Jim"]))
+    );
+}
+
+#[tokio::test]
+async fn test_subset_and_superset_of_primitive_mixes() {
+    let t = FhirPathValue::Boolean(true);
+    let f = FhirPathValue::Boolean(false);
+    assert_eq!(
+        common::eval("(1 | 'a').subsetOf(1.0 | 'a' | true)", json!({})).await,
+        t
+    );
+    assert_eq!(
+        common::eval("(1 | 'a').subsetOf('1' | 'a')", json!({})).await,
+        f
+    );
+    assert_eq!(
+        common::eval("(1000 'mg').subsetOf(1 'g' | 2 'g')", json!({})).await,
+        t
+    );
+    assert_eq!(
+        common::eval("(1.0 | 'a' | true).supersetOf(1 | 'a')", json!({})).await,
+        t
+    );
+    assert_eq!(
+        common::eval("(1 | 'a').supersetOf(1 | 'true')", json!({})).await,
+        f
+    );
+}
+
+#[tokio::test]
+async fn test_subset_and_superset_of_empty_collections() {
+    let t = FhirPathValue::Boolean(true);
+    assert_eq!(common::eval("{}.subsetOf(1 | 2)", json!({})).await, t);
+    assert_eq!(common::eval("{}.subsetOf({})", json!({})).await, t);
+    assert_eq!(common::eval("(1 | 2).supersetOf({})", json!({})).await, t);
+    assert_eq!(
+        common::eval("(1 | 2).subsetOf({})", json!({})).await,
+        FhirPathValue::Boolean(false)
+    );
+}
+
+#[tokio::test]
+async fn test_subset_and_superset_of_resources() {
+    let bundle = json!({
+        "resourceType": "Bundle",
+        "entry": [
+            {"resource": {"resourceType": "Observation", "id": "a", "valueInteger": 1}},
+            {"resource": {"resourceType": "Observation", "id": "b", "valueInteger": 2}},
+            {"resource": {"resourceType": "Observation", "valueInteger": 1.0, "id": "a"}}
+        ]
+    });
+    let t = FhirPathValue::Boolean(true);
+    let f = FhirPathValue::Boolean(false);
+    // The third entry has the same content as the first
+    for (expression, expected) in [
+        (
+            "entry.resource.last().subsetOf(%resource.entry.resource.first())",
+            &t,
+        ),
+        (
+            "entry.resource.subsetOf(%resource.entry.resource.take(2))",
+            &t,
+        ),
+        (
+            "entry.resource.subsetOf(%resource.entry.resource.skip(1))",
+            &t,
+        ),
+        (
+            "entry.resource.subsetOf(%resource.entry.resource.first())",
+            &f,
+        ),
+        (
+            "entry.resource.first().supersetOf(%resource.entry.resource.last())",
+            &t,
+        ),
+        (
+            "entry.resource.take(2).supersetOf(%resource.entry.resource)",
+            &t,
+        ),
+        (
+            "entry.resource.skip(2).supersetOf(%resource.entry.resource)",
+            &f,
+        ),
+    ] {
+        assert_eq!(
+            &common::eval(expression, bundle.clone()).await,
+            expected,
+            "{expression}"
+        );
+    }
+}
+
+#[test]
+fn test_fhirpath_eq_differs_from_structural_equality() {
+    let integer = FhirPathValue::Integer(1);
+    let decimal = FhirPathValue::Decimal(Decimal::from_str("1.0").unwrap());
+
+    // Structurally an integer is never a decimal, but `1 = 1.0` is true
+    assert_ne!(integer, decimal);
+    assert!(integer.fhirpath_eq(&decimal));
+    assert!(decimal.fhirpath_eq(&integer));
+
+    let centimetre = FhirPathValue::quantity(Decimal::ONE, Some("cm".into()));
+    let millimetres = FhirPathValue::quantity(Decimal::TEN, Some("mm".into()));
+    assert_ne!(centimetre, millimetres);
+    assert!(centimetre.fhirpath_eq(&millimetres));
+
+    // Dates of different precision are structurally different and `=` is
+    // empty, so neither equality holds
+    let year = FhirPathValue::Date(PrecisionDate::parse("2015").unwrap());
+    let day = FhirPathValue::Date(PrecisionDate::parse("2015-01-01").unwrap());
+    assert_ne!(year, day);
+    assert!(!year.fhirpath_eq(&day));
+    assert!(year.fhirpath_eq(&year.clone()));
+}
+
+#[tokio::test]
+async fn test_set_operations_use_fhirpath_equality() {
+    assert_eq!(count("1 | 1.0").await, 1);
+    assert_eq!(count("(1 | 2).union(1.0 | 2.0)").await, 2);
+    assert_eq!(count("(1 | 2 | 3).exclude(2.0)").await, 2);
+    assert_eq!(count("(1 | 1.0 | 2).distinct()").await, 2);
+    assert_eq!(
+        common::eval("1.combine(1.0).isDistinct()", json!({})).await,
+        FhirPathValue::collection(vec![FhirPathValue::Boolean(false)])
+    );
+}
+
+#[tokio::test]
+async fn test_membership_uses_equality() {
+    for expression in [
+        "1 in (1.0)",
+        "(1 | 2) contains 1.0",
+        "1000 'mg' in (1 'g')",
+        "@2018-01-01T10:00:00Z in (@2018-01-01T10:00:00.000Z)",
+    ] {
+        assert_eq!(
+            common::eval(expression, json!({})).await,
+            FhirPathValue::collection(vec![FhirPathValue::Boolean(true)]),
+            "{expression}"
+        );
+    }
+    assert_eq!(
+        common::eval("(1 | 2) contains 3.0", json!({})).await,
+        FhirPathValue::collection(vec![FhirPathValue::Boolean(false)])
+    );
+}
@@ -0,0 +1,58 @@
+//! Tests for single() and its cardinality check
+
+mod common;
+
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use serde_json::{Value, json};
+
+fn patient() -> Value {
+    json!({
+        "resourceType": "Patient",
+        "gender": "female",
+        "name": [
+            {"family": "Chalmers", "given": ["Peter", "James"]},
+            {"family": "Windsor", "given": ["Jim"]}
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_empty_input_gives_empty() {
+    assert!(common::items(common::eval("{}.single()", patient()).await).is_empty());
+    assert!(common::items(common::eval("Patient.birthDate.single()", patient()).await).is_empty());
+}
+
+#[tokio::test]
+async fn test_one_item_is_returned() {
+    assert_eq!(
+        common::items(common::eval("Patient.gender.single()", patient()).await),
+        [FhirPathValue::String("female".into())]
+    );
+    assert_eq!(
+        common::items(
+            common::eval(
+                "Patient.name.where(family = 'Windsor').given.single()",
+                patient()
+            )
+            .await
+        ),
+        [FhirPathValue::String("Jim".into())]
+    );
+}
+
+#[tokio::test]
+async fn test_many_items_is_an_error_giving_the_count() {
+    let error = FhirPathEngine::new()
+        .evaluate("Patient.name.given.single()", patient())
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("single"), "{error}");
+    assert!(error.contains("got 3"), "{error}");
+
+    // first() and last() accept the same input
+    assert_eq!(
+        common::items(common::eval("Patient.name.given.first()", patient()).await),
+        [FhirPathValue::String("Peter".into())]
+    );
+}
@@ -0,0 +1,87 @@
+//! Tests for the counts skip(), take(), tail() and last() accept
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::json;
+
+fn integers(values: &[i64]) -> Vec<FhirPathValue> {
+    values.iter().map(|i| FhirPathValue::Integer(*i)).collect()
+}
+
+#[tokio::test]
+async fn test_skip_clamps_its_count() {
+    assert_eq!(
+        common::items(common::eval("(1 | 2 | 3).skip(1)", json!({})).await),
+        integers(&[2, 3])
+    );
+    assert_eq!(
+        common::items(common::eval("(1 | 2 | 3).skip(3)", json!({})).await),
+        integers(&[])
+    );
+    assert_eq!(
+        common::items(common::eval("(1 | 2 | 3).skip(10)", json!({})).await),
+        integers(&[])
+    );
+    assert_eq!(
+        common::items(common::eval("(1 | 2 | 3).skip(0)", json!({})).await),
+        integers(&[1, 2, 3])
+    );
+    // Negative counts are treated as zero
+    assert_eq!(
+        common::items(common::eval("(1 | 2 | 3).skip(-2)", json!({})).await),
+        integers(&[1, 2, 3])
+    );
+    assert_eq!(
+        common::items(common::eval("{}.skip(1)", json!({})).await),
+        integers(&[])
+    );
+}
+
+#[tokio::test]
+async fn test_take_clamps_its_count() {
+    assert_eq!(
+        common::items(common::eval("(1 | 2 | 3).take(2)", json!({})).await),
+        integers(&[1, 2])
+    );
+    assert_eq!(
+        common::items(common::eval("(1 | 2 | 3).take(10)", json!({})).await),
+        integers(&[1, 2, 3])
+    );
+    assert_eq!(
+        common::items(common::eval("(1 | 2 | 3).take(0)", json!({})).await),
+        integers(&[])
+    );
+    assert_eq!(
+        common::items(common::eval("(1 | 2 | 3).take(-2)", json!({})).await),
+        integers(&[])
+    );
+}
+
+#[tokio::test]
+async fn test_tail_and_last() {
+    assert_eq!(
+        common::items(common::eval("(1 | 2 | 3).tail()", json!({})).await),
+        integers(&[2, 3])
+    );
+    assert_eq!(
+        common::items(common::eval("1.tail()", json!({})).await),
+        integers(&[])
+    );
+    assert_eq!(
+        common::items(common::eval("{}.tail()", json!({})).await),
+        integers(&[])
+    );
+    assert_eq!(
+        common::items(common::eval("(1 | 2 | 3).last()", json!({})).await),
+        integers(&[3])
+    );
+    assert_eq!(
+        common::items(common::eval("1.last()", json!({})).await),
+        integers(&[1])
+    );
+    assert_eq!(
+        common::items(common::eval("{}.last()", json!({})).await),
+        integers(&[])
+    );
+}
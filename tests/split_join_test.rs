@@ -0,0 +1,113 @@
+//! Tests for split() and join() beyond the official suite
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+
+#[tokio::test]
+async fn test_split_on_literal_separator() {
+    assert_eq!(
+        common::eval("'a.b..c'.split('.')", common::patient()).await,
+        FhirPathValue::collection(common::strings(&["a", "b", "", "c"]))
+    );
+    // Regex metacharacters in the separator are not special
+    assert_eq!(
+        common::eval("'a|b'.split('|')", common::patient()).await,
+        FhirPathValue::collection(common::strings(&["a", "b"]))
+    );
+}
+
+#[tokio::test]
+async fn test_split_with_empty_separator_yields_characters() {
+    assert_eq!(
+        common::eval("'abc'.split('')", common::patient()).await,
+        FhirPathValue::collection(common::strings(&["a", "b", "c"]))
+    );
+    assert!(
+        common::eval("''.split('')", common::patient())
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_split_on_empty_input_is_empty() {
+    assert!(
+        common::eval("{}.split(',')", common::patient())
+            .await
+            .is_empty()
+    );
+    assert!(
+        common::eval("Patient.name.suffix.split(',')", common::patient())
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_join_with_and_without_separator() {
+    assert_eq!(
+        common::eval("Patient.name.given.join(', ')", common::patient()).await,
+        FhirPathValue::String("Peter, James".into())
+    );
+    assert_eq!(
+        common::eval("Patient.name.given.join()", common::patient()).await,
+        FhirPathValue::String("PeterJames".into())
+    );
+}
+
+#[tokio::test]
+async fn test_join_rejects_non_string_items() {
+    let err = common::try_eval("('a' | 1).join(',')", common::patient())
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("expects String input items, got Integer"),
+        "{err}"
+    );
+}
+
+const ROUND_TRIP_INPUTS: &[&str] = &[
+    "",
+    "a",
+    "a,b,c",
+    "a,,b",
+    ",a,",
+    ",",
+    ",,",
+    "a::b::",
+    "Müller, 日本, 👍",
+];
+
+#[tokio::test]
+async fn test_split_then_join_round_trips() {
+    for input in ROUND_TRIP_INPUTS {
+        for separator in [",", "::", " ", ""] {
+            let expression = format!("'{input}'.split('{separator}').join('{separator}')");
+            assert_eq!(
+                common::eval(&expression, common::patient()).await,
+                FhirPathValue::String((*input).into()),
+                "{expression}"
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_to_chars_then_join_round_trips() {
+    for input in ROUND_TRIP_INPUTS {
+        let expression = format!("'{input}'.toChars().join('')");
+        assert_eq!(
+            common::eval(&expression, common::patient()).await,
+            FhirPathValue::String((*input).into()),
+            "{expression}"
+        );
+        let expression = format!("'{input}'.toChars().join()");
+        assert_eq!(
+            common::eval(&expression, common::patient()).await,
+            FhirPathValue::String((*input).into()),
+            "{expression}"
+        );
+    }
+}
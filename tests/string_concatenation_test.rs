@@ -0,0 +1,60 @@
+//! End-to-end tests for building display strings with the `&` operator
+
+mod common;
+
+use serde_json::json;
+
+#[tokio::test]
+async fn test_display_name_with_all_parts() {
+    let patient = json!({
+        "resourceType": "Patient",
+        "name": [{"family": "Chalmers", "given": ["Peter", "James"]}]
+    });
+
+    assert_eq!(
+        common::unwrap_singleton(
+            common::eval("name.given.first() & ' ' & name.family", patient).await
+        ),
+        common::string("Peter Chalmers")
+    );
+}
+
+#[tokio::test]
+async fn test_display_name_missing_family() {
+    let patient = json!({
+        "resourceType": "Patient",
+        "name": [{"given": ["Peter", "James"]}]
+    });
+
+    assert_eq!(
+        common::unwrap_singleton(
+            common::eval("name.given.first() & ' ' & name.family", patient).await
+        ),
+        common::string("Peter ")
+    );
+}
+
+#[tokio::test]
+async fn test_display_name_missing_given() {
+    let patient = json!({
+        "resourceType": "Patient",
+        "name": [{"family": "Chalmers"}]
+    });
+
+    assert_eq!(
+        common::unwrap_singleton(
+            common::eval("name.given.first() & ' ' & name.family", patient).await
+        ),
+        common::string(" Chalmers")
+    );
+}
+
+#[tokio::test]
+async fn test_concatenating_only_empty_parts_yields_empty_string() {
+    let patient = json!({"resourceType": "Patient"});
+
+    assert_eq!(
+        common::unwrap_singleton(common::eval("name.given.first() & name.family", patient).await),
+        common::string("")
+    );
+}
@@ -0,0 +1,109 @@
+//! Tests for trim(), upper() and lower()
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_normalize_family_before_comparison() {
+    let patient = json!({
+        "resourceType": "Patient",
+        "name": [{"family": "  van Dijk\t", "given": ["Anna", "Maria"]}]
+    });
+    assert_eq!(
+        common::unwrap_singleton(common::eval("Patient.name.family.trim()", patient.clone()).await),
+        common::string("van Dijk")
+    );
+    assert_eq!(
+        common::eval("Patient.name.family.trim().upper() = 'VAN DIJK'", patient).await,
+        FhirPathValue::Boolean(true)
+    );
+}
+
+#[tokio::test]
+async fn test_trim_removes_unicode_whitespace() {
+    // No-break space, ideographic space, line separator and newline
+    assert_eq!(
+        common::unwrap_singleton(
+            common::eval("'\u{00A0}\u{3000}a b\u{2028}\n'.trim()", json!({})).await
+        ),
+        common::string("a b")
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("' \t '.trim()", json!({})).await),
+        common::string("")
+    );
+}
+
+#[tokio::test]
+async fn test_full_case_mapping() {
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'straße'.upper()", json!({})).await),
+        common::string("STRASSE")
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'ÀÉÎ'.lower()", json!({})).await),
+        common::string("àéî")
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'Σ'.lower()", json!({})).await),
+        common::string("σ")
+    );
+}
+
+#[tokio::test]
+async fn test_turkish_i_uses_locale_independent_mapping() {
+    // Dotless i upper-cases to the Latin capital I
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'ı'.upper()", json!({})).await),
+        common::string("I")
+    );
+    // Dotted capital I lower-cases to i followed by a combining dot above
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'İ'.lower()", json!({})).await),
+        common::string("i\u{0307}")
+    );
+    // Plain i and I round-trip without Turkish rules
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'i'.upper()", json!({})).await),
+        common::string("I")
+    );
+    assert_eq!(
+        common::unwrap_singleton(common::eval("'I'.lower()", json!({})).await),
+        common::string("i")
+    );
+}
+
+#[tokio::test]
+async fn test_empty_input_returns_empty() {
+    for expression in [
+        "{}.trim()",
+        "{}.upper()",
+        "{}.lower()",
+        "Patient.photo.trim()",
+    ] {
+        assert!(
+            common::eval(expression, common::patient()).await.is_empty(),
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_non_string_collection_is_an_error() {
+    for expression in [
+        "(1 | 2).trim()",
+        "(1 | 2).upper()",
+        "(1 | 2).lower()",
+        "Patient.lower()",
+    ] {
+        let err = common::try_eval(expression, common::patient())
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("expects String"),
+            "{expression}: {err}"
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Tests for substring() edge cases beyond the official suite
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+
+#[tokio::test]
+async fn test_substring_negative_start_is_empty() {
+    assert!(
+        common::eval("'12345'.substring(-1)", common::patient())
+            .await
+            .is_empty()
+    );
+    assert!(
+        common::eval("'12345'.substring(-1, 2)", common::patient())
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_substring_on_empty_input_is_empty() {
+    assert!(
+        common::eval("{}.substring(0)", common::patient())
+            .await
+            .is_empty()
+    );
+    assert!(
+        common::eval("Patient.name.suffix.substring(0, 1)", common::patient())
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_substring_clamps_length() {
+    assert_eq!(
+        common::eval("'12345'.substring(3, 100)", common::patient()).await,
+        FhirPathValue::String("45".into())
+    );
+}
+
+#[tokio::test]
+async fn test_substring_on_singleton_collection() {
+    assert_eq!(
+        common::eval("Patient.name.family.substring(0, 5)", common::patient()).await,
+        FhirPathValue::String("Chalm".into())
+    );
+}
+
+#[tokio::test]
+async fn test_substring_on_multiple_items_is_an_error() {
+    let error = common::try_eval("Patient.name.given.substring(0, 1)", common::patient())
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("multiple items"), "{error}");
+}
+
+#[tokio::test]
+async fn test_substring_per_item_inside_where() {
+    assert_eq!(
+        common::eval(
+            "Patient.name.given.where(substring($this.length()-3) = 'ter')",
+            common::patient()
+        )
+        .await,
+        FhirPathValue::collection(vec![FhirPathValue::String("Peter".into())])
+    );
+}
@@ -0,0 +1,70 @@
+//! Tests for `is` checks against System primitive types
+
+use octofhir_fhirpath::{FhirPathValue, engine::FhirPathEngine};
+use serde_json::json;
+
+/// Each scalar literal with its System type and a type it must not match
+const MATRIX: &[(&str, &str, &str)] = &[
+    ("true", "Boolean", "String"),
+    ("42", "Integer", "Decimal"),
+    ("4.2", "Decimal", "Integer"),
+    ("'text'", "String", "Boolean"),
+    ("@2014-01-25", "Date", "DateTime"),
+    ("@2014-01-25T14:30:14Z", "DateTime", "Date"),
+    ("@T14:30:14", "Time", "DateTime"),
+    ("(5 'mg')", "Quantity", "Decimal"),
+];
+
+async fn eval_bool(engine: &mut FhirPathEngine, expression: &str) -> bool {
+    let result = engine
+        .evaluate(
+            expression,
+            json!({"resourceType": "Patient", "active": true}),
+        )
+        .await
+        .expect("Should evaluate successfully");
+
+    match result {
+        FhirPathValue::Boolean(b) => b,
+        FhirPathValue::Collection(items) if items.len() == 1 => match items.get(0) {
+            Some(FhirPathValue::Boolean(b)) => *b,
+            other => panic!("{expression}: expected Boolean, got {other:?}"),
+        },
+        other => panic!("{expression}: expected Boolean, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_is_operator_system_types() {
+    let mut engine = FhirPathEngine::new();
+
+    for (literal, system_type, other_type) in MATRIX {
+        let matching = format!("{literal} is System.{system_type}");
+        assert!(eval_bool(&mut engine, &matching).await, "{matching}");
+
+        let mismatched = format!("{literal} is System.{other_type}");
+        assert!(!eval_bool(&mut engine, &mismatched).await, "{mismatched}");
+    }
+}
+
+#[tokio::test]
+async fn test_is_function_system_types() {
+    let mut engine = FhirPathEngine::new();
+
+    for (literal, system_type, other_type) in MATRIX {
+        let matching = format!("{literal}.is(System.{system_type})");
+        assert!(eval_bool(&mut engine, &matching).await, "{matching}");
+
+        let mismatched = format!("{literal}.is(System.{other_type})");
+        assert!(!eval_bool(&mut engine, &mismatched).await, "{mismatched}");
+    }
+}
+
+#[tokio::test]
+async fn test_is_system_any_and_resource_data() {
+    let mut engine = FhirPathEngine::new();
+
+    assert!(eval_bool(&mut engine, "'text' is System.Any").await);
+    assert!(eval_bool(&mut engine, "Patient.active is System.Boolean").await);
+    assert!(!eval_bool(&mut engine, "Patient is System.String").await);
+}
@@ -0,0 +1,87 @@
+//! Tests that comparing dates and date times of different precision gives
+//! empty unless a shared component already decides the answer
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::json;
+
+async fn assert_results(cases: &[(&str, Option<bool>)]) {
+    for (expression, expected) in cases {
+        let expected = expected.map_or(FhirPathValue::Empty, FhirPathValue::Boolean);
+        assert_eq!(
+            common::unwrap_singleton(common::eval(expression, json!({})).await),
+            expected,
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_dates_of_different_precision() {
+    assert_results(&[
+        ("@2012 < @2013", Some(true)),
+        ("@2012 = @2013-01", Some(false)),
+        ("@2012 >= @2013-01-01", Some(false)),
+        ("@2013 = @2013-01", None),
+        ("@2013 < @2013-01", None),
+        ("@2013-01 < @2013-01-02", None),
+        ("@2013-01 <= @2013-01-02", None),
+        ("@2013-01 > @2013-01-02", None),
+        ("@2013-01 >= @2013-01-02", None),
+        ("@2013-02 > @2013-01-31", Some(true)),
+        ("@2013-01-02 = @2013-01-02", Some(true)),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_date_times_of_different_precision() {
+    assert_results(&[
+        ("@2012-04-15 = @2012-04-15T10:00:00", None),
+        ("@2012-04-15 < @2012-04-16T10:00:00", Some(true)),
+        ("@2012-01-01T10:30 = @2012-01-01T10:30:00", None),
+        ("@2012-01-01T10:29 < @2012-01-01T10:30:00", Some(true)),
+        // Seconds and milliseconds are one precision for comparison
+        (
+            "@2012-01-01T10:30:00 = @2012-01-01T10:30:00.000",
+            Some(true),
+        ),
+        (
+            "@2012-01-01T10:30:00 < @2012-01-01T10:30:00.500",
+            Some(true),
+        ),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_date_times_are_compared_in_utc() {
+    assert_results(&[
+        (
+            "@2012-04-15T15:00:00Z = @2012-04-15T10:00:00-05:00",
+            Some(true),
+        ),
+        (
+            "@2012-04-15T15:00:00+02:00 < @2012-04-15T14:00:00Z",
+            Some(true),
+        ),
+        ("@2012-04-16T01:00+10:00 < @2012-04-15T16:00Z", Some(true)),
+        ("@2012-04-15T15:00Z != @2012-04-15T10:00-05:00", Some(false)),
+    ])
+    .await;
+}
+
+#[tokio::test]
+async fn test_date_times_with_and_without_timezone() {
+    assert_results(&[
+        ("@2012-04-15T15:00:00Z = @2012-04-15T10:00:00", None),
+        ("@2012-04-15T15:00:00Z != @2012-04-15T10:00:00", None),
+        ("@2012-04-15T15:00:00Z = @2012-04-15T15:00:00", None),
+        ("@2012-04-15T10:00:00 < @2012-04-15T15:00:00Z", None),
+        ("@2012-04-15T10:00:00 = @2012-04-15T10:00:00", Some(true)),
+        // Dates carry no time, so the missing timezone does not matter
+        ("@2012-04-14 < @2012-04-15T10:00:00Z", Some(true)),
+    ])
+    .await;
+}
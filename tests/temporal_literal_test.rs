@@ -0,0 +1,107 @@
+//! Tests that date, datetime and time literals keep the precision they are
+//! written to, from literal to value to string
+
+mod common;
+
+use octofhir_fhirpath::model::{PrecisionDate, TemporalPrecision};
+use octofhir_fhirpath::{FhirPathError, FhirPathValue, parse};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_literals_keep_precision() {
+    assert_eq!(
+        common::unwrap_singleton(common::eval("@2013", json!({})).await),
+        FhirPathValue::Date(PrecisionDate::parse("2013").unwrap())
+    );
+
+    for (literal, precision) in [
+        ("@2013-01T", TemporalPrecision::Month),
+        ("@2013-01-01T12", TemporalPrecision::Hour),
+        ("@2013-01-01T12:00:00Z", TemporalPrecision::Second),
+        ("@2013-01-01T12:00:00.000Z", TemporalPrecision::Millisecond),
+    ] {
+        match common::unwrap_singleton(common::eval(literal, json!({})).await) {
+            FhirPathValue::DateTime(dt) => assert_eq!(dt.precision, precision, "{literal}"),
+            other => panic!("{literal} gave {other:?}"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_literals_round_trip_through_to_string() {
+    for literal in [
+        "2013",
+        "2013-01",
+        "2013-01-01",
+        "2013-01-01T12",
+        "2013-01-01T12:30",
+        "2013-01-01T12:30:00.123",
+        "2013-01-01T12:30:00+10:00",
+        "T12",
+        "T12:30:00.500",
+    ] {
+        assert_eq!(
+            common::unwrap_singleton(
+                common::eval(&format!("@{literal}.toString()"), json!({})).await
+            ),
+            FhirPathValue::String(literal.trim_start_matches('T').into()),
+            "{literal}"
+        );
+    }
+
+    // The value prints back as the literal it was written as
+    for literal in ["@2013", "@2013-01T", "@2013-01-01T12:30-05:00", "@T12:30"] {
+        assert_eq!(
+            common::unwrap_singleton(common::eval(literal, json!({})).await).to_string(),
+            literal
+        );
+    }
+}
+
+#[test]
+fn test_invalid_literals_fail_to_parse() {
+    for expression in [
+        "@2013-13",
+        "@2013-02-30",
+        "@2013-01T10",
+        "@T24:00",
+        "@2013-01-01T10:61",
+    ] {
+        assert!(parse(expression).is_err(), "{expression} should not parse");
+    }
+    assert!(parse("@2012-02-29").is_ok());
+}
+
+#[test]
+fn test_unsupported_date_notations_are_named() {
+    let error = parse("@2018-W01").unwrap_err().to_string();
+    assert_eq!(
+        error,
+        "Syntax error at position 0: ISO week date '@2018-W01' is not supported, \
+         use a calendar date such as @2018-01-01"
+    );
+
+    for (expression, notation) in [
+        ("@2018-W01-1", "ISO week date '@2018-W01-1'"),
+        ("@2018W01", "ISO week date '@2018W01'"),
+        ("@2018-001", "ISO ordinal date '@2018-001'"),
+        ("Patient.birthDate < @2018-W52", "ISO week date '@2018-W52'"),
+    ] {
+        let error = parse(expression).unwrap_err().to_string();
+        assert!(error.contains(notation), "{expression}: {error}");
+    }
+}
+
+#[tokio::test]
+async fn test_engine_reports_unsupported_date_notations() {
+    match common::try_eval("Patient.birthDate < @2018-W01", json!({})).await {
+        Err(FhirPathError::ParseError { position, message }) => {
+            assert_eq!(position, 20);
+            assert!(
+                message.contains("ISO week date '@2018-W01' is not supported"),
+                "{message}"
+            );
+        }
+        other => panic!("expected a parse error, got {other:?}"),
+    }
+}
@@ -0,0 +1,53 @@
+//! Tests for toChars() with multi-byte UTF-8 input
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_to_chars_keeps_emoji_whole() {
+    assert_eq!(
+        common::eval("'😀abc'.toChars()", json!({})).await,
+        FhirPathValue::collection(common::strings(&["😀", "a", "b", "c"]))
+    );
+    assert_eq!(
+        common::eval("'😀abc'.toChars().count()", json!({})).await,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(4)])
+    );
+}
+
+#[tokio::test]
+async fn test_to_chars_accented_latin() {
+    assert_eq!(
+        common::eval("'café'.toChars()", json!({})).await,
+        FhirPathValue::collection(common::strings(&["c", "a", "f", "é"]))
+    );
+    assert_eq!(
+        common::eval(
+            "Patient.name.family.toChars()",
+            json!({"resourceType": "Patient", "name": [{"family": "Müller"}]})
+        )
+        .await,
+        FhirPathValue::collection(common::strings(&["M", "ü", "l", "l", "e", "r"]))
+    );
+}
+
+#[tokio::test]
+async fn test_to_chars_cjk() {
+    assert_eq!(
+        common::eval("'漢字テスト'.toChars()", json!({})).await,
+        FhirPathValue::collection(common::strings(&["漢", "字", "テ", "ス", "ト"]))
+    );
+}
+
+#[tokio::test]
+async fn test_to_chars_on_empty_input_is_empty() {
+    assert!(common::eval("{}.toChars()", json!({})).await.is_empty());
+    assert!(common::eval("''.toChars()", json!({})).await.is_empty());
+    assert!(
+        common::eval("Patient.name.suffix.toChars()", common::patient())
+            .await
+            .is_empty()
+    );
+}
@@ -0,0 +1,81 @@
+//! Tests for toTime() and precision-aware Time comparison
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::json;
+
+fn single(result: FhirPathValue) -> Option<FhirPathValue> {
+    match result {
+        FhirPathValue::Collection(items) if items.len() == 1 => items.get(0).cloned(),
+        FhirPathValue::Collection(items) if items.is_empty() => None,
+        FhirPathValue::Empty => None,
+        other => Some(other),
+    }
+}
+
+#[tokio::test]
+async fn test_to_time_from_string() {
+    match single(common::eval("'14:30:00'.toTime()", json!({})).await) {
+        Some(FhirPathValue::Time(time)) => assert_eq!(time.to_string(), "14:30:00"),
+        other => panic!("Expected a Time, got {other:?}"),
+    }
+
+    match single(common::eval("'14:30:00.125'.toTime()", json!({})).await) {
+        Some(FhirPathValue::Time(time)) => assert_eq!(time.to_string(), "14:30:00.125"),
+        other => panic!("Expected a Time, got {other:?}"),
+    }
+
+    assert_eq!(
+        single(common::eval("'2pm'.toTime()", json!({})).await),
+        None
+    );
+    assert_eq!(
+        single(common::eval("'25:00'.toTime()", json!({})).await),
+        None
+    );
+    assert_eq!(
+        single(common::eval("' 14:30 '.toTime()", json!({})).await),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_time_comparison() {
+    let cases = [
+        ("'14:30:00'.toTime() = @T14:30:00", true),
+        ("'14:30:00'.toTime() < @T14:30:01", true),
+        ("@T14:30:00.5 > @T14:30:00.25", true),
+        ("@T10 < @T09:59", false),
+        ("@T14:30:00 = @T14:30:00.000", true),
+    ];
+
+    for (expression, expected) in cases {
+        assert_eq!(
+            single(common::eval(expression, json!({})).await),
+            Some(FhirPathValue::Boolean(expected)),
+            "{expression}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_time_comparison_differing_precision_is_empty() {
+    for expression in [
+        "@T10:30 = @T10:30:00",
+        "'10:30'.toTime() < @T10:30:00",
+        "@T10 >= @T10:00",
+    ] {
+        assert_eq!(
+            single(common::eval(expression, json!({})).await),
+            None,
+            "{expression}"
+        );
+    }
+
+    // A difference in a shared component still decides the comparison
+    assert_eq!(
+        single(common::eval("@T10:30 < @T10:31:00", json!({})).await),
+        Some(FhirPathValue::Boolean(true))
+    );
+}
@@ -0,0 +1,78 @@
+//! Tests for trace() output delivered to a TraceSink
+
+mod common;
+
+use common::{items, strings};
+use octofhir_fhirpath::FhirPathValue;
+
+/// The trace() calls logged so far, with each value as its items
+fn take_calls(log: &common::TraceLog) -> Vec<(String, Vec<FhirPathValue>)> {
+    log.take()
+        .into_iter()
+        .map(|(name, value)| (name, items(value)))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_sink_receives_the_traced_collection() {
+    let (engine, log) = common::collecting_trace_engine();
+    let result = common::eval_with(
+        &engine,
+        "Bundle.entry.trace('entries').resource.id",
+        common::bundle(),
+    )
+    .await;
+
+    assert_eq!(items(result), strings(&["p1", "p2"]));
+    let calls = take_calls(&log);
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].0, "entries");
+    assert_eq!(calls[0].1.len(), 2);
+}
+
+#[tokio::test]
+async fn test_projection_is_logged_but_input_passes_through() {
+    let (engine, log) = common::collecting_trace_engine();
+    let result = common::eval_with(
+        &engine,
+        "Bundle.entry.trace('ids', resource.id).resource.count()",
+        common::bundle(),
+    )
+    .await;
+
+    assert_eq!(items(result), vec![FhirPathValue::Integer(2)]);
+    assert_eq!(
+        take_calls(&log),
+        [("ids".to_string(), strings(&["p1", "p2"]))]
+    );
+}
+
+#[tokio::test]
+async fn test_projection_is_evaluated_per_item() {
+    let (engine, log) = common::collecting_trace_engine();
+    common::eval_with(
+        &engine,
+        "Bundle.entry.trace('positions', $index.toString() + ':' + resource.id)",
+        common::bundle(),
+    )
+    .await;
+
+    assert_eq!(
+        take_calls(&log),
+        [("positions".to_string(), strings(&["0:p1", "1:p2"]))]
+    );
+}
+
+#[tokio::test]
+async fn test_empty_trace_reaches_the_sink() {
+    let (engine, log) = common::collecting_trace_engine();
+    let result = common::eval_with(
+        &engine,
+        "Bundle.entry.resource.where(id = 'p3').trace('none')",
+        common::bundle(),
+    )
+    .await;
+
+    assert!(items(result).is_empty());
+    assert_eq!(take_calls(&log), [("none".to_string(), Vec::new())]);
+}
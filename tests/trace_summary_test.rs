@@ -0,0 +1,52 @@
+//! Tests that large collections are traced as a summary
+
+mod common;
+
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::{Value, json};
+
+fn patient(names: usize) -> Value {
+    let given: Vec<String> = (1..=names).map(|i| format!("n{i}")).collect();
+    json!({
+        "resourceType": "Patient",
+        "name": [{"family": "Doe", "given": given}]
+    })
+}
+
+#[tokio::test]
+async fn test_large_collection_is_summarized() {
+    let (engine, log) = common::collecting_trace_engine_with(|engine, sink| {
+        engine.with_summarized_trace_sink(sink, 10)
+    });
+    let result = common::eval_with(&engine, "name.given.trace('given')", patient(200)).await;
+    assert_eq!(
+        log.take_values(),
+        [FhirPathValue::String(
+            "200 items: [n1, n2, n3, n4, n5, ...]".into()
+        )]
+    );
+
+    // The full collection still flows through
+    let untraced = common::eval("name.given", patient(200)).await;
+    assert_eq!(result, untraced);
+    let count = common::eval("name.given.trace('given').count()", patient(200)).await;
+    assert_eq!(
+        count,
+        FhirPathValue::collection(vec![FhirPathValue::Integer(200)])
+    );
+}
+
+#[tokio::test]
+async fn test_small_collection_is_traced_in_full() {
+    let (engine, log) = common::collecting_trace_engine_with(|engine, sink| {
+        engine.with_summarized_trace_sink(sink, 3)
+    });
+    let result = common::eval_with(&engine, "name.given.trace('given')", patient(3)).await;
+    assert_eq!(log.take_values(), [result]);
+
+    common::eval_with(&engine, "name.given.trace('given')", patient(4)).await;
+    assert_eq!(
+        log.take_values(),
+        [FhirPathValue::String("4 items: [n1, n2, n3, n4]".into())]
+    );
+}
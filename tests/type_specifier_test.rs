@@ -0,0 +1,112 @@
+//! Tests that qualified and unqualified type names mean the same type in
+//! `is`, `as`, `ofType()` and `type()`
+
+mod common;
+
+use common::items;
+use octofhir_fhirpath::FhirPathValue;
+use serde_json::{Value, json};
+
+fn observation() -> Value {
+    json!({
+        "resourceType": "Observation",
+        "id": "o1",
+        "status": "final",
+        "valueQuantity": {"value": 185, "unit": "lbs"}
+    })
+}
+
+/// Evaluate `path` with each way of testing or casting to each of `types`,
+/// and check that every form agrees with `expected_is`
+async fn assert_forms_agree(path: &str, types: &[&str], expected_is: bool) {
+    let value = items(common::eval(path, observation()).await);
+    let cast = if expected_is {
+        value.clone()
+    } else {
+        Vec::new()
+    };
+
+    for type_name in types {
+        for expression in [
+            format!("{path} is {type_name}"),
+            format!("{path}.is({type_name})"),
+        ] {
+            assert_eq!(
+                items(common::eval(&expression, observation()).await),
+                [FhirPathValue::Boolean(expected_is)],
+                "{expression}"
+            );
+        }
+        for expression in [
+            format!("{path} as {type_name}"),
+            format!("{path}.as({type_name})"),
+            format!("{path}.ofType({type_name})"),
+        ] {
+            assert_eq!(
+                items(common::eval(&expression, observation()).await),
+                cast,
+                "{expression}"
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_fhir_complex_type_names() {
+    let quantity = ["Quantity", "FHIR.Quantity", "FHIR.`Quantity`", "`Quantity`"];
+    assert_forms_agree("Observation.value", &quantity, true).await;
+    assert_forms_agree("Observation.value", &["System.Quantity", "Period"], false).await;
+}
+
+#[tokio::test]
+async fn test_resource_type_names() {
+    let observation = [
+        "Observation",
+        "FHIR.Observation",
+        "DomainResource",
+        "FHIR.Resource",
+    ];
+    assert_forms_agree("Observation", &observation, true).await;
+    assert_forms_agree("Observation", &["Patient", "System.Observation"], false).await;
+}
+
+#[tokio::test]
+async fn test_primitive_type_names() {
+    let string = [
+        "String",
+        "System.String",
+        "FHIR.string",
+        "string",
+        "code",
+        "FHIR.code",
+    ];
+    assert_forms_agree("Observation.status", &string, true).await;
+    assert_forms_agree("Observation.status", &["Integer", "FHIR.integer"], false).await;
+
+    let integer = [
+        "Integer",
+        "System.Integer",
+        "FHIR.integer",
+        "System.`Integer`",
+    ];
+    assert_forms_agree("1", &integer, true).await;
+    assert_forms_agree("1", &["FHIR.Integer", "System.integer", "Decimal"], false).await;
+}
+
+#[tokio::test]
+async fn test_type_names_round_trip() {
+    for (path, expected) in [
+        ("Observation", "FHIR.Observation"),
+        ("Observation.value", "FHIR.Quantity"),
+        ("Observation.status", "System.String"),
+        ("1", "System.Integer"),
+    ] {
+        let expression = format!("{path}.type().namespace + '.' + {path}.type().name");
+        assert_eq!(
+            items(common::eval(&expression, observation()).await),
+            [FhirPathValue::String(expected.into())],
+            "{expression}"
+        );
+        assert_forms_agree(path, &[expected], true).await;
+    }
+}
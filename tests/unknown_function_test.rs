@@ -0,0 +1,42 @@
+//! Tests that calls to unknown functions suggest the closest known name
+
+use octofhir_fhirpath::engine::FhirPathEngine;
+use serde_json::json;
+
+async fn error_message(expression: &str) -> String {
+    FhirPathEngine::new()
+        .evaluate(
+            expression,
+            json!({"resourceType": "Patient", "name": [{"family": "Doe"}]}),
+        )
+        .await
+        .expect_err(expression)
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_typos_suggest_the_closest_function() {
+    for (expression, suggestion) in [
+        ("name.whre(family.exists())", "where"),
+        ("name.frist()", "first"),
+        ("name.slect(family)", "select"),
+        ("name.family.exist()", "exists"),
+        ("name.Count()", "count"),
+        ("'1'.toInterger()", "toInteger"),
+    ] {
+        let message = error_message(expression).await;
+        assert!(
+            message.ends_with(&format!("did you mean '{suggestion}'?")),
+            "{expression}: {message}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_no_suggestion_for_unrelated_names() {
+    let message = error_message("name.frobnicate()").await;
+    assert!(
+        message.ends_with("Unknown function: frobnicate"),
+        "{message}"
+    );
+}